@@ -1,4 +1,4 @@
 -- Add down migration script here
-DROP TABLE IF EXISTS "users";
-DROP TABLE IF EXISTS "jobs";
 DROP TABLE IF EXISTS "applications";
+DROP TABLE IF EXISTS "jobs";
+DROP TABLE IF EXISTS "users";
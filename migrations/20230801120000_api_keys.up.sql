@@ -0,0 +1,22 @@
+-- Add up migration script here
+CREATE TABLE
+    "api_keys" (
+        id UUID NOT NULL PRIMARY KEY DEFAULT (uuid_generate_v4()),
+        user_id UUID NOT NULL REFERENCES users (id) ON DELETE CASCADE,
+        company_name VARCHAR(100),
+        name VARCHAR(100) NOT NULL,
+        prefix VARCHAR(16) NOT NULL UNIQUE,
+        key_hash VARCHAR(64) NOT NULL,
+        scopes TEXT[] NOT NULL DEFAULT '{}',
+        last_used_at TIMESTAMP WITH TIME ZONE,
+        expires_at TIMESTAMP WITH TIME ZONE,
+        revoked_at TIMESTAMP WITH TIME ZONE,
+        created_at TIMESTAMP
+        WITH
+            TIME ZONE DEFAULT NOW(),
+            updated_at TIMESTAMP
+        WITH
+            TIME ZONE DEFAULT NOW()
+    );
+
+CREATE INDEX api_keys_user_id_idx ON api_keys (user_id);
@@ -0,0 +1,2 @@
+-- Add up migration script here
+ALTER TABLE "users" ADD COLUMN is_active BOOLEAN NOT NULL DEFAULT TRUE;
@@ -0,0 +1,26 @@
+-- Add up migration script here
+CREATE TABLE
+    "jobs_queue" (
+        id UUID NOT NULL PRIMARY KEY DEFAULT (uuid_generate_v4()),
+        kind VARCHAR(50) NOT NULL,
+        payload JSONB NOT NULL,
+        status VARCHAR(20) NOT NULL DEFAULT 'pending',
+        attempts INTEGER NOT NULL DEFAULT 0,
+        max_attempts INTEGER NOT NULL DEFAULT 5,
+        run_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+        locked_at TIMESTAMP WITH TIME ZONE,
+        locked_by VARCHAR(100),
+        last_error TEXT,
+        completed_at TIMESTAMP WITH TIME ZONE,
+        created_at TIMESTAMP
+        WITH
+            TIME ZONE DEFAULT NOW(),
+            updated_at TIMESTAMP
+        WITH
+            TIME ZONE DEFAULT NOW()
+    );
+
+-- workers only ever look for runnable jobs, keep that lookup small
+CREATE INDEX jobs_queue_runnable_idx ON jobs_queue (run_at) WHERE status IN ('pending', 'running');
+
+CREATE INDEX jobs_queue_status_idx ON jobs_queue (status, created_at);
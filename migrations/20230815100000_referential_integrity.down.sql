@@ -0,0 +1,14 @@
+-- Add down migration script here
+DROP TRIGGER IF EXISTS jobs_queue_set_updated_at ON jobs_queue;
+DROP TRIGGER IF EXISTS api_keys_set_updated_at ON api_keys;
+DROP TRIGGER IF EXISTS applications_set_updated_at ON applications;
+DROP TRIGGER IF EXISTS jobs_set_updated_at ON jobs;
+DROP TRIGGER IF EXISTS users_set_updated_at ON users;
+DROP FUNCTION IF EXISTS set_updated_at();
+
+DROP INDEX IF EXISTS applications_user_id_idx;
+
+ALTER TABLE applications
+    DROP CONSTRAINT IF EXISTS applications_job_id_user_id_key,
+    DROP CONSTRAINT IF EXISTS applications_user_id_fkey,
+    DROP CONSTRAINT IF EXISTS applications_job_id_fkey;
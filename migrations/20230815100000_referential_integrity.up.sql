@@ -0,0 +1,44 @@
+-- Add up migration script here
+
+-- applications written before the constraints existed may point at deleted
+-- rows or be duplicates from the racy double submit; keep the oldest
+DELETE FROM applications
+WHERE
+    job_id NOT IN (SELECT id FROM jobs)
+    OR user_id NOT IN (SELECT id FROM users);
+
+DELETE FROM applications a USING applications b
+WHERE
+    a.job_id = b.job_id
+    AND a.user_id = b.user_id
+    AND (a.created_at, a.id) > (b.created_at, b.id);
+
+ALTER TABLE applications
+    ADD CONSTRAINT applications_job_id_fkey FOREIGN KEY (job_id) REFERENCES jobs (id) ON DELETE CASCADE,
+    ADD CONSTRAINT applications_user_id_fkey FOREIGN KEY (user_id) REFERENCES users (id) ON DELETE CASCADE,
+    ADD CONSTRAINT applications_job_id_user_id_key UNIQUE (job_id, user_id);
+
+-- the unique index above leads with job_id, so it also serves lookups by job
+CREATE INDEX applications_user_id_idx ON applications (user_id);
+
+CREATE OR REPLACE FUNCTION set_updated_at() RETURNS TRIGGER AS $$
+BEGIN
+    NEW.updated_at = NOW();
+    RETURN NEW;
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE TRIGGER users_set_updated_at BEFORE UPDATE ON users
+    FOR EACH ROW EXECUTE FUNCTION set_updated_at();
+
+CREATE TRIGGER jobs_set_updated_at BEFORE UPDATE ON jobs
+    FOR EACH ROW EXECUTE FUNCTION set_updated_at();
+
+CREATE TRIGGER applications_set_updated_at BEFORE UPDATE ON applications
+    FOR EACH ROW EXECUTE FUNCTION set_updated_at();
+
+CREATE TRIGGER api_keys_set_updated_at BEFORE UPDATE ON api_keys
+    FOR EACH ROW EXECUTE FUNCTION set_updated_at();
+
+CREATE TRIGGER jobs_queue_set_updated_at BEFORE UPDATE ON jobs_queue
+    FOR EACH ROW EXECUTE FUNCTION set_updated_at();
@@ -0,0 +1,7 @@
+-- Add down migration script here
+DROP INDEX IF EXISTS applications_created_at_idx;
+DROP INDEX IF EXISTS users_deletion_requested_at_idx;
+
+ALTER TABLE "users"
+    DROP COLUMN IF EXISTS deleted_at,
+    DROP COLUMN IF EXISTS deletion_requested_at;
@@ -0,0 +1,12 @@
+-- Add up migration script here
+-- deletion_requested_at starts the grace period, deleted_at marks a user whose
+-- personal data has been scrubbed. The row stays so applications keep their
+-- counts without pointing at anyone.
+ALTER TABLE "users"
+    ADD COLUMN deletion_requested_at TIMESTAMP WITH TIME ZONE,
+    ADD COLUMN deleted_at TIMESTAMP WITH TIME ZONE;
+
+CREATE INDEX users_deletion_requested_at_idx ON users (deletion_requested_at)
+    WHERE deletion_requested_at IS NOT NULL AND deleted_at IS NULL;
+
+CREATE INDEX applications_created_at_idx ON applications (created_at);
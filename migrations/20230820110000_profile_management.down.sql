@@ -0,0 +1,3 @@
+-- Add down migration script here
+DROP TABLE IF EXISTS email_changes;
+ALTER TABLE "users" DROP COLUMN IF EXISTS session_version;
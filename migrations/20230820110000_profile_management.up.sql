@@ -0,0 +1,24 @@
+-- Add up migration script here
+-- bumped on password change; tokens carry the version they were issued for
+ALTER TABLE "users" ADD COLUMN session_version INTEGER NOT NULL DEFAULT 0;
+
+CREATE TABLE
+    "email_changes" (
+        id UUID NOT NULL PRIMARY KEY DEFAULT (uuid_generate_v4()),
+        user_id UUID NOT NULL REFERENCES users (id) ON DELETE CASCADE,
+        new_email VARCHAR(255) NOT NULL,
+        token_hash VARCHAR(64) NOT NULL UNIQUE,
+        expires_at TIMESTAMP WITH TIME ZONE NOT NULL,
+        confirmed_at TIMESTAMP WITH TIME ZONE,
+        created_at TIMESTAMP
+        WITH
+            TIME ZONE DEFAULT NOW(),
+            updated_at TIMESTAMP
+        WITH
+            TIME ZONE DEFAULT NOW()
+    );
+
+CREATE INDEX email_changes_user_id_idx ON email_changes (user_id);
+
+CREATE TRIGGER email_changes_set_updated_at BEFORE UPDATE ON email_changes
+    FOR EACH ROW EXECUTE FUNCTION set_updated_at();
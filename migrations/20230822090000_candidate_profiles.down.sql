@@ -0,0 +1,7 @@
+-- Add down migration script here
+DROP TABLE IF EXISTS portfolio_links;
+DROP TABLE IF EXISTS candidate_skills;
+DROP TABLE IF EXISTS certifications;
+DROP TABLE IF EXISTS educations;
+DROP TABLE IF EXISTS work_experiences;
+DROP TABLE IF EXISTS candidate_profiles;
@@ -0,0 +1,134 @@
+-- Add up migration script here
+-- One row per candidate with the parts of a profile that are not lists.
+-- Salaries are yearly amounts in salary_currency.
+CREATE TABLE
+    "candidate_profiles" (
+        user_id UUID NOT NULL PRIMARY KEY REFERENCES users (id) ON DELETE CASCADE,
+        headline VARCHAR(150) NOT NULL DEFAULT '',
+        summary TEXT NOT NULL DEFAULT '',
+        city VARCHAR(255) NOT NULL DEFAULT '',
+        country VARCHAR(100) NOT NULL DEFAULT '',
+        open_to_remote BOOLEAN NOT NULL DEFAULT FALSE,
+        desired_roles TEXT[] NOT NULL DEFAULT '{}',
+        salary_min INTEGER CHECK (salary_min >= 0),
+        salary_max INTEGER,
+        salary_currency VARCHAR(3),
+        CHECK (salary_max >= salary_min),
+        created_at TIMESTAMP
+        WITH
+            TIME ZONE DEFAULT NOW(),
+            updated_at TIMESTAMP
+        WITH
+            TIME ZONE DEFAULT NOW()
+    );
+
+CREATE TABLE
+    "work_experiences" (
+        id UUID NOT NULL PRIMARY KEY DEFAULT (uuid_generate_v4()),
+        user_id UUID NOT NULL REFERENCES users (id) ON DELETE CASCADE,
+        company_name VARCHAR(150) NOT NULL,
+        title VARCHAR(150) NOT NULL,
+        location VARCHAR(255) NOT NULL DEFAULT '',
+        start_date DATE NOT NULL,
+        -- NULL while the candidate still works there
+        end_date DATE,
+        description TEXT NOT NULL DEFAULT '',
+        CHECK (end_date >= start_date),
+        created_at TIMESTAMP
+        WITH
+            TIME ZONE DEFAULT NOW(),
+            updated_at TIMESTAMP
+        WITH
+            TIME ZONE DEFAULT NOW()
+    );
+
+CREATE TABLE
+    "educations" (
+        id UUID NOT NULL PRIMARY KEY DEFAULT (uuid_generate_v4()),
+        user_id UUID NOT NULL REFERENCES users (id) ON DELETE CASCADE,
+        institution VARCHAR(150) NOT NULL,
+        degree VARCHAR(150) NOT NULL,
+        field_of_study VARCHAR(150) NOT NULL DEFAULT '',
+        start_date DATE NOT NULL,
+        end_date DATE,
+        CHECK (end_date >= start_date),
+        created_at TIMESTAMP
+        WITH
+            TIME ZONE DEFAULT NOW(),
+            updated_at TIMESTAMP
+        WITH
+            TIME ZONE DEFAULT NOW()
+    );
+
+CREATE TABLE
+    "certifications" (
+        id UUID NOT NULL PRIMARY KEY DEFAULT (uuid_generate_v4()),
+        user_id UUID NOT NULL REFERENCES users (id) ON DELETE CASCADE,
+        name VARCHAR(150) NOT NULL,
+        issuer VARCHAR(150) NOT NULL DEFAULT '',
+        issued_on DATE NOT NULL,
+        expires_on DATE,
+        credential_url VARCHAR(500) NOT NULL DEFAULT '',
+        CHECK (expires_on >= issued_on),
+        created_at TIMESTAMP
+        WITH
+            TIME ZONE DEFAULT NOW(),
+            updated_at TIMESTAMP
+        WITH
+            TIME ZONE DEFAULT NOW()
+    );
+
+CREATE TABLE
+    "candidate_skills" (
+        id UUID NOT NULL PRIMARY KEY DEFAULT (uuid_generate_v4()),
+        user_id UUID NOT NULL REFERENCES users (id) ON DELETE CASCADE,
+        name VARCHAR(100) NOT NULL,
+        proficiency VARCHAR(20) NOT NULL CHECK (proficiency IN ('beginner', 'intermediate', 'advanced', 'expert')),
+        years_of_experience INTEGER CHECK (years_of_experience >= 0),
+        created_at TIMESTAMP
+        WITH
+            TIME ZONE DEFAULT NOW(),
+            updated_at TIMESTAMP
+        WITH
+            TIME ZONE DEFAULT NOW()
+    );
+
+-- "Rust" and "rust" are the same skill
+CREATE UNIQUE INDEX candidate_skills_user_id_name_key ON candidate_skills (user_id, LOWER(name));
+
+CREATE TABLE
+    "portfolio_links" (
+        id UUID NOT NULL PRIMARY KEY DEFAULT (uuid_generate_v4()),
+        user_id UUID NOT NULL REFERENCES users (id) ON DELETE CASCADE,
+        label VARCHAR(100) NOT NULL,
+        url VARCHAR(500) NOT NULL,
+        created_at TIMESTAMP
+        WITH
+            TIME ZONE DEFAULT NOW(),
+            updated_at TIMESTAMP
+        WITH
+            TIME ZONE DEFAULT NOW()
+    );
+
+CREATE INDEX work_experiences_user_id_idx ON work_experiences (user_id);
+CREATE INDEX educations_user_id_idx ON educations (user_id);
+CREATE INDEX certifications_user_id_idx ON certifications (user_id);
+CREATE INDEX portfolio_links_user_id_idx ON portfolio_links (user_id);
+
+CREATE TRIGGER candidate_profiles_set_updated_at BEFORE UPDATE ON candidate_profiles
+    FOR EACH ROW EXECUTE FUNCTION set_updated_at();
+
+CREATE TRIGGER work_experiences_set_updated_at BEFORE UPDATE ON work_experiences
+    FOR EACH ROW EXECUTE FUNCTION set_updated_at();
+
+CREATE TRIGGER educations_set_updated_at BEFORE UPDATE ON educations
+    FOR EACH ROW EXECUTE FUNCTION set_updated_at();
+
+CREATE TRIGGER certifications_set_updated_at BEFORE UPDATE ON certifications
+    FOR EACH ROW EXECUTE FUNCTION set_updated_at();
+
+CREATE TRIGGER candidate_skills_set_updated_at BEFORE UPDATE ON candidate_skills
+    FOR EACH ROW EXECUTE FUNCTION set_updated_at();
+
+CREATE TRIGGER portfolio_links_set_updated_at BEFORE UPDATE ON portfolio_links
+    FOR EACH ROW EXECUTE FUNCTION set_updated_at();
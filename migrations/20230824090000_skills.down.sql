@@ -0,0 +1,6 @@
+-- Add down migration script here
+DROP INDEX IF EXISTS candidate_skills_skill_id_idx;
+ALTER TABLE candidate_skills DROP COLUMN IF EXISTS skill_id;
+DROP TABLE IF EXISTS job_skills;
+DROP TABLE IF EXISTS skill_aliases;
+DROP TABLE IF EXISTS skills;
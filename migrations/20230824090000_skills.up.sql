@@ -0,0 +1,69 @@
+-- Add up migration script here
+-- The canonical skill names jobs and candidates are tagged with. Aliases are
+-- other spellings of the same skill, stored lowercase.
+CREATE TABLE
+    "skills" (
+        id UUID NOT NULL PRIMARY KEY DEFAULT (uuid_generate_v4()),
+        name VARCHAR(100) NOT NULL,
+        created_at TIMESTAMP
+        WITH
+            TIME ZONE DEFAULT NOW(),
+            updated_at TIMESTAMP
+        WITH
+            TIME ZONE DEFAULT NOW()
+    );
+
+-- text_pattern_ops so the autocomplete prefix search can use it
+CREATE UNIQUE INDEX skills_name_key ON skills (LOWER(name) text_pattern_ops);
+
+CREATE TABLE
+    "skill_aliases" (
+        alias VARCHAR(100) NOT NULL PRIMARY KEY CHECK (alias = LOWER(alias)),
+        skill_id UUID NOT NULL REFERENCES skills (id) ON DELETE CASCADE
+    );
+
+CREATE INDEX skill_aliases_skill_id_idx ON skill_aliases (skill_id);
+CREATE INDEX skill_aliases_alias_pattern_idx ON skill_aliases (alias text_pattern_ops);
+
+CREATE TABLE
+    "job_skills" (
+        job_id UUID NOT NULL REFERENCES jobs (id) ON DELETE CASCADE,
+        skill_id UUID NOT NULL REFERENCES skills (id) ON DELETE CASCADE,
+        -- false for nice to have
+        required BOOLEAN NOT NULL DEFAULT TRUE,
+        PRIMARY KEY (job_id, skill_id)
+    );
+
+CREATE INDEX job_skills_skill_id_idx ON job_skills (skill_id);
+
+-- Candidates keep whatever they typed when it is not in the taxonomy, so the
+-- link is optional
+ALTER TABLE candidate_skills
+ADD
+    COLUMN skill_id UUID REFERENCES skills (id) ON DELETE SET NULL;
+
+CREATE INDEX candidate_skills_skill_id_idx ON candidate_skills (skill_id);
+
+CREATE TRIGGER skills_set_updated_at BEFORE UPDATE ON skills
+    FOR EACH ROW EXECUTE FUNCTION set_updated_at();
+
+-- a starting taxonomy, admins add to it through POST /api/skills
+INSERT INTO skills (name) VALUES
+    ('Rust'), ('Go'), ('Python'), ('Java'), ('Kotlin'), ('Swift'), ('JavaScript'),
+    ('TypeScript'), ('C#'), ('C++'), ('Ruby'), ('PHP'), ('SQL'), ('PostgreSQL'),
+    ('MySQL'), ('Redis'), ('React'), ('Vue.js'), ('Angular'), ('Node.js'),
+    ('Docker'), ('Kubernetes'), ('Terraform'), ('AWS'), ('Google Cloud'), ('Azure'),
+    ('Linux'), ('Git'), ('GraphQL'), ('Machine Learning'), ('Figma'), ('iOS'), ('Android');
+
+INSERT INTO skill_aliases (alias, skill_id)
+SELECT aliases.alias, skills.id
+FROM (
+        VALUES ('golang', 'Go'), ('js', 'JavaScript'), ('ecmascript', 'JavaScript'),
+            ('ts', 'TypeScript'), ('csharp', 'C#'), ('cpp', 'C++'), ('postgres', 'PostgreSQL'),
+            ('psql', 'PostgreSQL'), ('reactjs', 'React'), ('react.js', 'React'),
+            ('vue', 'Vue.js'), ('vuejs', 'Vue.js'), ('angularjs', 'Angular'),
+            ('node', 'Node.js'), ('nodejs', 'Node.js'), ('k8s', 'Kubernetes'),
+            ('amazon web services', 'AWS'), ('gcp', 'Google Cloud'), ('microsoft azure', 'Azure'),
+            ('ml', 'Machine Learning')
+    ) AS aliases (alias, name)
+    INNER JOIN skills ON skills.name = aliases.name;
@@ -0,0 +1,3 @@
+-- Add down migration script here
+DROP INDEX IF EXISTS jobs_created_at_idx;
+DROP TABLE IF EXISTS saved_searches;
@@ -0,0 +1,30 @@
+-- Add up migration script here
+-- filters holds the query parameters of GET /api/jobs the search was saved
+-- with. Alerts cover the jobs posted after last_notified_at.
+CREATE TABLE
+    "saved_searches" (
+        id UUID NOT NULL PRIMARY KEY DEFAULT (uuid_generate_v4()),
+        user_id UUID NOT NULL REFERENCES users (id) ON DELETE CASCADE,
+        name VARCHAR(100) NOT NULL,
+        filters JSONB NOT NULL DEFAULT '{}',
+        frequency VARCHAR(10) NOT NULL CHECK (frequency IN ('instant', 'daily', 'weekly')),
+        alerts_enabled BOOLEAN NOT NULL DEFAULT TRUE,
+        last_notified_at TIMESTAMP
+        WITH
+            TIME ZONE NOT NULL DEFAULT NOW(),
+            -- in every alert email, only good for turning the alerts off
+            unsubscribe_token VARCHAR(64) NOT NULL UNIQUE,
+            created_at TIMESTAMP
+        WITH
+            TIME ZONE DEFAULT NOW(),
+            updated_at TIMESTAMP
+        WITH
+            TIME ZONE DEFAULT NOW()
+    );
+
+CREATE INDEX saved_searches_user_id_idx ON saved_searches (user_id);
+CREATE INDEX saved_searches_due_idx ON saved_searches (last_notified_at) WHERE alerts_enabled;
+CREATE INDEX jobs_created_at_idx ON jobs (created_at);
+
+CREATE TRIGGER saved_searches_set_updated_at BEFORE UPDATE ON saved_searches
+    FOR EACH ROW EXECUTE FUNCTION set_updated_at();
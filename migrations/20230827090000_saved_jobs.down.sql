@@ -0,0 +1,5 @@
+-- Add down migration script here
+DROP TABLE IF EXISTS saved_jobs;
+DROP INDEX IF EXISTS jobs_expires_at_idx;
+
+ALTER TABLE "jobs" DROP COLUMN IF EXISTS expires_at;
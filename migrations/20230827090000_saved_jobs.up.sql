@@ -0,0 +1,21 @@
+-- Add up migration script here
+-- A job without expires_at stays open until it is removed
+ALTER TABLE "jobs" ADD COLUMN expires_at TIMESTAMP WITH TIME ZONE;
+
+CREATE INDEX jobs_expires_at_idx ON jobs (expires_at) WHERE expires_at IS NOT NULL;
+
+-- reminded_at is set once the reminder before the job expires went out
+CREATE TABLE
+    "saved_jobs" (
+        user_id UUID NOT NULL REFERENCES users (id) ON DELETE CASCADE,
+        job_id UUID NOT NULL REFERENCES jobs (id) ON DELETE CASCADE,
+        reminded_at TIMESTAMP
+        WITH
+            TIME ZONE,
+            created_at TIMESTAMP
+        WITH
+            TIME ZONE DEFAULT NOW(),
+            PRIMARY KEY (user_id, job_id)
+    );
+
+CREATE INDEX saved_jobs_job_id_idx ON saved_jobs (job_id);
@@ -0,0 +1,19 @@
+-- Add down migration script here
+DROP INDEX IF EXISTS jobs_earth_idx;
+DROP INDEX IF EXISTS jobs_location_id_idx;
+
+ALTER TABLE "jobs"
+    DROP COLUMN IF EXISTS remote_timezones,
+    DROP COLUMN IF EXISTS remote_countries,
+    DROP COLUMN IF EXISTS work_mode,
+    DROP COLUMN IF EXISTS longitude,
+    DROP COLUMN IF EXISTS latitude,
+    DROP COLUMN IF EXISTS region,
+    DROP COLUMN IF EXISTS country_code,
+    DROP COLUMN IF EXISTS location_id;
+
+DROP TABLE IF EXISTS locations;
+DROP TABLE IF EXISTS countries;
+
+DROP EXTENSION IF EXISTS earthdistance;
+DROP EXTENSION IF EXISTS cube;
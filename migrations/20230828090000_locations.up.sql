@@ -0,0 +1,46 @@
+-- Add up migration script here
+CREATE EXTENSION IF NOT EXISTS cube;
+CREATE EXTENSION IF NOT EXISTS earthdistance;
+
+-- Filled by the import-locations command from a geonames dump. The id of a
+-- location is its geonames id, so importing again updates in place.
+CREATE TABLE
+    "countries" (
+        code VARCHAR(2) NOT NULL PRIMARY KEY,
+        name VARCHAR(100) NOT NULL
+    );
+
+CREATE INDEX countries_name_idx ON countries (LOWER(name));
+
+CREATE TABLE
+    "locations" (
+        id BIGINT NOT NULL PRIMARY KEY,
+        name VARCHAR(200) NOT NULL,
+        ascii_name VARCHAR(200) NOT NULL,
+        country_code VARCHAR(2) NOT NULL REFERENCES countries (code) ON DELETE CASCADE,
+        region VARCHAR(100),
+        latitude DOUBLE PRECISION NOT NULL,
+        longitude DOUBLE PRECISION NOT NULL,
+        population BIGINT NOT NULL DEFAULT 0,
+        timezone VARCHAR(40) NOT NULL DEFAULT ''
+    );
+
+CREATE INDEX locations_name_idx ON locations (country_code, LOWER(name));
+CREATE INDEX locations_ascii_name_idx ON locations (country_code, LOWER(ascii_name));
+
+-- city and country stay as typed for display, the columns below are what
+-- they resolved to. Jobs nobody could place have no coordinates.
+ALTER TABLE "jobs"
+    ADD COLUMN location_id BIGINT REFERENCES locations (id) ON DELETE SET NULL,
+    ADD COLUMN country_code VARCHAR(2),
+    ADD COLUMN region VARCHAR(100),
+    ADD COLUMN latitude DOUBLE PRECISION,
+    ADD COLUMN longitude DOUBLE PRECISION,
+    ADD COLUMN work_mode VARCHAR(10) NOT NULL DEFAULT 'onsite' CHECK (work_mode IN ('onsite', 'hybrid', 'remote')),
+    -- where remote workers may live, empty means anywhere
+    ADD COLUMN remote_countries VARCHAR(2)[] NOT NULL DEFAULT '{}',
+    ADD COLUMN remote_timezones VARCHAR(40)[] NOT NULL DEFAULT '{}';
+
+CREATE INDEX jobs_location_id_idx ON jobs (location_id);
+CREATE INDEX jobs_earth_idx ON jobs USING gist (ll_to_earth(latitude, longitude))
+    WHERE latitude IS NOT NULL AND longitude IS NOT NULL;
@@ -0,0 +1,7 @@
+-- Add down migration script here
+DROP INDEX IF EXISTS jobs_category_id_idx;
+ALTER TABLE jobs
+    DROP COLUMN IF EXISTS category_id,
+    DROP COLUMN IF EXISTS seniority,
+    DROP COLUMN IF EXISTS employment_type;
+DROP TABLE IF EXISTS job_categories;
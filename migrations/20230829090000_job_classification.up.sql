@@ -0,0 +1,52 @@
+-- Add up migration script here
+-- A tree of job categories. Searching for a category also finds the jobs in
+-- the categories below it.
+CREATE TABLE
+    "job_categories" (
+        id UUID NOT NULL PRIMARY KEY DEFAULT (uuid_generate_v4()),
+        parent_id UUID REFERENCES job_categories (id) ON DELETE RESTRICT,
+        slug VARCHAR(100) NOT NULL UNIQUE,
+        name VARCHAR(100) NOT NULL,
+        created_at TIMESTAMP
+        WITH
+            TIME ZONE DEFAULT NOW()
+    );
+
+CREATE INDEX job_categories_parent_id_idx ON job_categories (parent_id);
+
+ALTER TABLE "jobs"
+    ADD COLUMN employment_type VARCHAR(20) NOT NULL DEFAULT 'full-time'
+        CHECK (employment_type IN ('full-time', 'part-time', 'contract', 'internship', 'temporary')),
+    ADD COLUMN seniority VARCHAR(10) CHECK (seniority IN ('junior', 'mid', 'senior', 'lead')),
+    ADD COLUMN category_id UUID REFERENCES job_categories (id) ON DELETE SET NULL;
+
+CREATE INDEX jobs_category_id_idx ON jobs (category_id);
+
+-- the same guess the matching made from titles so far
+UPDATE jobs SET seniority = CASE
+    WHEN title ~* '\m(lead|head|manager|director|principal|staff|architect)\M' THEN 'lead'
+    WHEN title ~* '\m(senior|sr)\M' THEN 'senior'
+    WHEN title ~* '\m(junior|jr|intern|internship|trainee|graduate|entry)\M' THEN 'junior'
+    ELSE 'mid'
+END;
+
+-- a starting taxonomy, admins add to it through POST /api/categories
+INSERT INTO job_categories (slug, name) VALUES
+    ('engineering', 'Engineering'), ('data', 'Data'), ('design', 'Design'),
+    ('product', 'Product'), ('sales', 'Sales'), ('marketing', 'Marketing'),
+    ('customer-support', 'Customer Support'), ('operations', 'Operations'),
+    ('finance', 'Finance'), ('people', 'People');
+
+INSERT INTO job_categories (parent_id, slug, name)
+SELECT parents.id, children.slug, children.name
+FROM (
+        VALUES ('engineering', 'backend', 'Backend'), ('engineering', 'frontend', 'Frontend'),
+            ('engineering', 'mobile', 'Mobile'), ('engineering', 'devops', 'DevOps'),
+            ('engineering', 'qa', 'Quality Assurance'),
+            ('engineering', 'engineering-management', 'Engineering Management'),
+            ('data', 'data-analysis', 'Data Analysis'), ('data', 'data-engineering', 'Data Engineering'),
+            ('data', 'machine-learning', 'Machine Learning'),
+            ('design', 'product-design', 'Product Design'), ('design', 'ux-research', 'UX Research'),
+            ('product', 'product-management', 'Product Management')
+    ) AS children (parent, slug, name)
+    INNER JOIN job_categories parents ON parents.slug = children.parent;
@@ -0,0 +1,4 @@
+-- Add down migration script here
+ALTER TABLE jobs
+    DROP COLUMN IF EXISTS salary_max,
+    DROP COLUMN IF EXISTS salary_min;
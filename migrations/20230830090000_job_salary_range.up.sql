@@ -0,0 +1,17 @@
+-- Add up migration script here
+-- The yearly range read from the free text salary when a job is posted, so
+-- searches can bucket jobs by pay without parsing text per row.
+ALTER TABLE "jobs"
+    ADD COLUMN salary_min DOUBLE PRECISION,
+    ADD COLUMN salary_max DOUBLE PRECISION;
+
+-- Existing jobs with a plain "45000" or "45000-55000 EUR" salary. Other
+-- formats need the parser in the server and stay without a range.
+UPDATE jobs
+SET salary_min = amounts[1]::DOUBLE PRECISION,
+    salary_max = COALESCE(amounts[2], amounts[1])::DOUBLE PRECISION
+FROM (
+        SELECT id, regexp_match(salary, '^\s*(\d+)(?:\s*-\s*(\d+))?(?:\s+[A-Za-z]{3})?\s*$') AS amounts
+        FROM jobs
+    ) parsed
+WHERE parsed.id = jobs.id AND parsed.amounts IS NOT NULL;
@@ -0,0 +1,16 @@
+-- Add up migration script here
+-- What visitors did on a job page, at most once per visitor, kind and day.
+-- visitor is a hash of the user id or of the anonymous visitor cookie.
+CREATE TABLE
+    "job_events" (
+        job_id UUID NOT NULL REFERENCES jobs (id) ON DELETE CASCADE,
+        kind VARCHAR(20) NOT NULL CHECK (kind IN ('view', 'apply_click')),
+        visitor VARCHAR(64) NOT NULL,
+        day DATE NOT NULL DEFAULT ((NOW() AT TIME ZONE 'UTC')::DATE),
+        created_at TIMESTAMP
+        WITH
+            TIME ZONE DEFAULT NOW(),
+            PRIMARY KEY (job_id, day, kind, visitor)
+    );
+
+CREATE INDEX applications_job_id_created_at_idx ON applications (job_id, created_at);
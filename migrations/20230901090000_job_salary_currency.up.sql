@@ -0,0 +1,14 @@
+-- Add up migration script here
+-- The currency read from the free text salary next to its range. Salary
+-- facets are bucketed per currency, a job without one cannot be compared.
+ALTER TABLE "jobs"
+    ADD COLUMN salary_currency VARCHAR(3);
+
+-- Existing jobs with a "45000-55000 EUR" salary, as in the range migration
+UPDATE jobs
+SET salary_currency = UPPER(parsed.currency[1])
+FROM (
+        SELECT id, regexp_match(salary, '^\s*\d+(?:\s*-\s*\d+)?\s+([A-Za-z]{3})\s*$') AS currency
+        FROM jobs
+    ) parsed
+WHERE parsed.id = jobs.id AND parsed.currency IS NOT NULL AND jobs.salary_max IS NOT NULL;
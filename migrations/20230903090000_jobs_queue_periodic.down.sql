@@ -0,0 +1,2 @@
+-- Add down migration script here
+DROP INDEX IF EXISTS jobs_queue_periodic_kind_idx;
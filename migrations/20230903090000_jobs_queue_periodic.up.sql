@@ -0,0 +1,14 @@
+-- Add up migration script here
+-- Every instance schedules the periodic tasks, at most one of each kind may
+-- wait or run at a time. Duplicates queued before this index are dropped.
+DELETE FROM jobs_queue duplicate
+USING jobs_queue kept
+WHERE duplicate.kind = kept.kind
+  AND duplicate.kind IN ('purge_stale_data', 'send_job_alerts', 'send_saved_job_reminders')
+  AND duplicate.status = 'pending'
+  AND kept.status IN ('pending', 'running')
+  AND (kept.status = 'running' OR (kept.created_at, kept.id) < (duplicate.created_at, duplicate.id));
+
+CREATE UNIQUE INDEX jobs_queue_periodic_kind_idx ON jobs_queue (kind)
+WHERE status IN ('pending', 'running')
+  AND kind IN ('purge_stale_data', 'send_job_alerts', 'send_saved_job_reminders');
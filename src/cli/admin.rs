@@ -0,0 +1,52 @@
+use crate::{
+    model::user_model::User,
+    repository::user_repository::PgUserRepository,
+    schema::user_schema::RegisterUserSchema,
+    service::user_service::{UserService, ROLE_ADMIN},
+};
+
+use sqlx::{Pool, Postgres};
+use std::{
+    error::Error,
+    io::{BufRead, IsTerminal, Write},
+    sync::Arc,
+};
+
+const MIN_PASSWORD_LENGTH: usize = 12;
+
+// Goes through the same registration as the API, minus the welcome email,
+// so the account is indistinguishable from one made by another admin.
+pub async fn create_admin(
+    db: &Pool<Postgres>,
+    email: &str,
+    first_name: &str,
+    last_name: &str,
+    password: &str,
+) -> Result<User, Box<dyn Error>>{
+    if password.chars().count() < MIN_PASSWORD_LENGTH{
+        return Err(format!("admin passwords need at least {} characters", MIN_PASSWORD_LENGTH).into());
+    }
+
+    let users = UserService::new(Arc::new(PgUserRepository::new(db.clone())));
+    let body = RegisterUserSchema{
+        first_name: first_name.to_string(),
+        last_name: last_name.to_string(),
+        email: email.to_string(),
+        password: password.to_string(),
+    };
+    Ok(users.register(&body, ROLE_ADMIN).await?)
+}
+
+// One line from stdin so scripts can pipe the password in instead of putting
+// it on the command line
+pub fn read_password() -> Result<String, Box<dyn Error>>{
+    let stdin = std::io::stdin();
+    if stdin.is_terminal(){
+        eprint!("Password: ");
+        std::io::stderr().flush()?;
+    }
+
+    let mut password = String::new();
+    stdin.lock().read_line(&mut password)?;
+    Ok(password.trim_end_matches(['\r', '\n']).to_string())
+}
@@ -0,0 +1,129 @@
+use crate::{
+    model::location_model::{Country, Location},
+    repository::location_repository::{LocationRepository, PgLocationRepository},
+};
+
+use sqlx::{Pool, Postgres};
+use std::{
+    collections::HashMap,
+    error::Error,
+    fs::File,
+    io::{BufRead, BufReader},
+    path::Path,
+};
+
+// rows per INSERT, well under the bind limit since every column is one array
+const BATCH_SIZE: usize = 1000;
+
+pub struct ImportSummary{
+    pub countries: u64,
+    pub locations: u64,
+    // existing jobs that could be geocoded with the new data
+    pub jobs_placed: u64,
+}
+
+// Loads a geonames dump: countryInfo.txt, one of the cities*.txt files (or
+// allCountries.txt) and optionally admin1CodesASCII.txt for region names.
+// Importing again updates what changed, nothing is removed.
+pub async fn import(
+    db: &Pool<Postgres>,
+    countries_path: &Path,
+    cities_path: &Path,
+    regions_path: Option<&Path>,
+) -> Result<ImportSummary, Box<dyn Error>>{
+    let repository = PgLocationRepository::new(db.clone());
+
+    let mut countries = Vec::new();
+    for line in read_lines(countries_path)?{
+        if let Some(country) = parse_country(&line?){
+            countries.push(country);
+        }
+    }
+    let imported_countries = repository.import_countries(&countries).await?;
+
+    let mut regions = HashMap::new();
+    if let Some(path) = regions_path{
+        for line in read_lines(path)?{
+            if let Some((code, name)) = parse_region(&line?){
+                regions.insert(code, name);
+            }
+        }
+    }
+
+    let mut imported_locations = 0;
+    let mut batch = Vec::with_capacity(BATCH_SIZE);
+    for line in read_lines(cities_path)?{
+        if let Some(location) = parse_location(&line?, &regions){
+            batch.push(location);
+        }
+        if batch.len() == BATCH_SIZE{
+            imported_locations += repository.import_locations(&batch).await?;
+            batch.clear();
+        }
+    }
+    if !batch.is_empty(){
+        imported_locations += repository.import_locations(&batch).await?;
+    }
+
+    Ok(ImportSummary{
+        countries: imported_countries,
+        locations: imported_locations,
+        jobs_placed: repository.place_jobs().await?,
+    })
+}
+
+fn read_lines(path: &Path) -> Result<std::io::Lines<BufReader<File>>, Box<dyn Error>>{
+    let file = File::open(path).map_err(|e| format!("cannot open {}: {}", path.display(), e))?;
+    Ok(BufReader::new(file).lines())
+}
+
+// countryInfo.txt: ISO, ISO3, ISO-Numeric, fips, Country, ... with # comments
+pub fn parse_country(line: &str) -> Option<Country>{
+    if line.starts_with('#'){
+        return None;
+    }
+    let fields: Vec<&str> = line.split('\t').collect();
+    let code = fields.first()?.trim();
+    let name = fields.get(4)?.trim();
+    if code.len() != 2 || name.is_empty(){
+        return None;
+    }
+    Some(Country{ code: code.to_uppercase(), name: name.to_string() })
+}
+
+// admin1CodesASCII.txt: "ES.29", name, ascii name, geonames id
+pub fn parse_region(line: &str) -> Option<(String, String)>{
+    let mut fields = line.split('\t');
+    let code = fields.next()?.trim();
+    let name = fields.next()?.trim();
+    if code.is_empty() || name.is_empty(){
+        return None;
+    }
+    Some((code.to_string(), name.to_string()))
+}
+
+// The geoname table format shared by cities*.txt and allCountries.txt. Only
+// populated places (feature class P) are kept; regions are looked up by
+// country and admin1 code.
+pub fn parse_location(line: &str, regions: &HashMap<String, String>) -> Option<Location>{
+    let fields: Vec<&str> = line.split('\t').collect();
+    if fields.len() < 18 || fields[6] != "P"{
+        return None;
+    }
+    let country_code = fields[8].trim().to_uppercase();
+    if country_code.len() != 2{
+        return None;
+    }
+
+    Some(Location{
+        id: fields[0].parse().ok()?,
+        name: fields[1].trim().to_string(),
+        ascii_name: fields[2].trim().to_string(),
+        region: regions.get(&format!("{}.{}", country_code, fields[10].trim())).cloned(),
+        latitude: fields[4].parse().ok()?,
+        longitude: fields[5].parse().ok()?,
+        population: fields[14].parse().unwrap_or(0),
+        timezone: fields[17].trim().to_string(),
+        country_code,
+    })
+}
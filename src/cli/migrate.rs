@@ -0,0 +1,92 @@
+use crate::MIGRATOR;
+
+use sqlx::{
+    migrate::{AppliedMigration, Migrate, MigrateError},
+    Pool, Postgres,
+};
+
+pub struct MigrationStatus{
+    pub version: i64,
+    pub description: String,
+    // applied, pending, failed (stopped halfway, fix by hand), modified (the
+    // file changed after it was applied) or missing (not in this binary)
+    pub state: &'static str,
+}
+
+async fn applied(db: &Pool<Postgres>) -> Result<(Vec<AppliedMigration>, Option<i64>), MigrateError>{
+    let mut conn = db.acquire().await?;
+    conn.ensure_migrations_table().await?;
+    let dirty = conn.dirty_version().await?;
+    let mut applied = conn.list_applied_migrations().await?;
+    applied.sort_by_key(|migration| migration.version);
+    Ok((applied, dirty))
+}
+
+// Returns the versions that were applied. Instances migrating at the same
+// time wait on the advisory lock sqlx takes, so only one does the work.
+pub async fn up(db: &Pool<Postgres>) -> Result<Vec<i64>, MigrateError>{
+    let (before, _) = applied(db).await?;
+    MIGRATOR.run(db).await?;
+
+    Ok(MIGRATOR
+        .iter()
+        .filter(|migration| !migration.migration_type.is_down_migration())
+        .map(|migration| migration.version)
+        .filter(|version| !before.iter().any(|applied| applied.version == *version))
+        .collect())
+}
+
+// Reverts every applied migration newer than target, newest first. Without a
+// target only the latest one is reverted; 0 reverts everything.
+pub async fn down(db: &Pool<Postgres>, target: Option<i64>) -> Result<Vec<i64>, MigrateError>{
+    let (applied, _) = applied(db).await?;
+    let target = match target{
+        Some(target) => target,
+        None if applied.len() >= 2 => applied[applied.len() - 2].version,
+        None => 0,
+    };
+
+    MIGRATOR.undo(db, target).await?;
+
+    Ok(applied
+        .iter()
+        .rev()
+        .map(|migration| migration.version)
+        .filter(|version| *version > target)
+        .collect())
+}
+
+pub async fn status(db: &Pool<Postgres>) -> Result<Vec<MigrationStatus>, MigrateError>{
+    let (applied, dirty) = applied(db).await?;
+
+    let mut statuses: Vec<MigrationStatus> = MIGRATOR
+        .iter()
+        .filter(|migration| !migration.migration_type.is_down_migration())
+        .map(|migration|{
+            let state = match applied.iter().find(|applied| applied.version == migration.version){
+                _ if dirty == Some(migration.version) => "failed",
+                Some(applied) if applied.checksum != migration.checksum => "modified",
+                Some(_) => "applied",
+                None => "pending",
+            };
+            MigrationStatus{
+                version: migration.version,
+                description: migration.description.to_string(),
+                state,
+            }
+        })
+        .collect();
+
+    for migration in &applied{
+        if !statuses.iter().any(|status| status.version == migration.version){
+            statuses.push(MigrationStatus{
+                version: migration.version,
+                description: String::new(),
+                state: "missing",
+            });
+        }
+    }
+    statuses.sort_by_key(|status| status.version);
+
+    Ok(statuses)
+}
@@ -0,0 +1,122 @@
+pub mod migrate;
+pub mod admin;
+pub mod seed;
+pub mod locations;
+
+use clap::{Parser, Subcommand};
+use sqlx::{Pool, Postgres};
+use std::{error::Error, path::PathBuf};
+
+// One binary for the server and the chores around it, so ops and CI deploy
+// and run exactly the same artifact. Without a subcommand it serves.
+#[derive(Debug, Parser)]
+#[command(name = "trabajo-server", version, about = "Trabajo job board API")]
+pub struct Cli{
+    #[command(subcommand)]
+    pub command: Option<Command>,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum Command{
+    /// Run the HTTP server and the queue workers
+    Serve,
+    /// Apply, revert or list the embedded migrations
+    #[command(subcommand)]
+    Migrate(MigrateCommand),
+    /// Create an administrator account
+    CreateAdmin{
+        #[arg(long)]
+        email: String,
+        #[arg(long, default_value = "Admin")]
+        first_name: String,
+        #[arg(long, default_value = "")]
+        last_name: String,
+        /// Read from stdin when not given
+        #[arg(long, env = "ADMIN_PASSWORD", hide_env_values = true)]
+        password: Option<String>,
+    },
+    /// Fill an empty database with sample jobs and a demo candidate
+    Seed{
+        /// Password of the demo candidate, candidate@trabajo.local
+        #[arg(long, default_value = "password123")]
+        password: String,
+    },
+    /// Load countries and cities from a geonames dump and geocode the jobs
+    ImportLocations{
+        /// countryInfo.txt
+        #[arg(long)]
+        countries: PathBuf,
+        /// cities500.txt, cities15000.txt or allCountries.txt
+        #[arg(long)]
+        cities: PathBuf,
+        /// admin1CodesASCII.txt, for region names
+        #[arg(long)]
+        regions: Option<PathBuf>,
+    },
+}
+
+#[derive(Debug, Subcommand)]
+pub enum MigrateCommand{
+    /// Apply every pending migration
+    Up,
+    /// Revert the latest migration, or everything after --target
+    Down{
+        #[arg(long)]
+        target: Option<i64>,
+    },
+    /// List the migrations and whether they are applied
+    Status,
+}
+
+// Everything but serve, which needs the whole application around it
+pub async fn run(command: Command, db: &Pool<Postgres>) -> Result<(), Box<dyn Error>>{
+    match command{
+        Command::Serve => unreachable!("serve is started by main"),
+        Command::Migrate(MigrateCommand::Up) => {
+            let applied = migrate::up(db).await?;
+            if applied.is_empty(){
+                println!("database is up to date");
+            }
+            for version in applied{
+                println!("applied {}", version);
+            }
+        }
+        Command::Migrate(MigrateCommand::Down{ target }) => {
+            let reverted = migrate::down(db, target).await?;
+            if reverted.is_empty(){
+                println!("nothing to revert");
+            }
+            for version in reverted{
+                println!("reverted {}", version);
+            }
+        }
+        Command::Migrate(MigrateCommand::Status) => {
+            for migration in migrate::status(db).await?{
+                println!("{:<16}{:<12}{}", migration.version, migration.state, migration.description);
+            }
+        }
+        Command::CreateAdmin{ email, first_name, last_name, password } => {
+            let password = match password{
+                Some(password) => password,
+                None => admin::read_password()?,
+            };
+            let admin = admin::create_admin(db, &email, &first_name, &last_name, &password).await?;
+            println!("created admin {} ({})", admin.email, admin.id);
+        }
+        Command::Seed{ password } => {
+            if seed::seed(db, &password).await?{
+                println!("seeded sample jobs and candidate@trabajo.local");
+            } else{
+                println!("database already has jobs, nothing seeded");
+            }
+        }
+        Command::ImportLocations{ countries, cities, regions } => {
+            let summary = locations::import(db, &countries, &cities, regions.as_deref()).await?;
+            println!(
+                "imported {} countries and {} locations, geocoded {} jobs",
+                summary.countries, summary.locations, summary.jobs_placed
+            );
+        }
+    }
+    Ok(())
+}
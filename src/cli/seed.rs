@@ -0,0 +1,106 @@
+use crate::{
+    core::helpers::telemetry::db_span,
+    repository::{
+        application_repository::{ApplicationRepository, PgApplicationRepository},
+        category_repository::{CategoryRepository, PgCategoryRepository},
+        job_repository::{JobRepository, PgJobRepository},
+        location_repository::{LocationRepository, PgLocationRepository},
+        user_repository::{NewUser, PgUserRepository, UserRepository},
+    },
+    schema::{
+        job_schema::{CreateJobPosting, NormalizedJob, Seniority, EMPLOYMENT_FULL_TIME},
+        location_schema::{WORK_MODE_ONSITE, WORK_MODE_REMOTE},
+    },
+    service::{
+        scoring::parse_salary,
+        user_service::{hash_password, ROLE_USER},
+    },
+};
+
+use sqlx::{Pool, Postgres};
+use tracing::Instrument;
+
+pub const DEMO_EMAIL: &str = "candidate@trabajo.local";
+
+// title, company, city, country, salary, description, category
+const SAMPLE_JOBS: [(&str, &str, &str, &str, &str, &str, &str); 8] = [
+    ("Backend Engineer", "Acme", "Madrid", "Spain", "45000-55000 EUR", "Build and run the APIs behind our marketplace in Rust and Postgres.", "backend"),
+    ("Frontend Developer", "Acme", "Barcelona", "Spain", "40000-50000 EUR", "Own the React frontend of our customer dashboard.", "frontend"),
+    ("Site Reliability Engineer", "Globex", "Berlin", "Germany", "70000-85000 EUR", "Keep our Kubernetes clusters healthy and our pagers quiet.", "devops"),
+    ("Data Analyst", "Globex", "Lisbon", "Portugal", "35000-42000 EUR", "Turn product events into reports the whole company reads.", "data-analysis"),
+    ("Mobile Developer", "Initech", "Mexico City", "Mexico", "60000-75000 USD", "Ship our iOS and Android apps from a shared Kotlin codebase.", "mobile"),
+    ("Product Designer", "Initech", "Buenos Aires", "Argentina", "50000-60000 USD", "Design flows for millions of small businesses.", "product-design"),
+    ("Engineering Manager", "Umbrella", "London", "United Kingdom", "90000-110000 GBP", "Lead a team of six engineers working on payments.", "engineering-management"),
+    ("Junior QA Engineer", "Umbrella", "Remote", "Spain", "28000-32000 EUR", "Write and maintain our end to end test suites.", "qa"),
+];
+
+// Sample data for local development and demos, not for production. Only
+// seeds a database without jobs so running it twice changes nothing.
+// Returns whether anything was inserted.
+pub async fn seed(db: &Pool<Postgres>, password: &str) -> Result<bool, sqlx::Error>{
+    let has_jobs = sqlx::query_scalar!("SELECT EXISTS(SELECT 1 FROM jobs)")
+        .fetch_one(db)
+        .instrument(db_span("SELECT", "jobs"))
+        .await?;
+    if has_jobs.unwrap_or(false){
+        return Ok(false);
+    }
+
+    let jobs = PgJobRepository::new(db.clone());
+    let locations = PgLocationRepository::new(db.clone());
+    let categories = PgCategoryRepository::new(db.clone());
+    let mut created = Vec::new();
+    for (title, company_name, city, country, salary, description, category) in SAMPLE_JOBS{
+        let work_mode = if city == "Remote"{ WORK_MODE_REMOTE } else{ WORK_MODE_ONSITE };
+        let job = CreateJobPosting{
+            title: title.to_string(),
+            company_name: company_name.to_string(),
+            city: city.to_string(),
+            country: country.to_string(),
+            salary: salary.to_string(),
+            description: description.to_string(),
+            required_skills: Vec::new(),
+            nice_to_have_skills: Vec::new(),
+            expires_at: None,
+            work_mode: work_mode.to_string(),
+            remote_countries: Vec::new(),
+            remote_timezones: Vec::new(),
+            employment_type: EMPLOYMENT_FULL_TIME.to_string(),
+            seniority: None,
+            category: Some(category.to_string()),
+        };
+        let salary = parse_salary(salary);
+        let normalized = NormalizedJob{
+            // placed on the map when a geonames dump was imported first
+            location: locations.geocode(city, country).await?.unwrap_or_default(),
+            seniority: Seniority::from_title(title).name().to_string(),
+            category_id: categories.find_by_slug(category).await?.map(|category| category.id),
+            salary_min: salary.as_ref().map(|salary| salary.min),
+            salary_max: salary.as_ref().map(|salary| salary.max),
+            salary_currency: salary.and_then(|salary| salary.currency),
+        };
+        created.push(jobs.create(&job, &[], &normalized).await?);
+    }
+
+    let users = PgUserRepository::new(db.clone());
+    let candidate = match users.find_by_email(DEMO_EMAIL).await?{
+        Some(candidate) => candidate,
+        None => {
+            let candidate = NewUser{
+                first_name: "Demo".to_string(),
+                last_name: "Candidate".to_string(),
+                email: DEMO_EMAIL.to_string(),
+                password_hash: hash_password(password),
+                role: ROLE_USER.to_string(),
+            };
+            users.create(&candidate, None).await?
+        }
+    };
+
+    let applications = PgApplicationRepository::new(db.clone());
+    for job in created.iter().take(2){
+        applications.create(&candidate.id, &job.id).await?;
+    }
+
+    Ok(true)
+}
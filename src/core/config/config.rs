@@ -1,22 +1,385 @@
+use core::fmt;
+use std::path::Path;
+
+use chrono::Duration;
+
+const DEFAULT_CONFIG_FILE: &str = "config.toml";
+const ALLOWED_METHODS: [&str; 7] = ["GET", "POST", "PUT", "PATCH", "DELETE", "HEAD", "OPTIONS"];
+
 #[derive(Debug, Clone)]
 pub struct Config{
+    pub server_host: String,
+    pub server_port: u16,
+    pub server_workers: Option<usize>,
+    pub server_shutdown_timeout: Duration,
     pub database_url: String,
+    pub database_max_connections: u32,
+    pub database_acquire_timeout: Duration,
+    pub database_migrate_on_start: bool,
+    pub storage_dir: String,
+    pub log_format: String,
+    pub log_level: String,
+    pub otel_exporter_otlp_endpoint: Option<String>,
+    pub otel_service_name: String,
+    pub otel_sampling_ratio: f64,
+    pub cors_allowed_origins: Vec<String>,
+    pub cors_allowed_methods: Vec<String>,
+    pub cors_max_age: usize,
     pub jwt_secret: String,
-    pub jwt_expires_in: String,
-    pub jwt_maxage: i32,
+    pub jwt_expires_in: Duration,
+    pub jwt_maxage: Duration,
+    pub jwt_remember_me_expires_in: Duration,
+    pub jwt_algorithm: String,
+    pub jwt_keys_dir: Option<String>,
+    pub jwt_active_kid: Option<String>,
+    pub jwt_issuer: String,
+    pub jwt_audience: String,
+    pub cookie_secure: bool,
+    pub cookie_same_site: String,
+    pub cookie_domain: Option<String>,
+    pub queue_workers: usize,
+    pub queue_poll_interval: Duration,
+    pub queue_lock_timeout: Duration,
+    pub smtp_host: Option<String>,
+    pub smtp_port: u16,
+    pub smtp_starttls: bool,
+    pub smtp_username: Option<String>,
+    pub smtp_password: Option<String>,
+    pub smtp_from: String,
+    pub privacy_deletion_grace_period: Duration,
+    pub privacy_application_retention: Duration,
+    pub privacy_retention_interval: Duration,
+    pub alerts_interval: Duration,
+    pub alerts_saved_job_reminder: Duration,
+    pub search_facets_ttl: Duration,
+    pub frontend_url: String,
+}
+
+#[derive(Debug)]
+pub struct ConfigError{
+    pub errors: Vec<String>,
+}
+
+impl fmt::Display for ConfigError{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result{
+        writeln!(f, "invalid configuration:")?;
+        for error in &self.errors{
+            writeln!(f, "  - {}", error)?;
+        }
+        Ok(())
+    }
+}
+
+// Settings are looked up by dotted key. An environment variable named after
+// the key (`server.port` -> SERVER_PORT) wins over the TOML file, which wins
+// over the default. The file is CONFIG_FILE if set, otherwise ./config.toml
+// when it exists.
+struct Layers{
+    file: Option<toml::Value>,
+}
+
+impl Layers{
+    fn load() -> Result<Layers, ConfigError>{
+        let path = match std::env::var("CONFIG_FILE"){
+            Ok(path) => path,
+            Err(_) if Path::new(DEFAULT_CONFIG_FILE).exists() => DEFAULT_CONFIG_FILE.to_string(),
+            Err(_) => return Ok(Layers{ file: None }),
+        };
+
+        let contents = std::fs::read_to_string(&path).map_err(|e| ConfigError{
+            errors: vec![format!("cannot read config file {}: {}", path, e)],
+        })?;
+        let file = contents.parse::<toml::Value>().map_err(|e| ConfigError{
+            errors: vec![format!("cannot parse config file {}: {}", path, e)],
+        })?;
+
+        Ok(Layers{ file: Some(file) })
+    }
+
+    fn get(&self, key: &str) -> Option<String>{
+        let env_name = key.replace('.', "_").to_uppercase();
+        if let Ok(value) = std::env::var(env_name){
+            return Some(value);
+        }
+
+        let mut value = self.file.as_ref()?;
+        for part in key.split('.'){
+            value = value.get(part)?;
+        }
+
+        match value{
+            toml::Value::String(s) => Some(s.to_owned()),
+            toml::Value::Array(items) => Some(
+                items
+                    .iter()
+                    .map(|item| item.as_str().map(|s| s.to_string()).unwrap_or_else(|| item.to_string()))
+                    .collect::<Vec<String>>()
+                    .join(","),
+            ),
+            other => Some(other.to_string()),
+        }
+    }
+}
+
+// Collects every problem instead of stopping at the first one, so a broken
+// deployment reports all of its mistakes in one go.
+struct Reader{
+    layers: Layers,
+    errors: Vec<String>,
+}
+
+impl Reader{
+    fn required(&mut self, key: &str) -> String{
+        match self.layers.get(key).filter(|v| !v.trim().is_empty()){
+            Some(value) => value,
+            None => {
+                self.errors.push(format!("{} is required (set {})", key, key.replace('.', "_").to_uppercase()));
+                String::new()
+            }
+        }
+    }
+
+    fn optional(&mut self, key: &str) -> Option<String>{
+        self.layers.get(key).filter(|v| !v.trim().is_empty())
+    }
+
+    fn string(&mut self, key: &str, default: &str) -> String{
+        self.optional(key).unwrap_or_else(|| default.to_string())
+    }
+
+    fn parse<T: std::str::FromStr>(&mut self, key: &str, default: T, expected: &str) -> T{
+        match self.optional(key){
+            None => default,
+            Some(value) => match value.trim().parse::<T>(){
+                Ok(parsed) => parsed,
+                Err(_) => {
+                    self.errors.push(format!("{} must be {}, got {:?}", key, expected, value));
+                    default
+                }
+            },
+        }
+    }
+
+    fn parse_optional<T: std::str::FromStr>(&mut self, key: &str, expected: &str) -> Option<T>{
+        let value = self.optional(key)?;
+        match value.trim().parse::<T>(){
+            Ok(parsed) => Some(parsed),
+            Err(_) => {
+                self.errors.push(format!("{} must be {}, got {:?}", key, expected, value));
+                None
+            }
+        }
+    }
+
+    fn duration(&mut self, key: &str, default: &str) -> Duration{
+        let value = self.string(key, default);
+        match parse_duration(&value){
+            Some(duration) if duration > Duration::zero() => duration,
+            _ => {
+                self.errors.push(format!("{} must be a duration such as 60m, 12h or 7d, got {:?}", key, value));
+                Duration::zero()
+            }
+        }
+    }
+
+    fn list(&mut self, key: &str, default: &str) -> Vec<String>{
+        self.string(key, default)
+            .split(',')
+            .map(|item| item.trim().to_string())
+            .filter(|item| !item.is_empty())
+            .collect()
+    }
 }
 
 impl Config{
-    pub fn init()-> Config{
-        let database_url = std::env::var("DATABASE_URL").expect("DATABASE_URL must be set");
-        let jwt_secret = std::env::var("JWT_SECRET").expect("JWT_SECRET must be set");
-        let jwt_expires_in = std::env::var("JWT_EXPIRES_IN").expect("EXPIRES_IN must be set");
-        let jwt_maxage = std::env::var("JWT_MAXAGE").expect("JWT_MAXAGE");
-        Config{
-            database_url,
+    pub fn init()-> Result<Config, ConfigError>{
+        Config::from_layers(Layers::load()?)
+    }
+
+    // Same as init but with the file contents given directly instead of read
+    // from CONFIG_FILE. Environment variables still take precedence.
+    pub fn from_toml(contents: &str) -> Result<Config, ConfigError>{
+        let file = contents.parse::<toml::Value>().map_err(|e| ConfigError{
+            errors: vec![format!("cannot parse config: {}", e)],
+        })?;
+        Config::from_layers(Layers{ file: Some(file) })
+    }
+
+    fn from_layers(layers: Layers) -> Result<Config, ConfigError>{
+        let mut reader = Reader{ layers, errors: Vec::new() };
+
+        let jwt_algorithm = reader.string("jwt.algorithm", "HS256");
+        let jwt_secret = if jwt_algorithm == "HS256"{
+            reader.required("jwt.secret")
+        } else{
+            reader.string("jwt.secret", "")
+        };
+
+        let config = Config{
+            server_host: reader.string("server.host", "127.0.0.1"),
+            server_port: reader.parse("server.port", 8000, "a port number"),
+            server_workers: reader.parse_optional("server.workers", "a positive number"),
+            server_shutdown_timeout: reader.duration("server.shutdown_timeout", "30s"),
+            database_url: reader.required("database.url"),
+            database_max_connections: reader.parse("database.max_connections", 10, "a positive number"),
+            database_acquire_timeout: reader.duration("database.acquire_timeout", "30s"),
+            database_migrate_on_start: reader.parse("database.migrate_on_start", false, "true or false"),
+            storage_dir: reader.string("storage.dir", "./static"),
+            log_format: reader.string("log.format", "json"),
+            log_level: reader.string("log.level", "info"),
+            otel_exporter_otlp_endpoint: reader.optional("otel.exporter_otlp_endpoint"),
+            otel_service_name: reader.string("otel.service_name", "trabajo-server"),
+            otel_sampling_ratio: reader.parse("otel.sampling_ratio", 1.0, "a number between 0 and 1"),
+            cors_allowed_origins: reader.list("cors.allowed_origins", "http://localhost:3000"),
+            cors_allowed_methods: reader.list("cors.allowed_methods", "GET,POST,PUT,PATCH,DELETE"),
+            cors_max_age: reader.parse("cors.max_age", 3600, "a number of seconds"),
             jwt_secret,
-            jwt_expires_in,
-            jwt_maxage: jwt_maxage.parse::<i32>().unwrap(),
+            jwt_expires_in: reader.duration("jwt.expires_in", "60m"),
+            jwt_maxage: reader.duration("jwt.maxage", "60m"),
+            jwt_remember_me_expires_in: reader.duration("jwt.remember_me_expires_in", "30d"),
+            jwt_algorithm,
+            jwt_keys_dir: reader.optional("jwt.keys_dir"),
+            jwt_active_kid: reader.optional("jwt.active_kid"),
+            jwt_issuer: reader.string("jwt.issuer", "trabajo-server"),
+            jwt_audience: reader.string("jwt.audience", "trabajo"),
+            cookie_secure: reader.parse("cookie.secure", false, "true or false"),
+            cookie_same_site: reader.string("cookie.same_site", "Lax"),
+            cookie_domain: reader.optional("cookie.domain"),
+            queue_workers: reader.parse("queue.workers", 2, "a number"),
+            queue_poll_interval: reader.duration("queue.poll_interval", "2s"),
+            queue_lock_timeout: reader.duration("queue.lock_timeout", "15m"),
+            smtp_host: reader.optional("smtp.host"),
+            smtp_port: reader.parse("smtp.port", 587, "a port number"),
+            smtp_starttls: reader.parse("smtp.starttls", true, "true or false"),
+            smtp_username: reader.optional("smtp.username"),
+            smtp_password: reader.optional("smtp.password"),
+            smtp_from: reader.string("smtp.from", "Trabajo <no-reply@trabajo.local>"),
+            privacy_deletion_grace_period: reader.duration("privacy.deletion_grace_period", "30d"),
+            privacy_application_retention: reader.duration("privacy.application_retention", "730d"),
+            privacy_retention_interval: reader.duration("privacy.retention_interval", "24h"),
+            alerts_interval: reader.duration("alerts.interval", "15m"),
+            alerts_saved_job_reminder: reader.duration("alerts.saved_job_reminder", "3d"),
+            search_facets_ttl: reader.duration("search.facets_ttl", "30s"),
+            frontend_url: reader.string("frontend.url", "http://localhost:3000"),
+        };
+
+        let mut errors = reader.errors;
+        errors.extend(config.validate());
+
+        if errors.is_empty(){
+            Ok(config)
+        } else{
+            Err(ConfigError{ errors })
         }
     }
-}
\ No newline at end of file
+
+    fn validate(&self) -> Vec<String>{
+        let mut errors = Vec::new();
+
+        if self.server_port == 0{
+            errors.push("server.port must not be 0".to_string());
+        }
+        if self.server_workers == Some(0){
+            errors.push("server.workers must be at least 1".to_string());
+        }
+        if self.database_max_connections == 0{
+            errors.push("database.max_connections must be at least 1".to_string());
+        }
+        if self.smtp_from.parse::<lettre::message::Mailbox>().is_err(){
+            errors.push(format!("smtp.from must be an address such as \"Trabajo <no-reply@example.com>\", got {:?}", self.smtp_from));
+        }
+        if self.smtp_username.is_some() != self.smtp_password.is_some(){
+            errors.push("smtp.username and smtp.password must be set together".to_string());
+        }
+        if !self.frontend_url.starts_with("http://") && !self.frontend_url.starts_with("https://"){
+            errors.push(format!("frontend.url must start with http:// or https://, got {:?}", self.frontend_url));
+        }
+        if self.log_format != "json" && self.log_format != "pretty"{
+            errors.push(format!("log.format must be json or pretty, got {:?}", self.log_format));
+        }
+        if !(0.0..=1.0).contains(&self.otel_sampling_ratio){
+            errors.push(format!("otel.sampling_ratio must be between 0 and 1, got {}", self.otel_sampling_ratio));
+        }
+        if !["HS256", "RS256", "EdDSA"].contains(&self.jwt_algorithm.as_str()){
+            errors.push(format!("jwt.algorithm must be HS256, RS256 or EdDSA, got {:?}", self.jwt_algorithm));
+        }
+        if self.jwt_algorithm != "HS256" && (self.jwt_keys_dir.is_none() || self.jwt_active_kid.is_none()){
+            errors.push(format!("jwt.keys_dir and jwt.active_kid are required for {}", self.jwt_algorithm));
+        }
+
+        match self.cookie_same_site.to_ascii_lowercase().as_str(){
+            "lax" | "strict" => {}
+            "none" if !self.cookie_secure => {
+                errors.push("cookie.same_site = None requires cookie.secure = true".to_string());
+            }
+            "none" => {}
+            _ => errors.push(format!("cookie.same_site must be Lax, Strict or None, got {:?}", self.cookie_same_site)),
+        }
+
+        if self.cors_allowed_origins.is_empty(){
+            errors.push("cors.allowed_origins must list at least one origin".to_string());
+        }
+        for origin in &self.cors_allowed_origins{
+            if let Err(e) = validate_origin(origin){
+                errors.push(format!("cors.allowed_origins: {}", e));
+            }
+        }
+        for method in &self.cors_allowed_methods{
+            if !ALLOWED_METHODS.contains(&method.to_uppercase().as_str()){
+                errors.push(format!("cors.allowed_methods: unknown method {:?}", method));
+            }
+        }
+
+        errors
+    }
+
+    // Exact origins match as-is; `https://*.example.com` matches any
+    // subdomain of example.com over https but not example.com itself.
+    pub fn origin_allowed(&self, origin: &str) -> bool{
+        self.cors_allowed_origins.iter().any(|allowed|{
+            match allowed.split_once("://*."){
+                Some((scheme, domain)) => origin
+                    .strip_prefix(scheme)
+                    .and_then(|rest| rest.strip_prefix("://"))
+                    .and_then(|host| host.strip_suffix(domain))
+                    .is_some_and(|sub| sub.len() > 1 && sub.ends_with('.') && !sub.starts_with('.')),
+                None => allowed.eq_ignore_ascii_case(origin),
+            }
+        })
+    }
+}
+
+fn validate_origin(origin: &str) -> Result<(), String>{
+    if origin == "*"{
+        return Err("\"*\" cannot be used with credentialed requests, list the origins instead".to_string());
+    }
+
+    let (scheme, host) = origin
+        .split_once("://")
+        .ok_or_else(|| format!("{:?} must look like https://example.com", origin))?;
+    if scheme != "http" && scheme != "https"{
+        return Err(format!("{:?} must use http or https", origin));
+    }
+
+    let host = host.strip_prefix("*.").unwrap_or(host);
+    if host.is_empty() || host.contains('*') || host.contains('/'){
+        return Err(format!("{:?} must be a bare origin, optionally with a leading *. wildcard", origin));
+    }
+
+    Ok(())
+}
+
+// Accepts human readable durations ("90s", "60m", "12h", "7d", "1h 30m").
+// A bare number is read as minutes, which is what JWT_MAXAGE has always meant.
+pub fn parse_duration(value: &str) -> Option<Duration>{
+    let value = value.trim();
+    if let Ok(minutes) = value.parse::<i64>(){
+        // Duration::minutes panics outside its range, which a config value
+        // must not be able to reach
+        let seconds = u64::try_from(minutes).ok()?.checked_mul(60)?;
+        return Duration::from_std(std::time::Duration::from_secs(seconds)).ok();
+    }
+
+    let parsed = humantime::parse_duration(value).ok()?;
+    Duration::from_std(parsed).ok()
+}
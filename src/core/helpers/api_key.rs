@@ -0,0 +1,73 @@
+use rand_core::{OsRng, RngCore};
+use sha2::{Digest, Sha256};
+
+pub const KEY_PREFIX: &str = "trb_";
+
+pub const JOBS_READ: &str = "jobs:read";
+pub const JOBS_WRITE: &str = "jobs:write";
+pub const APPLICATIONS_READ: &str = "applications:read";
+pub const APPLICATIONS_WRITE: &str = "applications:write";
+pub const PROFILE_READ: &str = "profile:read";
+pub const PROFILE_WRITE: &str = "profile:write";
+
+pub const ALL_SCOPES: [&str; 6] = [
+    JOBS_READ,
+    JOBS_WRITE,
+    APPLICATIONS_READ,
+    APPLICATIONS_WRITE,
+    PROFILE_READ,
+    PROFILE_WRITE,
+];
+
+pub struct GeneratedKey{
+    pub prefix: String,
+    pub key: String,
+    pub hash: String,
+}
+
+// keys look like trb_<prefix>_<secret>; the prefix is stored in clear so the
+// key can be looked up, the whole key is only ever stored as a sha256 hash
+pub fn generate() -> GeneratedKey{
+    let mut prefix_bytes = [0u8; 4];
+    let mut secret_bytes = [0u8; 24];
+    OsRng.fill_bytes(&mut prefix_bytes);
+    OsRng.fill_bytes(&mut secret_bytes);
+
+    let prefix = hex::encode(prefix_bytes);
+    let key = format!("{}{}_{}", KEY_PREFIX, prefix, hex::encode(secret_bytes));
+    let hash = hash(&key);
+
+    GeneratedKey{ prefix, key, hash }
+}
+
+pub fn hash(key: &str) -> String{
+    hex::encode(Sha256::digest(key.as_bytes()))
+}
+
+pub fn is_api_key(token: &str) -> bool{
+    token.starts_with(KEY_PREFIX)
+}
+
+pub fn parse_prefix(key: &str) -> Option<&str>{
+    let (prefix, secret) = key.strip_prefix(KEY_PREFIX)?.split_once('_')?;
+    if prefix.is_empty() || secret.is_empty(){
+        return None;
+    }
+    Some(prefix)
+}
+
+pub fn verify(key: &str, expected_hash: &str) -> bool{
+    let actual = hash(key);
+    if actual.len() != expected_hash.len(){
+        return false;
+    }
+    actual
+        .bytes()
+        .zip(expected_hash.bytes())
+        .fold(0u8, |acc, (a, b)| acc | (a ^ b))
+        == 0
+}
+
+pub fn is_known_scope(scope: &str) -> bool{
+    ALL_SCOPES.contains(&scope)
+}
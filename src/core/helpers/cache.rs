@@ -0,0 +1,44 @@
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+// An in-process cache for results that may be a little out of date. Each
+// instance of the server has its own. Entries are kept for ttl, a zero ttl
+// caches nothing. When full, expired entries make room, and everything goes
+// if none has expired.
+#[derive(Clone)]
+pub struct TtlCache<V>{
+    ttl: Duration,
+    capacity: usize,
+    entries: Arc<Mutex<HashMap<String, (Instant, V)>>>,
+}
+
+impl<V: Clone> TtlCache<V>{
+    pub fn new(ttl: Duration, capacity: usize) -> TtlCache<V>{
+        TtlCache{ ttl, capacity, entries: Arc::new(Mutex::new(HashMap::new())) }
+    }
+
+    pub fn get(&self, key: &str) -> Option<V>{
+        let entries = self.entries.lock().unwrap_or_else(|e| e.into_inner());
+        match entries.get(key){
+            Some((stored_at, value)) if stored_at.elapsed() < self.ttl => Some(value.clone()),
+            _ => None,
+        }
+    }
+
+    pub fn insert(&self, key: String, value: V){
+        if self.ttl.is_zero() || self.capacity == 0{
+            return;
+        }
+        let mut entries = self.entries.lock().unwrap_or_else(|e| e.into_inner());
+        if entries.len() >= self.capacity && !entries.contains_key(&key){
+            entries.retain(|_, (stored_at, _)| stored_at.elapsed() < self.ttl);
+            if entries.len() >= self.capacity{
+                entries.clear();
+            }
+        }
+        entries.insert(key, (Instant::now(), value));
+    }
+}
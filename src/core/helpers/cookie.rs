@@ -0,0 +1,57 @@
+use actix_web::cookie::{time::Duration as ActixWebDuration, Cookie, SameSite};
+use chrono::Duration;
+
+use crate::core::config::config::Config;
+use crate::core::middleware::csrf::CSRF_COOKIE;
+
+pub const VISITOR_COOKIE: &str = "visitor";
+// long enough that a returning visitor is still recognised
+const VISITOR_MAX_AGE_DAYS: i64 = 365;
+
+pub fn session_cookie<'c>(config: &Config, token: String, max_age: Duration) -> Cookie<'c>{
+    build(config, "token", token, ActixWebDuration::seconds(max_age.num_seconds()), true)
+}
+
+pub fn expired_session_cookie<'c>(config: &Config) -> Cookie<'c>{
+    build(config, "token", String::new(), ActixWebDuration::new(-1, 0), true)
+}
+
+// the frontend has to read this one to echo it back in X-CSRF-Token, so it
+// is not http only
+pub fn csrf_cookie<'c>(config: &Config, token: String, max_age: Duration) -> Cookie<'c>{
+    build(config, CSRF_COOKIE, token, ActixWebDuration::seconds(max_age.num_seconds()), false)
+}
+
+pub fn expired_csrf_cookie<'c>(config: &Config) -> Cookie<'c>{
+    build(config, CSRF_COOKIE, String::new(), ActixWebDuration::new(-1, 0), false)
+}
+
+// tells anonymous visitors apart for the job analytics
+pub fn visitor_cookie<'c>(config: &Config, id: String) -> Cookie<'c>{
+    build(config, VISITOR_COOKIE, id, ActixWebDuration::days(VISITOR_MAX_AGE_DAYS), true)
+}
+
+// the removal cookie has to carry the same domain/path as the one that was
+// set, otherwise the browser keeps the original
+fn build<'c>(config: &Config, name: &'c str, value: String, max_age: ActixWebDuration, http_only: bool) -> Cookie<'c>{
+    let mut builder = Cookie::build(name, value)
+        .path("/")
+        .max_age(max_age)
+        .http_only(http_only)
+        .secure(config.cookie_secure)
+        .same_site(same_site(&config.cookie_same_site));
+
+    if let Some(domain) = &config.cookie_domain{
+        builder = builder.domain(domain.to_owned());
+    }
+
+    builder.finish()
+}
+
+fn same_site(value: &str) -> SameSite{
+    match value.to_ascii_lowercase().as_str(){
+        "strict" => SameSite::Strict,
+        "none" => SameSite::None,
+        _ => SameSite::Lax,
+    }
+}
@@ -0,0 +1,154 @@
+use std::io::{self, Write};
+use std::sync::OnceLock;
+
+use opentelemetry::sdk::{
+    propagation::TraceContextPropagator,
+    trace::{self, Sampler, Tracer},
+    Resource,
+};
+use opentelemetry::{global, runtime, trace::TraceError, KeyValue};
+use opentelemetry_otlp::WithExportConfig;
+use regex::Regex;
+use tracing_subscriber::{fmt::MakeWriter, layer::SubscriberExt, util::SubscriberInitExt, EnvFilter};
+
+use crate::core::config::config::Config;
+
+// Patterns scrubbed from every log line before it leaves the process, so a
+// stray `?user` or header dump cannot leak credentials or contact details.
+// Sensitive fields are matched by name both as JSON fields and inside Debug
+// output embedded in messages.
+fn redactions() -> &'static [(Regex, &'static str)]{
+    static REDACTIONS: OnceLock<Vec<(Regex, &'static str)>> = OnceLock::new();
+    REDACTIONS.get_or_init(||{
+        vec![
+            (
+                Regex::new(r#"(?i)((?:password|token|secret|authorization|cookie|api_key|key_hash)\\?"?\s*[:=]\s*\\?")(?:[^"\\]|\\[^"])*"#).unwrap(),
+                "${1}[REDACTED]",
+            ),
+            // unquoted, as in query strings or `password={}` messages
+            (
+                Regex::new(r#"(?i)((?:password|token|secret|authorization|cookie|api_key|key_hash)=)[^\s"&;,\\]+"#).unwrap(),
+                "${1}[REDACTED]",
+            ),
+            (Regex::new(r"eyJ[A-Za-z0-9_-]+\.[A-Za-z0-9_-]+\.[A-Za-z0-9_-]*").unwrap(), "[REDACTED_JWT]"),
+            (Regex::new(r"trb_[0-9a-f]+_[0-9a-f]+").unwrap(), "[REDACTED_API_KEY]"),
+            (Regex::new(r"\$argon2[a-z]*\$[^\s\\]+").unwrap(), "[REDACTED_HASH]"),
+            (Regex::new(r"[A-Za-z0-9._%+-]+@[A-Za-z0-9.-]+\.[A-Za-z]{2,}").unwrap(), "[REDACTED_EMAIL]"),
+        ]
+    })
+}
+
+pub fn redact(line: &str) -> String{
+    redactions()
+        .iter()
+        .fold(line.to_string(), |acc, (pattern, replacement)| {
+            pattern.replace_all(&acc, *replacement).into_owned()
+        })
+}
+
+// Scrubs every line before handing it to the inner writer, stdout unless
+// the lines are being captured.
+#[derive(Clone)]
+pub struct RedactingWriter<M = fn() -> io::Stdout>{
+    inner: M,
+}
+
+impl RedactingWriter{
+    pub fn stdout() -> Self{
+        RedactingWriter{ inner: io::stdout }
+    }
+}
+
+impl<M> RedactingWriter<M>{
+    pub fn new(inner: M) -> Self{
+        RedactingWriter{ inner }
+    }
+}
+
+pub struct Redacted<W>(W);
+
+impl<W: Write> Write for Redacted<W>{
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize>{
+        let line = String::from_utf8_lossy(buf);
+        self.0.write_all(redact(&line).as_bytes())?;
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()>{
+        self.0.flush()
+    }
+}
+
+impl<'a, M: MakeWriter<'a>> MakeWriter<'a> for RedactingWriter<M>{
+    type Writer = Redacted<M::Writer>;
+
+    fn make_writer(&'a self) -> Self::Writer{
+        Redacted(self.inner.make_writer())
+    }
+}
+
+// RUST_LOG wins over log.level so a single deployment can be turned up
+// without touching its config file. When an OTLP endpoint is configured the
+// same spans are also exported as OpenTelemetry traces.
+pub fn init(config: &Config){
+    let filter = EnvFilter::try_from_default_env()
+        .unwrap_or_else(|_| EnvFilter::new(&config.log_level));
+
+    let (json_layer, pretty_layer) = if config.log_format == "pretty"{
+        (None, Some(tracing_subscriber::fmt::layer().with_writer(RedactingWriter::stdout())))
+    } else{
+        let layer = tracing_subscriber::fmt::layer()
+            .json()
+            .with_current_span(true)
+            .with_span_list(true)
+            .with_writer(RedactingWriter::stdout());
+        (Some(layer), None)
+    };
+
+    global::set_text_map_propagator(TraceContextPropagator::new());
+    let otel_layer = config
+        .otel_exporter_otlp_endpoint
+        .as_ref()
+        .map(|endpoint| init_tracer(config, endpoint))
+        .and_then(|tracer| match tracer{
+            Ok(tracer) => Some(tracing_opentelemetry::layer().with_tracer(tracer)),
+            Err(e) => {
+                eprintln!("cannot start OpenTelemetry exporter: {}", e);
+                None
+            }
+        });
+
+    tracing_subscriber::registry()
+        .with(filter)
+        .with(json_layer)
+        .with(pretty_layer)
+        .with(otel_layer)
+        .init();
+}
+
+// flushes spans still sitting in the batch exporter
+pub fn shutdown(){
+    global::shutdown_tracer_provider();
+}
+
+fn init_tracer(config: &Config, endpoint: &str) -> Result<Tracer, TraceError>{
+    let exporter = opentelemetry_otlp::new_exporter()
+        .http()
+        .with_endpoint(format!("{}/v1/traces", endpoint.trim_end_matches('/')));
+
+    // follow the caller's sampling decision, sample new traces by ratio
+    let sampler = Sampler::ParentBased(Box::new(Sampler::TraceIdRatioBased(config.otel_sampling_ratio)));
+
+    opentelemetry_otlp::new_pipeline()
+        .tracing()
+        .with_exporter(exporter)
+        .with_trace_config(
+            trace::config()
+                .with_sampler(sampler)
+                .with_resource(Resource::new(vec![KeyValue::new(
+                    "service.name",
+                    config.otel_service_name.to_owned(),
+                )])),
+        )
+        .install_batch(runtime::Tokio)
+}
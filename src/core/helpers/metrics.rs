@@ -0,0 +1,99 @@
+use prometheus::{
+    Encoder, HistogramOpts, HistogramVec, IntCounter, IntCounterVec, IntGaugeVec, Opts, Registry, TextEncoder,
+};
+use sqlx::{Pool, Postgres};
+
+// Prometheus collectors shared by the request middleware, the handlers that
+// bump domain counters and the /metrics endpoint. Cloning is cheap, every
+// collector is reference counted.
+#[derive(Clone)]
+pub struct Metrics{
+    registry: Registry,
+    pub http_requests_total: IntCounterVec,
+    pub http_request_duration_seconds: HistogramVec,
+    pub http_errors_total: IntCounterVec,
+    pub db_pool_connections: IntGaugeVec,
+    pub users_registered_total: IntCounter,
+    pub jobs_posted_total: IntCounter,
+    pub applications_created_total: IntCounter,
+    pub queue_jobs_total: IntCounterVec,
+}
+
+impl Metrics{
+    pub fn new() -> Metrics{
+        let registry = Registry::new_custom(Some("trabajo".to_string()), None).unwrap();
+
+        let http_requests_total = IntCounterVec::new(
+            Opts::new("http_requests_total", "HTTP requests by route and status"),
+            &["method", "route", "status"],
+        )
+        .unwrap();
+        let http_request_duration_seconds = HistogramVec::new(
+            HistogramOpts::new("http_request_duration_seconds", "HTTP request latency by route"),
+            &["method", "route"],
+        )
+        .unwrap();
+        let http_errors_total = IntCounterVec::new(
+            Opts::new("http_errors_total", "HTTP responses with a 4xx or 5xx status by route"),
+            &["method", "route", "class"],
+        )
+        .unwrap();
+        let db_pool_connections = IntGaugeVec::new(
+            Opts::new("db_pool_connections", "Database pool connections by state"),
+            &["state"],
+        )
+        .unwrap();
+        let users_registered_total = IntCounter::new("users_registered_total", "Users registered").unwrap();
+        let jobs_posted_total = IntCounter::new("jobs_posted_total", "Jobs posted").unwrap();
+        let applications_created_total =
+            IntCounter::new("applications_created_total", "Applications submitted").unwrap();
+        let queue_jobs_total = IntCounterVec::new(
+            Opts::new("queue_jobs_total", "Background jobs run by kind and outcome"),
+            &["kind", "outcome"],
+        )
+        .unwrap();
+
+        registry.register(Box::new(http_requests_total.clone())).unwrap();
+        registry.register(Box::new(http_request_duration_seconds.clone())).unwrap();
+        registry.register(Box::new(http_errors_total.clone())).unwrap();
+        registry.register(Box::new(db_pool_connections.clone())).unwrap();
+        registry.register(Box::new(users_registered_total.clone())).unwrap();
+        registry.register(Box::new(jobs_posted_total.clone())).unwrap();
+        registry.register(Box::new(applications_created_total.clone())).unwrap();
+        registry.register(Box::new(queue_jobs_total.clone())).unwrap();
+
+        Metrics{
+            registry,
+            http_requests_total,
+            http_request_duration_seconds,
+            http_errors_total,
+            db_pool_connections,
+            users_registered_total,
+            jobs_posted_total,
+            applications_created_total,
+            queue_jobs_total,
+        }
+    }
+
+    pub fn observe_pool(&self, pool: &Pool<Postgres>){
+        let size = pool.size() as i64;
+        let idle = pool.num_idle() as i64;
+        self.db_pool_connections.with_label_values(&["idle"]).set(idle);
+        self.db_pool_connections.with_label_values(&["active"]).set(size - idle);
+        self.db_pool_connections
+            .with_label_values(&["max"])
+            .set(pool.options().get_max_connections() as i64);
+    }
+
+    pub fn render(&self) -> String{
+        let mut buffer = Vec::new();
+        TextEncoder::new().encode(&self.registry.gather(), &mut buffer).unwrap();
+        String::from_utf8(buffer).unwrap()
+    }
+}
+
+impl Default for Metrics{
+    fn default() -> Self{
+        Metrics::new()
+    }
+}
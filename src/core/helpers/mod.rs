@@ -1 +1,9 @@
-pub mod response;
\ No newline at end of file
+pub mod response;
+pub mod api_key;
+pub mod token;
+pub mod cookie;
+pub mod metrics;
+pub mod logging;
+pub mod telemetry;
+pub mod shutdown;
+pub mod cache;
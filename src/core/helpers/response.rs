@@ -1,3 +1,5 @@
+use crate::service::ServiceError;
+use actix_web::HttpResponse;
 use chrono::prelude::*;
 use serde::Serialize;
 
@@ -25,4 +27,43 @@ pub struct UserResponse{
     pub status: String,
     pub message: String,
     pub data: UserData,
-}
\ No newline at end of file
+}
+
+#[allow(non_snake_case)]
+#[derive(Debug, Serialize)]
+pub struct FilteredApiKey{
+    pub id: String,
+    pub name: String,
+    pub prefix: String,
+    pub company_name: Option<String>,
+    pub scopes: Vec<String>,
+    pub last_used_at: Option<DateTime<Utc>>,
+    pub expires_at: Option<DateTime<Utc>>,
+    pub revoked_at: Option<DateTime<Utc>>,
+    pub createdAt: Option<DateTime<Utc>>,
+}
+
+// Database errors carry SQL, constraint names and connection details, they
+// are logged and the client only learns that something went wrong
+pub fn error_response(err: ServiceError) -> HttpResponse{
+    let mut response = match err{
+        ServiceError::NotFound(_) => HttpResponse::NotFound(),
+        ServiceError::Conflict(_) => HttpResponse::Conflict(),
+        ServiceError::Invalid(_) => HttpResponse::BadRequest(),
+        ServiceError::InvalidCredentials => HttpResponse::BadRequest(),
+        ServiceError::Disabled => HttpResponse::Forbidden(),
+        ServiceError::Unauthorized => HttpResponse::Unauthorized(),
+        ServiceError::Database(_) => HttpResponse::InternalServerError(),
+    };
+    let message = match &err{
+        ServiceError::Database(e) => {
+            tracing::error!(error = %e, "database error");
+            "Internal server error".to_string()
+        }
+        _ => err.to_string(),
+    };
+    response.json(serde_json::json!({
+        "status": "Error",
+        "message": message
+    }))
+}
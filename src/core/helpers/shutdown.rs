@@ -0,0 +1,140 @@
+use std::future::Future;
+use std::sync::Arc;
+use std::time::Duration;
+
+use actix_web::dev::Server;
+use tokio::sync::watch;
+use tokio::task::JoinHandle;
+
+// Handed to background workers and the readiness probe. Once triggered
+// workers must stop picking up new work and return after finishing what they
+// are doing.
+#[derive(Clone)]
+pub struct Shutdown{
+    rx: watch::Receiver<bool>,
+}
+
+impl Shutdown{
+    pub fn is_triggered(&self) -> bool{
+        *self.rx.borrow()
+    }
+
+    // resolves once shutdown has been triggered, use it in a select! next to
+    // the worker's own sleep/poll so idle workers wake up immediately
+    pub async fn triggered(&mut self){
+        while !*self.rx.borrow(){
+            if self.rx.changed().await.is_err(){
+                return;
+            }
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct ShutdownTrigger{
+    tx: Arc<watch::Sender<bool>>,
+}
+
+impl ShutdownTrigger{
+    pub fn trigger(&self){
+        let _ = self.tx.send(true);
+    }
+}
+
+pub struct BackgroundTasks{
+    tx: Arc<watch::Sender<bool>>,
+    rx: watch::Receiver<bool>,
+    handles: Vec<(&'static str, JoinHandle<()>)>,
+}
+
+impl BackgroundTasks{
+    pub fn new() -> BackgroundTasks{
+        let (tx, rx) = watch::channel(false);
+        BackgroundTasks{ tx: Arc::new(tx), rx, handles: Vec::new() }
+    }
+
+    pub fn shutdown_signal(&self) -> Shutdown{
+        Shutdown{ rx: self.rx.clone() }
+    }
+
+    pub fn spawn<F>(&mut self, name: &'static str, task: F)
+    where
+        F: Future<Output = ()> + Send + 'static,
+    {
+        tracing::info!(task = name, "starting background task");
+        self.handles.push((name, tokio::spawn(task)));
+    }
+
+    pub fn trigger_handle(&self) -> ShutdownTrigger{
+        ShutdownTrigger{ tx: self.tx.clone() }
+    }
+
+    // Waits for every task to return, aborting whatever is still running once
+    // the timeout is up so a stuck task cannot hold the process hostage.
+    pub async fn join(self, timeout: Duration){
+        let _ = self.tx.send(true);
+
+        let deadline = tokio::time::Instant::now() + timeout;
+        for (name, mut handle) in self.handles{
+            match tokio::time::timeout_at(deadline, &mut handle).await{
+                Ok(Ok(())) => tracing::info!(task = name, "background task stopped"),
+                Ok(Err(e)) => tracing::error!(task = name, error = %e, "background task panicked"),
+                Err(_) => {
+                    handle.abort();
+                    tracing::warn!(task = name, "background task did not stop in time, aborted");
+                }
+            }
+        }
+    }
+}
+
+impl Default for BackgroundTasks{
+    fn default() -> Self{
+        BackgroundTasks::new()
+    }
+}
+
+// Signals are handled here instead of by actix so that the HTTP drain and
+// the background workers shut down as one sequence: once `signal` resolves
+// the workers are told to stop, in-flight requests are drained and then the
+// workers are joined. The server must have been built with disable_signals.
+pub async fn serve_until<S>(server: Server, background: BackgroundTasks, signal: S, timeout: Duration) -> std::io::Result<()>
+where
+    S: Future<Output = ()> + Send + 'static,
+{
+    let server_handle = server.handle();
+    let trigger = background.trigger_handle();
+    tokio::spawn(async move{
+        signal.await;
+        tracing::info!("shutdown requested, draining in-flight requests");
+        trigger.trigger();
+        server_handle.stop(true).await;
+    });
+
+    let result = server.await;
+    background.join(timeout).await;
+    result
+}
+
+// SIGTERM from the orchestrator or Ctrl-C in a terminal
+pub async fn wait_for_signal(){
+    let ctrl_c = async{
+        let _ = tokio::signal::ctrl_c().await;
+    };
+
+    #[cfg(unix)]
+    let terminate = async{
+        match tokio::signal::unix::signal(tokio::signal::unix::SignalKind::terminate()){
+            Ok(mut signal) => { signal.recv().await; }
+            Err(_) => std::future::pending::<()>().await,
+        }
+    };
+
+    #[cfg(not(unix))]
+    let terminate = std::future::pending::<()>();
+
+    tokio::select!{
+        _ = ctrl_c => {},
+        _ = terminate => {},
+    }
+}
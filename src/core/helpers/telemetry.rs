@@ -0,0 +1,29 @@
+use actix_web::http::header::HeaderMap;
+use opentelemetry::propagation::Extractor;
+use tracing::Span;
+
+// Lets the W3C propagator read traceparent/tracestate from actix headers.
+pub struct HeaderExtractor<'a>(pub &'a HeaderMap);
+
+impl<'a> Extractor for HeaderExtractor<'a>{
+    fn get(&self, key: &str) -> Option<&str>{
+        self.0.get(key).and_then(|value| value.to_str().ok())
+    }
+
+    fn keys(&self) -> Vec<&str>{
+        self.0.keys().map(|key| key.as_str()).collect()
+    }
+}
+
+// Span around a single SQL statement, named the way OpenTelemetry database
+// conventions expect so the trace view shows e.g. "SELECT users".
+pub fn db_span(operation: &'static str, table: &'static str) -> Span{
+    tracing::info_span!(
+        "db.query",
+        otel.name = %format!("{} {}", operation, table),
+        otel.kind = "client",
+        db.system = "postgresql",
+        db.operation = operation,
+        db.sql.table = table,
+    )
+}
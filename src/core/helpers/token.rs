@@ -0,0 +1,202 @@
+use core::fmt;
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine as _};
+use chrono::{Duration, Utc};
+use jsonwebtoken::{decode, decode_header, encode, Algorithm, DecodingKey, EncodingKey, Header, Validation};
+use rsa::{pkcs8::DecodePublicKey, traits::PublicKeyParts, RsaPublicKey};
+use serde_json::{json, Value};
+
+use crate::core::config::config::Config;
+use crate::schema::user_schema::TokenClaims;
+
+// DER prefix of an Ed25519 SubjectPublicKeyInfo, the raw key is the 32 bytes after it
+const ED25519_SPKI_PREFIX: [u8; 12] = [0x30, 0x2a, 0x30, 0x05, 0x06, 0x03, 0x2b, 0x65, 0x70, 0x03, 0x21, 0x00];
+
+#[derive(Debug)]
+pub enum TokenError{
+    Config(String),
+    Invalid,
+}
+
+impl fmt::Display for TokenError{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result{
+        match self{
+            TokenError::Config(message) => write!(f, "invalid JWT configuration: {}", message),
+            TokenError::Invalid => write!(f, "invalid token"),
+        }
+    }
+}
+
+// Signing and verification keys. HS256 uses the shared JWT_SECRET; RS256 and
+// EdDSA load every `<kid>.pub.pem` in JWT_KEYS_DIR for verification and sign
+// with `<JWT_ACTIVE_KID>.pem`, so a key can be rotated by adding a new pair,
+// switching the active kid and removing the old public key once its tokens
+// have expired.
+#[derive(Clone)]
+pub struct JwtKeys{
+    algorithm: Algorithm,
+    active_kid: Option<String>,
+    encoding_key: EncodingKey,
+    decoding_keys: HashMap<String, DecodingKey>,
+    jwks: Vec<Value>,
+    issuer: String,
+    audience: String,
+}
+
+impl JwtKeys{
+    pub fn from_config(config: &Config) -> Result<JwtKeys, TokenError>{
+        let algorithm = match config.jwt_algorithm.as_str(){
+            "HS256" => Algorithm::HS256,
+            "RS256" => Algorithm::RS256,
+            "EdDSA" => Algorithm::EdDSA,
+            other => return Err(TokenError::Config(format!("unsupported algorithm {}", other))),
+        };
+
+        if algorithm == Algorithm::HS256{
+            let mut decoding_keys = HashMap::new();
+            decoding_keys.insert(String::new(), DecodingKey::from_secret(config.jwt_secret.as_ref()));
+            return Ok(JwtKeys{
+                algorithm,
+                active_kid: None,
+                encoding_key: EncodingKey::from_secret(config.jwt_secret.as_ref()),
+                decoding_keys,
+                jwks: Vec::new(),
+                issuer: config.jwt_issuer.to_owned(),
+                audience: config.jwt_audience.to_owned(),
+            });
+        }
+
+        let keys_dir = config
+            .jwt_keys_dir
+            .as_ref()
+            .ok_or_else(|| TokenError::Config("JWT_KEYS_DIR must be set".to_string()))?;
+        let active_kid = config
+            .jwt_active_kid
+            .to_owned()
+            .ok_or_else(|| TokenError::Config("JWT_ACTIVE_KID must be set".to_string()))?;
+
+        let entries = fs::read_dir(keys_dir)
+            .map_err(|e| TokenError::Config(format!("cannot read {}: {}", keys_dir, e)))?;
+
+        let mut decoding_keys = HashMap::new();
+        let mut jwks = Vec::new();
+        for entry in entries.flatten(){
+            let file_name = entry.file_name().to_string_lossy().to_string();
+            let kid = match file_name.strip_suffix(".pub.pem"){
+                Some(kid) => kid.to_string(),
+                None => continue,
+            };
+            let public_pem = read_key(&entry.path())?;
+            let (decoding_key, jwk) = load_public_key(algorithm, &kid, &public_pem)?;
+            decoding_keys.insert(kid, decoding_key);
+            jwks.push(jwk);
+        }
+
+        if !decoding_keys.contains_key(&active_kid){
+            return Err(TokenError::Config(format!("missing public key {}.pub.pem", active_kid)));
+        }
+
+        let private_pem = read_key(&Path::new(keys_dir).join(format!("{}.pem", active_kid)))?;
+        let encoding_key = match algorithm{
+            Algorithm::RS256 => EncodingKey::from_rsa_pem(&private_pem),
+            _ => EncodingKey::from_ed_pem(&private_pem),
+        }
+        .map_err(|e| TokenError::Config(format!("invalid private key {}: {}", active_kid, e)))?;
+
+        Ok(JwtKeys{
+            algorithm,
+            active_kid: Some(active_kid),
+            encoding_key,
+            decoding_keys,
+            jwks,
+            issuer: config.jwt_issuer.to_owned(),
+            audience: config.jwt_audience.to_owned(),
+        })
+    }
+
+    pub fn issue(&self, user_id: &uuid::Uuid, session_version: i32, lifetime: Duration) -> Result<String, TokenError>{
+        let now = Utc::now();
+        let claims = TokenClaims{
+            sub: user_id.to_string(),
+            iat: now.timestamp() as usize,
+            exp: (now + lifetime).timestamp() as usize,
+            iss: self.issuer.to_owned(),
+            aud: self.audience.to_owned(),
+            ver: session_version,
+        };
+
+        let mut header = Header::new(self.algorithm);
+        header.kid = self.active_kid.to_owned();
+
+        encode(&header, &claims, &self.encoding_key).map_err(|_| TokenError::Invalid)
+    }
+
+    pub fn verify(&self, token: &str) -> Result<TokenClaims, TokenError>{
+        let header = decode_header(token).map_err(|_| TokenError::Invalid)?;
+        if header.alg != self.algorithm{
+            return Err(TokenError::Invalid);
+        }
+
+        let kid = header
+            .kid
+            .or_else(|| self.active_kid.to_owned())
+            .unwrap_or_default();
+        let decoding_key = self.decoding_keys.get(&kid).ok_or(TokenError::Invalid)?;
+
+        let mut validation = Validation::new(self.algorithm);
+        validation.set_issuer(&[&self.issuer]);
+        validation.set_audience(&[&self.audience]);
+
+        decode::<TokenClaims>(token, decoding_key, &validation)
+            .map(|data| data.claims)
+            .map_err(|_| TokenError::Invalid)
+    }
+
+    pub fn jwks(&self) -> Value{
+        json!({ "keys": self.jwks })
+    }
+}
+
+fn read_key(path: &Path) -> Result<Vec<u8>, TokenError>{
+    fs::read(path).map_err(|e| TokenError::Config(format!("cannot read {}: {}", path.display(), e)))
+}
+
+fn load_public_key(algorithm: Algorithm, kid: &str, public_pem: &[u8]) -> Result<(DecodingKey, Value), TokenError>{
+    let invalid = |e: String| TokenError::Config(format!("invalid public key {}: {}", kid, e));
+
+    match algorithm{
+        Algorithm::RS256 => {
+            let decoding_key = DecodingKey::from_rsa_pem(public_pem).map_err(|e| invalid(e.to_string()))?;
+            let pem = std::str::from_utf8(public_pem).map_err(|e| invalid(e.to_string()))?;
+            let public_key = RsaPublicKey::from_public_key_pem(pem).map_err(|e| invalid(e.to_string()))?;
+            let jwk = json!({
+                "kty": "RSA",
+                "use": "sig",
+                "alg": "RS256",
+                "kid": kid,
+                "n": URL_SAFE_NO_PAD.encode(public_key.n().to_bytes_be()),
+                "e": URL_SAFE_NO_PAD.encode(public_key.e().to_bytes_be()),
+            });
+            Ok((decoding_key, jwk))
+        }
+        _ => {
+            let decoding_key = DecodingKey::from_ed_pem(public_pem).map_err(|e| invalid(e.to_string()))?;
+            let der = pem::parse(public_pem).map_err(|e| invalid(e.to_string()))?.contents;
+            if der.len() != ED25519_SPKI_PREFIX.len() + 32 || !der.starts_with(&ED25519_SPKI_PREFIX){
+                return Err(invalid("not an Ed25519 public key".to_string()));
+            }
+            let jwk = json!({
+                "kty": "OKP",
+                "crv": "Ed25519",
+                "use": "sig",
+                "alg": "EdDSA",
+                "kid": kid,
+                "x": URL_SAFE_NO_PAD.encode(&der[ED25519_SPKI_PREFIX.len()..]),
+            });
+            Ok((decoding_key, jwk))
+        }
+    }
+}
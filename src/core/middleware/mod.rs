@@ -1 +1,4 @@
-pub mod jwt_auth;
\ No newline at end of file
+pub mod jwt_auth;
+pub mod csrf;
+pub mod request_metrics;
+pub mod request_id;
\ No newline at end of file
@@ -0,0 +1,128 @@
+use std::future::{ready, Ready};
+use std::time::Instant;
+
+use actix_web::dev::{forward_ready, Service, ServiceRequest, ServiceResponse, Transform};
+use actix_web::http::header::{HeaderName, HeaderValue};
+use actix_web::Error as ActixWebError;
+use futures_util::future::LocalBoxFuture;
+use opentelemetry::global;
+use tracing::Instrument;
+use tracing_opentelemetry::OpenTelemetrySpanExt;
+
+use crate::core::helpers::telemetry::HeaderExtractor;
+
+pub const REQUEST_ID_HEADER: &str = "x-request-id";
+
+// Opens the `http_request` span every log event of a request is nested in.
+// The id is taken from an incoming X-Request-Id (so a trace can be followed
+// through the proxy in front of us) or generated, and is echoed back on the
+// response. `user_id` is filled in by the auth extractor once it is known.
+// The span doubles as the OpenTelemetry server span for the handler.
+pub struct RequestTracing;
+
+impl<S, B> Transform<S, ServiceRequest> for RequestTracing
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = ActixWebError>,
+    S::Future: 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<B>;
+    type Error = ActixWebError;
+    type InitError = ();
+    type Transform = RequestTracingMiddleware<S>;
+    type Future = Ready<Result<Self::Transform, Self::InitError>>;
+
+    fn new_transform(&self, service: S) -> Self::Future{
+        ready(Ok(RequestTracingMiddleware{ service }))
+    }
+}
+
+pub struct RequestTracingMiddleware<S>{
+    service: S,
+}
+
+impl<S, B> Service<ServiceRequest> for RequestTracingMiddleware<S>
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = ActixWebError>,
+    S::Future: 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<B>;
+    type Error = ActixWebError;
+    type Future = LocalBoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    forward_ready!(service);
+
+    fn call(&self, req: ServiceRequest) -> Self::Future{
+        let started = Instant::now();
+        let request_id = req
+            .headers()
+            .get(REQUEST_ID_HEADER)
+            .and_then(|h| h.to_str().ok())
+            .filter(|id| is_valid_request_id(id))
+            .map(|id| id.to_string())
+            .unwrap_or_else(|| uuid::Uuid::new_v4().to_string());
+        let route = req.match_pattern().unwrap_or_else(|| "unmatched".to_string());
+
+        let span = tracing::info_span!(
+            "http_request",
+            otel.name = %format!("{} {}", req.method(), route),
+            otel.kind = "server",
+            otel.status_code = tracing::field::Empty,
+            http.status_code = tracing::field::Empty,
+            request_id = %request_id,
+            method = %req.method(),
+            route = %route,
+            user_id = tracing::field::Empty,
+        );
+
+        // continue the caller's trace when it sent a W3C traceparent
+        let parent = global::get_text_map_propagator(|propagator| {
+            propagator.extract(&HeaderExtractor(req.headers()))
+        });
+        span.set_parent(parent);
+
+        let fut = {
+            let _entered = span.enter();
+            self.service.call(req)
+        };
+
+        let response_span = span.clone();
+        Box::pin(
+            async move{
+                let mut result = fut.await;
+                let latency_ms = started.elapsed().as_millis() as u64;
+
+                match &mut result{
+                    Ok(res) => {
+                        let status = res.status().as_u16();
+                        response_span.record("http.status_code", status);
+                        if res.status().is_server_error(){
+                            response_span.record("otel.status_code", "ERROR");
+                            tracing::error!(status, latency_ms, "request failed");
+                        } else{
+                            tracing::info!(status, latency_ms, "request completed");
+                        }
+                        if let Ok(value) = HeaderValue::from_str(&request_id){
+                            res.headers_mut().insert(HeaderName::from_static(REQUEST_ID_HEADER), value);
+                        }
+                    }
+                    Err(e) => {
+                        response_span.record("otel.status_code", "ERROR");
+                        tracing::error!(error = %e, latency_ms, "request failed");
+                    }
+                }
+
+                result
+            }
+            .instrument(span),
+        )
+    }
+}
+
+// ids from clients end up in our logs, so only accept short printable tokens
+fn is_valid_request_id(id: &str) -> bool{
+    !id.is_empty()
+        && id.len() <= 128
+        && id.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_' || c == '.')
+}
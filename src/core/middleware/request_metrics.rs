@@ -0,0 +1,90 @@
+use std::future::{ready, Ready};
+use std::time::Instant;
+
+use actix_web::dev::{forward_ready, Service, ServiceRequest, ServiceResponse, Transform};
+use actix_web::Error as ActixWebError;
+use futures_util::future::LocalBoxFuture;
+
+use crate::core::helpers::metrics::Metrics;
+
+// Records request count, latency and error class per route. Routes are
+// labelled by their pattern (`/api/job/{job_id}`) rather than the raw path so
+// ids don't blow up the number of series.
+pub struct RequestMetrics{
+    metrics: Metrics,
+}
+
+impl RequestMetrics{
+    pub fn new(metrics: Metrics) -> RequestMetrics{
+        RequestMetrics{ metrics }
+    }
+}
+
+impl<S, B> Transform<S, ServiceRequest> for RequestMetrics
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = ActixWebError>,
+    S::Future: 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<B>;
+    type Error = ActixWebError;
+    type InitError = ();
+    type Transform = RequestMetricsMiddleware<S>;
+    type Future = Ready<Result<Self::Transform, Self::InitError>>;
+
+    fn new_transform(&self, service: S) -> Self::Future{
+        ready(Ok(RequestMetricsMiddleware{ service, metrics: self.metrics.clone() }))
+    }
+}
+
+pub struct RequestMetricsMiddleware<S>{
+    service: S,
+    metrics: Metrics,
+}
+
+impl<S, B> Service<ServiceRequest> for RequestMetricsMiddleware<S>
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = ActixWebError>,
+    S::Future: 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<B>;
+    type Error = ActixWebError;
+    type Future = LocalBoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    forward_ready!(service);
+
+    fn call(&self, req: ServiceRequest) -> Self::Future{
+        let started = Instant::now();
+        let method = req.method().to_string();
+        let route = req.match_pattern().unwrap_or_else(|| "unmatched".to_string());
+        let metrics = self.metrics.clone();
+        let fut = self.service.call(req);
+
+        Box::pin(async move{
+            let result = fut.await;
+            let status = match &result{
+                Ok(res) => res.status(),
+                Err(e) => e.as_response_error().status_code(),
+            };
+
+            metrics
+                .http_requests_total
+                .with_label_values(&[&method, &route, status.as_str()])
+                .inc();
+            metrics
+                .http_request_duration_seconds
+                .with_label_values(&[&method, &route])
+                .observe(started.elapsed().as_secs_f64());
+            if status.is_client_error() || status.is_server_error(){
+                let class = if status.is_server_error() { "server" } else { "client" };
+                metrics
+                    .http_errors_total
+                    .with_label_values(&[&method, &route, class])
+                    .inc();
+            }
+
+            result
+        })
+    }
+}
@@ -0,0 +1,148 @@
+pub mod cli;
+pub mod core;
+use crate::core::{
+    config::config::Config,
+    helpers::{metrics::Metrics, shutdown::Shutdown, token::JwtKeys},
+    middleware::jwt_auth,
+};
+pub mod model;
+pub mod queue;
+pub mod repository;
+pub mod route;
+pub mod schema;
+pub mod service;
+
+use repository::{
+    analytics_repository::PgAnalyticsRepository,
+    api_key_repository::PgApiKeyRepository,
+    application_repository::PgApplicationRepository,
+    candidate_repository::PgCandidateRepository,
+    category_repository::PgCategoryRepository,
+    job_repository::PgJobRepository,
+    location_repository::PgLocationRepository,
+    queue_repository::PgQueueRepository,
+    saved_job_repository::PgSavedJobRepository,
+    saved_search_repository::PgSavedSearchRepository,
+    skill_repository::PgSkillRepository,
+    user_repository::PgUserRepository,
+};
+use service::{
+    account_service::AccountService,
+    analytics_service::AnalyticsService,
+    api_key_service::ApiKeyService,
+    application_service::ApplicationService,
+    candidate_service::CandidateService,
+    category_service::CategoryService,
+    job_service::JobService,
+    profile_service::ProfileService,
+    queue_service::QueueService,
+    recommendation_service::RecommendationService,
+    saved_job_service::SavedJobService,
+    saved_search_service::SavedSearchService,
+    skill_service::SkillService,
+    user_service::UserService,
+};
+use sqlx::{migrate::Migrator, Pool, Postgres};
+use std::sync::Arc;
+
+// compiled into the binary so a release needs nothing but itself to migrate
+pub static MIGRATOR: Migrator = sqlx::migrate!("./migrations");
+
+pub struct AppState {
+    pub db: Pool<Postgres>,
+    pub env: Config,
+    pub jwt_keys: JwtKeys,
+    pub metrics: Metrics,
+    pub shutdown: Shutdown,
+    pub users: UserService,
+    pub jobs: JobService,
+    pub applications: ApplicationService,
+    pub accounts: AccountService,
+    pub profiles: ProfileService,
+    pub candidates: CandidateService,
+    pub skills: SkillService,
+    pub categories: CategoryService,
+    pub recommendations: RecommendationService,
+    pub saved_searches: SavedSearchService,
+    pub saved_jobs: SavedJobService,
+    pub analytics: AnalyticsService,
+    pub api_keys: ApiKeyService,
+    pub queue: QueueService,
+}
+
+impl AppState{
+    // wires the Postgres repositories into the services
+    pub fn new(db: Pool<Postgres>, env: Config, jwt_keys: JwtKeys, metrics: Metrics, shutdown: Shutdown) -> AppState{
+        let user_repository = Arc::new(PgUserRepository::new(db.clone()));
+        let application_repository = Arc::new(PgApplicationRepository::new(db.clone()));
+        let candidate_repository = Arc::new(PgCandidateRepository::new(db.clone()));
+        let saved_search_repository = Arc::new(PgSavedSearchRepository::new(db.clone()));
+
+        let users = UserService::new(user_repository.clone());
+        let skills = SkillService::new(Arc::new(PgSkillRepository::new(db.clone())), users.clone());
+        let categories = CategoryService::new(Arc::new(PgCategoryRepository::new(db.clone())), users.clone());
+        let jobs = JobService::new(
+            Arc::new(PgJobRepository::new(db.clone())),
+            Arc::new(PgLocationRepository::new(db.clone())),
+            skills.clone(),
+            categories.clone(),
+            users.clone(),
+            env.search_facets_ttl,
+        );
+        let applications = ApplicationService::new(application_repository.clone(), users.clone());
+        let profiles = ProfileService::new(user_repository.clone(), users.clone(), &env.frontend_url);
+        let candidates = CandidateService::new(candidate_repository.clone(), skills.clone(), users.clone());
+        let recommendations = RecommendationService::new(
+            candidate_repository,
+            application_repository.clone(),
+            jobs.clone(),
+            users.clone(),
+        );
+        let saved_searches = SavedSearchService::new(
+            saved_search_repository.clone(),
+            jobs.clone(),
+            skills.clone(),
+            categories.clone(),
+            &env.frontend_url,
+        );
+        let saved_jobs = SavedJobService::new(
+            Arc::new(PgSavedJobRepository::new(db.clone())),
+            jobs.clone(),
+            &env.frontend_url,
+        );
+        let analytics = AnalyticsService::new(Arc::new(PgAnalyticsRepository::new(db.clone())), users.clone());
+        let api_keys = ApiKeyService::new(Arc::new(PgApiKeyRepository::new(db.clone())), users.clone());
+        let queue = QueueService::new(Arc::new(PgQueueRepository::new(db.clone())), users.clone());
+        let accounts = AccountService::new(
+            user_repository,
+            application_repository,
+            saved_search_repository,
+            users.clone(),
+            candidates.clone(),
+            saved_jobs.clone(),
+            env.privacy_deletion_grace_period,
+        );
+
+        AppState{
+            db,
+            env,
+            jwt_keys,
+            metrics,
+            shutdown,
+            users,
+            jobs,
+            applications,
+            accounts,
+            profiles,
+            candidates,
+            skills,
+            categories,
+            recommendations,
+            saved_searches,
+            saved_jobs,
+            analytics,
+            api_keys,
+            queue,
+        }
+    }
+}
@@ -1,74 +1,257 @@
-mod core;
-use crate::core::{
-    config::config::Config,
-    middleware::jwt_auth
+use trabajo_server::{
+    cli::{self, Cli, Command},
+    core::{
+        config::config::Config,
+        helpers::{
+            logging,
+            metrics::Metrics,
+            shutdown::{self, BackgroundTasks, Shutdown},
+            token::JwtKeys,
+        },
+        middleware::{csrf, request_id::RequestTracing, request_metrics::RequestMetrics},
+    },
+    queue,
+    route,
+    AppState,
+    MIGRATOR,
 };
-mod model;
-mod route;
-mod schema;
-mod service;
-
 
 use actix_files as fs;
 use actix_cors::Cors;
-use actix_web::middleware::Logger;
 use actix_web::{http::header, web, App, HttpServer};
+use clap::Parser;
 use dotenv::dotenv;
 use sqlx::{postgres::PgPoolOptions, Pool, Postgres};
-
-pub struct AppState {
-    db: Pool<Postgres>,
-    env: Config,
-}
+use std::sync::Arc;
 
 #[actix_web::main]
 async fn main() -> std::io::Result<()> {
-    if std::env::var_os("RUST_LOG").is_none() {
-        std::env::set_var("RUST_LOG", "actix_web=info");
-    }
     dotenv().ok();
-    env_logger::init();
+    let cli = Cli::parse();
+
+    let config = match Config::init(){
+        Ok(config) => config,
+        Err(err) => {
+            eprintln!("🔥 {}", err);
+            std::process::exit(1);
+        }
+    };
 
-    let config = Config::init();
+    match cli.command.unwrap_or(Command::Serve){
+        Command::Serve => serve(config).await,
+        command => {
+            let pool = match connect(&config).await{
+                Ok(pool) => pool,
+                Err(err) => {
+                    eprintln!("🔥 failed to connect to the database: {}", err);
+                    std::process::exit(1);
+                }
+            };
+            let result = cli::run(command, &pool).await;
+            pool.close().await;
+            if let Err(err) = result{
+                eprintln!("🔥 {}", err);
+                std::process::exit(1);
+            }
+            Ok(())
+        }
+    }
+}
 
-    let pool = match PgPoolOptions::new()
-        .max_connections(10)
+async fn connect(config: &Config) -> Result<Pool<Postgres>, sqlx::Error>{
+    PgPoolOptions::new()
+        .max_connections(config.database_max_connections)
+        .acquire_timeout(config.database_acquire_timeout.to_std().unwrap())
         .connect(&config.database_url)
         .await
-    {
+}
+
+async fn serve(config: Config) -> std::io::Result<()>{
+    logging::init(&config);
+
+    let jwt_keys = match JwtKeys::from_config(&config){
+        Ok(keys) => keys,
+        Err(err) => {
+            tracing::error!(error = %err, "cannot load JWT keys");
+            std::process::exit(1);
+        }
+    };
+
+    let pool = match connect(&config).await{
         Ok(pool) => {
-            println!("Connection to the database is successful! 🚀");
+            tracing::info!("connected to the database");
             pool
         }
         Err(err) => {
-            println!("🔥 Failed to connect to the database: {:?}", err);
+            tracing::error!(error = %err, "failed to connect to the database");
             std::process::exit(1);
         }
     };
 
-    println!("Lets goooo 😁");
+    if config.database_migrate_on_start{
+        if let Err(err) = MIGRATOR.run(&pool).await{
+            tracing::error!(error = %err, "failed to run migrations");
+            std::process::exit(1);
+        }
+        tracing::info!("database migrations are up to date");
+    }
+
+    let metrics = Metrics::new();
 
-    HttpServer::new(move || {
+    let mut background = BackgroundTasks::new();
+    let shutdown_signal = background.shutdown_signal();
+    background.spawn("pool_metrics", sample_pool_metrics(pool.clone(), metrics.clone(), background.shutdown_signal()));
+
+    let mailer = match queue::mailer::Mailer::from_config(&config){
+        Ok(mailer) => mailer,
+        Err(err) => {
+            tracing::error!(error = %err, "cannot set up the mailer");
+            std::process::exit(1);
+        }
+    };
+    // one state for every server worker and every queue worker, so the
+    // services and the facets cache they hold are built once
+    let state = web::Data::new(AppState::new(
+        pool.clone(),
+        config.clone(),
+        jwt_keys,
+        metrics.clone(),
+        shutdown_signal,
+    ));
+    let queue_context = Arc::new(queue::Context{
+        db: pool.clone(),
+        mailer,
+        state: state.clone(),
+    });
+    let process_name = queue::worker::process_name();
+    for i in 0..config.queue_workers{
+        let worker = queue::worker::Worker{
+            name: format!("{}-{}", process_name, i),
+            ctx: queue_context.clone(),
+            metrics: metrics.clone(),
+            poll_interval: config.queue_poll_interval.to_std().unwrap(),
+            lock_timeout: config.queue_lock_timeout,
+        };
+        background.spawn("queue_worker", worker.run(background.shutdown_signal()));
+    }
+
+    background.spawn(
+        "retention_schedule",
+        schedule_retention(pool.clone(), config.clone(), background.shutdown_signal()),
+    );
+    background.spawn(
+        "job_alerts_schedule",
+        schedule_job_alerts(pool.clone(), config.clone(), background.shutdown_signal()),
+    );
+
+    let bind_address = (config.server_host.to_owned(), config.server_port);
+    let workers = config.server_workers;
+    let shutdown_timeout = config.server_shutdown_timeout.to_std().unwrap();
+
+    let server = HttpServer::new(move || {
+        let cors_config = config.clone();
         let cors = Cors::default()
-            .allowed_origin("http://localhost:3000")
-            .allowed_methods(vec!["GET", "POST"])
+            .allowed_origin_fn(move |origin, _| {
+                origin
+                    .to_str()
+                    .is_ok_and(|origin| cors_config.origin_allowed(origin))
+            })
+            .allowed_methods(config.cors_allowed_methods.iter().map(|m| m.as_str()))
+            .max_age(config.cors_max_age)
             .allowed_headers(vec![
                 header::CONTENT_TYPE,
                 header::AUTHORIZATION,
                 header::ACCEPT,
+                header::HeaderName::from_static("x-csrf-token"),
+                header::HeaderName::from_static("x-request-id"),
             ])
+            .expose_headers(vec![header::HeaderName::from_static("x-request-id")])
             .supports_credentials();
         App::new()
-            .app_data(web::Data::new(AppState {
-                db: pool.clone(),
-                env: config.clone(),
-            }))
-            .service(fs::Files::new("/static", "./static").show_files_listing())
+            .app_data(state.clone())
+            .service(fs::Files::new("/static", &config.storage_dir).show_files_listing())
             .configure(route::config)
+            .wrap(csrf::Csrf)
             .wrap(cors)
-            .wrap(Logger::default())
-    })
-    .bind(("127.0.0.1", 8000))?
-    .run()
-    .await
-}
\ No newline at end of file
+            .wrap(RequestMetrics::new(metrics.clone()))
+            .wrap(RequestTracing)
+    });
+
+    let server = match workers{
+        Some(workers) => server.workers(workers),
+        None => server,
+    };
+
+    tracing::info!(host = %bind_address.0, port = bind_address.1, "starting server");
+
+    // actix counts its drain in whole seconds, round up so a sub-second
+    // timeout still gives in-flight requests a chance
+    let server = server
+        .disable_signals()
+        .shutdown_timeout(shutdown_timeout.as_secs_f64().ceil() as u64)
+        .bind(bind_address)?
+        .run();
+
+    let result = shutdown::serve_until(server, background, shutdown::wait_for_signal(), shutdown_timeout).await;
+
+    pool.close().await;
+    tracing::info!("shutdown complete");
+
+    logging::shutdown();
+    result
+}
+
+async fn sample_pool_metrics(pool: Pool<Postgres>, metrics: Metrics, mut shutdown: Shutdown){
+    let mut interval = tokio::time::interval(std::time::Duration::from_secs(15));
+    loop{
+        tokio::select!{
+            _ = interval.tick() => metrics.observe_pool(&pool),
+            _ = shutdown.triggered() => return,
+        }
+    }
+}
+
+// Queues the retention job every privacy.retention_interval. Every instance
+// does this, enqueue_once keeps it to one waiting job.
+async fn schedule_retention(pool: Pool<Postgres>, config: Config, mut shutdown: Shutdown){
+    let mut interval = tokio::time::interval(config.privacy_retention_interval.to_std().unwrap());
+    loop{
+        tokio::select!{
+            _ = interval.tick() => {
+                let now = chrono::Utc::now();
+                let task = queue::task::Task::PurgeStaleData{
+                    applications_before: now - config.privacy_application_retention,
+                    deletions_requested_before: now - config.privacy_deletion_grace_period,
+                };
+                if let Err(err) = queue::enqueue_once(&pool, &task, None).await{
+                    tracing::warn!(error = %err, "cannot queue the retention job");
+                }
+            }
+            _ = shutdown.triggered() => return,
+        }
+    }
+}
+
+// Queues the job alerts and the saved job reminders every alerts.interval,
+// one waiting job of each across instances like the retention job
+async fn schedule_job_alerts(pool: Pool<Postgres>, config: Config, mut shutdown: Shutdown){
+    let mut interval = tokio::time::interval(config.alerts_interval.to_std().unwrap());
+    loop{
+        tokio::select!{
+            _ = interval.tick() => {
+                let now = chrono::Utc::now();
+                let task = queue::task::Task::SendJobAlerts{ due_at: now };
+                if let Err(err) = queue::enqueue_once(&pool, &task, None).await{
+                    tracing::warn!(error = %err, "cannot queue the job alerts");
+                }
+                let task = queue::task::Task::SendSavedJobReminders{
+                    expiring_before: now + config.alerts_saved_job_reminder,
+                };
+                if let Err(err) = queue::enqueue_once(&pool, &task, None).await{
+                    tracing::warn!(error = %err, "cannot queue the saved job reminders");
+                }
+            }
+            _ = shutdown.triggered() => return,
+        }
+    }
+}
@@ -0,0 +1,28 @@
+use chrono::prelude::*;
+use serde::{Deserialize, Serialize};
+
+#[allow(non_snake_case)]
+#[derive(Debug, Deserialize, sqlx::FromRow, Serialize, Clone)]
+pub struct ApiKey{
+    pub id: uuid::Uuid,
+    pub user_id: uuid::Uuid,
+    pub company_name: Option<String>,
+    pub name: String,
+    pub prefix: String,
+    pub key_hash: String,
+    pub scopes: Vec<String>,
+    pub last_used_at: Option<DateTime<Utc>>,
+    pub expires_at: Option<DateTime<Utc>>,
+    pub revoked_at: Option<DateTime<Utc>>,
+    #[serde(rename = "createdAt")]
+    pub created_at: Option<DateTime<Utc>>,
+    #[serde(rename = "updatedAt")]
+    pub updated_at: Option<DateTime<Utc>>,
+}
+
+impl ApiKey{
+    pub fn is_usable(&self) -> bool{
+        self.revoked_at.is_none()
+            && self.expires_at.is_none_or(|expires| expires > Utc::now())
+    }
+}
@@ -0,0 +1,10 @@
+use serde::{Deserialize, Serialize};
+
+// a node of the job category tree, top level categories have no parent
+#[derive(Debug, Deserialize, sqlx::FromRow, Serialize, Clone)]
+pub struct Category{
+    pub id: uuid::Uuid,
+    pub parent_id: Option<uuid::Uuid>,
+    pub slug: String,
+    pub name: String,
+}
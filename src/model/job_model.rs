@@ -10,7 +10,28 @@ pub struct Job{
     pub city: String,
     pub country: String,
     pub salary: String,
+    // yearly range read from salary, None when it has no amount
+    pub salary_min: Option<f64>,
+    pub salary_max: Option<f64>,
+    // None when the salary names no currency
+    pub salary_currency: Option<String>,
     pub description: String,
+    // None for a job that stays open until it is removed
+    #[serde(rename = "expiresAt")]
+    pub expires_at: Option<DateTime<Utc>>,
+    // what city and country were geocoded to, None when nothing matched
+    pub location_id: Option<i64>,
+    pub country_code: Option<String>,
+    pub region: Option<String>,
+    pub latitude: Option<f64>,
+    pub longitude: Option<f64>,
+    pub work_mode: String,
+    pub remote_countries: Vec<String>,
+    pub remote_timezones: Vec<String>,
+    pub employment_type: String,
+    // None for jobs posted before it was recorded
+    pub seniority: Option<String>,
+    pub category_id: Option<uuid::Uuid>,
     #[serde(rename = "createdAt")]
     pub created_at: Option<DateTime<Utc>>,
     #[serde(rename = "updatedAt")]
@@ -0,0 +1,22 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Deserialize, sqlx::FromRow, Serialize, Clone)]
+pub struct Country{
+    // ISO 3166-1 alpha-2
+    pub code: String,
+    pub name: String,
+}
+
+// a populated place from the geonames dump, id is its geonames id
+#[derive(Debug, Deserialize, sqlx::FromRow, Serialize, Clone)]
+pub struct Location{
+    pub id: i64,
+    pub name: String,
+    pub ascii_name: String,
+    pub country_code: String,
+    pub region: Option<String>,
+    pub latitude: f64,
+    pub longitude: f64,
+    pub population: i64,
+    pub timezone: String,
+}
@@ -1,3 +1,12 @@
 pub mod user_model;
 pub mod job_model;
-pub mod application_model;
\ No newline at end of file
+pub mod application_model;
+pub mod api_key_model;
+pub mod queued_job_model;
+pub mod candidate_model;
+
+pub mod skill_model;
+pub mod saved_search_model;
+pub mod saved_job_model;
+pub mod location_model;
+pub mod category_model;
\ No newline at end of file
@@ -0,0 +1,10 @@
+use chrono::prelude::*;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Deserialize, sqlx::FromRow, Serialize, Clone)]
+pub struct SavedJob{
+    pub user_id: uuid::Uuid,
+    pub job_id: uuid::Uuid,
+    pub reminded_at: Option<DateTime<Utc>>,
+    pub created_at: Option<DateTime<Utc>>,
+}
@@ -0,0 +1,24 @@
+use chrono::prelude::*;
+use serde::{Deserialize, Serialize};
+use sqlx::types::Json;
+
+use crate::schema::job_schema::JobFilters;
+
+#[allow(non_snake_case)]
+#[derive(Debug, Deserialize, sqlx::FromRow, Serialize, Clone)]
+pub struct SavedSearch{
+    pub id: uuid::Uuid,
+    pub user_id: uuid::Uuid,
+    pub name: String,
+    pub filters: Json<JobFilters>,
+    pub frequency: String,
+    pub alerts_enabled: bool,
+    pub last_notified_at: DateTime<Utc>,
+    // only ever sent by email
+    #[serde(skip_serializing)]
+    pub unsubscribe_token: String,
+    #[serde(rename = "createdAt")]
+    pub created_at: Option<DateTime<Utc>>,
+    #[serde(rename = "updatedAt")]
+    pub updated_at: Option<DateTime<Utc>>,
+}
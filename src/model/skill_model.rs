@@ -0,0 +1,8 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Deserialize, sqlx::FromRow, Serialize, Clone)]
+pub struct Skill{
+    pub id: uuid::Uuid,
+    pub name: String,
+    pub aliases: Vec<String>,
+}
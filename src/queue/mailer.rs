@@ -0,0 +1,109 @@
+use crate::core::config::config::Config;
+use handlebars::Handlebars;
+use lettre::{
+    message::{header::ContentType, Mailbox},
+    transport::smtp::authentication::Credentials,
+    AsyncSmtpTransport, AsyncTransport, Message, Tokio1Executor,
+};
+use std::sync::Arc;
+
+use super::task::TaskError;
+
+// (name, subject, html body). Subjects are templates too so they can greet
+// the user by name.
+const TEMPLATES: [(&str, &str, &str); 5] = [
+    ("welcome", "Welcome to Trabajo, {{first_name}}", include_str!("../../templates/email/welcome.hbs")),
+    ("verify_email", "Confirm your new email address", include_str!("../../templates/email/verify_email.hbs")),
+    ("email_changed", "Your Trabajo email address was changed", include_str!("../../templates/email/email_changed.hbs")),
+    ("job_alert", "New jobs for your search {{{search_name}}}", include_str!("../../templates/email/job_alert.hbs")),
+    ("saved_job_reminder", "A job you saved closes soon: {{{title}}}", include_str!("../../templates/email/saved_job_reminder.hbs")),
+];
+
+#[derive(Clone)]
+pub struct Mailer{
+    // None when no SMTP host is configured, emails are then only logged
+    transport: Option<AsyncSmtpTransport<Tokio1Executor>>,
+    from: Mailbox,
+    templates: Arc<Handlebars<'static>>,
+}
+
+impl Mailer{
+    pub fn from_config(config: &Config) -> Result<Mailer, String>{
+        let mut templates = Handlebars::new();
+        templates.set_strict_mode(true);
+        for (name, subject, body) in TEMPLATES{
+            templates
+                .register_template_string(&format!("{}.subject", name), subject)
+                .map_err(|e| format!("invalid subject template {}: {}", name, e))?;
+            templates
+                .register_template_string(&format!("{}.body", name), body)
+                .map_err(|e| format!("invalid email template {}: {}", name, e))?;
+        }
+
+        let transport = match &config.smtp_host{
+            None => None,
+            Some(host) => {
+                let builder = if config.smtp_starttls{
+                    AsyncSmtpTransport::<Tokio1Executor>::starttls_relay(host)
+                        .map_err(|e| format!("invalid smtp.host {}: {}", host, e))?
+                } else{
+                    AsyncSmtpTransport::<Tokio1Executor>::builder_dangerous(host)
+                };
+                let builder = builder.port(config.smtp_port);
+                let builder = match (&config.smtp_username, &config.smtp_password){
+                    (Some(username), Some(password)) => {
+                        builder.credentials(Credentials::new(username.to_owned(), password.to_owned()))
+                    }
+                    _ => builder,
+                };
+                Some(builder.build())
+            }
+        };
+
+        Ok(Mailer{
+            transport,
+            from: config.smtp_from.parse().map_err(|e| format!("invalid smtp.from: {}", e))?,
+            templates: Arc::new(templates),
+        })
+    }
+
+    pub async fn send(&self, to: &str, template: &str, context: &serde_json::Value) -> Result<(), TaskError>{
+        let to: Mailbox = to
+            .parse()
+            .map_err(|e| TaskError::Fatal(format!("invalid recipient: {}", e)))?;
+        let subject = self.render(&format!("{}.subject", template), context)?;
+        let body = self.render(&format!("{}.body", template), context)?;
+
+        let transport = match &self.transport{
+            Some(transport) => transport,
+            None => {
+                tracing::info!(template, subject = %subject, "smtp is not configured, email not sent");
+                return Ok(());
+            }
+        };
+
+        let message = Message::builder()
+            .from(self.from.clone())
+            .to(to)
+            .subject(subject)
+            .header(ContentType::TEXT_HTML)
+            .body(body)
+            .map_err(|e| TaskError::Fatal(e.to_string()))?;
+
+        match transport.send(message).await{
+            Ok(_) => Ok(()),
+            // 5xx replies such as an unknown mailbox will not change on retry
+            Err(e) if e.is_permanent() => Err(TaskError::Fatal(e.to_string())),
+            Err(e) => Err(TaskError::Retry(e.to_string())),
+        }
+    }
+
+    fn render(&self, name: &str, context: &serde_json::Value) -> Result<String, TaskError>{
+        if !self.templates.has_template(name){
+            return Err(TaskError::Fatal(format!("unknown email template {}", name)));
+        }
+        self.templates
+            .render(name, context)
+            .map_err(|e| TaskError::Fatal(format!("cannot render {}: {}", name, e)))
+    }
+}
@@ -0,0 +1,73 @@
+pub mod mailer;
+pub mod task;
+pub mod worker;
+
+use crate::{core::helpers::telemetry::db_span, AppState};
+use actix_web::web;
+use chrono::{DateTime, Utc};
+use sqlx::{Pool, Postgres};
+use tracing::Instrument;
+use uuid::Uuid;
+
+use self::{mailer::Mailer, task::Task};
+
+pub const STATUS_PENDING: &str = "pending";
+pub const STATUS_RUNNING: &str = "running";
+pub const STATUS_COMPLETED: &str = "completed";
+pub const STATUS_DEAD: &str = "dead";
+
+// Everything a task needs while it runs. Shared by all workers in the process.
+// Tasks go through the same services as the HTTP handlers.
+pub struct Context{
+    pub db: Pool<Postgres>,
+    pub mailer: Mailer,
+    pub state: web::Data<AppState>,
+}
+
+// Adds a task to jobs_queue. Takes any executor so callers can enqueue inside
+// the transaction that creates the data the task works on. A task with a
+// run_at in the future is not picked up before then.
+pub async fn enqueue<'e, E>(db: E, task: &Task, run_at: Option<DateTime<Utc>>) -> Result<Uuid, sqlx::Error>
+where
+    E: sqlx::PgExecutor<'e>,
+{
+    let (kind, payload) = task.encode();
+    let id = sqlx::query_scalar!(
+        "INSERT INTO jobs_queue (kind, payload, max_attempts, run_at)
+         VALUES ($1, $2, $3, COALESCE($4, NOW())) RETURNING id",
+        kind,
+        payload,
+        task.max_attempts(),
+        run_at
+    )
+    .fetch_one(db)
+    .instrument(db_span("INSERT", "jobs_queue"))
+    .await?;
+
+    tracing::debug!(job_id = %id, kind = %kind, "job enqueued");
+    Ok(id)
+}
+
+// Like enqueue, but does nothing while a task of the same kind is still
+// waiting or running. For periodic tasks that every instance schedules, their
+// kinds have a unique index so two instances racing here queue one task.
+pub async fn enqueue_once<'e, E>(db: E, task: &Task, run_at: Option<DateTime<Utc>>) -> Result<Option<Uuid>, sqlx::Error>
+where
+    E: sqlx::PgExecutor<'e>,
+{
+    let (kind, payload) = task.encode();
+    sqlx::query_scalar!(
+        "INSERT INTO jobs_queue (kind, payload, max_attempts, run_at)
+         SELECT $1::VARCHAR, $2, $3, COALESCE($4, NOW())
+         WHERE NOT EXISTS (SELECT 1 FROM jobs_queue WHERE kind = $1::VARCHAR AND status IN ('pending', 'running'))
+         ON CONFLICT DO NOTHING
+         RETURNING id",
+        kind,
+        payload,
+        task.max_attempts(),
+        run_at
+    )
+    .fetch_optional(db)
+    .instrument(db_span("INSERT", "jobs_queue"))
+    .await
+}
@@ -0,0 +1,238 @@
+use crate::{core::helpers::telemetry::db_span, service::ServiceError};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use tracing::Instrument;
+use uuid::Uuid;
+
+use super::{enqueue, Context};
+
+const THUMBNAIL_SIZE: u32 = 256;
+
+// The work that can be queued. Stored as the variant name in jobs_queue.kind
+// and the fields in jobs_queue.payload, so renaming a variant or a field
+// strands whatever is still queued under the old name.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(tag = "kind", content = "payload", rename_all = "snake_case")]
+pub enum Task{
+    SendEmail{
+        to: String,
+        template: String,
+        context: serde_json::Value,
+    },
+    ParseResume{
+        user_id: Uuid,
+        path: String,
+    },
+    GenerateThumbnail{
+        path: String,
+    },
+    // runs when the grace period of a deletion request made at requested_at
+    // is over
+    PurgeAccount{
+        user_id: Uuid,
+        requested_at: DateTime<Utc>,
+    },
+    PurgeStaleData{
+        applications_before: DateTime<Utc>,
+        deletions_requested_before: DateTime<Utc>,
+    },
+    // alerts for the saved searches that are due at due_at
+    SendJobAlerts{
+        due_at: DateTime<Utc>,
+    },
+    // reminders for the saved jobs that expire before expiring_before
+    SendSavedJobReminders{
+        expiring_before: DateTime<Utc>,
+    },
+}
+
+#[derive(Debug)]
+pub enum TaskError{
+    // worth trying again later, e.g. the SMTP server was unreachable
+    Retry(String),
+    // will fail the same way every time, goes straight to the dead letters
+    Fatal(String),
+}
+
+impl std::fmt::Display for TaskError{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result{
+        match self{
+            TaskError::Retry(message) | TaskError::Fatal(message) => write!(f, "{}", message),
+        }
+    }
+}
+
+impl Task{
+    pub fn encode(&self) -> (String, serde_json::Value){
+        let mut value = serde_json::to_value(self).unwrap();
+        let kind = value["kind"].as_str().unwrap().to_string();
+        (kind, value["payload"].take())
+    }
+
+    pub fn decode(kind: &str, payload: &serde_json::Value) -> Result<Task, TaskError>{
+        serde_json::from_value(serde_json::json!({ "kind": kind, "payload": payload }))
+            .map_err(|e| TaskError::Fatal(format!("cannot decode {} job: {}", kind, e)))
+    }
+
+    pub fn max_attempts(&self) -> i32{
+        match self{
+            Task::SendEmail{ .. } => 8,
+            Task::ParseResume{ .. } | Task::GenerateThumbnail{ .. } => 3,
+            Task::PurgeAccount{ .. } | Task::PurgeStaleData{ .. } => 5,
+            Task::SendJobAlerts{ .. } | Task::SendSavedJobReminders{ .. } => 5,
+        }
+    }
+
+    pub async fn run(&self, ctx: &Context) -> Result<(), TaskError>{
+        match self{
+            Task::SendEmail{ to, template, context } => ctx.mailer.send(to, template, context).await,
+            Task::ParseResume{ user_id, path } => parse_resume(ctx, user_id, path).await,
+            Task::GenerateThumbnail{ path } => generate_thumbnail(path).await,
+            Task::PurgeAccount{ user_id, requested_at } => purge_account(ctx, user_id, requested_at).await,
+            Task::PurgeStaleData{ applications_before, deletions_requested_before } => {
+                purge_stale_data(ctx, applications_before, deletions_requested_before).await
+            }
+            Task::SendJobAlerts{ due_at } => send_job_alerts(ctx, due_at).await,
+            Task::SendSavedJobReminders{ expiring_before } => send_saved_job_reminders(ctx, expiring_before).await,
+        }
+    }
+}
+
+#[derive(PartialEq)]
+enum ResumeKind{
+    Pdf,
+    Image,
+}
+
+// The content type of an upload is whatever the client claimed, so check the
+// file itself. Files that are not what they say are removed from the profile.
+async fn parse_resume(ctx: &Context, user_id: &Uuid, path: &str) -> Result<(), TaskError>{
+    let contents = match tokio::fs::read(path).await{
+        Ok(contents) => contents,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+            tracing::info!(path, "resume was removed before it could be parsed");
+            return Ok(());
+        }
+        Err(e) => return Err(TaskError::Retry(format!("cannot read {}: {}", path, e))),
+    };
+
+    let kind = match sniff(&contents){
+        Some(kind) => kind,
+        None => {
+            tracing::warn!(%user_id, path, "uploaded resume is not a pdf or image, discarding it");
+            sqlx::query!(
+                "UPDATE users SET resume = '' WHERE id = $1 AND resume = $2",
+                user_id,
+                path
+            )
+            .execute(&ctx.db)
+            .instrument(db_span("UPDATE", "users"))
+            .await
+            .map_err(|e| TaskError::Retry(e.to_string()))?;
+            let _ = tokio::fs::remove_file(path).await;
+            return Ok(());
+        }
+    };
+
+    if kind == ResumeKind::Image{
+        enqueue(&ctx.db, &Task::GenerateThumbnail{ path: path.to_string() }, None)
+            .await
+            .map_err(|e| TaskError::Retry(e.to_string()))?;
+    }
+
+    Ok(())
+}
+
+fn sniff(contents: &[u8]) -> Option<ResumeKind>{
+    if contents.starts_with(b"%PDF-"){
+        Some(ResumeKind::Pdf)
+    } else if contents.starts_with(b"\x89PNG\r\n\x1a\n")
+        || contents.starts_with(&[0xFF, 0xD8, 0xFF])
+        || contents.starts_with(b"GIF87a")
+        || contents.starts_with(b"GIF89a")
+    {
+        Some(ResumeKind::Image)
+    } else{
+        None
+    }
+}
+
+// Written next to the original as <file>.thumb.png
+async fn generate_thumbnail(path: &str) -> Result<(), TaskError>{
+    let source = path.to_string();
+    let destination = format!("{}.thumb.png", path);
+
+    let result = tokio::task::spawn_blocking(move || {
+        if !Path::new(&source).exists(){
+            return Ok(());
+        }
+        image::open(&source)
+            .and_then(|img| img.thumbnail(THUMBNAIL_SIZE, THUMBNAIL_SIZE).save(&destination))
+    })
+    .await
+    .map_err(|e| TaskError::Retry(e.to_string()))?;
+
+    // a file that cannot be decoded now will not decode on the next attempt
+    result.map_err(|e| TaskError::Fatal(format!("cannot create thumbnail for {}: {}", path, e)))
+}
+
+// Scrubs the personal data of a user whose deletion was requested at or before
+// requested_before and not cancelled since.
+async fn purge_account(ctx: &Context, user_id: &Uuid, requested_before: &DateTime<Utc>) -> Result<(), TaskError>{
+    let purged = ctx
+        .state
+        .accounts
+        .purge(user_id, requested_before)
+        .await
+        .map_err(|e| TaskError::Retry(e.to_string()))?;
+
+    if purged{
+        tracing::info!(%user_id, "account purged");
+    } else{
+        tracing::info!(%user_id, "account deletion was cancelled, nothing to purge");
+    }
+    Ok(())
+}
+
+// The retention policy: applications and job page events older than the
+// retention period go, and accounts whose purge job got lost are purged
+// anyway.
+async fn purge_stale_data(
+    ctx: &Context,
+    applications_before: &DateTime<Utc>,
+    deletions_requested_before: &DateTime<Utc>,
+) -> Result<(), TaskError>{
+    let retry = |e: ServiceError| TaskError::Retry(e.to_string());
+
+    let applications = ctx.state.accounts.delete_applications_before(applications_before).await.map_err(retry)?;
+    let job_events = ctx.state.analytics.delete_events_before(applications_before).await.map_err(retry)?;
+    let accounts = ctx.state.accounts.purge_overdue(deletions_requested_before).await.map_err(retry)?;
+
+    tracing::info!(applications, job_events, accounts, "stale applicant data purged");
+    Ok(())
+}
+
+async fn send_job_alerts(ctx: &Context, due_at: &DateTime<Utc>) -> Result<(), TaskError>{
+    let sent = ctx
+        .state
+        .saved_searches
+        .send_alerts(due_at)
+        .await
+        .map_err(|e| TaskError::Retry(e.to_string()))?;
+
+    tracing::info!(sent, "job alerts queued");
+    Ok(())
+}
+
+async fn send_saved_job_reminders(ctx: &Context, expiring_before: &DateTime<Utc>) -> Result<(), TaskError>{
+    let sent = ctx
+        .state
+        .saved_jobs
+        .send_reminders(expiring_before)
+        .await
+        .map_err(|e| TaskError::Retry(e.to_string()))?;
+
+    tracing::info!(sent, "saved job reminders queued");
+    Ok(())
+}
@@ -0,0 +1,164 @@
+use crate::{
+    core::helpers::{metrics::Metrics, shutdown::Shutdown, telemetry::db_span},
+    model::queued_job_model::QueuedJob,
+};
+use chrono::{Duration, Utc};
+use std::sync::Arc;
+use tracing::Instrument;
+use uuid::Uuid;
+
+use super::{
+    task::{Task, TaskError},
+    Context, STATUS_COMPLETED, STATUS_DEAD, STATUS_PENDING,
+};
+
+const BASE_BACKOFF_SECONDS: i64 = 10;
+const MAX_BACKOFF_SECONDS: i64 = 60 * 60;
+
+// The name workers of this process put in locked_by, followed by their
+// index. It has to differ between replicas: in a container the pid is nearly
+// always 1 and the hostname can be reused, so a random id is added.
+pub fn process_name() -> String{
+    let host = std::env::var("HOSTNAME")
+        .ok()
+        .or_else(|| std::fs::read_to_string("/etc/hostname").ok())
+        .map(|host| host.trim().chars().take(50).collect::<String>())
+        .filter(|host| !host.is_empty())
+        .unwrap_or_else(|| "unknown".to_string());
+    format!("{}-{}", host, Uuid::new_v4().simple())
+}
+
+pub struct Worker{
+    pub name: String,
+    pub ctx: Arc<Context>,
+    pub metrics: Metrics,
+    pub poll_interval: std::time::Duration,
+    pub lock_timeout: Duration,
+}
+
+impl Worker{
+    // Claims and runs jobs until shutdown. A job that is running when shutdown
+    // is triggered is allowed to finish, the worker just stops claiming more.
+    pub async fn run(self, mut shutdown: Shutdown){
+        tracing::info!(worker = %self.name, "queue worker started");
+        while !shutdown.is_triggered(){
+            match self.claim().await{
+                Ok(Some(job)) => {
+                    self.process(job).await;
+                    continue;
+                }
+                Ok(None) => {}
+                Err(e) => tracing::error!(worker = %self.name, error = %e, "cannot claim job"),
+            }
+
+            tokio::select!{
+                _ = tokio::time::sleep(self.poll_interval) => {},
+                _ = shutdown.triggered() => {},
+            }
+        }
+        tracing::info!(worker = %self.name, "queue worker stopped");
+    }
+
+    // SKIP LOCKED lets any number of workers, in this process or others, poll
+    // the same table without handing the same job out twice. Jobs left
+    // running by a worker that died are picked up again once their lock is
+    // older than the lock timeout.
+    async fn claim(&self) -> Result<Option<QueuedJob>, sqlx::Error>{
+        let stale_before = Utc::now() - self.lock_timeout;
+        sqlx::query_as!(
+            QueuedJob,
+            "UPDATE jobs_queue
+             SET status = 'running', attempts = attempts + 1, locked_at = NOW(), locked_by = $1
+             WHERE id = (
+                 SELECT id FROM jobs_queue
+                 WHERE (status = 'pending' AND run_at <= NOW())
+                    OR (status = 'running' AND locked_at < $2)
+                 ORDER BY run_at
+                 FOR UPDATE SKIP LOCKED
+                 LIMIT 1
+             )
+             RETURNING *",
+            self.name,
+            stale_before
+        )
+        .fetch_optional(&self.ctx.db)
+        .instrument(db_span("UPDATE", "jobs_queue"))
+        .await
+    }
+
+    async fn process(&self, job: QueuedJob){
+        let span = tracing::info_span!("queue_job", job_id = %job.id, kind = %job.kind, attempt = job.attempts);
+
+        let result = async{
+            let task = Task::decode(&job.kind, &job.payload)?;
+            task.run(&self.ctx).await
+        }
+        .instrument(span.clone())
+        .await;
+
+        let outcome = async{
+            match result{
+                Ok(()) => {
+                    tracing::info!("job completed");
+                    self.finish(&job, STATUS_COMPLETED, None, None).await
+                }
+                Err(TaskError::Retry(error)) if job.attempts < job.max_attempts => {
+                    let run_at = Utc::now() + backoff(job.attempts);
+                    tracing::warn!(error = %error, retry_at = %run_at, "job failed, will retry");
+                    self.finish(&job, STATUS_PENDING, Some(error), Some(run_at)).await
+                }
+                Err(error) => {
+                    tracing::error!(error = %error, "job failed permanently, moved to dead letters");
+                    self.finish(&job, STATUS_DEAD, Some(error.to_string()), None).await
+                }
+            }
+        }
+        .instrument(span)
+        .await;
+
+        match outcome{
+            Ok(status) => {
+                let outcome = if status == STATUS_PENDING{ "retried" } else{ status };
+                self.metrics.queue_jobs_total.with_label_values(&[&job.kind, outcome]).inc();
+            }
+            // the lock will time out and the job will run again
+            Err(e) => tracing::error!(job_id = %job.id, error = %e, "cannot record job outcome"),
+        }
+    }
+
+    // Only touches the row while this worker still holds it, a job whose lock
+    // timed out may already belong to someone else.
+    async fn finish(
+        &self,
+        job: &QueuedJob,
+        status: &'static str,
+        error: Option<String>,
+        run_at: Option<chrono::DateTime<Utc>>,
+    ) -> Result<&'static str, sqlx::Error>{
+        sqlx::query!(
+            "UPDATE jobs_queue
+             SET status = $3::VARCHAR,
+                 last_error = COALESCE($4, last_error),
+                 run_at = COALESCE($5, run_at),
+                 completed_at = CASE WHEN $3::VARCHAR = 'completed' THEN NOW() ELSE NULL END,
+                 locked_at = NULL, locked_by = NULL
+             WHERE id = $1 AND locked_by = $2",
+            job.id,
+            self.name,
+            status,
+            error,
+            run_at
+        )
+        .execute(&self.ctx.db)
+        .instrument(db_span("UPDATE", "jobs_queue"))
+        .await?;
+        Ok(status)
+    }
+}
+
+// 10s, 20s, 40s ... capped at an hour
+pub fn backoff(attempt: i32) -> Duration{
+    let exponent = attempt.clamp(1, 20) as u32 - 1;
+    let seconds = BASE_BACKOFF_SECONDS.saturating_mul(2_i64.pow(exponent));
+    Duration::seconds(seconds.min(MAX_BACKOFF_SECONDS))
+}
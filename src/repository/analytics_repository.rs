@@ -0,0 +1,125 @@
+use crate::{
+    core::helpers::telemetry::db_span,
+    schema::analytics_schema::{DailyStats, JobTotals},
+};
+
+use async_trait::async_trait;
+use chrono::{DateTime, NaiveDate, Utc};
+use sqlx::{Pool, Postgres};
+use tracing::Instrument;
+use uuid::Uuid;
+
+#[async_trait]
+pub trait AnalyticsRepository: Send + Sync{
+    // false when the visitor already did the same today
+    async fn record(&self, job_id: &Uuid, kind: &str, visitor: &str) -> Result<bool, sqlx::Error>;
+    async fn company_job_ids(&self, company_name: &str) -> Result<Vec<Uuid>, sqlx::Error>;
+    // newest job first, unknown ids are left out
+    async fn totals(&self, job_ids: &[Uuid], from: &NaiveDate, to: &NaiveDate) -> Result<Vec<JobTotals>, sqlx::Error>;
+    // the jobs together, one entry for every day of the range
+    async fn daily(&self, job_ids: &[Uuid], from: &NaiveDate, to: &NaiveDate) -> Result<Vec<DailyStats>, sqlx::Error>;
+    // returns how many were deleted
+    async fn delete_before(&self, before: &DateTime<Utc>) -> Result<u64, sqlx::Error>;
+}
+
+pub struct PgAnalyticsRepository{
+    db: Pool<Postgres>,
+}
+
+impl PgAnalyticsRepository{
+    pub fn new(db: Pool<Postgres>) -> PgAnalyticsRepository{
+        PgAnalyticsRepository{ db }
+    }
+}
+
+#[async_trait]
+impl AnalyticsRepository for PgAnalyticsRepository{
+    async fn record(&self, job_id: &Uuid, kind: &str, visitor: &str) -> Result<bool, sqlx::Error>{
+        let result = sqlx::query!(
+            "INSERT INTO job_events (job_id, kind, visitor) VALUES ($1, $2, $3) ON CONFLICT DO NOTHING",
+            job_id,
+            kind,
+            visitor
+        )
+        .execute(&self.db)
+        .instrument(db_span("INSERT", "job_events"))
+        .await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    async fn company_job_ids(&self, company_name: &str) -> Result<Vec<Uuid>, sqlx::Error>{
+        sqlx::query_scalar!("SELECT id FROM jobs WHERE company_name = $1", company_name)
+            .fetch_all(&self.db)
+            .instrument(db_span("SELECT", "jobs"))
+            .await
+    }
+
+    // applications are bucketed by their UTC day like the events
+    async fn totals(&self, job_ids: &[Uuid], from: &NaiveDate, to: &NaiveDate) -> Result<Vec<JobTotals>, sqlx::Error>{
+        sqlx::query_as!(
+            JobTotals,
+            r#"SELECT jobs.id AS job_id, jobs.title, jobs.company_name,
+                    (SELECT COUNT(*) FROM job_events
+                     WHERE job_id = jobs.id AND kind = 'view' AND day BETWEEN $2 AND $3) AS "views!",
+                    (SELECT COUNT(*) FROM job_events
+                     WHERE job_id = jobs.id AND kind = 'apply_click' AND day BETWEEN $2 AND $3) AS "apply_clicks!",
+                    (SELECT COUNT(*) FROM applications
+                     WHERE job_id = jobs.id
+                       AND created_at >= $2::DATE::TIMESTAMP AT TIME ZONE 'UTC'
+                       AND created_at < ($3::DATE + 1)::TIMESTAMP AT TIME ZONE 'UTC') AS "applications!",
+                    (SELECT EXTRACT(EPOCH FROM MIN(applications.created_at) - jobs.created_at) / 3600
+                     FROM applications WHERE job_id = jobs.id)::FLOAT8 AS first_application_hours
+             FROM jobs
+             WHERE jobs.id = ANY($1)
+             ORDER BY jobs.created_at DESC"#,
+            job_ids,
+            from,
+            to
+        )
+        .fetch_all(&self.db)
+        .instrument(db_span("SELECT", "job_events"))
+        .await
+    }
+
+    async fn daily(&self, job_ids: &[Uuid], from: &NaiveDate, to: &NaiveDate) -> Result<Vec<DailyStats>, sqlx::Error>{
+        sqlx::query_as!(
+            DailyStats,
+            r#"WITH events AS (
+                 SELECT day, COUNT(*) FILTER (WHERE kind = 'view') AS views,
+                        COUNT(*) FILTER (WHERE kind = 'apply_click') AS apply_clicks
+                 FROM job_events
+                 WHERE job_id = ANY($1) AND day BETWEEN $2 AND $3
+                 GROUP BY day
+             ),
+             applied AS (
+                 SELECT (created_at AT TIME ZONE 'UTC')::DATE AS day, COUNT(*) AS applications
+                 FROM applications
+                 WHERE job_id = ANY($1)
+                   AND created_at >= $2::DATE::TIMESTAMP AT TIME ZONE 'UTC'
+                   AND created_at < ($3::DATE + 1)::TIMESTAMP AT TIME ZONE 'UTC'
+                 GROUP BY 1
+             )
+             SELECT days.day::DATE AS "day!", COALESCE(events.views, 0) AS "views!",
+                    COALESCE(events.apply_clicks, 0) AS "apply_clicks!",
+                    COALESCE(applied.applications, 0) AS "applications!"
+             FROM generate_series($2::DATE::TIMESTAMP, $3::DATE::TIMESTAMP, INTERVAL '1 day') AS days (day)
+             LEFT JOIN events ON events.day = days.day::DATE
+             LEFT JOIN applied ON applied.day = days.day::DATE
+             ORDER BY days.day"#,
+            job_ids,
+            from,
+            to
+        )
+        .fetch_all(&self.db)
+        .instrument(db_span("SELECT", "job_events"))
+        .await
+    }
+
+    async fn delete_before(&self, before: &DateTime<Utc>) -> Result<u64, sqlx::Error>{
+        let result = sqlx::query!("DELETE FROM job_events WHERE created_at < $1", before)
+            .execute(&self.db)
+            .instrument(db_span("DELETE", "job_events"))
+            .await?;
+        Ok(result.rows_affected())
+    }
+}
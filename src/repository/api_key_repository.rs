@@ -0,0 +1,182 @@
+use crate::{
+    core::helpers::telemetry::db_span,
+    model::api_key_model::ApiKey,
+};
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use sqlx::{Pool, Postgres};
+use tracing::Instrument;
+use uuid::Uuid;
+
+pub struct NewApiKey{
+    pub user_id: Uuid,
+    pub company_name: Option<String>,
+    pub name: String,
+    pub prefix: String,
+    pub key_hash: String,
+    pub scopes: Vec<String>,
+    pub expires_at: Option<DateTime<Utc>>,
+}
+
+#[async_trait]
+pub trait ApiKeyRepository: Send + Sync{
+    async fn create(&self, key: &NewApiKey) -> Result<ApiKey, sqlx::Error>;
+    // newest first, revoked and expired keys included
+    async fn list_for_user(&self, user_id: &Uuid) -> Result<Vec<ApiKey>, sqlx::Error>;
+    async fn find_by_prefix(&self, prefix: &str) -> Result<Option<ApiKey>, sqlx::Error>;
+    // Copies a live key of the user under a new prefix and hash. The old key
+    // is revoked, or expires at old_key_expires_at when that is given. None
+    // when the user has no such live key.
+    async fn rotate(
+        &self,
+        id: &Uuid,
+        user_id: &Uuid,
+        prefix: &str,
+        key_hash: &str,
+        old_key_expires_at: Option<DateTime<Utc>>,
+    ) -> Result<Option<ApiKey>, sqlx::Error>;
+    // false when the user has no such live key
+    async fn revoke(&self, id: &Uuid, user_id: &Uuid) -> Result<bool, sqlx::Error>;
+    async fn touch(&self, id: &Uuid) -> Result<(), sqlx::Error>;
+}
+
+pub struct PgApiKeyRepository{
+    db: Pool<Postgres>,
+}
+
+impl PgApiKeyRepository{
+    pub fn new(db: Pool<Postgres>) -> PgApiKeyRepository{
+        PgApiKeyRepository{ db }
+    }
+}
+
+#[async_trait]
+impl ApiKeyRepository for PgApiKeyRepository{
+    async fn create(&self, key: &NewApiKey) -> Result<ApiKey, sqlx::Error>{
+        sqlx::query_as!(
+            ApiKey,
+            "INSERT INTO api_keys (user_id, company_name, name, prefix, key_hash, scopes, expires_at)
+             VALUES ($1, $2, $3, $4, $5, $6, $7) RETURNING *",
+            key.user_id,
+            key.company_name,
+            key.name,
+            key.prefix,
+            key.key_hash,
+            &key.scopes,
+            key.expires_at
+        )
+        .fetch_one(&self.db)
+        .instrument(db_span("INSERT", "api_keys"))
+        .await
+    }
+
+    async fn list_for_user(&self, user_id: &Uuid) -> Result<Vec<ApiKey>, sqlx::Error>{
+        sqlx::query_as!(
+            ApiKey,
+            "SELECT * FROM api_keys WHERE user_id = $1 ORDER BY created_at DESC",
+            user_id
+        )
+        .fetch_all(&self.db)
+        .instrument(db_span("SELECT", "api_keys"))
+        .await
+    }
+
+    async fn find_by_prefix(&self, prefix: &str) -> Result<Option<ApiKey>, sqlx::Error>{
+        sqlx::query_as!(ApiKey, "SELECT * FROM api_keys WHERE prefix = $1", prefix)
+            .fetch_optional(&self.db)
+            .instrument(db_span("SELECT", "api_keys"))
+            .await
+    }
+
+    async fn rotate(
+        &self,
+        id: &Uuid,
+        user_id: &Uuid,
+        prefix: &str,
+        key_hash: &str,
+        old_key_expires_at: Option<DateTime<Utc>>,
+    ) -> Result<Option<ApiKey>, sqlx::Error>{
+        let mut tx = self.db.begin().await?;
+
+        let current = sqlx::query_as!(
+            ApiKey,
+            "SELECT * FROM api_keys WHERE id = $1 AND user_id = $2 AND revoked_at IS NULL FOR UPDATE",
+            id,
+            user_id
+        )
+        .fetch_optional(&mut *tx)
+        .instrument(db_span("SELECT", "api_keys"))
+        .await?;
+
+        let current = match current{
+            Some(key) => key,
+            None => return Ok(None),
+        };
+
+        let rotated = sqlx::query_as!(
+            ApiKey,
+            "INSERT INTO api_keys (user_id, company_name, name, prefix, key_hash, scopes, expires_at)
+             VALUES ($1, $2, $3, $4, $5, $6, $7) RETURNING *",
+            current.user_id,
+            current.company_name,
+            current.name,
+            prefix,
+            key_hash,
+            &current.scopes,
+            current.expires_at
+        )
+        .fetch_one(&mut *tx)
+        .instrument(db_span("INSERT", "api_keys"))
+        .await?;
+
+        match old_key_expires_at{
+            None => {
+                sqlx::query!("UPDATE api_keys SET revoked_at = NOW() WHERE id = $1", current.id)
+                    .execute(&mut *tx)
+                    .instrument(db_span("UPDATE", "api_keys"))
+                    .await?;
+            }
+            Some(expires_at) => {
+                sqlx::query!(
+                    "UPDATE api_keys SET expires_at = LEAST(COALESCE(expires_at, $2), $2) WHERE id = $1",
+                    current.id,
+                    expires_at
+                )
+                .execute(&mut *tx)
+                .instrument(db_span("UPDATE", "api_keys"))
+                .await?;
+            }
+        }
+
+        tx.commit().await?;
+        Ok(Some(rotated))
+    }
+
+    async fn revoke(&self, id: &Uuid, user_id: &Uuid) -> Result<bool, sqlx::Error>{
+        let revoked = sqlx::query!(
+            "UPDATE api_keys SET revoked_at = NOW()
+             WHERE id = $1 AND user_id = $2 AND revoked_at IS NULL",
+            id,
+            user_id
+        )
+        .execute(&self.db)
+        .instrument(db_span("UPDATE", "api_keys"))
+        .await?;
+        Ok(revoked.rows_affected() > 0)
+    }
+
+    // only touches the row once a minute so busy integrations don't turn
+    // every request into a write
+    async fn touch(&self, id: &Uuid) -> Result<(), sqlx::Error>{
+        sqlx::query!(
+            "UPDATE api_keys SET last_used_at = NOW()
+             WHERE id = $1 AND (last_used_at IS NULL OR last_used_at < NOW() - INTERVAL '1 minute')",
+            id
+        )
+        .execute(&self.db)
+        .instrument(db_span("UPDATE", "api_keys"))
+        .await?;
+        Ok(())
+    }
+}
@@ -0,0 +1,135 @@
+use crate::{
+    core::helpers::telemetry::db_span,
+    model::application_model::Application,
+    schema::application_schema::{FetchApplication, UserApplication},
+};
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use sqlx::{Pool, Postgres};
+use tracing::Instrument;
+use uuid::Uuid;
+
+#[async_trait]
+pub trait ApplicationRepository: Send + Sync{
+    // None when the user already applied to the job
+    async fn create(&self, user_id: &Uuid, job_id: &Uuid) -> Result<Option<Application>, sqlx::Error>;
+    async fn find(&self, user_id: &Uuid, job_id: &Uuid) -> Result<Option<Application>, sqlx::Error>;
+    // None for an unknown job, false once it has expired
+    async fn job_is_open(&self, job_id: &Uuid) -> Result<Option<bool>, sqlx::Error>;
+    async fn list(&self, limit: i64, offset: i64) -> Result<Vec<FetchApplication>, sqlx::Error>;
+    async fn list_for_job(&self, job_id: &Uuid) -> Result<Vec<FetchApplication>, sqlx::Error>;
+    async fn list_for_user(&self, user_id: &Uuid) -> Result<Vec<UserApplication>, sqlx::Error>;
+    // returns how many were deleted
+    async fn delete_before(&self, before: &DateTime<Utc>) -> Result<u64, sqlx::Error>;
+}
+
+pub struct PgApplicationRepository{
+    db: Pool<Postgres>,
+}
+
+impl PgApplicationRepository{
+    pub fn new(db: Pool<Postgres>) -> PgApplicationRepository{
+        PgApplicationRepository{ db }
+    }
+}
+
+#[async_trait]
+impl ApplicationRepository for PgApplicationRepository{
+    async fn create(&self, user_id: &Uuid, job_id: &Uuid) -> Result<Option<Application>, sqlx::Error>{
+        sqlx::query_as!(
+            Application,
+            "INSERT INTO applications (job_id, user_id) VALUES ($1, $2)
+             ON CONFLICT (job_id, user_id) DO NOTHING RETURNING *",
+            job_id,
+            user_id
+        )
+        .fetch_optional(&self.db)
+        .instrument(db_span("INSERT", "applications"))
+        .await
+    }
+
+    async fn find(&self, user_id: &Uuid, job_id: &Uuid) -> Result<Option<Application>, sqlx::Error>{
+        sqlx::query_as!(
+            Application,
+            "SELECT * FROM applications WHERE user_id = $1 AND job_id = $2",
+            user_id,
+            job_id
+        )
+        .fetch_optional(&self.db)
+        .instrument(db_span("SELECT", "applications"))
+        .await
+    }
+
+    async fn job_is_open(&self, job_id: &Uuid) -> Result<Option<bool>, sqlx::Error>{
+        sqlx::query_scalar!(
+            r#"SELECT (expires_at IS NULL OR expires_at > NOW()) AS "open!" FROM jobs WHERE id = $1"#,
+            job_id
+        )
+        .fetch_optional(&self.db)
+        .instrument(db_span("SELECT", "jobs"))
+        .await
+    }
+
+    async fn list(&self, limit: i64, offset: i64) -> Result<Vec<FetchApplication>, sqlx::Error>{
+        sqlx::query_as!(
+            FetchApplication,
+            r#"SELECT applications.id, applications.job_id, applications.created_at, users.first_name, users.last_name, users.id AS applicant_id,
+                    COALESCE(candidate_profiles.headline, '') AS "headline!",
+                    COALESCE(candidate_profiles.city, '') AS "city!",
+                    COALESCE(candidate_profiles.country, '') AS "country!",
+                    ARRAY(SELECT name FROM candidate_skills WHERE candidate_skills.user_id = users.id ORDER BY created_at) AS "skills!"
+             FROM applications
+             INNER JOIN users ON applications.user_id = users.id
+             LEFT JOIN candidate_profiles ON candidate_profiles.user_id = users.id
+             LIMIT $1 OFFSET $2"#,
+            limit,
+            offset
+        )
+        .fetch_all(&self.db)
+        .instrument(db_span("SELECT", "applications"))
+        .await
+    }
+
+    async fn list_for_job(&self, job_id: &Uuid) -> Result<Vec<FetchApplication>, sqlx::Error>{
+        sqlx::query_as!(
+            FetchApplication,
+            r#"SELECT applications.id, applications.job_id, applications.created_at, users.first_name, users.last_name, users.id AS applicant_id,
+                    COALESCE(candidate_profiles.headline, '') AS "headline!",
+                    COALESCE(candidate_profiles.city, '') AS "city!",
+                    COALESCE(candidate_profiles.country, '') AS "country!",
+                    ARRAY(SELECT name FROM candidate_skills WHERE candidate_skills.user_id = users.id ORDER BY created_at) AS "skills!"
+             FROM applications
+             INNER JOIN users ON applications.user_id = users.id
+             LEFT JOIN candidate_profiles ON candidate_profiles.user_id = users.id
+             WHERE applications.job_id = $1"#,
+            job_id
+        )
+        .fetch_all(&self.db)
+        .instrument(db_span("SELECT", "applications"))
+        .await
+    }
+
+    async fn list_for_user(&self, user_id: &Uuid) -> Result<Vec<UserApplication>, sqlx::Error>{
+        sqlx::query_as!(
+            UserApplication,
+            "SELECT applications.id, applications.job_id, jobs.title, jobs.company_name, applications.created_at
+             FROM applications
+             INNER JOIN jobs ON applications.job_id = jobs.id
+             WHERE applications.user_id = $1
+             ORDER BY applications.created_at",
+            user_id
+        )
+        .fetch_all(&self.db)
+        .instrument(db_span("SELECT", "applications"))
+        .await
+    }
+
+    async fn delete_before(&self, before: &DateTime<Utc>) -> Result<u64, sqlx::Error>{
+        let result = sqlx::query!("DELETE FROM applications WHERE created_at < $1", before)
+            .execute(&self.db)
+            .instrument(db_span("DELETE", "applications"))
+            .await?;
+        Ok(result.rows_affected())
+    }
+}
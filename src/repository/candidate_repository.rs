@@ -0,0 +1,414 @@
+use crate::{
+    core::helpers::telemetry::db_span,
+    model::candidate_model::{
+        CandidateProfile, CandidateSkill, Certification, Education, PortfolioLink, WorkExperience,
+    },
+    schema::{
+        candidate_schema::{
+            CertificationInput, EducationInput, PortfolioLinkInput, ProfileSection, SkillInput,
+            UpdateCandidateProfile, WorkExperienceInput,
+        },
+        recommendation_schema::MatchProfile,
+    },
+};
+
+use async_trait::async_trait;
+use sqlx::{Pool, Postgres};
+use tracing::Instrument;
+use uuid::Uuid;
+
+// Every entry is scoped to its owner, an id that belongs to somebody else
+// behaves as if it did not exist. Updates return None in that case.
+#[async_trait]
+pub trait CandidateRepository: Send + Sync{
+    async fn find_profile(&self, user_id: &Uuid) -> Result<Option<CandidateProfile>, sqlx::Error>;
+    async fn upsert_profile(&self, user_id: &Uuid, profile: &UpdateCandidateProfile) -> Result<CandidateProfile, sqlx::Error>;
+    async fn list_experience(&self, user_id: &Uuid) -> Result<Vec<WorkExperience>, sqlx::Error>;
+    async fn add_experience(&self, user_id: &Uuid, entry: &WorkExperienceInput) -> Result<WorkExperience, sqlx::Error>;
+    async fn update_experience(&self, user_id: &Uuid, id: &Uuid, entry: &WorkExperienceInput) -> Result<Option<WorkExperience>, sqlx::Error>;
+    async fn list_education(&self, user_id: &Uuid) -> Result<Vec<Education>, sqlx::Error>;
+    async fn add_education(&self, user_id: &Uuid, entry: &EducationInput) -> Result<Education, sqlx::Error>;
+    async fn update_education(&self, user_id: &Uuid, id: &Uuid, entry: &EducationInput) -> Result<Option<Education>, sqlx::Error>;
+    async fn list_certifications(&self, user_id: &Uuid) -> Result<Vec<Certification>, sqlx::Error>;
+    async fn add_certification(&self, user_id: &Uuid, entry: &CertificationInput) -> Result<Certification, sqlx::Error>;
+    async fn update_certification(&self, user_id: &Uuid, id: &Uuid, entry: &CertificationInput) -> Result<Option<Certification>, sqlx::Error>;
+    async fn list_skills(&self, user_id: &Uuid) -> Result<Vec<CandidateSkill>, sqlx::Error>;
+    // skill_id links the entry to the skills taxonomy
+    async fn add_skill(&self, user_id: &Uuid, entry: &SkillInput, skill_id: Option<&Uuid>) -> Result<CandidateSkill, sqlx::Error>;
+    async fn update_skill(&self, user_id: &Uuid, id: &Uuid, entry: &SkillInput, skill_id: Option<&Uuid>) -> Result<Option<CandidateSkill>, sqlx::Error>;
+    async fn list_links(&self, user_id: &Uuid) -> Result<Vec<PortfolioLink>, sqlx::Error>;
+    async fn add_link(&self, user_id: &Uuid, entry: &PortfolioLinkInput) -> Result<PortfolioLink, sqlx::Error>;
+    async fn update_link(&self, user_id: &Uuid, id: &Uuid, entry: &PortfolioLinkInput) -> Result<Option<PortfolioLink>, sqlx::Error>;
+    // false when there was nothing to remove
+    async fn remove(&self, section: ProfileSection, user_id: &Uuid, id: &Uuid) -> Result<bool, sqlx::Error>;
+    async fn find_match_profile(&self, user_id: &Uuid) -> Result<Option<MatchProfile>, sqlx::Error>;
+    // active candidates that filled in a profile or skills, most recently
+    // updated first
+    async fn list_match_profiles(&self, limit: i64) -> Result<Vec<MatchProfile>, sqlx::Error>;
+}
+
+pub struct PgCandidateRepository{
+    db: Pool<Postgres>,
+}
+
+impl PgCandidateRepository{
+    pub fn new(db: Pool<Postgres>) -> PgCandidateRepository{
+        PgCandidateRepository{ db }
+    }
+}
+
+#[async_trait]
+impl CandidateRepository for PgCandidateRepository{
+    async fn find_profile(&self, user_id: &Uuid) -> Result<Option<CandidateProfile>, sqlx::Error>{
+        sqlx::query_as!(CandidateProfile, "SELECT * FROM candidate_profiles WHERE user_id = $1", user_id)
+            .fetch_optional(&self.db)
+            .instrument(db_span("SELECT", "candidate_profiles"))
+            .await
+    }
+
+    async fn upsert_profile(&self, user_id: &Uuid, profile: &UpdateCandidateProfile) -> Result<CandidateProfile, sqlx::Error>{
+        sqlx::query_as!(
+            CandidateProfile,
+            "INSERT INTO candidate_profiles
+                (user_id, headline, summary, city, country, open_to_remote, desired_roles, salary_min, salary_max, salary_currency)
+             VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)
+             ON CONFLICT (user_id) DO UPDATE SET
+                headline = EXCLUDED.headline, summary = EXCLUDED.summary, city = EXCLUDED.city,
+                country = EXCLUDED.country, open_to_remote = EXCLUDED.open_to_remote,
+                desired_roles = EXCLUDED.desired_roles, salary_min = EXCLUDED.salary_min,
+                salary_max = EXCLUDED.salary_max, salary_currency = EXCLUDED.salary_currency
+             RETURNING *",
+            user_id,
+            profile.headline,
+            profile.summary,
+            profile.city,
+            profile.country,
+            profile.open_to_remote,
+            &profile.desired_roles,
+            profile.salary_min,
+            profile.salary_max,
+            profile.salary_currency
+        )
+        .fetch_one(&self.db)
+        .instrument(db_span("INSERT", "candidate_profiles"))
+        .await
+    }
+
+    async fn list_experience(&self, user_id: &Uuid) -> Result<Vec<WorkExperience>, sqlx::Error>{
+        sqlx::query_as!(
+            WorkExperience,
+            "SELECT * FROM work_experiences WHERE user_id = $1
+             ORDER BY end_date DESC NULLS FIRST, start_date DESC",
+            user_id
+        )
+        .fetch_all(&self.db)
+        .instrument(db_span("SELECT", "work_experiences"))
+        .await
+    }
+
+    async fn add_experience(&self, user_id: &Uuid, entry: &WorkExperienceInput) -> Result<WorkExperience, sqlx::Error>{
+        sqlx::query_as!(
+            WorkExperience,
+            "INSERT INTO work_experiences (user_id, company_name, title, location, start_date, end_date, description)
+             VALUES ($1, $2, $3, $4, $5, $6, $7) RETURNING *",
+            user_id,
+            entry.company_name,
+            entry.title,
+            entry.location,
+            entry.start_date,
+            entry.end_date,
+            entry.description
+        )
+        .fetch_one(&self.db)
+        .instrument(db_span("INSERT", "work_experiences"))
+        .await
+    }
+
+    async fn update_experience(&self, user_id: &Uuid, id: &Uuid, entry: &WorkExperienceInput) -> Result<Option<WorkExperience>, sqlx::Error>{
+        sqlx::query_as!(
+            WorkExperience,
+            "UPDATE work_experiences
+             SET company_name = $3, title = $4, location = $5, start_date = $6, end_date = $7, description = $8
+             WHERE id = $1 AND user_id = $2 RETURNING *",
+            id,
+            user_id,
+            entry.company_name,
+            entry.title,
+            entry.location,
+            entry.start_date,
+            entry.end_date,
+            entry.description
+        )
+        .fetch_optional(&self.db)
+        .instrument(db_span("UPDATE", "work_experiences"))
+        .await
+    }
+
+    async fn list_education(&self, user_id: &Uuid) -> Result<Vec<Education>, sqlx::Error>{
+        sqlx::query_as!(
+            Education,
+            "SELECT * FROM educations WHERE user_id = $1
+             ORDER BY end_date DESC NULLS FIRST, start_date DESC",
+            user_id
+        )
+        .fetch_all(&self.db)
+        .instrument(db_span("SELECT", "educations"))
+        .await
+    }
+
+    async fn add_education(&self, user_id: &Uuid, entry: &EducationInput) -> Result<Education, sqlx::Error>{
+        sqlx::query_as!(
+            Education,
+            "INSERT INTO educations (user_id, institution, degree, field_of_study, start_date, end_date)
+             VALUES ($1, $2, $3, $4, $5, $6) RETURNING *",
+            user_id,
+            entry.institution,
+            entry.degree,
+            entry.field_of_study,
+            entry.start_date,
+            entry.end_date
+        )
+        .fetch_one(&self.db)
+        .instrument(db_span("INSERT", "educations"))
+        .await
+    }
+
+    async fn update_education(&self, user_id: &Uuid, id: &Uuid, entry: &EducationInput) -> Result<Option<Education>, sqlx::Error>{
+        sqlx::query_as!(
+            Education,
+            "UPDATE educations
+             SET institution = $3, degree = $4, field_of_study = $5, start_date = $6, end_date = $7
+             WHERE id = $1 AND user_id = $2 RETURNING *",
+            id,
+            user_id,
+            entry.institution,
+            entry.degree,
+            entry.field_of_study,
+            entry.start_date,
+            entry.end_date
+        )
+        .fetch_optional(&self.db)
+        .instrument(db_span("UPDATE", "educations"))
+        .await
+    }
+
+    async fn list_certifications(&self, user_id: &Uuid) -> Result<Vec<Certification>, sqlx::Error>{
+        sqlx::query_as!(
+            Certification,
+            "SELECT * FROM certifications WHERE user_id = $1 ORDER BY issued_on DESC",
+            user_id
+        )
+        .fetch_all(&self.db)
+        .instrument(db_span("SELECT", "certifications"))
+        .await
+    }
+
+    async fn add_certification(&self, user_id: &Uuid, entry: &CertificationInput) -> Result<Certification, sqlx::Error>{
+        sqlx::query_as!(
+            Certification,
+            "INSERT INTO certifications (user_id, name, issuer, issued_on, expires_on, credential_url)
+             VALUES ($1, $2, $3, $4, $5, $6) RETURNING *",
+            user_id,
+            entry.name,
+            entry.issuer,
+            entry.issued_on,
+            entry.expires_on,
+            entry.credential_url
+        )
+        .fetch_one(&self.db)
+        .instrument(db_span("INSERT", "certifications"))
+        .await
+    }
+
+    async fn update_certification(&self, user_id: &Uuid, id: &Uuid, entry: &CertificationInput) -> Result<Option<Certification>, sqlx::Error>{
+        sqlx::query_as!(
+            Certification,
+            "UPDATE certifications
+             SET name = $3, issuer = $4, issued_on = $5, expires_on = $6, credential_url = $7
+             WHERE id = $1 AND user_id = $2 RETURNING *",
+            id,
+            user_id,
+            entry.name,
+            entry.issuer,
+            entry.issued_on,
+            entry.expires_on,
+            entry.credential_url
+        )
+        .fetch_optional(&self.db)
+        .instrument(db_span("UPDATE", "certifications"))
+        .await
+    }
+
+    async fn list_skills(&self, user_id: &Uuid) -> Result<Vec<CandidateSkill>, sqlx::Error>{
+        sqlx::query_as!(
+            CandidateSkill,
+            "SELECT * FROM candidate_skills WHERE user_id = $1 ORDER BY created_at",
+            user_id
+        )
+        .fetch_all(&self.db)
+        .instrument(db_span("SELECT", "candidate_skills"))
+        .await
+    }
+
+    async fn add_skill(&self, user_id: &Uuid, entry: &SkillInput, skill_id: Option<&Uuid>) -> Result<CandidateSkill, sqlx::Error>{
+        sqlx::query_as!(
+            CandidateSkill,
+            "INSERT INTO candidate_skills (user_id, name, proficiency, years_of_experience, skill_id)
+             VALUES ($1, $2, $3, $4, $5) RETURNING *",
+            user_id,
+            entry.name,
+            entry.proficiency,
+            entry.years_of_experience,
+            skill_id
+        )
+        .fetch_one(&self.db)
+        .instrument(db_span("INSERT", "candidate_skills"))
+        .await
+    }
+
+    async fn update_skill(&self, user_id: &Uuid, id: &Uuid, entry: &SkillInput, skill_id: Option<&Uuid>) -> Result<Option<CandidateSkill>, sqlx::Error>{
+        sqlx::query_as!(
+            CandidateSkill,
+            "UPDATE candidate_skills SET name = $3, proficiency = $4, years_of_experience = $5, skill_id = $6
+             WHERE id = $1 AND user_id = $2 RETURNING *",
+            id,
+            user_id,
+            entry.name,
+            entry.proficiency,
+            entry.years_of_experience,
+            skill_id
+        )
+        .fetch_optional(&self.db)
+        .instrument(db_span("UPDATE", "candidate_skills"))
+        .await
+    }
+
+    async fn list_links(&self, user_id: &Uuid) -> Result<Vec<PortfolioLink>, sqlx::Error>{
+        sqlx::query_as!(
+            PortfolioLink,
+            "SELECT * FROM portfolio_links WHERE user_id = $1 ORDER BY created_at",
+            user_id
+        )
+        .fetch_all(&self.db)
+        .instrument(db_span("SELECT", "portfolio_links"))
+        .await
+    }
+
+    async fn add_link(&self, user_id: &Uuid, entry: &PortfolioLinkInput) -> Result<PortfolioLink, sqlx::Error>{
+        sqlx::query_as!(
+            PortfolioLink,
+            "INSERT INTO portfolio_links (user_id, label, url) VALUES ($1, $2, $3) RETURNING *",
+            user_id,
+            entry.label,
+            entry.url
+        )
+        .fetch_one(&self.db)
+        .instrument(db_span("INSERT", "portfolio_links"))
+        .await
+    }
+
+    async fn update_link(&self, user_id: &Uuid, id: &Uuid, entry: &PortfolioLinkInput) -> Result<Option<PortfolioLink>, sqlx::Error>{
+        sqlx::query_as!(
+            PortfolioLink,
+            "UPDATE portfolio_links SET label = $3, url = $4 WHERE id = $1 AND user_id = $2 RETURNING *",
+            id,
+            user_id,
+            entry.label,
+            entry.url
+        )
+        .fetch_optional(&self.db)
+        .instrument(db_span("UPDATE", "portfolio_links"))
+        .await
+    }
+
+    async fn remove(&self, section: ProfileSection, user_id: &Uuid, id: &Uuid) -> Result<bool, sqlx::Error>{
+        let result = match section{
+            ProfileSection::Experience => {
+                sqlx::query!("DELETE FROM work_experiences WHERE id = $1 AND user_id = $2", id, user_id)
+                    .execute(&self.db)
+                    .instrument(db_span("DELETE", "work_experiences"))
+                    .await?
+            }
+            ProfileSection::Education => {
+                sqlx::query!("DELETE FROM educations WHERE id = $1 AND user_id = $2", id, user_id)
+                    .execute(&self.db)
+                    .instrument(db_span("DELETE", "educations"))
+                    .await?
+            }
+            ProfileSection::Certifications => {
+                sqlx::query!("DELETE FROM certifications WHERE id = $1 AND user_id = $2", id, user_id)
+                    .execute(&self.db)
+                    .instrument(db_span("DELETE", "certifications"))
+                    .await?
+            }
+            ProfileSection::Skills => {
+                sqlx::query!("DELETE FROM candidate_skills WHERE id = $1 AND user_id = $2", id, user_id)
+                    .execute(&self.db)
+                    .instrument(db_span("DELETE", "candidate_skills"))
+                    .await?
+            }
+            ProfileSection::Links => {
+                sqlx::query!("DELETE FROM portfolio_links WHERE id = $1 AND user_id = $2", id, user_id)
+                    .execute(&self.db)
+                    .instrument(db_span("DELETE", "portfolio_links"))
+                    .await?
+            }
+        };
+        Ok(result.rows_affected() > 0)
+    }
+
+    async fn find_match_profile(&self, user_id: &Uuid) -> Result<Option<MatchProfile>, sqlx::Error>{
+        sqlx::query_as!(
+            MatchProfile,
+            r#"SELECT users.id AS user_id, users.first_name, users.last_name,
+                    COALESCE(candidate_profiles.headline, '') AS "headline!",
+                    COALESCE(candidate_profiles.city, '') AS "city!",
+                    COALESCE(candidate_profiles.country, '') AS "country!",
+                    (SELECT code FROM countries
+                     WHERE code = UPPER(TRIM(candidate_profiles.country)) OR LOWER(name) = LOWER(TRIM(candidate_profiles.country))
+                     LIMIT 1) AS "country_code?",
+                    COALESCE(candidate_profiles.open_to_remote, FALSE) AS "open_to_remote!",
+                    candidate_profiles.salary_min AS "salary_min?",
+                    candidate_profiles.salary_max AS "salary_max?",
+                    candidate_profiles.salary_currency AS "salary_currency?",
+                    ARRAY(SELECT skill_id FROM candidate_skills WHERE candidate_skills.user_id = users.id AND skill_id IS NOT NULL) AS "skill_ids!: Vec<Uuid>",
+                    (SELECT (CURRENT_DATE - MIN(start_date))::FLOAT8 / 365.25 FROM work_experiences WHERE work_experiences.user_id = users.id) AS "years_of_experience?"
+             FROM users
+             LEFT JOIN candidate_profiles ON candidate_profiles.user_id = users.id
+             WHERE users.id = $1"#,
+            user_id
+        )
+        .fetch_optional(&self.db)
+        .instrument(db_span("SELECT", "users"))
+        .await
+    }
+
+    async fn list_match_profiles(&self, limit: i64) -> Result<Vec<MatchProfile>, sqlx::Error>{
+        sqlx::query_as!(
+            MatchProfile,
+            r#"SELECT users.id AS user_id, users.first_name, users.last_name,
+                    COALESCE(candidate_profiles.headline, '') AS "headline!",
+                    COALESCE(candidate_profiles.city, '') AS "city!",
+                    COALESCE(candidate_profiles.country, '') AS "country!",
+                    (SELECT code FROM countries
+                     WHERE code = UPPER(TRIM(candidate_profiles.country)) OR LOWER(name) = LOWER(TRIM(candidate_profiles.country))
+                     LIMIT 1) AS "country_code?",
+                    COALESCE(candidate_profiles.open_to_remote, FALSE) AS "open_to_remote!",
+                    candidate_profiles.salary_min AS "salary_min?",
+                    candidate_profiles.salary_max AS "salary_max?",
+                    candidate_profiles.salary_currency AS "salary_currency?",
+                    ARRAY(SELECT skill_id FROM candidate_skills WHERE candidate_skills.user_id = users.id AND skill_id IS NOT NULL) AS "skill_ids!: Vec<Uuid>",
+                    (SELECT (CURRENT_DATE - MIN(start_date))::FLOAT8 / 365.25 FROM work_experiences WHERE work_experiences.user_id = users.id) AS "years_of_experience?"
+             FROM users
+             LEFT JOIN candidate_profiles ON candidate_profiles.user_id = users.id
+             WHERE users.role = 'user' AND users.is_active AND users.deletion_requested_at IS NULL
+               AND (candidate_profiles.user_id IS NOT NULL OR EXISTS (SELECT 1 FROM candidate_skills WHERE candidate_skills.user_id = users.id))
+             ORDER BY GREATEST(candidate_profiles.updated_at, users.updated_at) DESC
+             LIMIT $1"#,
+            limit
+        )
+        .fetch_all(&self.db)
+        .instrument(db_span("SELECT", "users"))
+        .await
+    }
+}
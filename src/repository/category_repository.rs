@@ -0,0 +1,73 @@
+use crate::{core::helpers::telemetry::db_span, model::category_model::Category};
+
+use async_trait::async_trait;
+use sqlx::{Pool, Postgres};
+use tracing::Instrument;
+use uuid::Uuid;
+
+#[async_trait]
+pub trait CategoryRepository: Send + Sync{
+    // every category, by name
+    async fn list(&self) -> Result<Vec<Category>, sqlx::Error>;
+    async fn find_by_slug(&self, slug: &str) -> Result<Option<Category>, sqlx::Error>;
+    async fn create(&self, parent_id: Option<&Uuid>, slug: &str, name: &str) -> Result<Category, sqlx::Error>;
+    // the category with that slug and all the ones below it, empty when
+    // the slug is unknown
+    async fn subtree(&self, slug: &str) -> Result<Vec<Uuid>, sqlx::Error>;
+}
+
+pub struct PgCategoryRepository{
+    db: Pool<Postgres>,
+}
+
+impl PgCategoryRepository{
+    pub fn new(db: Pool<Postgres>) -> PgCategoryRepository{
+        PgCategoryRepository{ db }
+    }
+}
+
+#[async_trait]
+impl CategoryRepository for PgCategoryRepository{
+    async fn list(&self) -> Result<Vec<Category>, sqlx::Error>{
+        sqlx::query_as!(Category, "SELECT id, parent_id, slug, name FROM job_categories ORDER BY name")
+            .fetch_all(&self.db)
+            .instrument(db_span("SELECT", "job_categories"))
+            .await
+    }
+
+    async fn find_by_slug(&self, slug: &str) -> Result<Option<Category>, sqlx::Error>{
+        sqlx::query_as!(Category, "SELECT id, parent_id, slug, name FROM job_categories WHERE slug = $1", slug)
+            .fetch_optional(&self.db)
+            .instrument(db_span("SELECT", "job_categories"))
+            .await
+    }
+
+    async fn create(&self, parent_id: Option<&Uuid>, slug: &str, name: &str) -> Result<Category, sqlx::Error>{
+        sqlx::query_as!(
+            Category,
+            "INSERT INTO job_categories (parent_id, slug, name) VALUES ($1, $2, $3) RETURNING id, parent_id, slug, name",
+            parent_id,
+            slug,
+            name
+        )
+        .fetch_one(&self.db)
+        .instrument(db_span("INSERT", "job_categories"))
+        .await
+    }
+
+    async fn subtree(&self, slug: &str) -> Result<Vec<Uuid>, sqlx::Error>{
+        sqlx::query_scalar!(
+            r#"WITH RECURSIVE subtree (id) AS (
+                 SELECT id FROM job_categories WHERE slug = $1
+                 UNION ALL
+                 SELECT job_categories.id FROM job_categories
+                 INNER JOIN subtree ON job_categories.parent_id = subtree.id
+             )
+             SELECT id AS "id!" FROM subtree"#,
+            slug
+        )
+        .fetch_all(&self.db)
+        .instrument(db_span("SELECT", "job_categories"))
+        .await
+    }
+}
@@ -0,0 +1,298 @@
+use crate::{
+    core::helpers::telemetry::db_span,
+    model::job_model::Job,
+    schema::job_schema::{CreateJobPosting, FacetCount, JobSearch, NormalizedJob},
+};
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use sqlx::{Pool, Postgres};
+use tracing::Instrument;
+use uuid::Uuid;
+
+use super::escape_like;
+
+// values listed for the country, city and company facets
+const FACET_VALUE_LIMIT: i64 = 20;
+
+#[async_trait]
+pub trait JobRepository: Send + Sync{
+    // skills are (skill id, required) pairs
+    async fn create(&self, job: &CreateJobPosting, skills: &[(Uuid, bool)], normalized: &NormalizedJob) -> Result<Job, sqlx::Error>;
+    // Lookups by id return expired jobs too, the listings below only open
+    // ones.
+    async fn find_by_id(&self, id: &Uuid) -> Result<Option<Job>, sqlx::Error>;
+    // unknown ids are left out, the order is not kept
+    async fn find_by_ids(&self, ids: &[Uuid]) -> Result<Vec<Job>, sqlx::Error>;
+    async fn list(&self, search: &JobSearch, limit: i64, offset: i64) -> Result<Vec<Job>, sqlx::Error>;
+    // open jobs, newest first
+    async fn recent(&self, limit: i64) -> Result<Vec<Job>, sqlx::Error>;
+    // matches posted after since and no later than until, newest first
+    async fn list_new(
+        &self,
+        search: &JobSearch,
+        since: &DateTime<Utc>,
+        until: &DateTime<Utc>,
+        limit: i64,
+    ) -> Result<Vec<Job>, sqlx::Error>;
+    // counts of every match per facet value, see JobFacets
+    async fn facets(&self, search: &JobSearch) -> Result<Vec<FacetCount>, sqlx::Error>;
+}
+
+pub struct PgJobRepository{
+    db: Pool<Postgres>,
+}
+
+impl PgJobRepository{
+    pub fn new(db: Pool<Postgres>) -> PgJobRepository{
+        PgJobRepository{ db }
+    }
+}
+
+#[async_trait]
+impl JobRepository for PgJobRepository{
+    async fn create(&self, job: &CreateJobPosting, skills: &[(Uuid, bool)], normalized: &NormalizedJob) -> Result<Job, sqlx::Error>{
+        let mut tx = self.db.begin().await?;
+
+        let created = sqlx::query_as!(
+            Job,
+            "INSERT INTO jobs (title, company_name, city, country, salary, description, expires_at, location_id,
+                               country_code, region, latitude, longitude, work_mode, remote_countries, remote_timezones,
+                               employment_type, seniority, category_id, salary_min, salary_max, salary_currency)
+             VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13,
+                     ARRAY(SELECT UPPER(code) FROM UNNEST($14::VARCHAR[]) code), $15, $16, $17, $18, $19, $20, $21)
+             RETURNING *",
+            job.title,
+            job.company_name,
+            job.city,
+            job.country,
+            job.salary,
+            job.description,
+            job.expires_at,
+            normalized.location.location_id,
+            normalized.location.country_code,
+            normalized.location.region,
+            normalized.location.latitude,
+            normalized.location.longitude,
+            job.work_mode,
+            &job.remote_countries,
+            &job.remote_timezones,
+            job.employment_type,
+            normalized.seniority,
+            normalized.category_id,
+            normalized.salary_min,
+            normalized.salary_max,
+            normalized.salary_currency
+        )
+        .fetch_one(&mut *tx)
+        .instrument(db_span("INSERT", "jobs"))
+        .await?;
+
+        if !skills.is_empty(){
+            let (skill_ids, required): (Vec<Uuid>, Vec<bool>) = skills.iter().copied().unzip();
+            sqlx::query!(
+                "INSERT INTO job_skills (job_id, skill_id, required) SELECT $1, * FROM UNNEST($2::UUID[], $3::BOOLEAN[])",
+                created.id,
+                &skill_ids,
+                &required
+            )
+            .execute(&mut *tx)
+            .instrument(db_span("INSERT", "job_skills"))
+            .await?;
+        }
+
+        tx.commit().await?;
+        Ok(created)
+    }
+
+    async fn find_by_id(&self, id: &Uuid) -> Result<Option<Job>, sqlx::Error>{
+        sqlx::query_as!(Job, "SELECT * FROM jobs WHERE id = $1", id)
+            .fetch_optional(&self.db)
+            .instrument(db_span("SELECT", "jobs"))
+            .await
+    }
+
+    async fn find_by_ids(&self, ids: &[Uuid]) -> Result<Vec<Job>, sqlx::Error>{
+        sqlx::query_as!(Job, "SELECT * FROM jobs WHERE id = ANY($1)", ids)
+            .fetch_all(&self.db)
+            .instrument(db_span("SELECT", "jobs"))
+            .await
+    }
+
+    // Filters that are not set match every open job, expired ones are never
+    // listed. The skill filter counts the wanted skills a job has, which has
+    // to be all of them. Jobs without coordinates never match a radius search.
+    async fn list(&self, search: &JobSearch, limit: i64, offset: i64) -> Result<Vec<Job>, sqlx::Error>{
+        let pattern = search.text.as_deref().map(|text| format!("%{}%", escape_like(text)));
+        sqlx::query_as!(
+            Job,
+            "SELECT * FROM jobs
+             WHERE ($1::TEXT IS NULL OR title ILIKE $1 OR company_name ILIKE $1 OR description ILIKE $1)
+               AND (SELECT COUNT(*) FROM job_skills WHERE job_skills.job_id = jobs.id AND job_skills.skill_id = ANY($2))
+                   = CARDINALITY($2::UUID[])
+               AND ($3::FLOAT8 IS NULL OR (
+                   latitude IS NOT NULL AND longitude IS NOT NULL
+                   AND earth_box(ll_to_earth($3, $4), $5) @> ll_to_earth(latitude, longitude)
+                   AND earth_distance(ll_to_earth($3, $4), ll_to_earth(latitude, longitude)) <= $5
+               ))
+               AND ($6::VARCHAR IS NULL OR work_mode = $6)
+               AND ($7::VARCHAR IS NULL OR employment_type = $7)
+               AND ($8::VARCHAR IS NULL OR seniority = $8)
+               AND ($9::UUID[] IS NULL OR category_id = ANY($9))
+               AND (expires_at IS NULL OR expires_at > NOW())
+             ORDER BY RANDOM() LIMIT $10 OFFSET $11",
+            pattern,
+            &search.skill_ids,
+            search.near.map(|near| near.latitude),
+            search.near.map(|near| near.longitude),
+            search.radius_km.map(|radius| radius * 1000.0),
+            search.work_mode,
+            search.employment_type,
+            search.seniority,
+            search.category_ids.as_deref(),
+            limit,
+            offset
+        )
+        .fetch_all(&self.db)
+        .instrument(db_span("SELECT", "jobs"))
+        .await
+    }
+
+    async fn recent(&self, limit: i64) -> Result<Vec<Job>, sqlx::Error>{
+        sqlx::query_as!(
+            Job,
+            "SELECT * FROM jobs WHERE expires_at IS NULL OR expires_at > NOW() ORDER BY created_at DESC LIMIT $1",
+            limit
+        )
+        .fetch_all(&self.db)
+        .instrument(db_span("SELECT", "jobs"))
+        .await
+    }
+
+    async fn list_new(
+        &self,
+        search: &JobSearch,
+        since: &DateTime<Utc>,
+        until: &DateTime<Utc>,
+        limit: i64,
+    ) -> Result<Vec<Job>, sqlx::Error>{
+        let pattern = search.text.as_deref().map(|text| format!("%{}%", escape_like(text)));
+        sqlx::query_as!(
+            Job,
+            "SELECT * FROM jobs
+             WHERE ($1::TEXT IS NULL OR title ILIKE $1 OR company_name ILIKE $1 OR description ILIKE $1)
+               AND (SELECT COUNT(*) FROM job_skills WHERE job_skills.job_id = jobs.id AND job_skills.skill_id = ANY($2))
+                   = CARDINALITY($2::UUID[])
+               AND ($3::FLOAT8 IS NULL OR (
+                   latitude IS NOT NULL AND longitude IS NOT NULL
+                   AND earth_box(ll_to_earth($3, $4), $5) @> ll_to_earth(latitude, longitude)
+                   AND earth_distance(ll_to_earth($3, $4), ll_to_earth(latitude, longitude)) <= $5
+               ))
+               AND ($6::VARCHAR IS NULL OR work_mode = $6)
+               AND ($7::VARCHAR IS NULL OR employment_type = $7)
+               AND ($8::VARCHAR IS NULL OR seniority = $8)
+               AND ($9::UUID[] IS NULL OR category_id = ANY($9))
+               AND (expires_at IS NULL OR expires_at > NOW())
+               AND created_at > $10 AND created_at <= $11
+             ORDER BY created_at DESC LIMIT $12",
+            pattern,
+            &search.skill_ids,
+            search.near.map(|near| near.latitude),
+            search.near.map(|near| near.longitude),
+            search.radius_km.map(|radius| radius * 1000.0),
+            search.work_mode,
+            search.employment_type,
+            search.seniority,
+            search.category_ids.as_deref(),
+            since,
+            until,
+            limit
+        )
+        .fetch_all(&self.db)
+        .instrument(db_span("SELECT", "jobs"))
+        .await
+    }
+
+    // Same filters as list, counted in one pass over the matches. Each
+    // category also counts the jobs of the categories below it, so paths
+    // pairs every category with itself and each of its ancestors.
+    async fn facets(&self, search: &JobSearch) -> Result<Vec<FacetCount>, sqlx::Error>{
+        let pattern = search.text.as_deref().map(|text| format!("%{}%", escape_like(text)));
+        sqlx::query_as!(
+            FacetCount,
+            r#"WITH RECURSIVE matched AS (
+                 SELECT company_name, employment_type, seniority, category_id, salary_max, salary_currency, created_at,
+                        COALESCE((SELECT name FROM countries WHERE code = jobs.country_code), country) AS country,
+                        COALESCE((SELECT name FROM locations WHERE id = jobs.location_id), city) AS city
+                 FROM jobs
+                 WHERE ($1::TEXT IS NULL OR title ILIKE $1 OR company_name ILIKE $1 OR description ILIKE $1)
+                   AND (SELECT COUNT(*) FROM job_skills WHERE job_skills.job_id = jobs.id AND job_skills.skill_id = ANY($2))
+                       = CARDINALITY($2::UUID[])
+                   AND ($3::FLOAT8 IS NULL OR (
+                       latitude IS NOT NULL AND longitude IS NOT NULL
+                       AND earth_box(ll_to_earth($3, $4), $5) @> ll_to_earth(latitude, longitude)
+                       AND earth_distance(ll_to_earth($3, $4), ll_to_earth(latitude, longitude)) <= $5
+                   ))
+                   AND ($6::VARCHAR IS NULL OR work_mode = $6)
+                   AND ($7::VARCHAR IS NULL OR employment_type = $7)
+                   AND ($8::VARCHAR IS NULL OR seniority = $8)
+                   AND ($9::UUID[] IS NULL OR category_id = ANY($9))
+                   AND (expires_at IS NULL OR expires_at > NOW())
+             ),
+             paths (ancestor_id, category_id) AS (
+                 SELECT id, id FROM job_categories
+                 UNION ALL
+                 SELECT job_categories.parent_id, paths.category_id
+                 FROM paths
+                 INNER JOIN job_categories ON job_categories.id = paths.ancestor_id
+                 WHERE job_categories.parent_id IS NOT NULL
+             )
+             (SELECT 'country' AS "facet!", country AS "value!", COUNT(*) AS "count!"
+              FROM matched GROUP BY country ORDER BY COUNT(*) DESC, country LIMIT $10)
+             UNION ALL
+             (SELECT 'city', city, COUNT(*) FROM matched GROUP BY city ORDER BY COUNT(*) DESC, city LIMIT $10)
+             UNION ALL
+             (SELECT 'company', company_name, COUNT(*)
+              FROM matched GROUP BY company_name ORDER BY COUNT(*) DESC, company_name LIMIT $10)
+             UNION ALL
+             SELECT 'employment_type', employment_type, COUNT(*)
+             FROM matched GROUP BY employment_type
+             UNION ALL
+             SELECT 'seniority', seniority, COUNT(*)
+             FROM matched WHERE seniority IS NOT NULL GROUP BY seniority
+             UNION ALL
+             SELECT 'category', job_categories.slug, COUNT(*)
+             FROM matched
+             INNER JOIN paths ON paths.category_id = matched.category_id
+             INNER JOIN job_categories ON job_categories.id = paths.ancestor_id
+             GROUP BY job_categories.slug
+             UNION ALL
+             SELECT 'salary', salary_currency || ' ' || CASE
+                     WHEN salary_max < 30000 THEN 'under-30k'
+                     WHEN salary_max < 50000 THEN '30k-50k'
+                     WHEN salary_max < 75000 THEN '50k-75k'
+                     WHEN salary_max < 100000 THEN '75k-100k'
+                     ELSE '100k-plus'
+                 END AS bucket, COUNT(*)
+             FROM matched WHERE salary_max IS NOT NULL AND salary_currency IS NOT NULL GROUP BY bucket
+             UNION ALL
+             SELECT 'posted', posted.age, COUNT(*) FILTER (WHERE matched.created_at > NOW() - posted.within)
+             FROM (VALUES ('24h', INTERVAL '1 day'), ('7d', INTERVAL '7 days'), ('30d', INTERVAL '30 days'))
+                 AS posted (age, within)
+             CROSS JOIN matched GROUP BY posted.age"#,
+            pattern,
+            &search.skill_ids,
+            search.near.map(|near| near.latitude),
+            search.near.map(|near| near.longitude),
+            search.radius_km.map(|radius| radius * 1000.0),
+            search.work_mode,
+            search.employment_type,
+            search.seniority,
+            search.category_ids.as_deref(),
+            FACET_VALUE_LIMIT
+        )
+        .fetch_all(&self.db)
+        .instrument(db_span("SELECT", "jobs"))
+        .await
+    }
+}
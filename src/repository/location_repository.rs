@@ -0,0 +1,132 @@
+use crate::{
+    core::helpers::telemetry::db_span,
+    model::location_model::{Country, Location},
+    schema::location_schema::ResolvedLocation,
+};
+
+use async_trait::async_trait;
+use sqlx::{Pool, Postgres};
+use tracing::Instrument;
+
+#[async_trait]
+pub trait LocationRepository: Send + Sync{
+    // country is a name or an ISO code; among cities of the same name the
+    // most populous wins. None when the country is unknown.
+    async fn geocode(&self, city: &str, country: &str) -> Result<Option<ResolvedLocation>, sqlx::Error>;
+    // both insert or update by primary key, returning how many rows they wrote
+    async fn import_countries(&self, countries: &[Country]) -> Result<u64, sqlx::Error>;
+    // locations in countries that were not imported are skipped
+    async fn import_locations(&self, locations: &[Location]) -> Result<u64, sqlx::Error>;
+    // geocodes the jobs posted before their city was known, returns how many
+    async fn place_jobs(&self) -> Result<u64, sqlx::Error>;
+}
+
+pub struct PgLocationRepository{
+    db: Pool<Postgres>,
+}
+
+impl PgLocationRepository{
+    pub fn new(db: Pool<Postgres>) -> PgLocationRepository{
+        PgLocationRepository{ db }
+    }
+}
+
+#[async_trait]
+impl LocationRepository for PgLocationRepository{
+    async fn geocode(&self, city: &str, country: &str) -> Result<Option<ResolvedLocation>, sqlx::Error>{
+        sqlx::query_as!(
+            ResolvedLocation,
+            r#"SELECT locations.id AS "location_id?", countries.code AS "country_code?", locations.region,
+                    locations.latitude AS "latitude?", locations.longitude AS "longitude?"
+             FROM countries
+             LEFT JOIN LATERAL (
+                 SELECT * FROM locations
+                 WHERE locations.country_code = countries.code
+                   AND (LOWER(locations.name) = LOWER($1) OR LOWER(locations.ascii_name) = LOWER($1))
+                 ORDER BY locations.population DESC LIMIT 1
+             ) locations ON TRUE
+             WHERE countries.code = UPPER($2) OR LOWER(countries.name) = LOWER($2)
+             LIMIT 1"#,
+            city.trim(),
+            country.trim()
+        )
+        .fetch_optional(&self.db)
+        .instrument(db_span("SELECT", "locations"))
+        .await
+    }
+
+    async fn import_countries(&self, countries: &[Country]) -> Result<u64, sqlx::Error>{
+        let codes: Vec<String> = countries.iter().map(|country| country.code.to_owned()).collect();
+        let names: Vec<String> = countries.iter().map(|country| country.name.to_owned()).collect();
+        let result = sqlx::query!(
+            "INSERT INTO countries (code, name) SELECT * FROM UNNEST($1::VARCHAR[], $2::VARCHAR[])
+             ON CONFLICT (code) DO UPDATE SET name = EXCLUDED.name",
+            &codes,
+            &names
+        )
+        .execute(&self.db)
+        .instrument(db_span("INSERT", "countries"))
+        .await?;
+        Ok(result.rows_affected())
+    }
+
+    async fn import_locations(&self, locations: &[Location]) -> Result<u64, sqlx::Error>{
+        let ids: Vec<i64> = locations.iter().map(|location| location.id).collect();
+        let names: Vec<String> = locations.iter().map(|location| location.name.to_owned()).collect();
+        let ascii_names: Vec<String> = locations.iter().map(|location| location.ascii_name.to_owned()).collect();
+        let country_codes: Vec<String> = locations.iter().map(|location| location.country_code.to_owned()).collect();
+        let regions: Vec<String> = locations.iter().map(|location| location.region.to_owned().unwrap_or_default()).collect();
+        let latitudes: Vec<f64> = locations.iter().map(|location| location.latitude).collect();
+        let longitudes: Vec<f64> = locations.iter().map(|location| location.longitude).collect();
+        let populations: Vec<i64> = locations.iter().map(|location| location.population).collect();
+        let timezones: Vec<String> = locations.iter().map(|location| location.timezone.to_owned()).collect();
+
+        let result = sqlx::query!(
+            "INSERT INTO locations (id, name, ascii_name, country_code, region, latitude, longitude, population, timezone)
+             SELECT id, name, ascii_name, country_code, NULLIF(region, ''), latitude, longitude, population, timezone
+             FROM UNNEST($1::BIGINT[], $2::VARCHAR[], $3::VARCHAR[], $4::VARCHAR[], $5::VARCHAR[],
+                         $6::FLOAT8[], $7::FLOAT8[], $8::BIGINT[], $9::VARCHAR[])
+                 AS imported (id, name, ascii_name, country_code, region, latitude, longitude, population, timezone)
+             WHERE EXISTS (SELECT 1 FROM countries WHERE countries.code = imported.country_code)
+             ON CONFLICT (id) DO UPDATE SET
+                 name = EXCLUDED.name, ascii_name = EXCLUDED.ascii_name, country_code = EXCLUDED.country_code,
+                 region = EXCLUDED.region, latitude = EXCLUDED.latitude, longitude = EXCLUDED.longitude,
+                 population = EXCLUDED.population, timezone = EXCLUDED.timezone",
+            &ids,
+            &names,
+            &ascii_names,
+            &country_codes,
+            &regions,
+            &latitudes,
+            &longitudes,
+            &populations,
+            &timezones
+        )
+        .execute(&self.db)
+        .instrument(db_span("INSERT", "locations"))
+        .await?;
+        Ok(result.rows_affected())
+    }
+
+    async fn place_jobs(&self) -> Result<u64, sqlx::Error>{
+        let result = sqlx::query!(
+            "UPDATE jobs
+             SET location_id = placed.id, country_code = placed.country_code, region = placed.region,
+                 latitude = placed.latitude, longitude = placed.longitude
+             FROM (
+                 SELECT DISTINCT ON (jobs.id) jobs.id AS job_id, locations.*
+                 FROM jobs
+                 JOIN countries ON countries.code = UPPER(jobs.country) OR LOWER(countries.name) = LOWER(jobs.country)
+                 JOIN locations ON locations.country_code = countries.code
+                     AND (LOWER(locations.name) = LOWER(jobs.city) OR LOWER(locations.ascii_name) = LOWER(jobs.city))
+                 WHERE jobs.location_id IS NULL
+                 ORDER BY jobs.id, locations.population DESC
+             ) placed
+             WHERE jobs.id = placed.job_id"
+        )
+        .execute(&self.db)
+        .instrument(db_span("UPDATE", "jobs"))
+        .await?;
+        Ok(result.rows_affected())
+    }
+}
@@ -0,0 +1,17 @@
+pub mod user_repository;
+pub mod job_repository;
+pub mod application_repository;
+pub mod candidate_repository;
+pub mod skill_repository;
+pub mod saved_search_repository;
+pub mod saved_job_repository;
+pub mod location_repository;
+pub mod category_repository;
+pub mod analytics_repository;
+pub mod api_key_repository;
+pub mod queue_repository;
+
+// user input inside a LIKE pattern matches itself and nothing more
+pub fn escape_like(value: &str) -> String{
+    value.replace('\\', "\\\\").replace('%', "\\%").replace('_', "\\_")
+}
@@ -0,0 +1,80 @@
+use crate::{
+    core::helpers::telemetry::db_span,
+    model::queued_job_model::QueuedJob,
+};
+
+use async_trait::async_trait;
+use sqlx::{Pool, Postgres, Row};
+use tracing::Instrument;
+use uuid::Uuid;
+
+// Read and retry access to jobs_queue for the admin endpoints. Enqueueing
+// and running jobs stays in the queue module.
+#[async_trait]
+pub trait QueueRepository: Send + Sync{
+    // (status, count) for every status that has jobs
+    async fn count_by_status(&self) -> Result<Vec<(String, i64)>, sqlx::Error>;
+    // newest first
+    async fn list(&self, status: Option<&str>, kind: Option<&str>, limit: i64, offset: i64) -> Result<Vec<QueuedJob>, sqlx::Error>;
+    async fn find(&self, id: &Uuid) -> Result<Option<QueuedJob>, sqlx::Error>;
+    // None unless the job is dead
+    async fn retry_dead(&self, id: &Uuid) -> Result<Option<QueuedJob>, sqlx::Error>;
+}
+
+pub struct PgQueueRepository{
+    db: Pool<Postgres>,
+}
+
+impl PgQueueRepository{
+    pub fn new(db: Pool<Postgres>) -> PgQueueRepository{
+        PgQueueRepository{ db }
+    }
+}
+
+#[async_trait]
+impl QueueRepository for PgQueueRepository{
+    async fn count_by_status(&self) -> Result<Vec<(String, i64)>, sqlx::Error>{
+        let rows = sqlx::query("SELECT status, COUNT(*) AS count FROM jobs_queue GROUP BY status")
+            .fetch_all(&self.db)
+            .instrument(db_span("SELECT", "jobs_queue"))
+            .await?;
+        Ok(rows.iter().map(|row| (row.get("status"), row.get("count"))).collect())
+    }
+
+    async fn list(&self, status: Option<&str>, kind: Option<&str>, limit: i64, offset: i64) -> Result<Vec<QueuedJob>, sqlx::Error>{
+        sqlx::query_as!(
+            QueuedJob,
+            "SELECT * FROM jobs_queue
+             WHERE ($1::VARCHAR IS NULL OR status = $1) AND ($2::VARCHAR IS NULL OR kind = $2)
+             ORDER BY created_at DESC LIMIT $3 OFFSET $4",
+            status,
+            kind,
+            limit,
+            offset
+        )
+        .fetch_all(&self.db)
+        .instrument(db_span("SELECT", "jobs_queue"))
+        .await
+    }
+
+    async fn find(&self, id: &Uuid) -> Result<Option<QueuedJob>, sqlx::Error>{
+        sqlx::query_as!(QueuedJob, "SELECT * FROM jobs_queue WHERE id = $1", id)
+            .fetch_optional(&self.db)
+            .instrument(db_span("SELECT", "jobs_queue"))
+            .await
+    }
+
+    // a fresh set of attempts; last_error is kept so it can be compared if
+    // the job fails again
+    async fn retry_dead(&self, id: &Uuid) -> Result<Option<QueuedJob>, sqlx::Error>{
+        sqlx::query_as!(
+            QueuedJob,
+            "UPDATE jobs_queue SET status = 'pending', attempts = 0, run_at = NOW()
+             WHERE id = $1 AND status = 'dead' RETURNING *",
+            id
+        )
+        .fetch_optional(&self.db)
+        .instrument(db_span("UPDATE", "jobs_queue"))
+        .await
+    }
+}
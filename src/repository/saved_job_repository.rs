@@ -0,0 +1,106 @@
+use crate::{
+    core::helpers::telemetry::db_span,
+    model::saved_job_model::SavedJob,
+    queue::{self, task::Task},
+    schema::saved_job_schema::ExpiringSavedJob,
+};
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use sqlx::{Pool, Postgres};
+use tracing::Instrument;
+use uuid::Uuid;
+
+#[async_trait]
+pub trait SavedJobRepository: Send + Sync{
+    // false when the job was already saved
+    async fn save(&self, user_id: &Uuid, job_id: &Uuid) -> Result<bool, sqlx::Error>;
+    async fn remove(&self, user_id: &Uuid, job_id: &Uuid) -> Result<bool, sqlx::Error>;
+    // most recently saved first
+    async fn list_for_user(&self, user_id: &Uuid) -> Result<Vec<SavedJob>, sqlx::Error>;
+    // Saved jobs that are still open but expire before expiring_before and
+    // have not been reminded of, leaving out jobs the user already applied
+    // to and accounts that are disabled or being deleted
+    async fn list_expiring(&self, expiring_before: &DateTime<Utc>) -> Result<Vec<ExpiringSavedJob>, sqlx::Error>;
+    async fn mark_reminded(&self, user_id: &Uuid, job_id: &Uuid, reminder: &Task) -> Result<(), sqlx::Error>;
+}
+
+pub struct PgSavedJobRepository{
+    db: Pool<Postgres>,
+}
+
+impl PgSavedJobRepository{
+    pub fn new(db: Pool<Postgres>) -> PgSavedJobRepository{
+        PgSavedJobRepository{ db }
+    }
+}
+
+#[async_trait]
+impl SavedJobRepository for PgSavedJobRepository{
+    async fn save(&self, user_id: &Uuid, job_id: &Uuid) -> Result<bool, sqlx::Error>{
+        let result = sqlx::query!(
+            "INSERT INTO saved_jobs (user_id, job_id) VALUES ($1, $2) ON CONFLICT DO NOTHING",
+            user_id,
+            job_id
+        )
+        .execute(&self.db)
+        .instrument(db_span("INSERT", "saved_jobs"))
+        .await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    async fn remove(&self, user_id: &Uuid, job_id: &Uuid) -> Result<bool, sqlx::Error>{
+        let result = sqlx::query!("DELETE FROM saved_jobs WHERE user_id = $1 AND job_id = $2", user_id, job_id)
+            .execute(&self.db)
+            .instrument(db_span("DELETE", "saved_jobs"))
+            .await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    async fn list_for_user(&self, user_id: &Uuid) -> Result<Vec<SavedJob>, sqlx::Error>{
+        sqlx::query_as!(SavedJob, "SELECT * FROM saved_jobs WHERE user_id = $1 ORDER BY created_at DESC", user_id)
+            .fetch_all(&self.db)
+            .instrument(db_span("SELECT", "saved_jobs"))
+            .await
+    }
+
+    async fn list_expiring(&self, expiring_before: &DateTime<Utc>) -> Result<Vec<ExpiringSavedJob>, sqlx::Error>{
+        sqlx::query_as!(
+            ExpiringSavedJob,
+            r#"SELECT saved_jobs.user_id, saved_jobs.job_id, users.email, users.first_name,
+                    jobs.title, jobs.company_name, jobs.expires_at AS "expires_at!"
+             FROM saved_jobs
+             JOIN jobs ON jobs.id = saved_jobs.job_id
+             JOIN users ON users.id = saved_jobs.user_id
+             WHERE saved_jobs.reminded_at IS NULL
+               AND jobs.expires_at > NOW() AND jobs.expires_at <= $1
+               AND users.is_active AND users.deletion_requested_at IS NULL AND users.deleted_at IS NULL
+               AND NOT EXISTS (
+                   SELECT 1 FROM applications
+                   WHERE applications.user_id = saved_jobs.user_id AND applications.job_id = saved_jobs.job_id
+               )
+             ORDER BY jobs.expires_at"#,
+            expiring_before
+        )
+        .fetch_all(&self.db)
+        .instrument(db_span("SELECT", "saved_jobs"))
+        .await
+    }
+
+    async fn mark_reminded(&self, user_id: &Uuid, job_id: &Uuid, reminder: &Task) -> Result<(), sqlx::Error>{
+        let mut tx = self.db.begin().await?;
+        sqlx::query!(
+            "UPDATE saved_jobs SET reminded_at = NOW() WHERE user_id = $1 AND job_id = $2",
+            user_id,
+            job_id
+        )
+        .execute(&mut *tx)
+        .instrument(db_span("UPDATE", "saved_jobs"))
+        .await?;
+
+        queue::enqueue(&mut *tx, reminder, None).await?;
+
+        tx.commit().await?;
+        Ok(())
+    }
+}
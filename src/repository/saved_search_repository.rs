@@ -0,0 +1,188 @@
+use crate::{
+    core::helpers::telemetry::db_span,
+    model::saved_search_model::SavedSearch,
+    queue::{self, task::Task},
+    schema::{job_schema::JobFilters, saved_search_schema::DueSearch},
+};
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use sqlx::{types::Json, Pool, Postgres};
+use tracing::Instrument;
+use uuid::Uuid;
+
+#[async_trait]
+pub trait SavedSearchRepository: Send + Sync{
+    async fn create(
+        &self,
+        user_id: &Uuid,
+        name: &str,
+        filters: &JobFilters,
+        frequency: &str,
+        alerts_enabled: bool,
+        unsubscribe_token: &str,
+    ) -> Result<SavedSearch, sqlx::Error>;
+    async fn count_for_user(&self, user_id: &Uuid) -> Result<i64, sqlx::Error>;
+    // newest first
+    async fn list_for_user(&self, user_id: &Uuid) -> Result<Vec<SavedSearch>, sqlx::Error>;
+    // None when the user has no such search
+    async fn update(
+        &self,
+        user_id: &Uuid,
+        id: &Uuid,
+        name: &str,
+        filters: &JobFilters,
+        frequency: &str,
+        alerts_enabled: bool,
+    ) -> Result<Option<SavedSearch>, sqlx::Error>;
+    async fn delete(&self, user_id: &Uuid, id: &Uuid) -> Result<bool, sqlx::Error>;
+    // false for an unknown token
+    async fn disable_alerts(&self, unsubscribe_token: &str) -> Result<bool, sqlx::Error>;
+    // searches whose frequency has passed since their last alert at now,
+    // leaving out accounts that are disabled or being deleted
+    async fn list_due(&self, now: &DateTime<Utc>) -> Result<Vec<DueSearch>, sqlx::Error>;
+    async fn mark_notified(&self, id: &Uuid, notified_at: &DateTime<Utc>, follow_up: Option<&Task>) -> Result<(), sqlx::Error>;
+}
+
+pub struct PgSavedSearchRepository{
+    db: Pool<Postgres>,
+}
+
+impl PgSavedSearchRepository{
+    pub fn new(db: Pool<Postgres>) -> PgSavedSearchRepository{
+        PgSavedSearchRepository{ db }
+    }
+}
+
+#[async_trait]
+impl SavedSearchRepository for PgSavedSearchRepository{
+    async fn create(
+        &self,
+        user_id: &Uuid,
+        name: &str,
+        filters: &JobFilters,
+        frequency: &str,
+        alerts_enabled: bool,
+        unsubscribe_token: &str,
+    ) -> Result<SavedSearch, sqlx::Error>{
+        sqlx::query_as!(
+            SavedSearch,
+            r#"INSERT INTO saved_searches (user_id, name, filters, frequency, alerts_enabled, unsubscribe_token)
+             VALUES ($1, $2, $3, $4, $5, $6)
+             RETURNING id, user_id, name, filters AS "filters: Json<JobFilters>", frequency, alerts_enabled,
+                       last_notified_at, unsubscribe_token, created_at, updated_at"#,
+            user_id,
+            name,
+            Json(filters) as _,
+            frequency,
+            alerts_enabled,
+            unsubscribe_token
+        )
+        .fetch_one(&self.db)
+        .instrument(db_span("INSERT", "saved_searches"))
+        .await
+    }
+
+    async fn count_for_user(&self, user_id: &Uuid) -> Result<i64, sqlx::Error>{
+        sqlx::query_scalar!(r#"SELECT COUNT(*) AS "count!" FROM saved_searches WHERE user_id = $1"#, user_id)
+            .fetch_one(&self.db)
+            .instrument(db_span("SELECT", "saved_searches"))
+            .await
+    }
+
+    async fn list_for_user(&self, user_id: &Uuid) -> Result<Vec<SavedSearch>, sqlx::Error>{
+        sqlx::query_as!(
+            SavedSearch,
+            r#"SELECT id, user_id, name, filters AS "filters: Json<JobFilters>", frequency, alerts_enabled,
+                    last_notified_at, unsubscribe_token, created_at, updated_at
+             FROM saved_searches WHERE user_id = $1 ORDER BY created_at DESC"#,
+            user_id
+        )
+        .fetch_all(&self.db)
+        .instrument(db_span("SELECT", "saved_searches"))
+        .await
+    }
+
+    async fn update(
+        &self,
+        user_id: &Uuid,
+        id: &Uuid,
+        name: &str,
+        filters: &JobFilters,
+        frequency: &str,
+        alerts_enabled: bool,
+    ) -> Result<Option<SavedSearch>, sqlx::Error>{
+        sqlx::query_as!(
+            SavedSearch,
+            r#"UPDATE saved_searches SET name = $3, filters = $4, frequency = $5, alerts_enabled = $6
+             WHERE id = $1 AND user_id = $2
+             RETURNING id, user_id, name, filters AS "filters: Json<JobFilters>", frequency, alerts_enabled,
+                       last_notified_at, unsubscribe_token, created_at, updated_at"#,
+            id,
+            user_id,
+            name,
+            Json(filters) as _,
+            frequency,
+            alerts_enabled
+        )
+        .fetch_optional(&self.db)
+        .instrument(db_span("UPDATE", "saved_searches"))
+        .await
+    }
+
+    async fn delete(&self, user_id: &Uuid, id: &Uuid) -> Result<bool, sqlx::Error>{
+        let result = sqlx::query!("DELETE FROM saved_searches WHERE id = $1 AND user_id = $2", id, user_id)
+            .execute(&self.db)
+            .instrument(db_span("DELETE", "saved_searches"))
+            .await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    async fn disable_alerts(&self, unsubscribe_token: &str) -> Result<bool, sqlx::Error>{
+        let result = sqlx::query!(
+            "UPDATE saved_searches SET alerts_enabled = FALSE WHERE unsubscribe_token = $1",
+            unsubscribe_token
+        )
+        .execute(&self.db)
+        .instrument(db_span("UPDATE", "saved_searches"))
+        .await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    async fn list_due(&self, now: &DateTime<Utc>) -> Result<Vec<DueSearch>, sqlx::Error>{
+        sqlx::query_as!(
+            DueSearch,
+            r#"SELECT saved_searches.id, saved_searches.name, saved_searches.filters AS "filters: Json<JobFilters>",
+                    saved_searches.last_notified_at, saved_searches.unsubscribe_token, users.email, users.first_name
+             FROM saved_searches
+             JOIN users ON users.id = saved_searches.user_id
+             WHERE saved_searches.alerts_enabled
+               AND users.is_active AND users.deletion_requested_at IS NULL AND users.deleted_at IS NULL
+               AND saved_searches.last_notified_at + CASE saved_searches.frequency
+                       WHEN 'daily' THEN INTERVAL '1 day'
+                       WHEN 'weekly' THEN INTERVAL '7 days'
+                       ELSE INTERVAL '0'
+                   END < $1
+             ORDER BY saved_searches.last_notified_at"#,
+            now
+        )
+        .fetch_all(&self.db)
+        .instrument(db_span("SELECT", "saved_searches"))
+        .await
+    }
+
+    async fn mark_notified(&self, id: &Uuid, notified_at: &DateTime<Utc>, follow_up: Option<&Task>) -> Result<(), sqlx::Error>{
+        let mut tx = self.db.begin().await?;
+        sqlx::query!("UPDATE saved_searches SET last_notified_at = $2 WHERE id = $1", id, notified_at)
+            .execute(&mut *tx)
+            .instrument(db_span("UPDATE", "saved_searches"))
+            .await?;
+
+        if let Some(task) = follow_up{
+            queue::enqueue(&mut *tx, task, None).await?;
+        }
+
+        tx.commit().await?;
+        Ok(())
+    }
+}
@@ -0,0 +1,106 @@
+use crate::{
+    core::helpers::telemetry::db_span,
+    model::skill_model::Skill,
+    schema::skill_schema::{JobSkill, ResolvedSkill},
+};
+
+use async_trait::async_trait;
+use sqlx::{Pool, Postgres};
+use tracing::Instrument;
+use uuid::Uuid;
+
+use super::escape_like;
+
+#[async_trait]
+pub trait SkillRepository: Send + Sync{
+    // skills whose name or one of whose aliases starts with prefix
+    async fn search(&self, prefix: &str, limit: i64) -> Result<Vec<Skill>, sqlx::Error>;
+    async fn create(&self, name: &str, aliases: &[String]) -> Result<Skill, sqlx::Error>;
+    // keys are lowercase names or aliases, unknown ones are left out
+    async fn resolve(&self, keys: &[String]) -> Result<Vec<ResolvedSkill>, sqlx::Error>;
+    async fn for_jobs(&self, job_ids: &[Uuid]) -> Result<Vec<JobSkill>, sqlx::Error>;
+}
+
+pub struct PgSkillRepository{
+    db: Pool<Postgres>,
+}
+
+impl PgSkillRepository{
+    pub fn new(db: Pool<Postgres>) -> PgSkillRepository{
+        PgSkillRepository{ db }
+    }
+}
+
+#[async_trait]
+impl SkillRepository for PgSkillRepository{
+    async fn search(&self, prefix: &str, limit: i64) -> Result<Vec<Skill>, sqlx::Error>{
+        let prefix = prefix.to_lowercase();
+        sqlx::query_as!(
+            Skill,
+            r#"SELECT skills.id, skills.name,
+                    ARRAY(SELECT alias FROM skill_aliases WHERE skill_id = skills.id ORDER BY alias) AS "aliases!"
+             FROM skills
+             WHERE LOWER(skills.name) LIKE $1
+                OR EXISTS (SELECT 1 FROM skill_aliases WHERE skill_id = skills.id AND alias LIKE $1)
+             ORDER BY LOWER(skills.name) = $2 DESC, LENGTH(skills.name), skills.name
+             LIMIT $3"#,
+            format!("{}%", escape_like(&prefix)),
+            prefix,
+            limit
+        )
+        .fetch_all(&self.db)
+        .instrument(db_span("SELECT", "skills"))
+        .await
+    }
+
+    async fn create(&self, name: &str, aliases: &[String]) -> Result<Skill, sqlx::Error>{
+        let mut tx = self.db.begin().await?;
+
+        let id = sqlx::query_scalar!("INSERT INTO skills (name) VALUES ($1) RETURNING id", name)
+            .fetch_one(&mut *tx)
+            .instrument(db_span("INSERT", "skills"))
+            .await?;
+        sqlx::query!(
+            "INSERT INTO skill_aliases (alias, skill_id) SELECT UNNEST($1::TEXT[]), $2",
+            aliases,
+            id
+        )
+        .execute(&mut *tx)
+        .instrument(db_span("INSERT", "skill_aliases"))
+        .await?;
+
+        tx.commit().await?;
+        Ok(Skill{ id, name: name.to_string(), aliases: aliases.to_vec() })
+    }
+
+    async fn resolve(&self, keys: &[String]) -> Result<Vec<ResolvedSkill>, sqlx::Error>{
+        sqlx::query_as!(
+            ResolvedSkill,
+            r#"SELECT LOWER(name) AS "key!", id AS "id!", name AS "name!" FROM skills WHERE LOWER(name) = ANY($1)
+             UNION ALL
+             SELECT skill_aliases.alias, skills.id, skills.name
+             FROM skill_aliases
+             INNER JOIN skills ON skills.id = skill_aliases.skill_id
+             WHERE skill_aliases.alias = ANY($1)"#,
+            keys
+        )
+        .fetch_all(&self.db)
+        .instrument(db_span("SELECT", "skills"))
+        .await
+    }
+
+    async fn for_jobs(&self, job_ids: &[Uuid]) -> Result<Vec<JobSkill>, sqlx::Error>{
+        sqlx::query_as!(
+            JobSkill,
+            "SELECT job_skills.job_id, skills.id, skills.name, job_skills.required
+             FROM job_skills
+             INNER JOIN skills ON skills.id = job_skills.skill_id
+             WHERE job_skills.job_id = ANY($1)
+             ORDER BY job_skills.required DESC, skills.name",
+            job_ids
+        )
+        .fetch_all(&self.db)
+        .instrument(db_span("SELECT", "job_skills"))
+        .await
+    }
+}
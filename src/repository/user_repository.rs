@@ -0,0 +1,368 @@
+use crate::{
+    core::helpers::telemetry::db_span,
+    model::user_model::User,
+    queue::{self, task::Task},
+};
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use sqlx::{Pool, Postgres};
+use tracing::Instrument;
+use uuid::Uuid;
+
+// a requested email change that has not been confirmed or expired yet
+pub struct PendingEmailChange{
+    pub user_id: Uuid,
+    pub new_email: String,
+}
+
+pub struct NewUser{
+    pub first_name: String,
+    pub last_name: String,
+    pub email: String,
+    pub password_hash: String,
+    pub role: String,
+}
+
+// Writes that have a follow up task take it as an argument so the task is
+// queued in the same transaction as the write it belongs to.
+#[async_trait]
+pub trait UserRepository: Send + Sync{
+    async fn find_by_id(&self, id: &Uuid) -> Result<Option<User>, sqlx::Error>;
+    async fn find_by_email(&self, email: &str) -> Result<Option<User>, sqlx::Error>;
+    async fn email_exists(&self, email: &str) -> Result<bool, sqlx::Error>;
+    async fn create(&self, user: &NewUser, follow_up: Option<&Task>) -> Result<User, sqlx::Error>;
+    async fn set_resume(&self, id: &Uuid, path: &str, follow_up: Option<&Task>) -> Result<(), sqlx::Error>;
+    // also revokes the user's API keys; purge runs at purge_at
+    async fn request_deletion(
+        &self,
+        id: &Uuid,
+        requested_at: &DateTime<Utc>,
+        purge: &Task,
+        purge_at: DateTime<Utc>,
+    ) -> Result<(), sqlx::Error>;
+    async fn cancel_deletion(&self, id: &Uuid) -> Result<(), sqlx::Error>;
+    // Scrubs the user and deletes their personal data if their deletion was
+    // requested at or before requested_before. Returns the resume path they
+    // had, None when the deletion was cancelled or already done.
+    async fn purge(&self, id: &Uuid, requested_before: &DateTime<Utc>) -> Result<Option<String>, sqlx::Error>;
+    async fn list_overdue_deletions(&self, requested_before: &DateTime<Utc>) -> Result<Vec<Uuid>, sqlx::Error>;
+    // None for an unknown user; fields left as None keep their value
+    async fn update_profile(&self, id: &Uuid, first_name: Option<&str>, last_name: Option<&str>) -> Result<Option<User>, sqlx::Error>;
+    // returns the new session version, which voids every token issued before
+    async fn set_password(&self, id: &Uuid, password_hash: &str) -> Result<i32, sqlx::Error>;
+    // replaces any change the user requested before
+    async fn create_email_change(
+        &self,
+        id: &Uuid,
+        new_email: &str,
+        token_hash: &str,
+        expires_at: DateTime<Utc>,
+        follow_up: Option<&Task>,
+    ) -> Result<(), sqlx::Error>;
+    async fn find_email_change(&self, token_hash: &str) -> Result<Option<PendingEmailChange>, sqlx::Error>;
+    // false when the change was confirmed in the meantime
+    async fn apply_email_change(&self, token_hash: &str, change: &PendingEmailChange, follow_up: Option<&Task>) -> Result<bool, sqlx::Error>;
+}
+
+pub struct PgUserRepository{
+    db: Pool<Postgres>,
+}
+
+impl PgUserRepository{
+    pub fn new(db: Pool<Postgres>) -> PgUserRepository{
+        PgUserRepository{ db }
+    }
+}
+
+#[async_trait]
+impl UserRepository for PgUserRepository{
+    async fn find_by_id(&self, id: &Uuid) -> Result<Option<User>, sqlx::Error>{
+        sqlx::query_as!(User, "SELECT * FROM users WHERE id = $1", id)
+            .fetch_optional(&self.db)
+            .instrument(db_span("SELECT", "users"))
+            .await
+    }
+
+    async fn find_by_email(&self, email: &str) -> Result<Option<User>, sqlx::Error>{
+        sqlx::query_as!(User, "SELECT * FROM users WHERE email = $1", email)
+            .fetch_optional(&self.db)
+            .instrument(db_span("SELECT", "users"))
+            .await
+    }
+
+    async fn email_exists(&self, email: &str) -> Result<bool, sqlx::Error>{
+        let exists = sqlx::query_scalar!("SELECT EXISTS(SELECT 1 FROM users WHERE email = $1)", email)
+            .fetch_one(&self.db)
+            .instrument(db_span("SELECT", "users"))
+            .await?;
+        Ok(exists.unwrap_or(false))
+    }
+
+    async fn create(&self, user: &NewUser, follow_up: Option<&Task>) -> Result<User, sqlx::Error>{
+        let mut tx = self.db.begin().await?;
+        let created = sqlx::query_as!(
+            User,
+            "INSERT INTO users (first_name, last_name, email, password, role) VALUES ($1, $2, $3, $4, $5) RETURNING *",
+            user.first_name,
+            user.last_name,
+            user.email,
+            user.password_hash,
+            user.role
+        )
+        .fetch_one(&mut *tx)
+        .instrument(db_span("INSERT", "users"))
+        .await?;
+
+        if let Some(task) = follow_up{
+            queue::enqueue(&mut *tx, task, None).await?;
+        }
+
+        tx.commit().await?;
+        Ok(created)
+    }
+
+    async fn set_resume(&self, id: &Uuid, path: &str, follow_up: Option<&Task>) -> Result<(), sqlx::Error>{
+        let mut tx = self.db.begin().await?;
+        sqlx::query!(
+            "UPDATE users SET resume = $1 WHERE id = $2",
+            path,
+            id
+        )
+        .execute(&mut *tx)
+        .instrument(db_span("UPDATE", "users"))
+        .await?;
+
+        if let Some(task) = follow_up{
+            queue::enqueue(&mut *tx, task, None).await?;
+        }
+
+        tx.commit().await
+    }
+
+    async fn request_deletion(
+        &self,
+        id: &Uuid,
+        requested_at: &DateTime<Utc>,
+        purge: &Task,
+        purge_at: DateTime<Utc>,
+    ) -> Result<(), sqlx::Error>{
+        let mut tx = self.db.begin().await?;
+        sqlx::query!(
+            "UPDATE users SET deletion_requested_at = $2 WHERE id = $1 AND deleted_at IS NULL",
+            id,
+            requested_at
+        )
+        .execute(&mut *tx)
+        .instrument(db_span("UPDATE", "users"))
+        .await?;
+
+        sqlx::query!(
+            "UPDATE api_keys SET revoked_at = NOW() WHERE user_id = $1 AND revoked_at IS NULL",
+            id
+        )
+        .execute(&mut *tx)
+        .instrument(db_span("UPDATE", "api_keys"))
+        .await?;
+
+        queue::enqueue(&mut *tx, purge, Some(purge_at)).await?;
+        tx.commit().await
+    }
+
+    async fn cancel_deletion(&self, id: &Uuid) -> Result<(), sqlx::Error>{
+        sqlx::query!(
+            "UPDATE users SET deletion_requested_at = NULL WHERE id = $1 AND deleted_at IS NULL",
+            id
+        )
+        .execute(&self.db)
+        .instrument(db_span("UPDATE", "users"))
+        .await?;
+        Ok(())
+    }
+
+    // The row itself stays, so the user's applications remain in the job
+    // statistics without pointing at anyone.
+    async fn purge(&self, id: &Uuid, requested_before: &DateTime<Utc>) -> Result<Option<String>, sqlx::Error>{
+        let mut tx = self.db.begin().await?;
+        let resume = sqlx::query_scalar!(
+            "UPDATE users
+             SET first_name = 'Deleted', last_name = 'User', email = 'deleted-' || users.id || '@deleted.invalid',
+                 password = '', resume = '', is_active = FALSE, deleted_at = NOW()
+             FROM (SELECT id, resume FROM users WHERE id = $1 FOR UPDATE) previous
+             WHERE users.id = previous.id AND users.deletion_requested_at <= $2 AND users.deleted_at IS NULL
+             RETURNING previous.resume",
+            id,
+            requested_before
+        )
+        .fetch_optional(&mut *tx)
+        .instrument(db_span("UPDATE", "users"))
+        .await?;
+        if resume.is_none(){
+            return Ok(None);
+        }
+
+        sqlx::query!("DELETE FROM api_keys WHERE user_id = $1", id)
+            .execute(&mut *tx)
+            .instrument(db_span("DELETE", "api_keys"))
+            .await?;
+        sqlx::query!("DELETE FROM email_changes WHERE user_id = $1", id)
+            .execute(&mut *tx)
+            .instrument(db_span("DELETE", "email_changes"))
+            .await?;
+        // the candidate profile is all personal data, every section goes
+        sqlx::query!("DELETE FROM candidate_profiles WHERE user_id = $1", id)
+            .execute(&mut *tx)
+            .instrument(db_span("DELETE", "candidate_profiles"))
+            .await?;
+        sqlx::query!("DELETE FROM work_experiences WHERE user_id = $1", id)
+            .execute(&mut *tx)
+            .instrument(db_span("DELETE", "work_experiences"))
+            .await?;
+        sqlx::query!("DELETE FROM educations WHERE user_id = $1", id)
+            .execute(&mut *tx)
+            .instrument(db_span("DELETE", "educations"))
+            .await?;
+        sqlx::query!("DELETE FROM certifications WHERE user_id = $1", id)
+            .execute(&mut *tx)
+            .instrument(db_span("DELETE", "certifications"))
+            .await?;
+        sqlx::query!("DELETE FROM candidate_skills WHERE user_id = $1", id)
+            .execute(&mut *tx)
+            .instrument(db_span("DELETE", "candidate_skills"))
+            .await?;
+        sqlx::query!("DELETE FROM portfolio_links WHERE user_id = $1", id)
+            .execute(&mut *tx)
+            .instrument(db_span("DELETE", "portfolio_links"))
+            .await?;
+        sqlx::query!("DELETE FROM saved_searches WHERE user_id = $1", id)
+            .execute(&mut *tx)
+            .instrument(db_span("DELETE", "saved_searches"))
+            .await?;
+        sqlx::query!("DELETE FROM saved_jobs WHERE user_id = $1", id)
+            .execute(&mut *tx)
+            .instrument(db_span("DELETE", "saved_jobs"))
+            .await?;
+        // the visitor hash of a signed in user is not salted, anyone with the
+        // user id could follow it back to them
+        sqlx::query!(
+            "DELETE FROM job_events WHERE visitor = encode(sha256(('user:' || $1::UUID)::BYTEA), 'hex')",
+            id
+        )
+        .execute(&mut *tx)
+        .instrument(db_span("DELETE", "job_events"))
+        .await?;
+
+        tx.commit().await?;
+        Ok(resume)
+    }
+
+    async fn list_overdue_deletions(&self, requested_before: &DateTime<Utc>) -> Result<Vec<Uuid>, sqlx::Error>{
+        sqlx::query_scalar!(
+            "SELECT id FROM users WHERE deletion_requested_at <= $1 AND deleted_at IS NULL",
+            requested_before
+        )
+        .fetch_all(&self.db)
+        .instrument(db_span("SELECT", "users"))
+        .await
+    }
+
+    async fn update_profile(&self, id: &Uuid, first_name: Option<&str>, last_name: Option<&str>) -> Result<Option<User>, sqlx::Error>{
+        sqlx::query_as!(
+            User,
+            "UPDATE users SET first_name = COALESCE($2, first_name), last_name = COALESCE($3, last_name)
+             WHERE id = $1 RETURNING *",
+            id,
+            first_name,
+            last_name
+        )
+        .fetch_optional(&self.db)
+        .instrument(db_span("UPDATE", "users"))
+        .await
+    }
+
+    async fn set_password(&self, id: &Uuid, password_hash: &str) -> Result<i32, sqlx::Error>{
+        sqlx::query_scalar!(
+            "UPDATE users SET password = $2, session_version = session_version + 1 WHERE id = $1 RETURNING session_version",
+            id,
+            password_hash
+        )
+        .fetch_one(&self.db)
+        .instrument(db_span("UPDATE", "users"))
+        .await
+    }
+
+    async fn create_email_change(
+        &self,
+        id: &Uuid,
+        new_email: &str,
+        token_hash: &str,
+        expires_at: DateTime<Utc>,
+        follow_up: Option<&Task>,
+    ) -> Result<(), sqlx::Error>{
+        let mut tx = self.db.begin().await?;
+        sqlx::query!("DELETE FROM email_changes WHERE user_id = $1 AND confirmed_at IS NULL", id)
+            .execute(&mut *tx)
+            .instrument(db_span("DELETE", "email_changes"))
+            .await?;
+
+        sqlx::query!(
+            "INSERT INTO email_changes (user_id, new_email, token_hash, expires_at) VALUES ($1, $2, $3, $4)",
+            id,
+            new_email,
+            token_hash,
+            expires_at
+        )
+        .execute(&mut *tx)
+        .instrument(db_span("INSERT", "email_changes"))
+        .await?;
+
+        if let Some(task) = follow_up{
+            queue::enqueue(&mut *tx, task, None).await?;
+        }
+
+        tx.commit().await
+    }
+
+    async fn find_email_change(&self, token_hash: &str) -> Result<Option<PendingEmailChange>, sqlx::Error>{
+        sqlx::query_as!(
+            PendingEmailChange,
+            "SELECT user_id, new_email FROM email_changes
+             WHERE token_hash = $1 AND confirmed_at IS NULL AND expires_at > NOW()",
+            token_hash
+        )
+        .fetch_optional(&self.db)
+        .instrument(db_span("SELECT", "email_changes"))
+        .await
+    }
+
+    async fn apply_email_change(&self, token_hash: &str, change: &PendingEmailChange, follow_up: Option<&Task>) -> Result<bool, sqlx::Error>{
+        let mut tx = self.db.begin().await?;
+        let confirmed = sqlx::query!(
+            "UPDATE email_changes SET confirmed_at = NOW() WHERE token_hash = $1 AND confirmed_at IS NULL",
+            token_hash
+        )
+        .execute(&mut *tx)
+        .instrument(db_span("UPDATE", "email_changes"))
+        .await?
+        .rows_affected();
+        if confirmed == 0{
+            return Ok(false);
+        }
+
+        sqlx::query!(
+            "UPDATE users SET email = $2 WHERE id = $1",
+            change.user_id,
+            change.new_email
+        )
+        .execute(&mut *tx)
+        .instrument(db_span("UPDATE", "users"))
+        .await?;
+
+        if let Some(task) = follow_up{
+            queue::enqueue(&mut *tx, task, None).await?;
+        }
+
+        tx.commit().await?;
+        Ok(true)
+    }
+}
@@ -0,0 +1,140 @@
+use crate::{
+    core::helpers::{api_key, cookie, response::error_response},
+    jwt_auth,
+    route::user_route::{filter_user_record, missing_scope, session_response},
+    schema::user_schema::{
+        ChangeEmailSchema, ChangePasswordSchema, ConfirmEmailSchema, DeleteAccountSchema, UpdateProfileSchema,
+    },
+    service::ServiceError,
+    AppState,
+};
+
+use actix_web::{
+    delete, get, http::header, patch, post, web, HttpResponse, Responder,
+};
+
+// Apart from the name fields, everything here hands over, destroys or takes
+// control of the account, so it needs the user themselves and never an API key
+
+#[patch("/me")]
+async fn update_profile_handler(
+    body: web::Json<UpdateProfileSchema>,
+    auth: jwt_auth::JwtMiddleware,
+    data: web::Data<AppState>,
+)-> impl Responder{
+    if !auth.has_scope(api_key::PROFILE_WRITE){
+        return missing_scope(api_key::PROFILE_WRITE);
+    }
+
+    match data.profiles.update(&auth.user_id, body.first_name.as_deref(), body.last_name.as_deref()).await{
+        Ok(user) => HttpResponse::Ok().json(serde_json::json!({
+            "status": "Success",
+            "message": "Profile updated",
+            "data": serde_json::json!({
+                "user": filter_user_record(&user)
+            })
+        })),
+        Err(e) => error_response(e),
+    }
+}
+
+// Signs out every session, including this one, and hands back a new session
+// in its place
+#[post("/me/password")]
+async fn change_password_handler(
+    body: web::Json<ChangePasswordSchema>,
+    auth: jwt_auth::JwtMiddleware,
+    data: web::Data<AppState>,
+)-> impl Responder{
+    if auth.is_api_key(){
+        return error_response(ServiceError::Unauthorized);
+    }
+
+    match data.profiles.change_password(&auth.user_id, &body.current_password, &body.new_password).await{
+        Ok(session_version) => session_response(&data, &auth.user_id, session_version, body.remember_me),
+        Err(e) => error_response(e),
+    }
+}
+
+#[post("/me/email")]
+async fn change_email_handler(
+    body: web::Json<ChangeEmailSchema>,
+    auth: jwt_auth::JwtMiddleware,
+    data: web::Data<AppState>,
+)-> impl Responder{
+    if auth.is_api_key(){
+        return error_response(ServiceError::Unauthorized);
+    }
+
+    match data.profiles.request_email_change(&auth.user_id, &body.new_email, &body.password).await{
+        Ok(()) => HttpResponse::Accepted().json(serde_json::json!({
+            "status": "Success",
+            "message": "Follow the link sent to the new address to confirm it"
+        })),
+        Err(e) => error_response(e),
+    }
+}
+
+// the token from the email is the only credential, it may be opened on a
+// device the user is not logged in on
+#[post("/me/email/confirm")]
+async fn confirm_email_handler(
+    body: web::Json<ConfirmEmailSchema>,
+    data: web::Data<AppState>,
+)-> impl Responder{
+    match data.profiles.confirm_email_change(&body.token).await{
+        Ok(email) => HttpResponse::Ok().json(serde_json::json!({
+            "status": "Success",
+            "message": "Email address changed",
+            "data": serde_json::json!({
+                "email": email
+            })
+        })),
+        Err(e) => error_response(e),
+    }
+}
+
+#[get("/me/export")]
+async fn export_account_handler(
+    auth: jwt_auth::JwtMiddleware,
+    data: web::Data<AppState>,
+)-> impl Responder{
+    if auth.is_api_key(){
+        return error_response(ServiceError::Unauthorized);
+    }
+
+    match data.accounts.export(&auth.user_id).await{
+        Ok(export) => HttpResponse::Ok()
+            .insert_header((
+                header::CONTENT_DISPOSITION,
+                format!("attachment; filename=\"trabajo-export-{}.json\"", export.exported_at.format("%Y-%m-%d")),
+            ))
+            .json(export),
+        Err(e) => error_response(e),
+    }
+}
+
+#[delete("/me")]
+async fn delete_account_handler(
+    body: web::Json<DeleteAccountSchema>,
+    auth: jwt_auth::JwtMiddleware,
+    data: web::Data<AppState>,
+)-> impl Responder{
+    if auth.is_api_key(){
+        return error_response(ServiceError::Unauthorized);
+    }
+
+    match data.accounts.request_deletion(&auth.user_id, &body.password).await{
+        Ok(purge_at) => HttpResponse::Ok()
+            .cookie(cookie::expired_session_cookie(&data.env))
+            .cookie(cookie::expired_csrf_cookie(&data.env))
+            .json(serde_json::json!({
+                "status": "Success",
+                "message": "Account scheduled for deletion, log in again before then to cancel",
+                "data": serde_json::json!({
+                    "purge_at": purge_at
+                })
+            })),
+        Err(e) => error_response(e),
+    }
+}
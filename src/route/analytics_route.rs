@@ -0,0 +1,115 @@
+use crate::{
+    core::{
+        config::config::Config,
+        helpers::{api_key, cookie, response::error_response},
+        middleware::csrf::generate_token,
+    },
+    jwt_auth,
+    route::user_route::missing_scope,
+    schema::analytics_schema::AnalyticsQuery,
+    AppState,
+};
+
+use actix_web::{
+    cookie::Cookie, get, post, web, HttpRequest, HttpResponse, Responder,
+};
+use uuid::Uuid;
+
+// Who is looking at a job: the logged in user, or else the anonymous
+// visitor cookie. A first time visitor gets a new cookie to send back.
+pub fn visitor(req: &HttpRequest, auth: &Option<jwt_auth::JwtMiddleware>, config: &Config) -> (String, Option<Cookie<'static>>){
+    if let Some(auth) = auth{
+        return (format!("user:{}", auth.user_id), None);
+    }
+    match req.cookie(cookie::VISITOR_COOKIE){
+        Some(visitor) => (format!("visitor:{}", visitor.value()), None),
+        None => {
+            let id = generate_token();
+            (format!("visitor:{}", id), Some(cookie::visitor_cookie(config, id)))
+        }
+    }
+}
+
+// the frontend calls this when someone heads to the application form
+#[post("/job/{job_id}/apply-click")]
+async fn apply_click_handler(
+    req: HttpRequest,
+    params: web::Path<Uuid>,
+    auth: Option<jwt_auth::JwtMiddleware>,
+    data: web::Data<AppState>,
+)-> impl Responder{
+    let (visitor, new_cookie) = visitor(&req, &auth, &data.env);
+
+    match data.analytics.record_apply_click(&params.into_inner(), &visitor).await{
+        Ok(_) => {
+            let mut response = HttpResponse::Ok();
+            if let Some(new_cookie) = new_cookie{
+                response.cookie(new_cookie);
+            }
+            response.json(serde_json::json!({
+                "status": "Success",
+                "message": "Apply click recorded"
+            }))
+        },
+        Err(e) => error_response(e),
+    }
+}
+
+#[get("/job/{job_id}/analytics")]
+async fn job_analytics_handler(
+    params: web::Path<Uuid>,
+    query: web::Query<AnalyticsQuery>,
+    auth: jwt_auth::JwtMiddleware,
+    data: web::Data<AppState>,
+)-> impl Responder{
+    if !auth.has_scope(api_key::JOBS_READ){
+        return missing_scope(api_key::JOBS_READ);
+    }
+
+    match data.analytics.job(&auth.user_id, &params.into_inner(), &query).await{
+        // company keys only see their own company's jobs
+        Ok(analytics) if auth.company_name().is_some_and(|company_name| company_name != analytics.company_name) => {
+            forbidden_company()
+        },
+        Ok(analytics) => HttpResponse::Ok().json(serde_json::json!({
+            "status": "Success",
+            "message": "Job analytics fetched",
+            "data": analytics
+        })),
+        Err(e) => error_response(e),
+    }
+}
+
+#[get("/company/{company_name}/analytics")]
+async fn company_analytics_handler(
+    params: web::Path<String>,
+    query: web::Query<AnalyticsQuery>,
+    auth: jwt_auth::JwtMiddleware,
+    data: web::Data<AppState>,
+)-> impl Responder{
+    if !auth.has_scope(api_key::JOBS_READ){
+        return missing_scope(api_key::JOBS_READ);
+    }
+    let company_name = params.into_inner();
+    if auth.company_name().is_some_and(|own| own != company_name){
+        return forbidden_company();
+    }
+
+    match data.analytics.company(&auth.user_id, &company_name, &query).await{
+        Ok(analytics) => HttpResponse::Ok().json(serde_json::json!({
+            "status": "Success",
+            "message": "Company analytics fetched",
+            "data": analytics
+        })),
+        Err(e) => error_response(e),
+    }
+}
+
+fn forbidden_company() -> HttpResponse{
+    HttpResponse::Forbidden().json(
+        serde_json::json!({
+            "status":"Error",
+            "message": "API key cannot read analytics for this company"
+        })
+    )
+}
@@ -0,0 +1,130 @@
+use crate::{
+    core::helpers::response::{error_response, FilteredApiKey},
+    jwt_auth,
+    model::api_key_model::ApiKey,
+    schema::api_key_schema::{CreateApiKey, RotateApiKey},
+    AppState,
+};
+
+use actix_web::{
+    delete, get, post, web, HttpResponse, Responder,
+};
+use uuid::Uuid;
+
+fn filter_api_key_record(key: &ApiKey) -> FilteredApiKey{
+    FilteredApiKey{
+        id: key.id.to_string(),
+        name: key.name.to_owned(),
+        prefix: key.prefix.to_owned(),
+        company_name: key.company_name.to_owned(),
+        scopes: key.scopes.to_owned(),
+        last_used_at: key.last_used_at,
+        expires_at: key.expires_at,
+        revoked_at: key.revoked_at,
+        createdAt: key.created_at,
+    }
+}
+
+fn api_keys_forbidden() -> HttpResponse{
+    HttpResponse::Forbidden().json(
+        serde_json::json!({
+            "status": "Error",
+            "message": "API keys cannot be used to manage API keys"
+        })
+    )
+}
+
+#[post("/key")]
+async fn create_api_key(
+    auth: jwt_auth::JwtMiddleware,
+    data: web::Data<AppState>,
+    body: web::Json<CreateApiKey>,
+)-> impl Responder{
+    if auth.is_api_key(){
+        return api_keys_forbidden();
+    }
+
+    match data.api_keys.create(&auth.user_id, &body).await{
+        Ok((key, api_key))=>{
+            HttpResponse::Ok().json(serde_json::json!({
+                "status": "Success",
+                "message": "API key created, it will not be shown again",
+                "data": serde_json::json!({
+                    "key": key,
+                    "api_key": filter_api_key_record(&api_key)
+                })
+            }))
+        }
+        Err(e)=> error_response(e),
+    }
+}
+
+#[get("/keys")]
+async fn fetch_api_keys(
+    auth: jwt_auth::JwtMiddleware,
+    data: web::Data<AppState>,
+)-> impl Responder{
+    if auth.is_api_key(){
+        return api_keys_forbidden();
+    }
+
+    match data.api_keys.list(&auth.user_id).await{
+        Ok(keys)=>{
+            let keys: Vec<FilteredApiKey> = keys.iter().map(filter_api_key_record).collect();
+            HttpResponse::Ok().json(serde_json::json!({
+                "status": "Success",
+                "message": "API keys fetched",
+                "data": keys
+            }))
+        }
+        Err(e)=> error_response(e),
+    }
+}
+
+#[post("/key/{key_id}/rotate")]
+async fn rotate_api_key(
+    auth: jwt_auth::JwtMiddleware,
+    data: web::Data<AppState>,
+    params: web::Path<Uuid>,
+    body: Option<web::Json<RotateApiKey>>,
+)-> impl Responder{
+    if auth.is_api_key(){
+        return api_keys_forbidden();
+    }
+
+    let grace_period_minutes = body.and_then(|b| b.grace_period_minutes).unwrap_or(0);
+    match data.api_keys.rotate(&auth.user_id, &params.into_inner(), grace_period_minutes).await{
+        Ok((key, rotated))=>{
+            HttpResponse::Ok().json(serde_json::json!({
+                "status": "Success",
+                "message": "API key rotated, it will not be shown again",
+                "data": serde_json::json!({
+                    "key": key,
+                    "api_key": filter_api_key_record(&rotated)
+                })
+            }))
+        }
+        Err(e)=> error_response(e),
+    }
+}
+
+#[delete("/key/{key_id}")]
+async fn revoke_api_key(
+    auth: jwt_auth::JwtMiddleware,
+    data: web::Data<AppState>,
+    params: web::Path<Uuid>,
+)-> impl Responder{
+    if auth.is_api_key(){
+        return api_keys_forbidden();
+    }
+
+    match data.api_keys.revoke(&auth.user_id, &params.into_inner()).await{
+        Ok(())=>{
+            HttpResponse::Ok().json(serde_json::json!({
+                "status": "Success",
+                "message": "API key revoked"
+            }))
+        }
+        Err(e)=> error_response(e),
+    }
+}
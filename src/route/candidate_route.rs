@@ -0,0 +1,242 @@
+use crate::{
+    core::helpers::{api_key, response::error_response},
+    jwt_auth,
+    route::user_route::missing_scope,
+    schema::candidate_schema::{
+        CertificationInput, EducationInput, PortfolioLinkInput, ProfileSection, SkillInput,
+        UpdateCandidateProfile, WorkExperienceInput,
+    },
+    service::ServiceError,
+    AppState,
+};
+
+use actix_web::{
+    delete, get, http::StatusCode, post, put, web, HttpResponse, Responder,
+};
+use serde::Serialize;
+use uuid::Uuid;
+
+// The candidate's own profile lives under /me/profile, one sub path per list
+// section. Adding an entry answers 201, replacing one 200.
+
+fn entry_response<T: Serialize>(result: Result<T, ServiceError>, status: StatusCode, message: &str) -> HttpResponse{
+    match result{
+        Ok(entry) => HttpResponse::build(status).json(serde_json::json!({
+            "status": "Success",
+            "message": message,
+            "data": entry
+        })),
+        Err(e) => error_response(e),
+    }
+}
+
+#[get("/me/profile")]
+async fn get_profile_handler(
+    auth: jwt_auth::JwtMiddleware,
+    data: web::Data<AppState>,
+)-> impl Responder{
+    if !auth.has_scope(api_key::PROFILE_READ){
+        return missing_scope(api_key::PROFILE_READ);
+    }
+
+    entry_response(data.candidates.profile(&auth.user_id).await, StatusCode::OK, "Profile fetched")
+}
+
+#[put("/me/profile")]
+async fn update_profile_handler(
+    body: web::Json<UpdateCandidateProfile>,
+    auth: jwt_auth::JwtMiddleware,
+    data: web::Data<AppState>,
+)-> impl Responder{
+    if !auth.has_scope(api_key::PROFILE_WRITE){
+        return missing_scope(api_key::PROFILE_WRITE);
+    }
+
+    let result = data.candidates.update_profile(&auth.user_id, body.into_inner()).await;
+    entry_response(result, StatusCode::OK, "Profile updated")
+}
+
+#[post("/me/profile/experience")]
+async fn add_experience_handler(
+    body: web::Json<WorkExperienceInput>,
+    auth: jwt_auth::JwtMiddleware,
+    data: web::Data<AppState>,
+)-> impl Responder{
+    if !auth.has_scope(api_key::PROFILE_WRITE){
+        return missing_scope(api_key::PROFILE_WRITE);
+    }
+
+    let result = data.candidates.add_experience(&auth.user_id, body.into_inner()).await;
+    entry_response(result, StatusCode::CREATED, "Experience added")
+}
+
+#[put("/me/profile/experience/{id}")]
+async fn update_experience_handler(
+    path: web::Path<Uuid>,
+    body: web::Json<WorkExperienceInput>,
+    auth: jwt_auth::JwtMiddleware,
+    data: web::Data<AppState>,
+)-> impl Responder{
+    if !auth.has_scope(api_key::PROFILE_WRITE){
+        return missing_scope(api_key::PROFILE_WRITE);
+    }
+
+    let result = data.candidates.update_experience(&auth.user_id, &path.into_inner(), body.into_inner()).await;
+    entry_response(result, StatusCode::OK, "Experience updated")
+}
+
+#[post("/me/profile/education")]
+async fn add_education_handler(
+    body: web::Json<EducationInput>,
+    auth: jwt_auth::JwtMiddleware,
+    data: web::Data<AppState>,
+)-> impl Responder{
+    if !auth.has_scope(api_key::PROFILE_WRITE){
+        return missing_scope(api_key::PROFILE_WRITE);
+    }
+
+    let result = data.candidates.add_education(&auth.user_id, body.into_inner()).await;
+    entry_response(result, StatusCode::CREATED, "Education added")
+}
+
+#[put("/me/profile/education/{id}")]
+async fn update_education_handler(
+    path: web::Path<Uuid>,
+    body: web::Json<EducationInput>,
+    auth: jwt_auth::JwtMiddleware,
+    data: web::Data<AppState>,
+)-> impl Responder{
+    if !auth.has_scope(api_key::PROFILE_WRITE){
+        return missing_scope(api_key::PROFILE_WRITE);
+    }
+
+    let result = data.candidates.update_education(&auth.user_id, &path.into_inner(), body.into_inner()).await;
+    entry_response(result, StatusCode::OK, "Education updated")
+}
+
+#[post("/me/profile/certifications")]
+async fn add_certification_handler(
+    body: web::Json<CertificationInput>,
+    auth: jwt_auth::JwtMiddleware,
+    data: web::Data<AppState>,
+)-> impl Responder{
+    if !auth.has_scope(api_key::PROFILE_WRITE){
+        return missing_scope(api_key::PROFILE_WRITE);
+    }
+
+    let result = data.candidates.add_certification(&auth.user_id, body.into_inner()).await;
+    entry_response(result, StatusCode::CREATED, "Certification added")
+}
+
+#[put("/me/profile/certifications/{id}")]
+async fn update_certification_handler(
+    path: web::Path<Uuid>,
+    body: web::Json<CertificationInput>,
+    auth: jwt_auth::JwtMiddleware,
+    data: web::Data<AppState>,
+)-> impl Responder{
+    if !auth.has_scope(api_key::PROFILE_WRITE){
+        return missing_scope(api_key::PROFILE_WRITE);
+    }
+
+    let result = data.candidates.update_certification(&auth.user_id, &path.into_inner(), body.into_inner()).await;
+    entry_response(result, StatusCode::OK, "Certification updated")
+}
+
+#[post("/me/profile/skills")]
+async fn add_skill_handler(
+    body: web::Json<SkillInput>,
+    auth: jwt_auth::JwtMiddleware,
+    data: web::Data<AppState>,
+)-> impl Responder{
+    if !auth.has_scope(api_key::PROFILE_WRITE){
+        return missing_scope(api_key::PROFILE_WRITE);
+    }
+
+    let result = data.candidates.add_skill(&auth.user_id, body.into_inner()).await;
+    entry_response(result, StatusCode::CREATED, "Skill added")
+}
+
+#[put("/me/profile/skills/{id}")]
+async fn update_skill_handler(
+    path: web::Path<Uuid>,
+    body: web::Json<SkillInput>,
+    auth: jwt_auth::JwtMiddleware,
+    data: web::Data<AppState>,
+)-> impl Responder{
+    if !auth.has_scope(api_key::PROFILE_WRITE){
+        return missing_scope(api_key::PROFILE_WRITE);
+    }
+
+    let result = data.candidates.update_skill(&auth.user_id, &path.into_inner(), body.into_inner()).await;
+    entry_response(result, StatusCode::OK, "Skill updated")
+}
+
+#[post("/me/profile/links")]
+async fn add_link_handler(
+    body: web::Json<PortfolioLinkInput>,
+    auth: jwt_auth::JwtMiddleware,
+    data: web::Data<AppState>,
+)-> impl Responder{
+    if !auth.has_scope(api_key::PROFILE_WRITE){
+        return missing_scope(api_key::PROFILE_WRITE);
+    }
+
+    let result = data.candidates.add_link(&auth.user_id, body.into_inner()).await;
+    entry_response(result, StatusCode::CREATED, "Link added")
+}
+
+#[put("/me/profile/links/{id}")]
+async fn update_link_handler(
+    path: web::Path<Uuid>,
+    body: web::Json<PortfolioLinkInput>,
+    auth: jwt_auth::JwtMiddleware,
+    data: web::Data<AppState>,
+)-> impl Responder{
+    if !auth.has_scope(api_key::PROFILE_WRITE){
+        return missing_scope(api_key::PROFILE_WRITE);
+    }
+
+    let result = data.candidates.update_link(&auth.user_id, &path.into_inner(), body.into_inner()).await;
+    entry_response(result, StatusCode::OK, "Link updated")
+}
+
+#[delete("/me/profile/{section}/{id}")]
+async fn remove_entry_handler(
+    path: web::Path<(String, Uuid)>,
+    auth: jwt_auth::JwtMiddleware,
+    data: web::Data<AppState>,
+)-> impl Responder{
+    if !auth.has_scope(api_key::PROFILE_WRITE){
+        return missing_scope(api_key::PROFILE_WRITE);
+    }
+
+    let (section, id) = path.into_inner();
+    let section = match ProfileSection::from_path(&section){
+        Some(section) => section,
+        None => return error_response(ServiceError::NotFound("Profile section")),
+    };
+
+    match data.candidates.remove(&auth.user_id, section, &id).await{
+        Ok(()) => HttpResponse::Ok().json(serde_json::json!({
+            "status": "Success",
+            "message": format!("{} removed", section.label())
+        })),
+        Err(e) => error_response(e),
+    }
+}
+
+// what recruiters see when they open an applicant
+#[get("/candidates/{user_id}/profile")]
+async fn fetch_candidate_profile(
+    path: web::Path<Uuid>,
+    auth: jwt_auth::JwtMiddleware,
+    data: web::Data<AppState>,
+)-> impl Responder{
+    if !auth.has_scope(api_key::APPLICATIONS_READ){
+        return missing_scope(api_key::APPLICATIONS_READ);
+    }
+
+    let result = data.candidates.profile_for_recruiter(&auth.user_id, &path.into_inner()).await;
+    entry_response(result, StatusCode::OK, "Profile fetched")
+}
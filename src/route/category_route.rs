@@ -0,0 +1,46 @@
+use crate::{
+    core::helpers::{api_key, response::error_response},
+    jwt_auth,
+    route::user_route::missing_scope,
+    schema::category_schema::CreateCategory,
+    AppState,
+};
+
+use actix_web::{
+    get, post, web, HttpResponse, Responder,
+};
+
+// the whole tree, for filter sidebars and the job form
+#[get("/categories")]
+async fn fetch_categories(
+    data: web::Data<AppState>,
+)-> impl Responder{
+    match data.categories.tree().await{
+        Ok(categories) => HttpResponse::Ok().json(serde_json::json!({
+            "status": "Success",
+            "message": "Categories fetched",
+            "data": categories
+        })),
+        Err(e) => error_response(e),
+    }
+}
+
+#[post("/categories")]
+async fn create_category(
+    body: web::Json<CreateCategory>,
+    auth: jwt_auth::JwtMiddleware,
+    data: web::Data<AppState>,
+)-> impl Responder{
+    if !auth.has_scope(api_key::JOBS_WRITE){
+        return missing_scope(api_key::JOBS_WRITE);
+    }
+
+    match data.categories.create(&auth.user_id, &body).await{
+        Ok(category) => HttpResponse::Created().json(serde_json::json!({
+            "status": "Success",
+            "message": "Category created",
+            "data": category
+        })),
+        Err(e) => error_response(e),
+    }
+}
@@ -1,5 +1,5 @@
 use actix_multipart::{ Multipart };
-use actix_web::{Responder, HttpMessage};
+use actix_web::Responder;
 use futures_util::{ TryStreamExt as _ };
 use mime::{ Mime, IMAGE_PNG, IMAGE_JPEG, IMAGE_GIF, APPLICATION_PDF };
 use serde_json::json;
@@ -13,8 +13,9 @@ use actix_web::{
     patch,
     http::header::CONTENT_LENGTH };
 use crate::{
+    core::helpers::{api_key, response::error_response},
     jwt_auth,
-    model::user_model::User,
+    route::user_route::missing_scope,
     AppState,
 };
     
@@ -25,11 +26,12 @@ async fn upload(
     mut payload: Multipart,
     req: HttpRequest, 
     data: web::Data<AppState>,
-    _: jwt_auth::JwtMiddleware,
+    auth: jwt_auth::JwtMiddleware,
 ) -> impl Responder {
 
-    let ext = req.extensions();
-    let user_id = ext.get::<uuid::Uuid>().unwrap();
+    if !auth.has_scope(api_key::PROFILE_WRITE) {
+        return missing_scope(api_key::PROFILE_WRITE);
+    }
 
     let content_length: usize = match req.headers().get(CONTENT_LENGTH) {
         Some(header_value) => header_value.to_str().unwrap_or("0").parse().unwrap(),
@@ -40,7 +42,7 @@ async fn upload(
     let max_file_size: usize = 300_000;
     let legal_filetypes: [Mime; 4] = [IMAGE_PNG, IMAGE_JPEG, IMAGE_GIF, APPLICATION_PDF];
     let mut current_count: usize = 0;
-    let dir: &str = "./static/";
+    let dir: &str = data.env.storage_dir.trim_end_matches('/');
 
     if content_length > max_file_size { 
         return HttpResponse::BadRequest()
@@ -59,36 +61,28 @@ async fn upload(
             if filetype.is_none() { continue; }
             if !legal_filetypes.contains(&filetype.unwrap()) { continue; }
 
-            println!("content_length: {:#?}", content_length);
-            println!("{}. picture:", current_count);
-            println!("name {}", field.name());
-
-            // In a multipart/form-data body, the HTTP Content-Disposition general header is a header that can be used on the subpart of a multipart body to give information about the field it applies to. The subpart is delimited by the boundary defined in the Content-Type header. Used on the body itself, Content-Disposition has no effect.
-            println!("content disposition {}", field.content_disposition()); // &ContentDisposition
+            tracing::debug!(
+                content_length,
+                file_index = current_count,
+                content_type = %filetype.unwrap(),
+                "receiving resume upload"
+            );
 
-            println!("filename {}", field.content_disposition().get_filename().unwrap()); // Option<&str>
-            
             let destination: String = format!(
-                "{}{}-{}",
+                "{}/{}-{}",
                 dir,
                 Uuid::new_v4(),
                 field.content_disposition().get_filename().unwrap()
             );
             let mut saved_file: fs::File = fs::File::create(&destination).await.unwrap();
-            println!("{:?} m,", saved_file);
 
             while let Ok(Some(chunk)) = field.try_next().await {
                 let _ = saved_file.write_all(&chunk).await.unwrap();
             }
 
-            let _ = sqlx::query_as!(
-                User,
-                "UPDATE users set resume = $1 WHERE id = $2 returning *",
-                destination,
-                user_id
-            )
-            .fetch_one(&data.db)
-            .await;
+            if let Err(e) = data.users.attach_resume(&auth.user_id, &destination).await{
+                return error_response(e);
+            }
 
         } else { break; }
         current_count += 1;
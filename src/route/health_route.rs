@@ -0,0 +1,77 @@
+use crate::AppState;
+
+use actix_web::{get, web, HttpResponse, Responder};
+use std::time::Duration;
+use uuid::Uuid;
+
+const READINESS_TIMEOUT: Duration = Duration::from_secs(2);
+
+#[get("/healthz")]
+async fn liveness_handler()-> impl Responder{
+    HttpResponse::Ok().json(serde_json::json!({
+        "status": "Success",
+        "message": "Alive"
+    }))
+}
+
+#[get("/readyz")]
+async fn readiness_handler(
+    data: web::Data<AppState>
+)-> impl Responder{
+    // tell the load balancer to stop routing here while we drain
+    if data.shutdown.is_triggered(){
+        return HttpResponse::ServiceUnavailable().json(serde_json::json!({
+            "status": "Error",
+            "message": "Shutting down"
+        }));
+    }
+
+    let database = check_database(&data).await;
+    let storage = check_storage(&data).await;
+
+    let checks = serde_json::json!({
+        "database": database.as_ref().map_or_else(|e| e.to_owned(), |_| "ok".to_string()),
+        "storage": storage.as_ref().map_or_else(|e| e.to_owned(), |_| "ok".to_string()),
+    });
+
+    if database.is_ok() && storage.is_ok(){
+        HttpResponse::Ok().json(serde_json::json!({
+            "status": "Success",
+            "message": "Ready",
+            "data": checks
+        }))
+    } else{
+        HttpResponse::ServiceUnavailable().json(serde_json::json!({
+            "status": "Error",
+            "message": "Not ready",
+            "data": checks
+        }))
+    }
+}
+
+#[get("/metrics")]
+async fn metrics_handler(
+    data: web::Data<AppState>
+)-> impl Responder{
+    data.metrics.observe_pool(&data.db);
+
+    HttpResponse::Ok()
+        .content_type("text/plain; version=0.0.4")
+        .body(data.metrics.render())
+}
+
+async fn check_database(data: &web::Data<AppState>) -> Result<(), String>{
+    let query = sqlx::query("SELECT 1").execute(&data.db);
+    match tokio::time::timeout(READINESS_TIMEOUT, query).await{
+        Ok(Ok(_)) => Ok(()),
+        Ok(Err(e)) => Err(e.to_string()),
+        Err(_) => Err("timed out".to_string()),
+    }
+}
+
+// uploads land in the storage dir, so it has to exist and be writable
+async fn check_storage(data: &web::Data<AppState>) -> Result<(), String>{
+    let probe = std::path::Path::new(&data.env.storage_dir).join(format!(".readyz-{}", Uuid::new_v4()));
+    tokio::fs::write(&probe, b"").await.map_err(|e| e.to_string())?;
+    tokio::fs::remove_file(&probe).await.map_err(|e| e.to_string())
+}
@@ -1,71 +1,50 @@
 
 use crate::{
+    core::helpers::{api_key, response::error_response},
     jwt_auth,
-    model::job_model::Job,
-    route::user_route::find_user_by_id,
+    route::{analytics_route::visitor, user_route::missing_scope},
     schema::job_schema::{CreateJobPosting, QueryParam},
     AppState,
 };
 
 use actix_web::{
-    get, post, web, HttpMessage, HttpRequest, HttpResponse, Responder,
+    get, post, web, HttpRequest, HttpResponse, Responder,
 };
 use uuid::Uuid;
 
 #[post("/job")]
 async fn create_job_posting(
-    req: HttpRequest,
     data: web::Data<AppState>,
-    _: jwt_auth::JwtMiddleware,
+    auth: jwt_auth::JwtMiddleware,
     body: web::Json<CreateJobPosting>,
 )-> impl Responder{
-    let ext = req.extensions();
-    let user_id: &uuid::Uuid = ext.get::<uuid::Uuid>().unwrap();
-
-    // check if user is an admin
-    let user_info = find_user_by_id(&data, user_id).await;
-    println!("{:?}", user_info);
-    if user_info.role != "Admin"{
-        return  HttpResponse::Unauthorized().json(
-            serde_json::json!({
-                "status":"Error",
-                "message": "Unauthorized"
-            })
-        );
+    if !auth.has_scope(api_key::JOBS_WRITE){
+        return missing_scope(api_key::JOBS_WRITE);
     }
-    
 
-    let query_result = sqlx::query_as!(
-        Job,
-        "INSERT INTO jobs (title, company_name, city, country, salary, description) VALUES ($1, $2, $3, $4, $5, $6) RETURNING *",
-        body.title.to_string(),
-        body.company_name.to_string(),
-        body.city.to_string(),
-        body.country.to_string(),
-        body.salary.to_string(),
-        body.description.to_string()
-    )
-    .fetch_one(&data.db)
-    .await;
+    // company keys may only post for their own company
+    if let Some(company_name) = auth.company_name(){
+        if company_name != body.company_name{
+            return HttpResponse::Forbidden().json(
+                serde_json::json!({
+                    "status":"Error",
+                    "message": "API key cannot post jobs for this company"
+                })
+            );
+        }
+    }
 
-    match query_result{
+    match data.jobs.create(&auth.user_id, &body).await{
         Ok(job)=>{
-            let create_job_response = serde_json::json!({
+            data.metrics.jobs_posted_total.inc();
+            HttpResponse::Ok().json(serde_json::json!({
                 "status":"Success",
                 "data": serde_json::json!({
                     "id": job.id
                 })
-            });
-            return HttpResponse::Ok().json(create_job_response);
+            }))
         },
-        Err(e)=>{
-            return HttpResponse::InternalServerError()
-                .json(serde_json::json!({
-                    "status":"Error",
-                    "message": format!("{:?}", e)
-                }));
-        }
-
+        Err(e) => error_response(e),
     }
 }
 
@@ -74,54 +53,73 @@ async fn fetch_job_posting(
     query: web::Query<QueryParam>,
     data: web::Data::<AppState>
 )-> impl Responder{
-
-    let mut offset = 0;
-
-    match &query.page{
-        None =>  {},
-        Some(x)=>{
-            offset = x.parse().unwrap();
-        }
+    let filters = match query.filters(){
+        Ok(filters) => filters,
+        Err(e) => return error_response(e),
     };
 
-    offset = offset * 10;
+    let jobs = match data.jobs.list(query.page_number(), &filters).await{
+        Ok(jobs) => jobs,
+        Err(e) => return error_response(e),
+    };
+    match data.jobs.facets(&filters).await{
+        Ok(facets) => HttpResponse::Ok().json(serde_json::json!({
+            "status": "Success",
+            "message": "Jobs fetched",
+            "data": jobs,
+            "facets": facets
+        })),
+        Err(e) => error_response(e),
+    }
+}
 
-    let jobs = sqlx::query_as!(Job, "SELECT * FROM jobs ORDER BY RANDOM() LIMIT 10 OFFSET $1", offset)
-    .fetch_all(&data.db)
-    .await
-    .unwrap();
+// the filter counts alone, for refreshing the sidebar without a page of jobs
+#[get("/jobs/facets")]
+async fn fetch_job_facets(
+    query: web::Query<QueryParam>,
+    data: web::Data::<AppState>
+)-> impl Responder{
+    let filters = match query.filters(){
+        Ok(filters) => filters,
+        Err(e) => return error_response(e),
+    };
 
-    let json_response = serde_json::json!({
-        "status": "Success",
-        "message": "Jobs fetched",
-        "data": jobs
-    });
-    HttpResponse::Ok().json(json_response)
+    match data.jobs.facets(&filters).await{
+        Ok(facets) => HttpResponse::Ok().json(serde_json::json!({
+            "status": "Success",
+            "message": "Facets fetched",
+            "data": facets
+        })),
+        Err(e) => error_response(e),
+    }
 }
 
+// counts a view for the job analytics, once a day per visitor
 #[get("/job/{job_id}")]
 async fn find_job_by_id(
+    req: HttpRequest,
     data: web::Data::<AppState>,
-    params: web::Path<String>
+    auth: Option<jwt_auth::JwtMiddleware>,
+    params: web::Path<Uuid>
 )-> impl Responder{
+    let job = match data.jobs.find(&params.into_inner()).await{
+        Ok(job) => job,
+        Err(e) => return error_response(e),
+    };
 
-    let job_id = params.into_inner();
-    let job_uuid: uuid::Uuid = Uuid::parse_str(&job_id).unwrap();
-
-    let jobs = sqlx::query_as!(
-        Job,
-        "SELECT * FROM jobs WHERE id = $1",
-        job_uuid
-    )
-    .fetch_one(&data.db)
-    .await
-    .unwrap();
+    // a view that cannot be recorded must not cost the visitor the page
+    let (visitor, new_cookie) = visitor(&req, &auth, &data.env);
+    if let Err(e) = data.analytics.record_view(&job.job.id, &visitor).await{
+        tracing::warn!(job_id = %job.job.id, error = %e, "job view not recorded");
+    }
 
-    let json_responder = serde_json::json!({
+    let mut response = HttpResponse::Ok();
+    if let Some(new_cookie) = new_cookie{
+        response.cookie(new_cookie);
+    }
+    response.json(serde_json::json!({
         "status": "Success",
         "message": "Jobs fetched",
-        "data": jobs
-    });
-
-    HttpResponse::Ok().json(json_responder)
-}
\ No newline at end of file
+        "data": job
+    }))
+}
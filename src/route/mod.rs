@@ -2,6 +2,17 @@ pub mod user_route;
 pub mod job_route;
 pub mod application_route;
 pub mod file_upload;
+pub mod api_key_route;
+pub mod health_route;
+pub mod queue_route;
+pub mod account_route;
+pub mod candidate_route;
+pub mod skill_route;
+pub mod recommendation_route;
+pub mod saved_search_route;
+pub mod saved_job_route;
+pub mod category_route;
+pub mod analytics_route;
 use actix_web::web;
 
 
@@ -11,14 +22,65 @@ pub fn config(conf: &mut web::ServiceConfig) {
         .service(user_route::login_user_handler)
         .service(user_route::logout_handler)
         .service(user_route::get_me_handler)
+        .service(user_route::csrf_token_handler)
         .service(user_route::register_admin_handler)
         .service(job_route::create_job_posting)
         .service(job_route::find_job_by_id)
         .service(job_route::fetch_job_posting)
+        .service(job_route::fetch_job_facets)
         .service(application_route::fetch_application)
         .service(application_route::create_application)
         .service(application_route::fetch_job_application)
-        .service(file_upload::upload);
+        .service(file_upload::upload)
+        .service(api_key_route::create_api_key)
+        .service(api_key_route::fetch_api_keys)
+        .service(api_key_route::rotate_api_key)
+        .service(api_key_route::revoke_api_key)
+        .service(queue_route::queue_stats)
+        .service(queue_route::fetch_queued_jobs)
+        .service(queue_route::find_queued_job)
+        .service(queue_route::retry_queued_job)
+        .service(account_route::export_account_handler)
+        .service(account_route::delete_account_handler)
+        .service(account_route::update_profile_handler)
+        .service(account_route::change_password_handler)
+        .service(account_route::change_email_handler)
+        .service(account_route::confirm_email_handler)
+        .service(candidate_route::get_profile_handler)
+        .service(candidate_route::update_profile_handler)
+        .service(candidate_route::add_experience_handler)
+        .service(candidate_route::update_experience_handler)
+        .service(candidate_route::add_education_handler)
+        .service(candidate_route::update_education_handler)
+        .service(candidate_route::add_certification_handler)
+        .service(candidate_route::update_certification_handler)
+        .service(candidate_route::add_skill_handler)
+        .service(candidate_route::update_skill_handler)
+        .service(candidate_route::add_link_handler)
+        .service(candidate_route::update_link_handler)
+        .service(candidate_route::remove_entry_handler)
+        .service(candidate_route::fetch_candidate_profile)
+        .service(skill_route::fetch_skills)
+        .service(skill_route::create_skill)
+        .service(recommendation_route::recommended_jobs)
+        .service(recommendation_route::suggested_candidates)
+        .service(saved_search_route::create_saved_search)
+        .service(saved_search_route::fetch_saved_searches)
+        .service(saved_search_route::update_saved_search)
+        .service(saved_search_route::delete_saved_search)
+        .service(saved_search_route::unsubscribe_handler)
+        .service(saved_job_route::save_job_handler)
+        .service(saved_job_route::remove_saved_job_handler)
+        .service(saved_job_route::fetch_saved_jobs)
+        .service(category_route::fetch_categories)
+        .service(category_route::create_category)
+        .service(analytics_route::apply_click_handler)
+        .service(analytics_route::job_analytics_handler)
+        .service(analytics_route::company_analytics_handler);
 
-    conf.service(scope);
+    conf.service(scope)
+        .service(user_route::jwks_handler)
+        .service(health_route::liveness_handler)
+        .service(health_route::readiness_handler)
+        .service(health_route::metrics_handler);
 }
\ No newline at end of file
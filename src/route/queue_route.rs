@@ -0,0 +1,101 @@
+use crate::{
+    core::helpers::response::error_response,
+    jwt_auth,
+    schema::queue_schema::QueueQuery,
+    service::ServiceError,
+    AppState,
+};
+
+use actix_web::{
+    get, post, web, HttpResponse, Responder,
+};
+use uuid::Uuid;
+
+// the queue is never reachable through an API key, the service checks that
+// the user is an admin
+fn reject_api_keys(auth: &jwt_auth::JwtMiddleware) -> Option<HttpResponse>{
+    auth.is_api_key().then(|| error_response(ServiceError::Unauthorized))
+}
+
+#[get("/queue/stats")]
+async fn queue_stats(
+    auth: jwt_auth::JwtMiddleware,
+    data: web::Data<AppState>,
+)-> impl Responder{
+    if let Some(response) = reject_api_keys(&auth){
+        return response;
+    }
+
+    match data.queue.stats(&auth.user_id).await{
+        Ok(stats) => HttpResponse::Ok().json(serde_json::json!({
+            "status": "Success",
+            "message": "Queue stats fetched",
+            "data": stats
+        })),
+        Err(e) => error_response(e),
+    }
+}
+
+#[get("/queue/jobs")]
+async fn fetch_queued_jobs(
+    auth: jwt_auth::JwtMiddleware,
+    data: web::Data<AppState>,
+    query: web::Query<QueueQuery>,
+)-> impl Responder{
+    if let Some(response) = reject_api_keys(&auth){
+        return response;
+    }
+
+    let jobs = data
+        .queue
+        .list(&auth.user_id, query.status.as_deref(), query.kind.as_deref(), query.page.unwrap_or(0))
+        .await;
+    match jobs{
+        Ok(jobs) => HttpResponse::Ok().json(serde_json::json!({
+            "status": "Success",
+            "message": "Queued jobs fetched",
+            "data": jobs
+        })),
+        Err(e) => error_response(e),
+    }
+}
+
+#[get("/queue/job/{job_id}")]
+async fn find_queued_job(
+    auth: jwt_auth::JwtMiddleware,
+    data: web::Data<AppState>,
+    params: web::Path<Uuid>,
+)-> impl Responder{
+    if let Some(response) = reject_api_keys(&auth){
+        return response;
+    }
+
+    match data.queue.find(&auth.user_id, &params.into_inner()).await{
+        Ok(job) => HttpResponse::Ok().json(serde_json::json!({
+            "status": "Success",
+            "message": "Queued job fetched",
+            "data": job
+        })),
+        Err(e) => error_response(e),
+    }
+}
+
+#[post("/queue/job/{job_id}/retry")]
+async fn retry_queued_job(
+    auth: jwt_auth::JwtMiddleware,
+    data: web::Data<AppState>,
+    params: web::Path<Uuid>,
+)-> impl Responder{
+    if let Some(response) = reject_api_keys(&auth){
+        return response;
+    }
+
+    match data.queue.retry(&auth.user_id, &params.into_inner()).await{
+        Ok(job) => HttpResponse::Ok().json(serde_json::json!({
+            "status": "Success",
+            "message": "Job queued for retry",
+            "data": job
+        })),
+        Err(e) => error_response(e),
+    }
+}
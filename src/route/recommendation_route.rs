@@ -0,0 +1,54 @@
+use crate::{
+    core::helpers::{api_key, response::error_response},
+    jwt_auth,
+    route::user_route::missing_scope,
+    schema::job_schema::QueryParam,
+    AppState,
+};
+
+use actix_web::{
+    get, web, HttpResponse, Responder,
+};
+use uuid::Uuid;
+
+// ranked by how well the profile of the caller fits, see service::scoring
+#[get("/me/recommended-jobs")]
+async fn recommended_jobs(
+    query: web::Query<QueryParam>,
+    auth: jwt_auth::JwtMiddleware,
+    data: web::Data<AppState>,
+)-> impl Responder{
+    if !auth.has_scope(api_key::PROFILE_READ){
+        return missing_scope(api_key::PROFILE_READ);
+    }
+
+    match data.recommendations.recommended_jobs(&auth.user_id, query.page_number()).await{
+        Ok(jobs) => HttpResponse::Ok().json(serde_json::json!({
+            "status": "Success",
+            "message": "Recommended jobs fetched",
+            "data": jobs
+        })),
+        Err(e) => error_response(e),
+    }
+}
+
+#[get("/job/{job_id}/candidates")]
+async fn suggested_candidates(
+    params: web::Path<Uuid>,
+    query: web::Query<QueryParam>,
+    auth: jwt_auth::JwtMiddleware,
+    data: web::Data<AppState>,
+)-> impl Responder{
+    if !auth.has_scope(api_key::APPLICATIONS_READ){
+        return missing_scope(api_key::APPLICATIONS_READ);
+    }
+
+    match data.recommendations.suggested_candidates(&auth.user_id, &params.into_inner(), query.page_number()).await{
+        Ok(candidates) => HttpResponse::Ok().json(serde_json::json!({
+            "status": "Success",
+            "message": "Suggested candidates fetched",
+            "data": candidates
+        })),
+        Err(e) => error_response(e),
+    }
+}
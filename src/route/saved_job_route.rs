@@ -0,0 +1,72 @@
+use crate::{
+    core::helpers::{api_key, response::error_response},
+    jwt_auth,
+    route::user_route::missing_scope,
+    AppState,
+};
+
+use actix_web::{
+    delete, get, post, web, HttpResponse, Responder,
+};
+use uuid::Uuid;
+
+#[post("/me/saved-jobs/{job_id}")]
+async fn save_job_handler(
+    path: web::Path<Uuid>,
+    auth: jwt_auth::JwtMiddleware,
+    data: web::Data<AppState>,
+)-> impl Responder{
+    if !auth.has_scope(api_key::PROFILE_WRITE){
+        return missing_scope(api_key::PROFILE_WRITE);
+    }
+
+    match data.saved_jobs.save(&auth.user_id, &path.into_inner()).await{
+        Ok(true) => HttpResponse::Created().json(serde_json::json!({
+            "status": "Success",
+            "message": "Job saved"
+        })),
+        Ok(false) => HttpResponse::Ok().json(serde_json::json!({
+            "status": "Success",
+            "message": "Job already saved"
+        })),
+        Err(e) => error_response(e),
+    }
+}
+
+#[delete("/me/saved-jobs/{job_id}")]
+async fn remove_saved_job_handler(
+    path: web::Path<Uuid>,
+    auth: jwt_auth::JwtMiddleware,
+    data: web::Data<AppState>,
+)-> impl Responder{
+    if !auth.has_scope(api_key::PROFILE_WRITE){
+        return missing_scope(api_key::PROFILE_WRITE);
+    }
+
+    match data.saved_jobs.remove(&auth.user_id, &path.into_inner()).await{
+        Ok(()) => HttpResponse::Ok().json(serde_json::json!({
+            "status": "Success",
+            "message": "Saved job removed"
+        })),
+        Err(e) => error_response(e),
+    }
+}
+
+#[get("/me/saved-jobs")]
+async fn fetch_saved_jobs(
+    auth: jwt_auth::JwtMiddleware,
+    data: web::Data<AppState>,
+)-> impl Responder{
+    if !auth.has_scope(api_key::PROFILE_READ){
+        return missing_scope(api_key::PROFILE_READ);
+    }
+
+    match data.saved_jobs.list(&auth.user_id).await{
+        Ok(jobs) => HttpResponse::Ok().json(serde_json::json!({
+            "status": "Success",
+            "message": "Saved jobs fetched",
+            "data": jobs
+        })),
+        Err(e) => error_response(e),
+    }
+}
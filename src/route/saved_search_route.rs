@@ -0,0 +1,106 @@
+use crate::{
+    core::helpers::{api_key, response::error_response},
+    jwt_auth,
+    route::user_route::missing_scope,
+    schema::saved_search_schema::{SavedSearchInput, UnsubscribeSchema},
+    AppState,
+};
+
+use actix_web::{
+    delete, get, post, put, web, HttpResponse, Responder,
+};
+use uuid::Uuid;
+
+#[post("/me/saved-searches")]
+async fn create_saved_search(
+    body: web::Json<SavedSearchInput>,
+    auth: jwt_auth::JwtMiddleware,
+    data: web::Data<AppState>,
+)-> impl Responder{
+    if !auth.has_scope(api_key::PROFILE_WRITE){
+        return missing_scope(api_key::PROFILE_WRITE);
+    }
+
+    match data.saved_searches.create(&auth.user_id, &body).await{
+        Ok(search) => HttpResponse::Created().json(serde_json::json!({
+            "status": "Success",
+            "message": "Search saved",
+            "data": search
+        })),
+        Err(e) => error_response(e),
+    }
+}
+
+#[get("/me/saved-searches")]
+async fn fetch_saved_searches(
+    auth: jwt_auth::JwtMiddleware,
+    data: web::Data<AppState>,
+)-> impl Responder{
+    if !auth.has_scope(api_key::PROFILE_READ){
+        return missing_scope(api_key::PROFILE_READ);
+    }
+
+    match data.saved_searches.list(&auth.user_id).await{
+        Ok(searches) => HttpResponse::Ok().json(serde_json::json!({
+            "status": "Success",
+            "message": "Saved searches fetched",
+            "data": searches
+        })),
+        Err(e) => error_response(e),
+    }
+}
+
+#[put("/me/saved-searches/{id}")]
+async fn update_saved_search(
+    path: web::Path<Uuid>,
+    body: web::Json<SavedSearchInput>,
+    auth: jwt_auth::JwtMiddleware,
+    data: web::Data<AppState>,
+)-> impl Responder{
+    if !auth.has_scope(api_key::PROFILE_WRITE){
+        return missing_scope(api_key::PROFILE_WRITE);
+    }
+
+    match data.saved_searches.update(&auth.user_id, &path.into_inner(), &body).await{
+        Ok(search) => HttpResponse::Ok().json(serde_json::json!({
+            "status": "Success",
+            "message": "Saved search updated",
+            "data": search
+        })),
+        Err(e) => error_response(e),
+    }
+}
+
+#[delete("/me/saved-searches/{id}")]
+async fn delete_saved_search(
+    path: web::Path<Uuid>,
+    auth: jwt_auth::JwtMiddleware,
+    data: web::Data<AppState>,
+)-> impl Responder{
+    if !auth.has_scope(api_key::PROFILE_WRITE){
+        return missing_scope(api_key::PROFILE_WRITE);
+    }
+
+    match data.saved_searches.delete(&auth.user_id, &path.into_inner()).await{
+        Ok(()) => HttpResponse::Ok().json(serde_json::json!({
+            "status": "Success",
+            "message": "Saved search removed"
+        })),
+        Err(e) => error_response(e),
+    }
+}
+
+// the unsubscribe link in alert emails, the token is all it takes
+#[post("/alerts/unsubscribe")]
+async fn unsubscribe_handler(
+    body: web::Json<UnsubscribeSchema>,
+    data: web::Data<AppState>,
+)-> impl Responder{
+    match data.saved_searches.unsubscribe(&body.token).await{
+        Ok(()) => HttpResponse::Ok().json(serde_json::json!({
+            "status": "Success",
+            "message": "Alerts turned off"
+        })),
+        Err(e) => error_response(e),
+    }
+}
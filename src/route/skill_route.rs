@@ -0,0 +1,47 @@
+use crate::{
+    core::helpers::{api_key, response::error_response},
+    jwt_auth,
+    route::user_route::missing_scope,
+    schema::skill_schema::{CreateSkill, SkillQuery},
+    AppState,
+};
+
+use actix_web::{
+    get, post, web, HttpResponse, Responder,
+};
+
+// autocomplete for skill inputs, public like the job listing
+#[get("/skills")]
+async fn fetch_skills(
+    query: web::Query<SkillQuery>,
+    data: web::Data<AppState>,
+)-> impl Responder{
+    match data.skills.suggest(query.q.as_deref()).await{
+        Ok(skills) => HttpResponse::Ok().json(serde_json::json!({
+            "status": "Success",
+            "message": "Skills fetched",
+            "data": skills
+        })),
+        Err(e) => error_response(e),
+    }
+}
+
+#[post("/skills")]
+async fn create_skill(
+    body: web::Json<CreateSkill>,
+    auth: jwt_auth::JwtMiddleware,
+    data: web::Data<AppState>,
+)-> impl Responder{
+    if !auth.has_scope(api_key::JOBS_WRITE){
+        return missing_scope(api_key::JOBS_WRITE);
+    }
+
+    match data.skills.create(&auth.user_id, &body).await{
+        Ok(skill) => HttpResponse::Created().json(serde_json::json!({
+            "status": "Success",
+            "message": "Skill created",
+            "data": skill
+        })),
+        Err(e) => error_response(e),
+    }
+}
@@ -1,28 +1,20 @@
-
 use crate::{
+    core::{helpers::{api_key, cookie, response::error_response}, middleware::csrf},
     jwt_auth,
     model::user_model::User,
-    schema::user_schema::{LoginUserSchema, RegisterUserSchema, TokenClaims},
+    schema::user_schema::{LoginUserSchema, RegisterUserSchema},
     core::helpers::response::FilteredUser,
+    service::user_service::{ROLE_ADMIN, ROLE_USER},
     AppState,
 };
 
 use actix_web::{
-    cookie::{time::Duration as ActixWebDuration, Cookie},
-    get, post, web, HttpMessage, HttpRequest, HttpResponse, Responder,
-};
-
-use argon2::{
-    password_hash::{rand_core::OsRng, PasswordHash, PasswordHasher, PasswordVerifier, SaltString},
-    Argon2,
+    get, post, web, HttpResponse, Responder,
 };
 
-use chrono::{prelude::*, Duration};
-use jsonwebtoken::{encode, EncodingKey, Header};
 use serde_json::json;
-use sqlx::Row;
 
-fn filter_user_record(user: &User) -> FilteredUser{
+pub fn filter_user_record(user: &User) -> FilteredUser{
     FilteredUser{
         id: user.id.to_string(),
         email: user.email.to_owned(),
@@ -41,55 +33,17 @@ async fn register_user_handler(
     body: web::Json<RegisterUserSchema>,
     data: web::Data<AppState>,
 ) -> impl Responder{
-    let exists: bool = sqlx::query("SELECT EXISTS(SELECT 1 FROM users WHERE email = $1)")
-        .bind(body.email.to_owned())
-        .fetch_one(&data.db)
-        .await
-        .unwrap()
-        .get(0);
-
-    if exists{
-        return HttpResponse::Conflict().json(
-            serde_json::json!({
-                "status": "Error",
-                "message": "Email already in use"
-            })
-        );
-    }
-
-    let salt = SaltString::generate(&mut OsRng);
-    let hashed_password = Argon2::default()
-        .hash_password(body.password.as_bytes(), &salt)
-        .expect("Error while hashing password")
-        .to_string();
-    let query_result = sqlx::query_as!(
-        User,
-        "INSERT INTO users (first_name, last_name, email, password) VALUES ($1, $2, $3, $4) RETURNING *",
-        body.first_name.to_string(),
-        body.last_name.to_string(),
-        body.email.to_string().to_lowercase(),
-        hashed_password
-    )
-    .fetch_one(&data.db)
-    .await;
-
-    match query_result{
+    match data.users.register(&body, ROLE_USER).await{
         Ok(user)=>{
-            let user_response = serde_json::json!({
+            data.metrics.users_registered_total.inc();
+            HttpResponse::Ok().json(serde_json::json!({
                 "status": "Success",
                 "data": serde_json::json!({
                     "user": filter_user_record(&user)
                 })
-            });
-            return HttpResponse::Ok().json(user_response);
-        }
-        Err(e)=>{
-            return HttpResponse::InternalServerError()
-                .json(serde_json::json!({
-                    "status": "Error",
-                    "message": format!("{:?}", e)
-                }));
+            }))
         }
+        Err(e) => error_response(e),
     }
 }
 
@@ -98,57 +52,16 @@ async fn register_admin_handler(
     body: web::Json::<RegisterUserSchema>,
     data: web::Data<AppState>
 )-> impl Responder{
-
-    let exists: bool = sqlx::query("SELECT EXISTS(SELECT 1 FROM users WHERE email = $1)")
-        .bind(body.email.to_owned())
-        .fetch_one(&data.db)
-        .await
-        .unwrap()
-        .get(0);
-
-    if exists{
-        return HttpResponse::Conflict().json(
-            serde_json::json!({
-                "status": "Error",
-                "message": "Email already in use"
-            })
-        );
-    }
-
-    let salt = SaltString::generate(&mut OsRng);
-    let hashed_password = Argon2::default()
-        .hash_password(body.password.as_bytes(), &salt)
-        .expect("Error while hashing password")
-        .to_string();
-    let query_result = sqlx::query_as!(
-        User,
-        "INSERT INTO users (first_name, last_name, email, password, role) VALUES ($1, $2, $3, $4, $5) RETURNING *",
-        body.first_name.to_string(),
-        body.last_name.to_string(),
-        body.email.to_string().to_lowercase(),
-        hashed_password,
-        "Admin"
-    )
-    .fetch_one(&data.db)
-    .await;
-
-    match query_result{
+    match data.users.register(&body, ROLE_ADMIN).await{
         Ok(user)=>{
-            let user_response = serde_json::json!({
+            HttpResponse::Ok().json(serde_json::json!({
                 "status": "Success",
                 "data": serde_json::json!({
                     "user": filter_user_record(&user)
                 })
-            });
-            return HttpResponse::Ok().json(user_response);
-        }
-        Err(e)=>{
-            return HttpResponse::InternalServerError()
-                .json(serde_json::json!({
-                    "status": "Error",
-                    "message": format!("{:?}", e)
-                }));
+            }))
         }
+        Err(e) => error_response(e),
     }
 }
 
@@ -157,70 +70,80 @@ async fn login_user_handler(
     body: web::Json::<LoginUserSchema>,
     data: web::Data<AppState>
 )-> impl Responder{
-    let query_result = sqlx::query_as!(User, "SELECT * FROM users WHERE email = $1", body.email)
-        .fetch_optional(&data.db)
-        .await
-        .unwrap();
-
-    let is_valid = query_result.to_owned().map_or(false, |user|{
-        let parsed_hash = PasswordHash::new(&user.password).unwrap();
-        Argon2::default()
-            .verify_password(body.password.as_bytes(), &parsed_hash)
-            .map_or(false, |_| true)
-    });
-
-    if !is_valid{
-        return HttpResponse::BadRequest()
-            .json(json!({
-                "status": "Error",
-                "message": "Invalid login details"
-            }));
-    }
+    let user = match data.users.authenticate(&body.email, &body.password).await{
+        Ok(user) => user,
+        Err(e) => return error_response(e),
+    };
 
-    let user = query_result.unwrap();
+    session_response(&data, &user.id, user.session_version, body.remember_me)
+}
 
-    let now = Utc::now();
-    let iat = now.timestamp() as usize;
-    let exp = (now+Duration::minutes(60)).timestamp() as usize;
-    let claims: TokenClaims = TokenClaims{
-        sub: user.id.to_string(),
-        exp,
-        iat,
+// The token and cookies of a new session, as handed out by login
+pub fn session_response(data: &AppState, user_id: &uuid::Uuid, session_version: i32, remember_me: bool) -> HttpResponse{
+    // "remember me" sessions outlive the browser session and the usual token lifetime
+    let (expires_in, max_age) = if remember_me{
+        (data.env.jwt_remember_me_expires_in, data.env.jwt_remember_me_expires_in)
+    } else{
+        (data.env.jwt_expires_in, data.env.jwt_maxage)
     };
 
-    let token = encode(
-        &Header::default(),
-        &claims,
-        &EncodingKey::from_secret(data.env.jwt_secret.as_ref()),
-    )
-    .unwrap();
+    let token = match data.jwt_keys.issue(user_id, session_version, expires_in){
+        Ok(token) => token,
+        Err(e) => {
+            return HttpResponse::InternalServerError()
+                .json(json!({
+                    "status": "Error",
+                    "message": e.to_string()
+                }));
+        }
+    };
 
-    let cookie = Cookie::build("token", token.to_owned())
-        .path("/")
-        .max_age(ActixWebDuration::new(60*60, 0))
-        .http_only(true)
-        .finish();
+    let cookie = cookie::session_cookie(&data.env, token.to_owned(), max_age);
+    let csrf_token = csrf::generate_token();
 
     HttpResponse::Ok()
         .cookie(cookie)
+        .cookie(cookie::csrf_cookie(&data.env, csrf_token.to_owned(), max_age))
+        .json(json!({
+            "status": "Success",
+            "token": token,
+            "csrf_token": csrf_token
+        }))
+}
+
+// hands out a fresh CSRF token for an existing cookie session
+#[get("/auth/csrf")]
+async fn csrf_token_handler(
+    data: web::Data<AppState>,
+    _: jwt_auth::JwtMiddleware
+)-> impl Responder{
+    let csrf_token = csrf::generate_token();
+
+    HttpResponse::Ok()
+        .cookie(cookie::csrf_cookie(&data.env, csrf_token.to_owned(), data.env.jwt_remember_me_expires_in))
         .json(json!({
             "status": "Success",
-            "token": token
+            "csrf_token": csrf_token
         }))
 }
 
+#[get("/.well-known/jwks.json")]
+async fn jwks_handler(
+    data: web::Data<AppState>
+)-> impl Responder{
+    HttpResponse::Ok().json(data.jwt_keys.jwks())
+}
+
 #[get("/auth/logout")]
 async fn logout_handler(
+    data: web::Data<AppState>,
     _: jwt_auth::JwtMiddleware
 )-> impl Responder{
-    let cookie = Cookie::build("token", "")
-        .path("/")
-        .max_age(ActixWebDuration::new(-1, 0))
-        .http_only(true)
-        .finish();
+    let cookie = cookie::expired_session_cookie(&data.env);
 
     HttpResponse::Ok()
         .cookie(cookie)
+        .cookie(cookie::expired_csrf_cookie(&data.env))
         .json(json!({
             "status": "Success",
             "message": "Session ended"
@@ -229,37 +152,30 @@ async fn logout_handler(
 
 #[get("/auth/me")]
 async fn get_me_handler(
-    req: HttpRequest,
     data: web::Data<AppState>,
-    _: jwt_auth::JwtMiddleware,
+    auth: jwt_auth::JwtMiddleware,
 ) -> impl Responder{
-    let ext = req.extensions();
-    let user_id = ext.get::<uuid::Uuid>().unwrap();
-
-    let user = sqlx::query_as!(User, "SELECT * FROM users WHERE id = $1", user_id)
-    .fetch_one(&data.db)
-    .await
-    .unwrap();
-
-    let json_response = serde_json::json!({
-        "status": "Success",
-        "data": serde_json::json!({
-            "user": filter_user_record(&user)
-        })
-    });
+    if !auth.has_scope(api_key::PROFILE_READ){
+        return missing_scope(api_key::PROFILE_READ);
+    }
 
-    HttpResponse::Ok().json(json_response)
+    match data.users.find(&auth.user_id).await{
+        Ok(user) => HttpResponse::Ok().json(serde_json::json!({
+            "status": "Success",
+            "data": serde_json::json!({
+                "user": filter_user_record(&user)
+            })
+        })),
+        Err(e) => error_response(e),
+    }
 }
 
 
-pub async fn find_user_by_id(
-    data: &web::Data<AppState>,
-    user_id: &uuid::Uuid
-)-> User{
-    
-    let user = sqlx::query_as!(User, "SELECT * FROM users WHERE id = $1", user_id)
-    .fetch_one(&data.db)
-    .await
-    .unwrap();
-    user
-}
\ No newline at end of file
+pub fn missing_scope(scope: &str) -> HttpResponse{
+    HttpResponse::Forbidden().json(
+        serde_json::json!({
+            "status": "Error",
+            "message": format!("API key is missing the {} scope", scope)
+        })
+    )
+}
@@ -0,0 +1,42 @@
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+
+use crate::model::saved_search_model::SavedSearch;
+
+use super::{
+    application_schema::UserApplication, candidate_schema::FullCandidateProfile, saved_job_schema::SavedJobListing,
+};
+
+// Everything stored about a user, as handed to them by GET /api/me/export
+#[derive(Debug, Serialize)]
+pub struct AccountExport{
+    pub exported_at: DateTime<Utc>,
+    pub profile: ExportedProfile,
+    pub candidate_profile: FullCandidateProfile,
+    pub applications: Vec<UserApplication>,
+    // with their filters and alert frequency, never the unsubscribe token
+    pub saved_searches: Vec<SavedSearch>,
+    // expired ones included, marked as closed
+    pub saved_jobs: Vec<SavedJobListing>,
+    pub documents: Vec<ExportedDocument>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ExportedProfile{
+    pub id: uuid::Uuid,
+    pub first_name: String,
+    pub last_name: String,
+    pub email: String,
+    pub role: String,
+    pub is_verified: bool,
+    pub created_at: Option<DateTime<Utc>>,
+    pub updated_at: Option<DateTime<Utc>>,
+}
+
+// uploaded files, base64 encoded so the archive stays one JSON document
+#[derive(Debug, Serialize)]
+pub struct ExportedDocument{
+    pub name: String,
+    pub size: usize,
+    pub content_base64: String,
+}
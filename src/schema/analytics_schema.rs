@@ -0,0 +1,79 @@
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+pub const EVENT_VIEW: &str = "view";
+pub const EVENT_APPLY_CLICK: &str = "apply_click";
+
+// Days in UTC, both ends included. The last 30 days when left out.
+#[derive(Debug, Deserialize)]
+pub struct AnalyticsQuery{
+    pub from: Option<NaiveDate>,
+    pub to: Option<NaiveDate>,
+}
+
+#[derive(Debug, Serialize, sqlx::FromRow)]
+pub struct DailyStats{
+    pub day: NaiveDate,
+    pub views: i64,
+    pub apply_clicks: i64,
+    pub applications: i64,
+}
+
+// One job's counts over the range. Time to first application is counted
+// from posting, whatever the range.
+#[derive(Debug, sqlx::FromRow)]
+pub struct JobTotals{
+    pub job_id: Uuid,
+    pub title: String,
+    pub company_name: String,
+    pub views: i64,
+    pub apply_clicks: i64,
+    pub applications: i64,
+    pub first_application_hours: Option<f64>,
+}
+
+// Views and apply clicks count each visitor once a day. Rates are None
+// when there was nothing to convert.
+#[derive(Debug, Default, Serialize)]
+pub struct Funnel{
+    pub views: i64,
+    pub apply_clicks: i64,
+    pub applications: i64,
+    pub view_to_apply_click: Option<f64>,
+    pub apply_click_to_application: Option<f64>,
+    pub view_to_application: Option<f64>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct JobAnalytics{
+    pub job_id: Uuid,
+    pub title: String,
+    pub company_name: String,
+    pub from: NaiveDate,
+    pub to: NaiveDate,
+    pub funnel: Funnel,
+    pub time_to_first_application_hours: Option<f64>,
+    pub daily: Vec<DailyStats>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct JobSummary{
+    pub job_id: Uuid,
+    pub title: String,
+    pub funnel: Funnel,
+    pub time_to_first_application_hours: Option<f64>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct CompanyAnalytics{
+    pub company_name: String,
+    pub from: NaiveDate,
+    pub to: NaiveDate,
+    pub funnel: Funnel,
+    // over the jobs that had an application
+    pub average_time_to_first_application_hours: Option<f64>,
+    pub daily: Vec<DailyStats>,
+    // newest first
+    pub jobs: Vec<JobSummary>,
+}
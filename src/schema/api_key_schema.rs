@@ -0,0 +1,14 @@
+use serde::Deserialize;
+
+#[derive(Debug, Deserialize)]
+pub struct CreateApiKey{
+    pub name: String,
+    pub scopes: Vec<String>,
+    pub company_name: Option<String>,
+    pub expires_in_days: Option<i64>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct RotateApiKey{
+    pub grace_period_minutes: Option<i64>,
+}
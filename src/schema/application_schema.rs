@@ -14,4 +14,19 @@ pub struct FetchApplication{
     pub last_name: String,
     pub created_at: Option<DateTime<Utc>>,
     pub applicant_id: uuid::Uuid,
-}
\ No newline at end of file
+    // from the candidate profile, empty until the applicant fills it in
+    pub headline: String,
+    pub city: String,
+    pub country: String,
+    pub skills: Vec<String>,
+}
+
+// an application as its applicant sees it
+#[derive(Debug, Deserialize, Serialize)]
+pub struct UserApplication{
+    pub id: uuid::Uuid,
+    pub job_id: uuid::Uuid,
+    pub title: String,
+    pub company_name: String,
+    pub created_at: Option<DateTime<Utc>>,
+}
@@ -0,0 +1,123 @@
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+
+use crate::model::candidate_model::{
+    CandidateProfile, CandidateSkill, Certification, Education, PortfolioLink, WorkExperience,
+};
+
+pub const PROFICIENCIES: [&str; 4] = ["beginner", "intermediate", "advanced", "expert"];
+
+// PUT semantics: fields that are left out are cleared
+#[derive(Debug, Deserialize)]
+pub struct UpdateCandidateProfile{
+    #[serde(default)]
+    pub headline: String,
+    #[serde(default)]
+    pub summary: String,
+    #[serde(default)]
+    pub city: String,
+    #[serde(default)]
+    pub country: String,
+    #[serde(default)]
+    pub open_to_remote: bool,
+    #[serde(default)]
+    pub desired_roles: Vec<String>,
+    pub salary_min: Option<i32>,
+    pub salary_max: Option<i32>,
+    pub salary_currency: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct WorkExperienceInput{
+    pub company_name: String,
+    pub title: String,
+    #[serde(default)]
+    pub location: String,
+    pub start_date: NaiveDate,
+    pub end_date: Option<NaiveDate>,
+    #[serde(default)]
+    pub description: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct EducationInput{
+    pub institution: String,
+    pub degree: String,
+    #[serde(default)]
+    pub field_of_study: String,
+    pub start_date: NaiveDate,
+    pub end_date: Option<NaiveDate>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CertificationInput{
+    pub name: String,
+    #[serde(default)]
+    pub issuer: String,
+    pub issued_on: NaiveDate,
+    pub expires_on: Option<NaiveDate>,
+    #[serde(default)]
+    pub credential_url: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SkillInput{
+    pub name: String,
+    pub proficiency: String,
+    pub years_of_experience: Option<i32>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct PortfolioLinkInput{
+    pub label: String,
+    pub url: String,
+}
+
+// A profile as its owner and recruiters see it. profile is None until the
+// candidate saves the non-list part for the first time.
+#[derive(Debug, Serialize)]
+pub struct FullCandidateProfile{
+    pub user_id: uuid::Uuid,
+    pub first_name: String,
+    pub last_name: String,
+    pub profile: Option<CandidateProfile>,
+    pub experience: Vec<WorkExperience>,
+    pub education: Vec<Education>,
+    pub certifications: Vec<Certification>,
+    pub skills: Vec<CandidateSkill>,
+    pub links: Vec<PortfolioLink>,
+}
+
+// The list parts of a profile, named as they appear in the URL
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ProfileSection{
+    Experience,
+    Education,
+    Certifications,
+    Skills,
+    Links,
+}
+
+impl ProfileSection{
+    pub fn from_path(section: &str) -> Option<ProfileSection>{
+        match section{
+            "experience" => Some(ProfileSection::Experience),
+            "education" => Some(ProfileSection::Education),
+            "certifications" => Some(ProfileSection::Certifications),
+            "skills" => Some(ProfileSection::Skills),
+            "links" => Some(ProfileSection::Links),
+            _ => None,
+        }
+    }
+
+    // used for the "<label> not found" message
+    pub fn label(&self) -> &'static str{
+        match self{
+            ProfileSection::Experience => "Experience",
+            ProfileSection::Education => "Education",
+            ProfileSection::Certifications => "Certification",
+            ProfileSection::Skills => "Skill",
+            ProfileSection::Links => "Link",
+        }
+    }
+}
@@ -0,0 +1,17 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Deserialize)]
+pub struct CreateCategory{
+    pub name: String,
+    // slug of the category to put it under, none for a top level one
+    #[serde(default)]
+    pub parent: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct CategoryNode{
+    pub id: uuid::Uuid,
+    pub slug: String,
+    pub name: String,
+    pub children: Vec<CategoryNode>,
+}
@@ -1,4 +1,88 @@
-use serde::{Deserialize};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+use super::{
+    location_schema::{GeoPoint, ResolvedLocation, WORK_MODE_ONSITE},
+    skill_schema::JobSkill,
+};
+use crate::{model::job_model::Job, service::ServiceError};
+
+pub const EMPLOYMENT_FULL_TIME: &str = "full-time";
+pub const EMPLOYMENT_PART_TIME: &str = "part-time";
+pub const EMPLOYMENT_CONTRACT: &str = "contract";
+pub const EMPLOYMENT_INTERNSHIP: &str = "internship";
+pub const EMPLOYMENT_TEMPORARY: &str = "temporary";
+pub const EMPLOYMENT_TYPES: [&str; 5] = [
+    EMPLOYMENT_FULL_TIME,
+    EMPLOYMENT_PART_TIME,
+    EMPLOYMENT_CONTRACT,
+    EMPLOYMENT_INTERNSHIP,
+    EMPLOYMENT_TEMPORARY,
+];
+
+// Ordered, so levels can be compared by how far apart they are
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Seniority{
+    Junior,
+    Mid,
+    Senior,
+    Lead,
+}
+
+impl Seniority{
+    pub const ALL: [Seniority; 4] = [Seniority::Junior, Seniority::Mid, Seniority::Senior, Seniority::Lead];
+
+    // as stored in jobs.seniority and given in the API
+    pub fn name(&self) -> &'static str{
+        match self{
+            Seniority::Junior => "junior",
+            Seniority::Mid => "mid",
+            Seniority::Senior => "senior",
+            Seniority::Lead => "lead",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Seniority>{
+        Seniority::ALL.into_iter().find(|level| level.name() == name)
+    }
+
+    // titles without a hint are mid level, like most postings
+    pub fn from_title(title: &str) -> Seniority{
+        let title = title.to_lowercase();
+        let has = |words: &[&str]| title.split(|c: char| !c.is_alphanumeric()).any(|word| words.contains(&word));
+        if has(&["lead", "head", "manager", "director", "principal", "staff", "architect"]){
+            Seniority::Lead
+        } else if has(&["senior", "sr"]){
+            Seniority::Senior
+        } else if has(&["junior", "jr", "intern", "internship", "trainee", "graduate", "entry"]){
+            Seniority::Junior
+        } else{
+            Seniority::Mid
+        }
+    }
+
+    pub fn from_years(years: f64) -> Seniority{
+        if years < 2.0{
+            Seniority::Junior
+        } else if years < 5.0{
+            Seniority::Mid
+        } else if years < 10.0{
+            Seniority::Senior
+        } else{
+            Seniority::Lead
+        }
+    }
+
+    pub fn label(&self) -> &'static str{
+        match self{
+            Seniority::Junior => "Junior",
+            Seniority::Mid => "Mid level",
+            Seniority::Senior => "Senior",
+            Seniority::Lead => "Lead",
+        }
+    }
+}
 
 #[derive(Debug, Deserialize)]
 pub struct CreateJobPosting{
@@ -8,10 +92,178 @@ pub struct CreateJobPosting{
     pub country: String,
     pub salary: String,
     pub description: String,
+    // skill names or aliases from GET /api/skills
+    #[serde(default)]
+    pub required_skills: Vec<String>,
+    #[serde(default)]
+    pub nice_to_have_skills: Vec<String>,
+    #[serde(default)]
+    pub expires_at: Option<DateTime<Utc>>,
+    // onsite, hybrid or remote
+    #[serde(default = "default_work_mode")]
+    pub work_mode: String,
+    // ISO country codes and time zones remote workers may live in, empty
+    // for anywhere
+    #[serde(default)]
+    pub remote_countries: Vec<String>,
+    #[serde(default)]
+    pub remote_timezones: Vec<String>,
+    #[serde(default = "default_employment_type")]
+    pub employment_type: String,
+    // junior, mid, senior or lead, guessed from the title when left out
+    #[serde(default)]
+    pub seniority: Option<String>,
+    // a slug from GET /api/categories
+    #[serde(default)]
+    pub category: Option<String>,
+}
+
+// What the service worked out from a posting before it is stored
+#[derive(Debug, Default)]
+pub struct NormalizedJob{
+    pub location: ResolvedLocation,
+    pub seniority: String,
+    pub category_id: Option<uuid::Uuid>,
+    pub salary_min: Option<f64>,
+    pub salary_max: Option<f64>,
+    pub salary_currency: Option<String>,
+}
+
+fn default_work_mode() -> String{
+    WORK_MODE_ONSITE.to_string()
+}
+
+fn default_employment_type() -> String{
+    EMPLOYMENT_FULL_TIME.to_string()
 }
 
 #[derive(Debug, Deserialize)]
 pub struct QueryParam{
     pub page: Option<String>,
     pub search_query: Option<String>,
-}
\ No newline at end of file
+    // comma separated skill names or aliases, a job has to have all of them
+    pub skills: Option<String>,
+    // lat,lng of the centre of a radius search
+    pub near: Option<String>,
+    pub radius_km: Option<String>,
+    pub work_mode: Option<String>,
+    pub employment_type: Option<String>,
+    pub seniority: Option<String>,
+    // also finds the jobs in the categories below it
+    pub category: Option<String>,
+}
+
+impl QueryParam{
+    // a missing or unreadable page is the first page
+    pub fn page_number(&self) -> i64{
+        self.page.as_deref().and_then(|page| page.parse().ok()).unwrap_or(0)
+    }
+
+    pub fn filters(&self) -> Result<JobFilters, ServiceError>{
+        let near = match self.near.as_deref().map(str::trim).filter(|near| !near.is_empty()){
+            Some(near) => Some(GeoPoint::parse(near).ok_or(ServiceError::Invalid("near must be latitude,longitude"))?),
+            None => None,
+        };
+        let radius_km = match self.radius_km.as_deref().map(str::trim).filter(|radius| !radius.is_empty()){
+            Some(radius) => Some(radius.parse().map_err(|_| ServiceError::Invalid("radius_km must be a number"))?),
+            None => None,
+        };
+
+        Ok(JobFilters{
+            search_query: self
+                .search_query
+                .as_deref()
+                .map(str::trim)
+                .filter(|text| !text.is_empty())
+                .map(str::to_string),
+            skills: self
+                .skills
+                .as_deref()
+                .unwrap_or_default()
+                .split(',')
+                .map(str::trim)
+                .filter(|name| !name.is_empty())
+                .map(str::to_string)
+                .collect(),
+            near,
+            radius_km,
+            work_mode: non_empty(&self.work_mode),
+            employment_type: non_empty(&self.employment_type),
+            seniority: non_empty(&self.seniority),
+            category: non_empty(&self.category).map(|slug| slug.to_lowercase()),
+        })
+    }
+}
+
+fn non_empty(value: &Option<String>) -> Option<String>{
+    value.as_deref().map(str::trim).filter(|value| !value.is_empty()).map(str::to_string)
+}
+
+// The filters of a job search as the user gave them. Saved searches store
+// them as JSON, so fields added later need a default.
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub struct JobFilters{
+    #[serde(default)]
+    pub search_query: Option<String>,
+    #[serde(default)]
+    pub skills: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub near: Option<GeoPoint>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub radius_km: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub work_mode: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub employment_type: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub seniority: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub category: Option<String>,
+}
+
+// JobFilters ready for the database, with skill names resolved
+#[derive(Debug, Default)]
+pub struct JobSearch{
+    pub text: Option<String>,
+    // each skill once, a job has to have all of them
+    pub skill_ids: Vec<uuid::Uuid>,
+    pub near: Option<GeoPoint>,
+    pub radius_km: Option<f64>,
+    pub work_mode: Option<String>,
+    pub employment_type: Option<String>,
+    pub seniority: Option<String>,
+    // the category searched for and everything below it
+    pub category_ids: Option<Vec<uuid::Uuid>>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct JobListing{
+    #[serde(flatten)]
+    pub job: Job,
+    pub skills: Vec<JobSkill>,
+}
+
+// one row of the facet query: how many matching jobs have value
+#[derive(Debug, sqlx::FromRow)]
+pub struct FacetCount{
+    pub facet: String,
+    pub value: String,
+    pub count: i64,
+}
+
+// Matching jobs per value of each filter, for the filter sidebar. A
+// category counts the jobs of the categories below it too. Country, city
+// and company only have their most common values, salary is bucketed by
+// the top of the yearly range per currency, leaving out salaries without
+// one, and posted counts the jobs of the last 24h, 7d and 30d.
+#[derive(Debug, Default, Clone, Serialize)]
+pub struct JobFacets{
+    pub country: BTreeMap<String, i64>,
+    pub city: BTreeMap<String, i64>,
+    pub company: BTreeMap<String, i64>,
+    pub employment_type: BTreeMap<String, i64>,
+    pub seniority: BTreeMap<String, i64>,
+    pub category: BTreeMap<String, i64>,
+    pub salary: BTreeMap<String, BTreeMap<String, i64>>,
+    pub posted: BTreeMap<String, i64>,
+}
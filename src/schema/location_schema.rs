@@ -0,0 +1,36 @@
+use serde::{Deserialize, Serialize};
+
+pub const WORK_MODE_ONSITE: &str = "onsite";
+pub const WORK_MODE_HYBRID: &str = "hybrid";
+pub const WORK_MODE_REMOTE: &str = "remote";
+pub const WORK_MODES: [&str; 3] = [WORK_MODE_ONSITE, WORK_MODE_HYBRID, WORK_MODE_REMOTE];
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct GeoPoint{
+    pub latitude: f64,
+    pub longitude: f64,
+}
+
+impl GeoPoint{
+    // "lat,lng" in decimal degrees, None when either is missing or out of range
+    pub fn parse(text: &str) -> Option<GeoPoint>{
+        let (latitude, longitude) = text.split_once(',')?;
+        let latitude: f64 = latitude.trim().parse().ok()?;
+        let longitude: f64 = longitude.trim().parse().ok()?;
+        if !(-90.0..=90.0).contains(&latitude) || !(-180.0..=180.0).contains(&longitude){
+            return None;
+        }
+        Some(GeoPoint{ latitude, longitude })
+    }
+}
+
+// What the city and country of a job resolved to. A known country with an
+// unknown city only gets its country code.
+#[derive(Debug, Default, Clone, sqlx::FromRow)]
+pub struct ResolvedLocation{
+    pub location_id: Option<i64>,
+    pub country_code: Option<String>,
+    pub region: Option<String>,
+    pub latitude: Option<f64>,
+    pub longitude: Option<f64>,
+}
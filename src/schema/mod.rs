@@ -1,3 +1,14 @@
 pub mod user_schema;
 pub mod job_schema;
-pub mod application_schema;
\ No newline at end of file
+pub mod application_schema;
+pub mod api_key_schema;
+pub mod queue_schema;
+pub mod account_schema;
+pub mod candidate_schema;
+pub mod skill_schema;
+pub mod recommendation_schema;
+pub mod saved_search_schema;
+pub mod saved_job_schema;
+pub mod location_schema;
+pub mod category_schema;
+pub mod analytics_schema;
\ No newline at end of file
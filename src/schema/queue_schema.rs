@@ -0,0 +1,8 @@
+use serde::Deserialize;
+
+#[derive(Debug, Deserialize)]
+pub struct QueueQuery{
+    pub status: Option<String>,
+    pub kind: Option<String>,
+    pub page: Option<i64>,
+}
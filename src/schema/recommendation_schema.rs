@@ -0,0 +1,60 @@
+use serde::Serialize;
+
+use super::job_schema::JobListing;
+
+// What scoring needs to know about a candidate
+#[derive(Debug)]
+pub struct MatchProfile{
+    pub user_id: uuid::Uuid,
+    pub first_name: String,
+    pub last_name: String,
+    pub headline: String,
+    pub city: String,
+    pub country: String,
+    // the country as found in countries, None when it is not there
+    pub country_code: Option<String>,
+    pub open_to_remote: bool,
+    pub salary_min: Option<i32>,
+    pub salary_max: Option<i32>,
+    pub salary_currency: Option<String>,
+    // taxonomy skills only, free text ones cannot be compared
+    pub skill_ids: Vec<uuid::Uuid>,
+    // since the start of the earliest position, None without any
+    pub years_of_experience: Option<f64>,
+}
+
+// One factor of a score. score is between 0 and 1, the weights of all
+// factors add up to 1.
+#[derive(Debug, Serialize, Clone)]
+pub struct ScoreComponent{
+    pub score: f64,
+    pub weight: f64,
+    pub reason: String,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct ScoreBreakdown{
+    pub skills: ScoreComponent,
+    pub location: ScoreComponent,
+    pub salary: ScoreComponent,
+    pub seniority: ScoreComponent,
+}
+
+#[derive(Debug, Serialize)]
+pub struct RecommendedJob{
+    #[serde(flatten)]
+    pub job: JobListing,
+    // 0 to 100
+    pub score: u8,
+    pub breakdown: ScoreBreakdown,
+}
+
+#[derive(Debug, Serialize)]
+pub struct SuggestedCandidate{
+    pub user_id: uuid::Uuid,
+    pub first_name: String,
+    pub last_name: String,
+    pub headline: String,
+    pub score: u8,
+    pub breakdown: ScoreBreakdown,
+}
@@ -0,0 +1,26 @@
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use uuid::Uuid;
+
+use super::job_schema::JobListing;
+
+#[derive(Debug, Serialize)]
+pub struct SavedJobListing{
+    #[serde(flatten)]
+    pub job: JobListing,
+    // false once expires_at has passed
+    pub is_open: bool,
+    pub saved_at: Option<DateTime<Utc>>,
+}
+
+// A saved job about to expire, with what the reminder needs of its owner
+#[derive(Debug, sqlx::FromRow)]
+pub struct ExpiringSavedJob{
+    pub user_id: Uuid,
+    pub job_id: Uuid,
+    pub email: String,
+    pub first_name: String,
+    pub title: String,
+    pub company_name: String,
+    pub expires_at: DateTime<Utc>,
+}
@@ -0,0 +1,42 @@
+use chrono::{DateTime, Utc};
+use serde::Deserialize;
+use sqlx::types::Json;
+use uuid::Uuid;
+
+use super::job_schema::JobFilters;
+
+pub const FREQUENCY_INSTANT: &str = "instant";
+pub const FREQUENCY_DAILY: &str = "daily";
+pub const FREQUENCY_WEEKLY: &str = "weekly";
+
+// Creating and replacing a saved search take the same body
+#[derive(Debug, Deserialize)]
+pub struct SavedSearchInput{
+    pub name: String,
+    #[serde(default)]
+    pub filters: JobFilters,
+    pub frequency: String,
+    #[serde(default = "alerts_enabled_default")]
+    pub alerts_enabled: bool,
+}
+
+fn alerts_enabled_default() -> bool{
+    true
+}
+
+#[derive(Debug, Deserialize)]
+pub struct UnsubscribeSchema{
+    pub token: String,
+}
+
+// A saved search whose alert is due, with what the email needs of its owner
+#[derive(Debug, sqlx::FromRow)]
+pub struct DueSearch{
+    pub id: Uuid,
+    pub name: String,
+    pub filters: Json<JobFilters>,
+    pub last_notified_at: DateTime<Utc>,
+    pub unsubscribe_token: String,
+    pub email: String,
+    pub first_name: String,
+}
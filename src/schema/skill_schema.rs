@@ -0,0 +1,31 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Deserialize)]
+pub struct CreateSkill{
+    pub name: String,
+    #[serde(default)]
+    pub aliases: Vec<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SkillQuery{
+    pub q: Option<String>,
+}
+
+// A name or alias as typed, lowercased, and the skill it stands for
+#[derive(Debug, Clone)]
+pub struct ResolvedSkill{
+    pub key: String,
+    pub id: uuid::Uuid,
+    pub name: String,
+}
+
+// a skill as attached to a job
+#[derive(Debug, Serialize, Clone)]
+pub struct JobSkill{
+    #[serde(skip)]
+    pub job_id: uuid::Uuid,
+    pub id: uuid::Uuid,
+    pub name: String,
+    pub required: bool,
+}
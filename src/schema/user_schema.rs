@@ -5,6 +5,11 @@ pub struct TokenClaims{
     pub sub: String,
     pub iat: usize,
     pub exp: usize,
+    pub iss: String,
+    pub aud: String,
+    // users.session_version at issue time, tokens from before it are void
+    #[serde(default)]
+    pub ver: i32,
 }
 
 #[derive(Debug, Deserialize)]
@@ -19,4 +24,37 @@ pub struct RegisterUserSchema{
 pub struct LoginUserSchema{
     pub email: String,
     pub password: String,
-}
\ No newline at end of file
+    #[serde(default)]
+    pub remember_me: bool,
+}
+// the password is asked again so a stolen session alone cannot delete the account
+#[derive(Debug, Deserialize)]
+pub struct DeleteAccountSchema{
+    pub password: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct UpdateProfileSchema{
+    pub first_name: Option<String>,
+    pub last_name: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ChangePasswordSchema{
+    pub current_password: String,
+    pub new_password: String,
+    // for the session that replaces the current one
+    #[serde(default)]
+    pub remember_me: bool,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ChangeEmailSchema{
+    pub new_email: String,
+    pub password: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ConfirmEmailSchema{
+    pub token: String,
+}
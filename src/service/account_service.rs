@@ -0,0 +1,135 @@
+use crate::{
+    queue::task::Task,
+    repository::{
+        application_repository::ApplicationRepository, saved_search_repository::SavedSearchRepository,
+        user_repository::UserRepository,
+    },
+    schema::account_schema::{AccountExport, ExportedDocument, ExportedProfile},
+};
+
+use base64::{engine::general_purpose::STANDARD, Engine};
+use chrono::{DateTime, Duration, Utc};
+use std::{path::Path, sync::Arc};
+use uuid::Uuid;
+
+use super::{
+    candidate_service::CandidateService, saved_job_service::SavedJobService, user_service::UserService, ServiceError,
+};
+
+// What a user can do about their own data: take it with them or have it removed
+#[derive(Clone)]
+pub struct AccountService{
+    users: Arc<dyn UserRepository>,
+    applications: Arc<dyn ApplicationRepository>,
+    saved_searches: Arc<dyn SavedSearchRepository>,
+    credentials: UserService,
+    candidates: CandidateService,
+    saved_jobs: SavedJobService,
+    deletion_grace_period: Duration,
+}
+
+impl AccountService{
+    pub fn new(
+        users: Arc<dyn UserRepository>,
+        applications: Arc<dyn ApplicationRepository>,
+        saved_searches: Arc<dyn SavedSearchRepository>,
+        credentials: UserService,
+        candidates: CandidateService,
+        saved_jobs: SavedJobService,
+        deletion_grace_period: Duration,
+    ) -> AccountService{
+        AccountService{ users, applications, saved_searches, credentials, candidates, saved_jobs, deletion_grace_period }
+    }
+
+    pub async fn export(&self, id: &Uuid) -> Result<AccountExport, ServiceError>{
+        let user = self.users.find_by_id(id).await?.ok_or(ServiceError::NotFound("User"))?;
+        let applications = self.applications.list_for_user(id).await?;
+        let saved_searches = self.saved_searches.list_for_user(id).await?;
+        let saved_jobs = self.saved_jobs.list(id).await?;
+        let candidate_profile = self.candidates.profile(id).await?;
+
+        // a resume the queue already discarded is simply not there any more
+        let mut documents = Vec::new();
+        if !user.resume.is_empty(){
+            if let Ok(contents) = tokio::fs::read(&user.resume).await{
+                documents.push(ExportedDocument{
+                    name: Path::new(&user.resume)
+                        .file_name()
+                        .map(|name| name.to_string_lossy().to_string())
+                        .unwrap_or_default(),
+                    size: contents.len(),
+                    content_base64: STANDARD.encode(&contents),
+                });
+            }
+        }
+
+        Ok(AccountExport{
+            exported_at: Utc::now(),
+            profile: ExportedProfile{
+                id: user.id,
+                first_name: user.first_name,
+                last_name: user.last_name,
+                email: user.email,
+                role: user.role,
+                is_verified: user.is_verified,
+                created_at: user.created_at,
+                updated_at: user.updated_at,
+            },
+            candidate_profile,
+            applications,
+            saved_searches,
+            saved_jobs,
+            documents,
+        })
+    }
+
+    // The account is locked straight away and purged once the grace period is
+    // over, unless the user logs in again before then. Returns when the purge
+    // is due.
+    pub async fn request_deletion(&self, id: &Uuid, password: &str) -> Result<DateTime<Utc>, ServiceError>{
+        let user = self.credentials.find(id).await?;
+        self.credentials.authenticate(&user.email, password).await?;
+
+        let requested_at = Utc::now();
+        let purge_at = requested_at + self.deletion_grace_period;
+        let purge = Task::PurgeAccount{ user_id: *id, requested_at };
+        self.users.request_deletion(id, &requested_at, &purge, purge_at).await?;
+
+        Ok(purge_at)
+    }
+
+    // Runs once the grace period is over. Returns false when the deletion
+    // was cancelled since, or the account is already purged.
+    pub async fn purge(&self, id: &Uuid, requested_before: &DateTime<Utc>) -> Result<bool, ServiceError>{
+        let resume = match self.users.purge(id, requested_before).await?{
+            Some(resume) => resume,
+            None => return Ok(false),
+        };
+
+        if !resume.is_empty(){
+            for file in [resume.to_owned(), format!("{}.thumb.png", resume)]{
+                match tokio::fs::remove_file(&file).await{
+                    Err(e) if e.kind() != std::io::ErrorKind::NotFound => {
+                        tracing::warn!(path = %file, error = %e, "cannot remove file of a deleted account");
+                    }
+                    _ => {}
+                }
+            }
+        }
+        Ok(true)
+    }
+
+    // purges the accounts whose purge job got lost, returns how many
+    pub async fn purge_overdue(&self, requested_before: &DateTime<Utc>) -> Result<usize, ServiceError>{
+        let overdue = self.users.list_overdue_deletions(requested_before).await?;
+        for id in &overdue{
+            self.purge(id, requested_before).await?;
+        }
+        Ok(overdue.len())
+    }
+
+    // for the retention policy, returns how many applications went
+    pub async fn delete_applications_before(&self, before: &DateTime<Utc>) -> Result<u64, ServiceError>{
+        Ok(self.applications.delete_before(before).await?)
+    }
+}
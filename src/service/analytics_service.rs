@@ -0,0 +1,145 @@
+use crate::{
+    repository::analytics_repository::AnalyticsRepository,
+    schema::analytics_schema::{
+        AnalyticsQuery, CompanyAnalytics, Funnel, JobAnalytics, JobSummary, JobTotals, EVENT_APPLY_CLICK, EVENT_VIEW,
+    },
+};
+
+use chrono::{DateTime, Duration, NaiveDate, Utc};
+use sha2::{Digest, Sha256};
+use std::sync::Arc;
+use uuid::Uuid;
+
+use super::{user_service::UserService, ServiceError};
+
+const DEFAULT_RANGE_DAYS: i64 = 30;
+const MAX_RANGE_DAYS: i64 = 366;
+
+// How job postings perform, for the recruiters who post them
+#[derive(Clone)]
+pub struct AnalyticsService{
+    analytics: Arc<dyn AnalyticsRepository>,
+    users: UserService,
+}
+
+impl AnalyticsService{
+    pub fn new(analytics: Arc<dyn AnalyticsRepository>, users: UserService) -> AnalyticsService{
+        AnalyticsService{ analytics, users }
+    }
+
+    // Visitors are a user id or an anonymous visitor cookie, only their
+    // hash is stored. Returns false when it was already counted today.
+    pub async fn record_view(&self, job_id: &Uuid, visitor: &str) -> Result<bool, ServiceError>{
+        self.record(job_id, EVENT_VIEW, visitor).await
+    }
+
+    pub async fn record_apply_click(&self, job_id: &Uuid, visitor: &str) -> Result<bool, ServiceError>{
+        self.record(job_id, EVENT_APPLY_CLICK, visitor).await
+    }
+
+    // only admins see analytics
+    pub async fn job(&self, actor_id: &Uuid, job_id: &Uuid, query: &AnalyticsQuery) -> Result<JobAnalytics, ServiceError>{
+        self.users.ensure_admin(actor_id).await?;
+        let (from, to) = range(query)?;
+
+        let totals = self
+            .analytics
+            .totals(&[*job_id], &from, &to)
+            .await?
+            .pop()
+            .ok_or(ServiceError::NotFound("Job"))?;
+        let daily = self.analytics.daily(&[*job_id], &from, &to).await?;
+
+        Ok(JobAnalytics{
+            job_id: totals.job_id,
+            funnel: funnel(totals.views, totals.apply_clicks, totals.applications),
+            time_to_first_application_hours: totals.first_application_hours,
+            title: totals.title,
+            company_name: totals.company_name,
+            from,
+            to,
+            daily,
+        })
+    }
+
+    // every job posted under the company name, together and one by one
+    pub async fn company(&self, actor_id: &Uuid, company_name: &str, query: &AnalyticsQuery) -> Result<CompanyAnalytics, ServiceError>{
+        self.users.ensure_admin(actor_id).await?;
+        let (from, to) = range(query)?;
+
+        let job_ids = self.analytics.company_job_ids(company_name).await?;
+        if job_ids.is_empty(){
+            return Err(ServiceError::NotFound("Company"));
+        }
+        let totals = self.analytics.totals(&job_ids, &from, &to).await?;
+        let daily = self.analytics.daily(&job_ids, &from, &to).await?;
+
+        let sum = |count: fn(&JobTotals) -> i64| totals.iter().map(count).sum::<i64>();
+        let first_applications: Vec<f64> = totals.iter().filter_map(|job| job.first_application_hours).collect();
+
+        Ok(CompanyAnalytics{
+            company_name: company_name.to_string(),
+            from,
+            to,
+            funnel: funnel(sum(|job| job.views), sum(|job| job.apply_clicks), sum(|job| job.applications)),
+            average_time_to_first_application_hours: if first_applications.is_empty(){
+                None
+            } else{
+                Some(first_applications.iter().sum::<f64>() / first_applications.len() as f64)
+            },
+            daily,
+            jobs: totals
+                .into_iter()
+                .map(|job| JobSummary{
+                    job_id: job.job_id,
+                    title: job.title,
+                    funnel: funnel(job.views, job.apply_clicks, job.applications),
+                    time_to_first_application_hours: job.first_application_hours,
+                })
+                .collect(),
+        })
+    }
+
+    // for the retention policy, returns how many events went
+    pub async fn delete_events_before(&self, before: &DateTime<Utc>) -> Result<u64, ServiceError>{
+        Ok(self.analytics.delete_before(before).await?)
+    }
+
+    async fn record(&self, job_id: &Uuid, kind: &str, visitor: &str) -> Result<bool, ServiceError>{
+        let visitor = hex::encode(Sha256::digest(visitor.as_bytes()));
+        match self.analytics.record(job_id, kind, &visitor).await{
+            Err(sqlx::Error::Database(e)) if e.is_foreign_key_violation() => Err(ServiceError::NotFound("Job")),
+            result => Ok(result?),
+        }
+    }
+}
+
+fn range(query: &AnalyticsQuery) -> Result<(NaiveDate, NaiveDate), ServiceError>{
+    let to = query.to.unwrap_or_else(|| Utc::now().date_naive());
+    let from = query.from.unwrap_or(to - Duration::days(DEFAULT_RANGE_DAYS - 1));
+    if from > to{
+        return Err(ServiceError::Invalid("from must not be after to"));
+    }
+    if (to - from).num_days() + 1 > MAX_RANGE_DAYS{
+        return Err(ServiceError::Invalid("from and to must span at most 366 days"));
+    }
+    Ok((from, to))
+}
+
+fn funnel(views: i64, apply_clicks: i64, applications: i64) -> Funnel{
+    let rate = |converted: i64, total: i64| {
+        if total == 0{
+            None
+        } else{
+            Some((converted as f64 / total as f64 * 10000.0).round() / 10000.0)
+        }
+    };
+    Funnel{
+        views,
+        apply_clicks,
+        applications,
+        view_to_apply_click: rate(apply_clicks, views),
+        apply_click_to_application: rate(applications, apply_clicks),
+        view_to_application: rate(applications, views),
+    }
+}
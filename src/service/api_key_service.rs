@@ -0,0 +1,107 @@
+use crate::{
+    core::helpers::api_key,
+    model::api_key_model::ApiKey,
+    repository::api_key_repository::{ApiKeyRepository, NewApiKey},
+    schema::api_key_schema::CreateApiKey,
+};
+
+use chrono::{Duration, Utc};
+use std::sync::Arc;
+use uuid::Uuid;
+
+use super::{user_service::UserService, ServiceError};
+
+// a key lives ten years at most and an old key overlaps its successor for a
+// week at most
+pub const MAX_EXPIRES_IN_DAYS: i64 = 3650;
+pub const MAX_GRACE_PERIOD_MINUTES: i64 = 10080;
+
+#[derive(Clone)]
+pub struct ApiKeyService{
+    keys: Arc<dyn ApiKeyRepository>,
+    users: UserService,
+}
+
+impl ApiKeyService{
+    pub fn new(keys: Arc<dyn ApiKeyRepository>, users: UserService) -> ApiKeyService{
+        ApiKeyService{ keys, users }
+    }
+
+    // Returns the plain key next to the stored record, it is never shown
+    // again. Company keys act for a whole company, so only admins may mint
+    // them.
+    pub async fn create(&self, user_id: &Uuid, body: &CreateApiKey) -> Result<(String, ApiKey), ServiceError>{
+        if body.name.trim().is_empty() || body.scopes.is_empty(){
+            return Err(ServiceError::Invalid("A name and at least one scope are required"));
+        }
+        if !body.scopes.iter().all(|scope| api_key::is_known_scope(scope)){
+            return Err(ServiceError::Invalid("Unknown scope"));
+        }
+        if body.company_name.is_some(){
+            self.users.ensure_admin(user_id).await?;
+        }
+
+        let expires_at = match body.expires_in_days{
+            None => None,
+            Some(days) if (1..=MAX_EXPIRES_IN_DAYS).contains(&days) => Some(Utc::now() + Duration::days(days)),
+            Some(_) => return Err(ServiceError::Invalid("expires_in_days must be between 1 and 3650")),
+        };
+
+        let generated = api_key::generate();
+        let key = NewApiKey{
+            user_id: *user_id,
+            company_name: body.company_name.to_owned(),
+            name: body.name.trim().to_string(),
+            prefix: generated.prefix,
+            key_hash: generated.hash,
+            scopes: body.scopes.to_owned(),
+            expires_at,
+        };
+        Ok((generated.key, self.keys.create(&key).await?))
+    }
+
+    pub async fn list(&self, user_id: &Uuid) -> Result<Vec<ApiKey>, ServiceError>{
+        Ok(self.keys.list_for_user(user_id).await?)
+    }
+
+    // The old key either stops working now or keeps working for the grace
+    // period so deployed integrations can be switched over.
+    pub async fn rotate(&self, user_id: &Uuid, key_id: &Uuid, grace_period_minutes: i64) -> Result<(String, ApiKey), ServiceError>{
+        let grace_period_minutes = grace_period_minutes.max(0);
+        if grace_period_minutes > MAX_GRACE_PERIOD_MINUTES{
+            return Err(ServiceError::Invalid("grace_period_minutes must be at most 10080"));
+        }
+        let old_key_expires_at = (grace_period_minutes > 0).then(|| Utc::now() + Duration::minutes(grace_period_minutes));
+
+        let generated = api_key::generate();
+        let rotated = self
+            .keys
+            .rotate(key_id, user_id, &generated.prefix, &generated.hash, old_key_expires_at)
+            .await?
+            .ok_or(ServiceError::NotFound("API key"))?;
+        Ok((generated.key, rotated))
+    }
+
+    pub async fn revoke(&self, user_id: &Uuid, key_id: &Uuid) -> Result<(), ServiceError>{
+        if !self.keys.revoke(key_id, user_id).await?{
+            return Err(ServiceError::NotFound("API key"));
+        }
+        Ok(())
+    }
+
+    // None for a key that is unknown, does not match, or was revoked or
+    // has expired
+    pub async fn authenticate(&self, key: &str) -> Result<Option<ApiKey>, ServiceError>{
+        let prefix = match api_key::parse_prefix(key){
+            Some(prefix) => prefix,
+            None => return Ok(None),
+        };
+        let stored = match self.keys.find_by_prefix(prefix).await?{
+            Some(stored) if api_key::verify(key, &stored.key_hash) && stored.is_usable() => stored,
+            _ => return Ok(None),
+        };
+
+        self.keys.touch(&stored.id).await?;
+        Ok(Some(stored))
+    }
+}
@@ -0,0 +1,56 @@
+use crate::{
+    model::application_model::Application,
+    repository::application_repository::ApplicationRepository,
+    schema::application_schema::FetchApplication,
+};
+
+use std::sync::Arc;
+use uuid::Uuid;
+
+use super::{job_service::PAGE_SIZE, user_service::UserService, ServiceError};
+
+#[derive(Clone)]
+pub struct ApplicationService{
+    applications: Arc<dyn ApplicationRepository>,
+    users: UserService,
+}
+
+impl ApplicationService{
+    pub fn new(applications: Arc<dyn ApplicationRepository>, users: UserService) -> ApplicationService{
+        ApplicationService{ applications, users }
+    }
+
+    // Applying twice is not an error, the existing application is returned.
+    // The bool tells whether a new application was created. The unique
+    // constraint on (job_id, user_id) settles concurrent applies. A job
+    // that has expired takes no more applications.
+    pub async fn apply(&self, user_id: &Uuid, job_id: &Uuid) -> Result<(Application, bool), ServiceError>{
+        match self.applications.job_is_open(job_id).await?{
+            Some(true) => {}
+            Some(false) => return Err(ServiceError::Conflict("This job is no longer accepting applications")),
+            None => return Err(ServiceError::NotFound("Job")),
+        }
+        match self.applications.create(user_id, job_id).await{
+            Ok(Some(application)) => Ok((application, true)),
+            Ok(None) => self
+                .applications
+                .find(user_id, job_id)
+                .await?
+                .map(|application| (application, false))
+                .ok_or(ServiceError::NotFound("Application")),
+            Err(sqlx::Error::Database(e)) if e.is_foreign_key_violation() => Err(ServiceError::NotFound("Job")),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    // applicants are only visible to admins
+    pub async fn list(&self, actor_id: &Uuid, page: i64) -> Result<Vec<FetchApplication>, ServiceError>{
+        self.users.ensure_admin(actor_id).await?;
+        Ok(self.applications.list(PAGE_SIZE, page.max(0) * PAGE_SIZE).await?)
+    }
+
+    pub async fn list_for_job(&self, actor_id: &Uuid, job_id: &Uuid) -> Result<Vec<FetchApplication>, ServiceError>{
+        self.users.ensure_admin(actor_id).await?;
+        Ok(self.applications.list_for_job(job_id).await?)
+    }
+}
@@ -0,0 +1,275 @@
+use crate::{
+    model::candidate_model::{
+        CandidateProfile, CandidateSkill, Certification, Education, PortfolioLink, WorkExperience,
+    },
+    repository::candidate_repository::CandidateRepository,
+    schema::candidate_schema::{
+        CertificationInput, EducationInput, FullCandidateProfile, PortfolioLinkInput, ProfileSection,
+        SkillInput, UpdateCandidateProfile, WorkExperienceInput, PROFICIENCIES,
+    },
+};
+
+use chrono::NaiveDate;
+use std::sync::Arc;
+use uuid::Uuid;
+
+use super::{skill_service::SkillService, user_service::UserService, ServiceError};
+
+// keeps a profile readable, the columns allow a little more
+const MAX_DESIRED_ROLES: usize = 10;
+const MAX_SUMMARY_LENGTH: usize = 5000;
+
+// The structured profile candidates keep next to their resume. Recruiters
+// read it, only its owner writes it.
+#[derive(Clone)]
+pub struct CandidateService{
+    candidates: Arc<dyn CandidateRepository>,
+    skills: SkillService,
+    users: UserService,
+}
+
+impl CandidateService{
+    pub fn new(candidates: Arc<dyn CandidateRepository>, skills: SkillService, users: UserService) -> CandidateService{
+        CandidateService{ candidates, skills, users }
+    }
+
+    pub async fn profile(&self, user_id: &Uuid) -> Result<FullCandidateProfile, ServiceError>{
+        let user = self.users.find(user_id).await?;
+        Ok(FullCandidateProfile{
+            user_id: user.id,
+            first_name: user.first_name,
+            last_name: user.last_name,
+            profile: self.candidates.find_profile(user_id).await?,
+            experience: self.candidates.list_experience(user_id).await?,
+            education: self.candidates.list_education(user_id).await?,
+            certifications: self.candidates.list_certifications(user_id).await?,
+            skills: self.candidates.list_skills(user_id).await?,
+            links: self.candidates.list_links(user_id).await?,
+        })
+    }
+
+    // candidates are only visible to admins, like their applications
+    pub async fn profile_for_recruiter(&self, actor_id: &Uuid, candidate_id: &Uuid) -> Result<FullCandidateProfile, ServiceError>{
+        self.users.ensure_admin(actor_id).await?;
+        self.profile(candidate_id).await
+    }
+
+    pub async fn update_profile(&self, user_id: &Uuid, mut profile: UpdateCandidateProfile) -> Result<CandidateProfile, ServiceError>{
+        profile.headline = profile.headline.trim().to_string();
+        profile.city = profile.city.trim().to_string();
+        profile.country = profile.country.trim().to_string();
+        check_length(&profile.headline, 0, 150, "headline must be at most 150 characters")?;
+        check_length(&profile.summary, 0, MAX_SUMMARY_LENGTH, "summary must be at most 5000 characters")?;
+        check_length(&profile.city, 0, 255, "city must be at most 255 characters")?;
+        check_length(&profile.country, 0, 100, "country must be at most 100 characters")?;
+
+        profile.desired_roles = profile
+            .desired_roles
+            .iter()
+            .map(|role| role.trim().to_string())
+            .filter(|role| !role.is_empty())
+            .collect();
+        if profile.desired_roles.len() > MAX_DESIRED_ROLES{
+            return Err(ServiceError::Invalid("desired_roles can have at most 10 entries"));
+        }
+        for role in &profile.desired_roles{
+            check_length(role, 1, 100, "desired_roles entries must be at most 100 characters")?;
+        }
+
+        if profile.salary_min.is_some_and(|salary| salary < 0) || profile.salary_max.is_some_and(|salary| salary < 0){
+            return Err(ServiceError::Invalid("salary_min and salary_max must not be negative"));
+        }
+        if let (Some(min), Some(max)) = (profile.salary_min, profile.salary_max){
+            if max < min{
+                return Err(ServiceError::Invalid("salary_max must not be below salary_min"));
+            }
+        }
+        profile.salary_currency = profile.salary_currency.map(|currency| currency.trim().to_uppercase());
+        if profile
+            .salary_currency
+            .as_ref()
+            .is_some_and(|currency| currency.len() != 3 || !currency.chars().all(|c| c.is_ascii_uppercase()))
+        {
+            return Err(ServiceError::Invalid("salary_currency must be a three letter ISO 4217 code"));
+        }
+
+        Ok(self.candidates.upsert_profile(user_id, &profile).await?)
+    }
+
+    pub async fn add_experience(&self, user_id: &Uuid, entry: WorkExperienceInput) -> Result<WorkExperience, ServiceError>{
+        let entry = validate_experience(entry)?;
+        Ok(self.candidates.add_experience(user_id, &entry).await?)
+    }
+
+    pub async fn update_experience(&self, user_id: &Uuid, id: &Uuid, entry: WorkExperienceInput) -> Result<WorkExperience, ServiceError>{
+        let entry = validate_experience(entry)?;
+        self.candidates
+            .update_experience(user_id, id, &entry)
+            .await?
+            .ok_or(ServiceError::NotFound(ProfileSection::Experience.label()))
+    }
+
+    pub async fn add_education(&self, user_id: &Uuid, entry: EducationInput) -> Result<Education, ServiceError>{
+        let entry = validate_education(entry)?;
+        Ok(self.candidates.add_education(user_id, &entry).await?)
+    }
+
+    pub async fn update_education(&self, user_id: &Uuid, id: &Uuid, entry: EducationInput) -> Result<Education, ServiceError>{
+        let entry = validate_education(entry)?;
+        self.candidates
+            .update_education(user_id, id, &entry)
+            .await?
+            .ok_or(ServiceError::NotFound(ProfileSection::Education.label()))
+    }
+
+    pub async fn add_certification(&self, user_id: &Uuid, entry: CertificationInput) -> Result<Certification, ServiceError>{
+        let entry = validate_certification(entry)?;
+        Ok(self.candidates.add_certification(user_id, &entry).await?)
+    }
+
+    pub async fn update_certification(&self, user_id: &Uuid, id: &Uuid, entry: CertificationInput) -> Result<Certification, ServiceError>{
+        let entry = validate_certification(entry)?;
+        self.candidates
+            .update_certification(user_id, id, &entry)
+            .await?
+            .ok_or(ServiceError::NotFound(ProfileSection::Certifications.label()))
+    }
+
+    pub async fn add_skill(&self, user_id: &Uuid, entry: SkillInput) -> Result<CandidateSkill, ServiceError>{
+        let (entry, skill_id) = self.normalize_skill(validate_skill(entry)?).await?;
+        skill_conflict(self.candidates.add_skill(user_id, &entry, skill_id.as_ref()).await)
+    }
+
+    pub async fn update_skill(&self, user_id: &Uuid, id: &Uuid, entry: SkillInput) -> Result<CandidateSkill, ServiceError>{
+        let (entry, skill_id) = self.normalize_skill(validate_skill(entry)?).await?;
+        skill_conflict(self.candidates.update_skill(user_id, id, &entry, skill_id.as_ref()).await)?
+            .ok_or(ServiceError::NotFound(ProfileSection::Skills.label()))
+    }
+
+    pub async fn add_link(&self, user_id: &Uuid, entry: PortfolioLinkInput) -> Result<PortfolioLink, ServiceError>{
+        let entry = validate_link(entry)?;
+        Ok(self.candidates.add_link(user_id, &entry).await?)
+    }
+
+    pub async fn update_link(&self, user_id: &Uuid, id: &Uuid, entry: PortfolioLinkInput) -> Result<PortfolioLink, ServiceError>{
+        let entry = validate_link(entry)?;
+        self.candidates
+            .update_link(user_id, id, &entry)
+            .await?
+            .ok_or(ServiceError::NotFound(ProfileSection::Links.label()))
+    }
+
+    // Skills the taxonomy knows are stored under their canonical name, so
+    // "k8s" and "Kubernetes" are one skill. Others are kept as typed.
+    async fn normalize_skill(&self, mut entry: SkillInput) -> Result<(SkillInput, Option<Uuid>), ServiceError>{
+        let resolved = self.skills.resolve(std::slice::from_ref(&entry.name)).await?.pop().flatten();
+        match resolved{
+            Some(skill) => {
+                entry.name = skill.name;
+                Ok((entry, Some(skill.id)))
+            }
+            None => Ok((entry, None)),
+        }
+    }
+
+    pub async fn remove(&self, user_id: &Uuid, section: ProfileSection, id: &Uuid) -> Result<(), ServiceError>{
+        if self.candidates.remove(section, user_id, id).await?{
+            Ok(())
+        } else{
+            Err(ServiceError::NotFound(section.label()))
+        }
+    }
+}
+
+// the same skill twice, whatever the case, hits the unique index
+fn skill_conflict<T>(result: Result<T, sqlx::Error>) -> Result<T, ServiceError>{
+    match result{
+        Err(sqlx::Error::Database(e)) if e.is_unique_violation() => Err(ServiceError::Conflict("Skill is already on the profile")),
+        result => Ok(result?),
+    }
+}
+
+fn check_length(value: &str, min: usize, max: usize, message: &'static str) -> Result<(), ServiceError>{
+    let length = value.chars().count();
+    if length < min || length > max{
+        return Err(ServiceError::Invalid(message));
+    }
+    Ok(())
+}
+
+fn check_dates(start: NaiveDate, end: Option<NaiveDate>, message: &'static str) -> Result<(), ServiceError>{
+    if end.is_some_and(|end| end < start){
+        return Err(ServiceError::Invalid(message));
+    }
+    Ok(())
+}
+
+// Links end up as href attributes in the frontend, anything but plain web
+// addresses (javascript: and friends) is refused.
+fn check_url(url: &str, message: &'static str) -> Result<(), ServiceError>{
+    let rest = url
+        .strip_prefix("https://")
+        .or_else(|| url.strip_prefix("http://"))
+        .ok_or(ServiceError::Invalid(message))?;
+    if rest.is_empty() || rest.starts_with('/') || url.contains(char::is_whitespace) || url.len() > 500{
+        return Err(ServiceError::Invalid(message));
+    }
+    Ok(())
+}
+
+fn validate_experience(mut entry: WorkExperienceInput) -> Result<WorkExperienceInput, ServiceError>{
+    entry.company_name = entry.company_name.trim().to_string();
+    entry.title = entry.title.trim().to_string();
+    entry.location = entry.location.trim().to_string();
+    check_length(&entry.company_name, 1, 150, "company_name must be between 1 and 150 characters")?;
+    check_length(&entry.title, 1, 150, "title must be between 1 and 150 characters")?;
+    check_length(&entry.location, 0, 255, "location must be at most 255 characters")?;
+    check_length(&entry.description, 0, MAX_SUMMARY_LENGTH, "description must be at most 5000 characters")?;
+    check_dates(entry.start_date, entry.end_date, "end_date must not be before start_date")?;
+    Ok(entry)
+}
+
+fn validate_education(mut entry: EducationInput) -> Result<EducationInput, ServiceError>{
+    entry.institution = entry.institution.trim().to_string();
+    entry.degree = entry.degree.trim().to_string();
+    entry.field_of_study = entry.field_of_study.trim().to_string();
+    check_length(&entry.institution, 1, 150, "institution must be between 1 and 150 characters")?;
+    check_length(&entry.degree, 1, 150, "degree must be between 1 and 150 characters")?;
+    check_length(&entry.field_of_study, 0, 150, "field_of_study must be at most 150 characters")?;
+    check_dates(entry.start_date, entry.end_date, "end_date must not be before start_date")?;
+    Ok(entry)
+}
+
+fn validate_certification(mut entry: CertificationInput) -> Result<CertificationInput, ServiceError>{
+    entry.name = entry.name.trim().to_string();
+    entry.issuer = entry.issuer.trim().to_string();
+    entry.credential_url = entry.credential_url.trim().to_string();
+    check_length(&entry.name, 1, 150, "name must be between 1 and 150 characters")?;
+    check_length(&entry.issuer, 0, 150, "issuer must be at most 150 characters")?;
+    check_dates(entry.issued_on, entry.expires_on, "expires_on must not be before issued_on")?;
+    if !entry.credential_url.is_empty(){
+        check_url(&entry.credential_url, "credential_url must be an http or https URL")?;
+    }
+    Ok(entry)
+}
+
+fn validate_skill(mut entry: SkillInput) -> Result<SkillInput, ServiceError>{
+    entry.name = entry.name.trim().to_string();
+    entry.proficiency = entry.proficiency.trim().to_lowercase();
+    check_length(&entry.name, 1, 100, "name must be between 1 and 100 characters")?;
+    if !PROFICIENCIES.contains(&entry.proficiency.as_str()){
+        return Err(ServiceError::Invalid("proficiency must be one of beginner, intermediate, advanced or expert"));
+    }
+    if entry.years_of_experience.is_some_and(|years| !(0..=70).contains(&years)){
+        return Err(ServiceError::Invalid("years_of_experience must be between 0 and 70"));
+    }
+    Ok(entry)
+}
+
+fn validate_link(mut entry: PortfolioLinkInput) -> Result<PortfolioLinkInput, ServiceError>{
+    entry.label = entry.label.trim().to_string();
+    entry.url = entry.url.trim().to_string();
+    check_length(&entry.label, 1, 100, "label must be between 1 and 100 characters")?;
+    check_url(&entry.url, "url must be an http or https URL")?;
+    Ok(entry)
+}
@@ -0,0 +1,88 @@
+use crate::{
+    model::category_model::Category,
+    repository::category_repository::CategoryRepository,
+    schema::category_schema::{CategoryNode, CreateCategory},
+};
+
+use std::sync::Arc;
+use uuid::Uuid;
+
+use super::{user_service::UserService, ServiceError};
+
+// matches the VARCHAR(100) name and slug columns
+const MAX_NAME_LENGTH: usize = 100;
+
+// The job category tree. Jobs and searches refer to categories by slug.
+#[derive(Clone)]
+pub struct CategoryService{
+    categories: Arc<dyn CategoryRepository>,
+    users: UserService,
+}
+
+impl CategoryService{
+    pub fn new(categories: Arc<dyn CategoryRepository>, users: UserService) -> CategoryService{
+        CategoryService{ categories, users }
+    }
+
+    // top level categories with everything below them, by name
+    pub async fn tree(&self) -> Result<Vec<CategoryNode>, ServiceError>{
+        let categories = self.categories.list().await?;
+        Ok(children_of(None, &categories))
+    }
+
+    // only admins grow the taxonomy; the slug is made from the name
+    pub async fn create(&self, actor_id: &Uuid, category: &CreateCategory) -> Result<Category, ServiceError>{
+        self.users.ensure_admin(actor_id).await?;
+
+        let name = category.name.trim();
+        let slug = slugify(name);
+        if slug.is_empty() || name.chars().count() > MAX_NAME_LENGTH{
+            return Err(ServiceError::Invalid("name must be between 1 and 100 characters with a letter or digit"));
+        }
+        let parent = match category.parent.as_deref().map(str::trim).filter(|parent| !parent.is_empty()){
+            Some(parent) => Some(
+                self.find(parent)
+                    .await?
+                    .ok_or(ServiceError::Invalid("parent must be a slug from /api/categories"))?,
+            ),
+            None => None,
+        };
+
+        match self.categories.create(parent.as_ref().map(|parent| &parent.id), &slug, name).await{
+            Err(sqlx::Error::Database(e)) if e.is_unique_violation() => Err(ServiceError::Conflict("Category already exists")),
+            result => Ok(result?),
+        }
+    }
+
+    pub async fn find(&self, slug: &str) -> Result<Option<Category>, ServiceError>{
+        Ok(self.categories.find_by_slug(&slug.trim().to_lowercase()).await?)
+    }
+
+    // None for an unknown slug
+    pub async fn subtree(&self, slug: &str) -> Result<Option<Vec<Uuid>>, ServiceError>{
+        let ids = self.categories.subtree(&slug.trim().to_lowercase()).await?;
+        Ok(if ids.is_empty(){ None } else{ Some(ids) })
+    }
+}
+
+fn children_of(parent_id: Option<Uuid>, categories: &[Category]) -> Vec<CategoryNode>{
+    categories
+        .iter()
+        .filter(|category| category.parent_id == parent_id)
+        .map(|category| CategoryNode{
+            id: category.id,
+            slug: category.slug.to_owned(),
+            name: category.name.to_owned(),
+            children: children_of(Some(category.id), categories),
+        })
+        .collect()
+}
+
+// "UX Research" is ux-research
+pub fn slugify(name: &str) -> String{
+    name.to_lowercase()
+        .split(|c: char| !c.is_ascii_alphanumeric())
+        .filter(|part| !part.is_empty())
+        .collect::<Vec<&str>>()
+        .join("-")
+}
@@ -0,0 +1,276 @@
+use crate::{
+    core::helpers::cache::TtlCache,
+    model::job_model::Job,
+    repository::{job_repository::JobRepository, location_repository::LocationRepository},
+    schema::{
+        job_schema::{
+            CreateJobPosting, JobFacets, JobFilters, JobListing, JobSearch, NormalizedJob, Seniority, EMPLOYMENT_TYPES,
+        },
+        location_schema::{WORK_MODES, WORK_MODE_ONSITE},
+    },
+};
+
+use chrono::{DateTime, Duration, Utc};
+use std::sync::Arc;
+use uuid::Uuid;
+
+use super::{
+    category_service::CategoryService, scoring::parse_salary, skill_service::SkillService, user_service::UserService,
+    ServiceError,
+};
+
+pub const PAGE_SIZE: i64 = 10;
+// a radius search without radius_km
+const DEFAULT_RADIUS_KM: f64 = 50.0;
+const MAX_RADIUS_KM: f64 = 500.0;
+const MAX_REMOTE_ENTRIES: usize = 50;
+// matches the VARCHAR(40) time zone column
+const MAX_TIMEZONE_LENGTH: usize = 40;
+// distinct searches whose facets are kept at once
+const FACETS_CACHE_SIZE: usize = 1000;
+
+#[derive(Clone)]
+pub struct JobService{
+    jobs: Arc<dyn JobRepository>,
+    locations: Arc<dyn LocationRepository>,
+    skills: SkillService,
+    categories: CategoryService,
+    users: UserService,
+    facets_cache: TtlCache<JobFacets>,
+}
+
+impl JobService{
+    pub fn new(
+        jobs: Arc<dyn JobRepository>,
+        locations: Arc<dyn LocationRepository>,
+        skills: SkillService,
+        categories: CategoryService,
+        users: UserService,
+        facets_ttl: Duration,
+    ) -> JobService{
+        let facets_cache = TtlCache::new(facets_ttl.to_std().unwrap_or_default(), FACETS_CACHE_SIZE);
+        JobService{ jobs, locations, skills, categories, users, facets_cache }
+    }
+
+    // only admins post jobs
+    pub async fn create(&self, actor_id: &Uuid, job: &CreateJobPosting) -> Result<Job, ServiceError>{
+        self.users.ensure_admin(actor_id).await?;
+        if job.expires_at.is_some_and(|expires_at| expires_at <= Utc::now()){
+            return Err(ServiceError::Invalid("expires_at must be in the future"));
+        }
+        check_work_mode(job)?;
+        if !EMPLOYMENT_TYPES.contains(&job.employment_type.as_str()){
+            return Err(ServiceError::Invalid(
+                "employment_type must be full-time, part-time, contract, internship or temporary",
+            ));
+        }
+        let seniority = match job.seniority.as_deref(){
+            Some(name) => Seniority::from_name(name).ok_or(ServiceError::Invalid("seniority must be junior, mid, senior or lead"))?,
+            None => Seniority::from_title(&job.title),
+        };
+        let category_id = match job.category.as_deref(){
+            Some(slug) => Some(
+                self.categories
+                    .find(slug)
+                    .await?
+                    .ok_or(ServiceError::Invalid("category must be a slug from /api/categories"))?
+                    .id,
+            ),
+            None => None,
+        };
+        let salary = parse_salary(&job.salary);
+
+        // a skill listed as both required and nice to have is required
+        let mut skills: Vec<(Uuid, bool)> = Vec::new();
+        for (names, required) in [(&job.required_skills, true), (&job.nice_to_have_skills, false)]{
+            for skill in self.skills.resolve(names).await?{
+                let skill = skill.ok_or(ServiceError::Invalid(
+                    "required_skills and nice_to_have_skills must be names or aliases from /api/skills",
+                ))?;
+                if !skills.iter().any(|(id, _)| *id == skill.id){
+                    skills.push((skill.id, required));
+                }
+            }
+        }
+
+        // a place the dataset does not know is kept as typed, without
+        // coordinates, rather than refused
+        let location = self.locations.geocode(&job.city, &job.country).await?.unwrap_or_default();
+
+        let normalized = NormalizedJob{
+            location,
+            seniority: seniority.name().to_string(),
+            category_id,
+            salary_min: salary.as_ref().map(|salary| salary.min),
+            salary_max: salary.as_ref().map(|salary| salary.max),
+            salary_currency: salary.and_then(|salary| salary.currency),
+        };
+        Ok(self.jobs.create(job, &skills, &normalized).await?)
+    }
+
+    pub async fn find(&self, id: &Uuid) -> Result<JobListing, ServiceError>{
+        let job = self.jobs.find_by_id(id).await?.ok_or(ServiceError::NotFound("Job"))?;
+        Ok(self.with_skills(vec![job]).await?.remove(0))
+    }
+
+    pub async fn find_many(&self, ids: &[Uuid]) -> Result<Vec<JobListing>, ServiceError>{
+        let jobs = self.jobs.find_by_ids(ids).await?;
+        self.with_skills(jobs).await
+    }
+
+    pub async fn list(&self, page: i64, filters: &JobFilters) -> Result<Vec<JobListing>, ServiceError>{
+        let search = match self.search_for(filters).await?{
+            Some(search) => search,
+            None => return Ok(Vec::new()),
+        };
+        let jobs = self.jobs.list(&search, PAGE_SIZE, page.max(0) * PAGE_SIZE).await?;
+        self.with_skills(jobs).await
+    }
+
+    // what a saved search has not seen yet
+    pub async fn list_new(
+        &self,
+        filters: &JobFilters,
+        since: &DateTime<Utc>,
+        until: &DateTime<Utc>,
+        limit: i64,
+    ) -> Result<Vec<JobListing>, ServiceError>{
+        let search = match self.search_for(filters).await?{
+            Some(search) => search,
+            None => return Ok(Vec::new()),
+        };
+        let jobs = self.jobs.list_new(&search, since, until, limit).await?;
+        self.with_skills(jobs).await
+    }
+
+    // Counts for the whole search, not just one page of it. The same
+    // search is answered from the cache until facets_ttl has passed.
+    pub async fn facets(&self, filters: &JobFilters) -> Result<JobFacets, ServiceError>{
+        let key = serde_json::to_string(filters).unwrap_or_default();
+        if let Some(facets) = self.facets_cache.get(&key){
+            return Ok(facets);
+        }
+        let search = match self.search_for(filters).await?{
+            Some(search) => search,
+            None => return Ok(JobFacets::default()),
+        };
+
+        let mut facets = JobFacets::default();
+        for count in self.jobs.facets(&search).await?{
+            if count.facet == "salary"{
+                // the value is the currency and the bucket
+                if let Some((currency, bucket)) = count.value.split_once(' '){
+                    facets.salary.entry(currency.to_string()).or_default().insert(bucket.to_string(), count.count);
+                }
+                continue;
+            }
+            let facet = match count.facet.as_str(){
+                "country" => &mut facets.country,
+                "city" => &mut facets.city,
+                "company" => &mut facets.company,
+                "employment_type" => &mut facets.employment_type,
+                "seniority" => &mut facets.seniority,
+                "category" => &mut facets.category,
+                _ => &mut facets.posted,
+            };
+            facet.insert(count.value, count.count);
+        }
+        self.facets_cache.insert(key, facets.clone());
+        Ok(facets)
+    }
+
+    pub async fn recent(&self, limit: i64) -> Result<Vec<JobListing>, ServiceError>{
+        let jobs = self.jobs.recent(limit).await?;
+        self.with_skills(jobs).await
+    }
+
+    // A skill or category nobody has heard of cannot be on any job, so
+    // None, the search matches nothing rather than ignoring it.
+    async fn search_for(&self, filters: &JobFilters) -> Result<Option<JobSearch>, ServiceError>{
+        check_filters(filters)?;
+        let mut search = JobSearch{
+            text: filters.search_query.to_owned(),
+            near: filters.near,
+            radius_km: filters.near.map(|_| filters.radius_km.unwrap_or(DEFAULT_RADIUS_KM)),
+            work_mode: filters.work_mode.to_owned(),
+            employment_type: filters.employment_type.to_owned(),
+            seniority: filters.seniority.to_owned(),
+            ..JobSearch::default()
+        };
+        if let Some(slug) = &filters.category{
+            match self.categories.subtree(slug).await?{
+                Some(ids) => search.category_ids = Some(ids),
+                None => return Ok(None),
+            }
+        }
+        // "rust,Rust" or an alias next to its skill name the same skill
+        // twice, and a job has every skill only once
+        for skill in self.skills.resolve(&filters.skills).await?{
+            match skill{
+                Some(skill) if search.skill_ids.contains(&skill.id) => {}
+                Some(skill) => search.skill_ids.push(skill.id),
+                None => return Ok(None),
+            }
+        }
+        Ok(Some(search))
+    }
+
+    async fn with_skills(&self, jobs: Vec<Job>) -> Result<Vec<JobListing>, ServiceError>{
+        let ids: Vec<Uuid> = jobs.iter().map(|job| job.id).collect();
+        let skills = self.skills.for_jobs(&ids).await?;
+
+        Ok(jobs
+            .into_iter()
+            .map(|job| JobListing{
+                skills: skills.iter().filter(|skill| skill.job_id == job.id).cloned().collect(),
+                job,
+            })
+            .collect())
+    }
+}
+
+// The parts of a search that are wrong whatever the jobs, shared with the
+// saved searches so a search that cannot run is never stored.
+pub fn check_filters(filters: &JobFilters) -> Result<(), ServiceError>{
+    if filters.radius_km.is_some() && filters.near.is_none(){
+        return Err(ServiceError::Invalid("radius_km needs near"));
+    }
+    if filters.radius_km.is_some_and(|radius| !(radius > 0.0 && radius <= MAX_RADIUS_KM)){
+        return Err(ServiceError::Invalid("radius_km must be more than 0 and at most 500"));
+    }
+    if filters.work_mode.as_deref().is_some_and(|mode| !WORK_MODES.contains(&mode)){
+        return Err(ServiceError::Invalid("work_mode must be onsite, hybrid or remote"));
+    }
+    if filters.employment_type.as_deref().is_some_and(|kind| !EMPLOYMENT_TYPES.contains(&kind)){
+        return Err(ServiceError::Invalid(
+            "employment_type must be full-time, part-time, contract, internship or temporary",
+        ));
+    }
+    if filters.seniority.as_deref().is_some_and(|name| Seniority::from_name(name).is_none()){
+        return Err(ServiceError::Invalid("seniority must be junior, mid, senior or lead"));
+    }
+    Ok(())
+}
+
+fn check_work_mode(job: &CreateJobPosting) -> Result<(), ServiceError>{
+    if !WORK_MODES.contains(&job.work_mode.as_str()){
+        return Err(ServiceError::Invalid("work_mode must be onsite, hybrid or remote"));
+    }
+    if job.work_mode == WORK_MODE_ONSITE && !(job.remote_countries.is_empty() && job.remote_timezones.is_empty()){
+        return Err(ServiceError::Invalid("remote_countries and remote_timezones are for remote or hybrid jobs"));
+    }
+    if job.remote_countries.len() > MAX_REMOTE_ENTRIES || job.remote_timezones.len() > MAX_REMOTE_ENTRIES{
+        return Err(ServiceError::Invalid("remote_countries and remote_timezones have at most 50 entries each"));
+    }
+    if !job.remote_countries.iter().all(|code| code.len() == 2 && code.chars().all(|c| c.is_ascii_alphabetic())){
+        return Err(ServiceError::Invalid("remote_countries must be ISO 3166 country codes such as ES"));
+    }
+    if !job
+        .remote_timezones
+        .iter()
+        .all(|zone| !zone.is_empty() && zone.len() <= MAX_TIMEZONE_LENGTH && !zone.contains(char::is_whitespace))
+    {
+        return Err(ServiceError::Invalid("remote_timezones must be time zone names such as Europe/Madrid"));
+    }
+    Ok(())
+}
@@ -1 +1,53 @@
 pub mod user_service;
+pub mod job_service;
+pub mod application_service;
+pub mod account_service;
+pub mod profile_service;
+pub mod candidate_service;
+pub mod skill_service;
+pub mod scoring;
+pub mod recommendation_service;
+pub mod saved_search_service;
+pub mod saved_job_service;
+pub mod category_service;
+pub mod analytics_service;
+pub mod api_key_service;
+pub mod queue_service;
+
+use core::fmt;
+
+// Business rule failures. Handlers map these onto HTTP responses, services
+// never build responses themselves.
+#[derive(Debug)]
+pub enum ServiceError{
+    NotFound(&'static str),
+    Conflict(&'static str),
+    // the request itself is wrong, the message says how
+    Invalid(&'static str),
+    InvalidCredentials,
+    Disabled,
+    Unauthorized,
+    Database(sqlx::Error),
+}
+
+impl fmt::Display for ServiceError{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result{
+        match self{
+            ServiceError::NotFound(what) => write!(f, "{} not found", what),
+            ServiceError::Conflict(message) => write!(f, "{}", message),
+            ServiceError::Invalid(message) => write!(f, "{}", message),
+            ServiceError::InvalidCredentials => write!(f, "Invalid login details"),
+            ServiceError::Disabled => write!(f, "Account is disabled"),
+            ServiceError::Unauthorized => write!(f, "Unauthorized"),
+            ServiceError::Database(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+impl std::error::Error for ServiceError{}
+
+impl From<sqlx::Error> for ServiceError{
+    fn from(e: sqlx::Error) -> Self{
+        ServiceError::Database(e)
+    }
+}
@@ -0,0 +1,145 @@
+use crate::{
+    core::helpers::api_key,
+    model::user_model::User,
+    queue::task::Task,
+    repository::user_repository::UserRepository,
+};
+
+use chrono::{Duration, Utc};
+use rand_core::{OsRng, RngCore};
+use std::sync::Arc;
+use uuid::Uuid;
+
+use super::{
+    user_service::{hash_password, UserService},
+    ServiceError,
+};
+
+pub const MIN_PASSWORD_LENGTH: usize = 8;
+// matches the VARCHAR(100) name columns
+const MAX_NAME_LENGTH: usize = 100;
+const EMAIL_CHANGE_LIFETIME_HOURS: i64 = 24;
+
+// Changes users make to their own profile
+#[derive(Clone)]
+pub struct ProfileService{
+    users: Arc<dyn UserRepository>,
+    credentials: UserService,
+    frontend_url: String,
+}
+
+impl ProfileService{
+    pub fn new(users: Arc<dyn UserRepository>, credentials: UserService, frontend_url: &str) -> ProfileService{
+        ProfileService{ users, credentials, frontend_url: frontend_url.trim_end_matches('/').to_string() }
+    }
+
+    pub async fn update(&self, id: &Uuid, first_name: Option<&str>, last_name: Option<&str>) -> Result<User, ServiceError>{
+        let first_name = first_name.map(str::trim);
+        let last_name = last_name.map(str::trim);
+        if first_name.is_some_and(|name| name.is_empty() || name.chars().count() > MAX_NAME_LENGTH){
+            return Err(ServiceError::Invalid("first_name must be between 1 and 100 characters"));
+        }
+        if last_name.is_some_and(|name| name.chars().count() > MAX_NAME_LENGTH){
+            return Err(ServiceError::Invalid("last_name must be at most 100 characters"));
+        }
+
+        self.users
+            .update_profile(id, first_name, last_name)
+            .await?
+            .ok_or(ServiceError::NotFound("User"))
+    }
+
+    // Every existing session ends, the returned session version is what the
+    // caller's new token has to carry.
+    pub async fn change_password(&self, id: &Uuid, current_password: &str, new_password: &str) -> Result<i32, ServiceError>{
+        if new_password.chars().count() < MIN_PASSWORD_LENGTH{
+            return Err(ServiceError::Invalid("new_password must be at least 8 characters"));
+        }
+        self.verify_password(id, current_password).await?;
+
+        Ok(self.users.set_password(id, &hash_password(new_password)).await?)
+    }
+
+    // The address only changes once the link sent to it has been followed,
+    // so nobody can take over an email they do not own.
+    pub async fn request_email_change(&self, id: &Uuid, new_email: &str, password: &str) -> Result<(), ServiceError>{
+        let new_email = new_email.trim().to_lowercase();
+        if !is_plausible_email(&new_email){
+            return Err(ServiceError::Invalid("new_email is not a valid email address"));
+        }
+        let user = self.verify_password(id, password).await?;
+        if self.users.email_exists(&new_email).await?{
+            return Err(ServiceError::Conflict("Email already in use"));
+        }
+
+        let mut bytes = [0u8; 32];
+        OsRng.fill_bytes(&mut bytes);
+        let token = hex::encode(bytes);
+
+        let verification = Task::SendEmail{
+            to: new_email.to_owned(),
+            template: "verify_email".to_string(),
+            context: serde_json::json!({
+                "first_name": user.first_name,
+                "link": format!("{}/verify-email?token={}", self.frontend_url, token),
+            }),
+        };
+        let expires_at = Utc::now() + Duration::hours(EMAIL_CHANGE_LIFETIME_HOURS);
+        self.users
+            .create_email_change(id, &new_email, &api_key::hash(&token), expires_at, Some(&verification))
+            .await?;
+
+        Ok(())
+    }
+
+    // the old address is told about the change in case it was not its owner
+    pub async fn confirm_email_change(&self, token: &str) -> Result<String, ServiceError>{
+        let token_hash = api_key::hash(token);
+        let change = self
+            .users
+            .find_email_change(&token_hash)
+            .await?
+            .ok_or(ServiceError::Invalid("Invalid or expired token"))?;
+        let user = self.credentials.find(&change.user_id).await?;
+
+        let notice = Task::SendEmail{
+            to: user.email.to_owned(),
+            template: "email_changed".to_string(),
+            context: serde_json::json!({
+                "first_name": user.first_name,
+                "new_email": change.new_email,
+            }),
+        };
+        match self.users.apply_email_change(&token_hash, &change, Some(&notice)).await{
+            Ok(true) => Ok(change.new_email),
+            Ok(false) => Err(ServiceError::Invalid("Invalid or expired token")),
+            Err(sqlx::Error::Database(e)) if e.is_unique_violation() => Err(ServiceError::Conflict("Email already in use")),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    async fn verify_password(&self, id: &Uuid, password: &str) -> Result<User, ServiceError>{
+        let user = self.credentials.find(id).await?;
+        match self.credentials.authenticate(&user.email, password).await{
+            Err(ServiceError::InvalidCredentials) => Err(ServiceError::Invalid("Password is incorrect")),
+            result => result,
+        }
+    }
+}
+
+// Deliverability is what the verification email is for, this only catches
+// typos such as a missing @ or domain.
+fn is_plausible_email(email: &str) -> bool{
+    match email.split_once('@'){
+        Some((local, domain)) => {
+            !local.is_empty()
+                && !domain.contains('@')
+                && domain.contains('.')
+                && !domain.starts_with('.')
+                && !domain.ends_with('.')
+                && !email.contains(char::is_whitespace)
+                && email.len() <= 255
+        }
+        None => false,
+    }
+}
@@ -0,0 +1,65 @@
+use crate::{
+    model::queued_job_model::QueuedJob,
+    queue::{STATUS_COMPLETED, STATUS_DEAD, STATUS_PENDING, STATUS_RUNNING},
+    repository::queue_repository::QueueRepository,
+};
+
+use std::collections::BTreeMap;
+use std::sync::Arc;
+use uuid::Uuid;
+
+use super::{user_service::UserService, ServiceError};
+
+pub const PAGE_SIZE: i64 = 20;
+
+// The queue holds emails and file paths of every user, so all of it is for
+// admins only.
+#[derive(Clone)]
+pub struct QueueService{
+    queue: Arc<dyn QueueRepository>,
+    users: UserService,
+}
+
+impl QueueService{
+    pub fn new(queue: Arc<dyn QueueRepository>, users: UserService) -> QueueService{
+        QueueService{ queue, users }
+    }
+
+    // every status is listed, with 0 when it has no jobs
+    pub async fn stats(&self, actor_id: &Uuid) -> Result<BTreeMap<String, i64>, ServiceError>{
+        self.users.ensure_admin(actor_id).await?;
+
+        let mut stats: BTreeMap<String, i64> = [STATUS_PENDING, STATUS_RUNNING, STATUS_COMPLETED, STATUS_DEAD]
+            .iter()
+            .map(|status| (status.to_string(), 0))
+            .collect();
+        stats.extend(self.queue.count_by_status().await?);
+        Ok(stats)
+    }
+
+    pub async fn list(
+        &self,
+        actor_id: &Uuid,
+        status: Option<&str>,
+        kind: Option<&str>,
+        page: i64,
+    ) -> Result<Vec<QueuedJob>, ServiceError>{
+        self.users.ensure_admin(actor_id).await?;
+        Ok(self.queue.list(status, kind, PAGE_SIZE, page.max(0) * PAGE_SIZE).await?)
+    }
+
+    pub async fn find(&self, actor_id: &Uuid, id: &Uuid) -> Result<QueuedJob, ServiceError>{
+        self.users.ensure_admin(actor_id).await?;
+        self.queue.find(id).await?.ok_or(ServiceError::NotFound("Queued job"))
+    }
+
+    // Puts a dead job back in the queue with a fresh set of attempts. A
+    // periodic job cannot come back while a newer one of its kind waits.
+    pub async fn retry(&self, actor_id: &Uuid, id: &Uuid) -> Result<QueuedJob, ServiceError>{
+        self.users.ensure_admin(actor_id).await?;
+        match self.queue.retry_dead(id).await{
+            Err(sqlx::Error::Database(e)) if e.is_unique_violation() => Err(ServiceError::Conflict("A job of this kind is already queued")),
+            result => result?.ok_or(ServiceError::NotFound("Dead job")),
+        }
+    }
+}
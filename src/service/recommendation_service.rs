@@ -0,0 +1,101 @@
+use crate::{
+    repository::{application_repository::ApplicationRepository, candidate_repository::CandidateRepository},
+    schema::recommendation_schema::{RecommendedJob, SuggestedCandidate},
+};
+
+use std::sync::Arc;
+use uuid::Uuid;
+
+use super::{job_service::{JobService, PAGE_SIZE}, scoring, user_service::UserService, ServiceError};
+
+// Scoring happens in memory, so only the newest jobs and the most recently
+// active candidates are considered
+const JOB_POOL: i64 = 500;
+const CANDIDATE_POOL: i64 = 500;
+
+// Ranks jobs for candidates and candidates for jobs with scoring::score
+#[derive(Clone)]
+pub struct RecommendationService{
+    candidates: Arc<dyn CandidateRepository>,
+    applications: Arc<dyn ApplicationRepository>,
+    jobs: JobService,
+    users: UserService,
+}
+
+impl RecommendationService{
+    pub fn new(
+        candidates: Arc<dyn CandidateRepository>,
+        applications: Arc<dyn ApplicationRepository>,
+        jobs: JobService,
+        users: UserService,
+    ) -> RecommendationService{
+        RecommendationService{ candidates, applications, jobs, users }
+    }
+
+    // jobs the candidate already applied to are left out
+    pub async fn recommended_jobs(&self, user_id: &Uuid, page: i64) -> Result<Vec<RecommendedJob>, ServiceError>{
+        let candidate = self
+            .candidates
+            .find_match_profile(user_id)
+            .await?
+            .ok_or(ServiceError::NotFound("User"))?;
+        let applied: Vec<Uuid> = self
+            .applications
+            .list_for_user(user_id)
+            .await?
+            .iter()
+            .map(|application| application.job_id)
+            .collect();
+
+        let mut recommended: Vec<RecommendedJob> = self
+            .jobs
+            .recent(JOB_POOL)
+            .await?
+            .into_iter()
+            .filter(|job| !applied.contains(&job.job.id))
+            .map(|job| {
+                let (score, breakdown) = scoring::score(&candidate, &job);
+                RecommendedJob{ job, score, breakdown }
+            })
+            .collect();
+        // the pool is newest first and the sort is stable, so newer jobs win ties
+        recommended.sort_by_key(|job| std::cmp::Reverse(job.score));
+
+        Ok(page_of(recommended, page))
+    }
+
+    // candidates are only visible to admins
+    pub async fn suggested_candidates(&self, actor_id: &Uuid, job_id: &Uuid, page: i64) -> Result<Vec<SuggestedCandidate>, ServiceError>{
+        self.users.ensure_admin(actor_id).await?;
+        let job = self.jobs.find(job_id).await?;
+
+        let mut suggested: Vec<SuggestedCandidate> = self
+            .candidates
+            .list_match_profiles(CANDIDATE_POOL)
+            .await?
+            .into_iter()
+            .map(|candidate| {
+                let (score, breakdown) = scoring::score(&candidate, &job);
+                SuggestedCandidate{
+                    user_id: candidate.user_id,
+                    first_name: candidate.first_name,
+                    last_name: candidate.last_name,
+                    headline: candidate.headline,
+                    score,
+                    breakdown,
+                }
+            })
+            .collect();
+        suggested.sort_by_key(|candidate| std::cmp::Reverse(candidate.score));
+
+        Ok(page_of(suggested, page))
+    }
+}
+
+fn page_of<T>(items: Vec<T>, page: i64) -> Vec<T>{
+    items
+        .into_iter()
+        .skip((page.max(0) * PAGE_SIZE) as usize)
+        .take(PAGE_SIZE as usize)
+        .collect()
+}
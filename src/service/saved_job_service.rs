@@ -0,0 +1,81 @@
+use crate::{
+    queue::task::Task,
+    repository::saved_job_repository::SavedJobRepository,
+    schema::saved_job_schema::SavedJobListing,
+};
+
+use chrono::{DateTime, Utc};
+use std::sync::Arc;
+use uuid::Uuid;
+
+use super::{job_service::JobService, ServiceError};
+
+// Jobs applicants shortlist before applying
+#[derive(Clone)]
+pub struct SavedJobService{
+    saved: Arc<dyn SavedJobRepository>,
+    jobs: JobService,
+    frontend_url: String,
+}
+
+impl SavedJobService{
+    pub fn new(saved: Arc<dyn SavedJobRepository>, jobs: JobService, frontend_url: &str) -> SavedJobService{
+        SavedJobService{ saved, jobs, frontend_url: frontend_url.trim_end_matches('/').to_string() }
+    }
+
+    // saving a job twice is not an error, returns false the second time
+    pub async fn save(&self, user_id: &Uuid, job_id: &Uuid) -> Result<bool, ServiceError>{
+        self.jobs.find(job_id).await?;
+        Ok(self.saved.save(user_id, job_id).await?)
+    }
+
+    pub async fn remove(&self, user_id: &Uuid, job_id: &Uuid) -> Result<(), ServiceError>{
+        if !self.saved.remove(user_id, job_id).await?{
+            return Err(ServiceError::NotFound("Saved job"));
+        }
+        Ok(())
+    }
+
+    // Expired jobs stay on the list, marked as closed, until the user
+    // removes them.
+    pub async fn list(&self, user_id: &Uuid) -> Result<Vec<SavedJobListing>, ServiceError>{
+        let saved = self.saved.list_for_user(user_id).await?;
+        let ids: Vec<Uuid> = saved.iter().map(|entry| entry.job_id).collect();
+        let mut jobs = self.jobs.find_many(&ids).await?;
+
+        let now = Utc::now();
+        Ok(saved
+            .iter()
+            .filter_map(|entry|{
+                let position = jobs.iter().position(|listing| listing.job.id == entry.job_id)?;
+                let job = jobs.swap_remove(position);
+                Some(SavedJobListing{
+                    is_open: job.job.expires_at.is_none_or(|expires_at| expires_at > now),
+                    saved_at: entry.created_at,
+                    job,
+                })
+            })
+            .collect())
+    }
+
+    // One reminder per saved job that expires before expiring_before,
+    // unless the user applied in the meantime. Returns how many were queued.
+    pub async fn send_reminders(&self, expiring_before: &DateTime<Utc>) -> Result<usize, ServiceError>{
+        let expiring = self.saved.list_expiring(expiring_before).await?;
+        for saved in &expiring{
+            let reminder = Task::SendEmail{
+                to: saved.email.to_owned(),
+                template: "saved_job_reminder".to_string(),
+                context: serde_json::json!({
+                    "first_name": saved.first_name,
+                    "title": saved.title,
+                    "company_name": saved.company_name,
+                    "expires_at": saved.expires_at.format("%B %-d, %Y at %H:%M UTC").to_string(),
+                    "link": format!("{}/jobs/{}", self.frontend_url, saved.job_id),
+                }),
+            };
+            self.saved.mark_reminded(&saved.user_id, &saved.job_id, &reminder).await?;
+        }
+        Ok(expiring.len())
+    }
+}
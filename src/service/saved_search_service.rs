@@ -0,0 +1,198 @@
+use crate::{
+    model::saved_search_model::SavedSearch,
+    queue::task::Task,
+    repository::saved_search_repository::SavedSearchRepository,
+    schema::{
+        job_schema::JobFilters,
+        saved_search_schema::{SavedSearchInput, FREQUENCY_DAILY, FREQUENCY_INSTANT, FREQUENCY_WEEKLY},
+    },
+};
+
+use chrono::{DateTime, Utc};
+use rand_core::{OsRng, RngCore};
+use std::sync::Arc;
+use uuid::Uuid;
+
+use super::{
+    category_service::CategoryService,
+    job_service::{check_filters, JobService},
+    skill_service::SkillService,
+    ServiceError,
+};
+
+const MAX_SAVED_SEARCHES: i64 = 20;
+// matches the VARCHAR(100) name column
+const MAX_NAME_LENGTH: usize = 100;
+const MAX_QUERY_LENGTH: usize = 200;
+const MAX_SKILLS: usize = 20;
+// jobs listed in one alert email, the search itself shows the rest
+const ALERT_JOB_LIMIT: i64 = 20;
+
+// Searches users keep to be told about new jobs that match them
+#[derive(Clone)]
+pub struct SavedSearchService{
+    searches: Arc<dyn SavedSearchRepository>,
+    jobs: JobService,
+    skills: SkillService,
+    categories: CategoryService,
+    frontend_url: String,
+}
+
+impl SavedSearchService{
+    pub fn new(
+        searches: Arc<dyn SavedSearchRepository>,
+        jobs: JobService,
+        skills: SkillService,
+        categories: CategoryService,
+        frontend_url: &str,
+    ) -> SavedSearchService{
+        SavedSearchService{
+            searches,
+            jobs,
+            skills,
+            categories,
+            frontend_url: frontend_url.trim_end_matches('/').to_string(),
+        }
+    }
+
+    // Alerts start with the jobs posted from now on, not everything that
+    // already matches.
+    pub async fn create(&self, user_id: &Uuid, search: &SavedSearchInput) -> Result<SavedSearch, ServiceError>{
+        let (name, filters) = self.validate(search).await?;
+        if self.searches.count_for_user(user_id).await? >= MAX_SAVED_SEARCHES{
+            return Err(ServiceError::Invalid("At most 20 searches can be saved"));
+        }
+
+        let mut bytes = [0u8; 32];
+        OsRng.fill_bytes(&mut bytes);
+        let token = hex::encode(bytes);
+
+        Ok(self
+            .searches
+            .create(user_id, &name, &filters, &search.frequency, search.alerts_enabled, &token)
+            .await?)
+    }
+
+    pub async fn list(&self, user_id: &Uuid) -> Result<Vec<SavedSearch>, ServiceError>{
+        Ok(self.searches.list_for_user(user_id).await?)
+    }
+
+    pub async fn update(&self, user_id: &Uuid, id: &Uuid, search: &SavedSearchInput) -> Result<SavedSearch, ServiceError>{
+        let (name, filters) = self.validate(search).await?;
+        self.searches
+            .update(user_id, id, &name, &filters, &search.frequency, search.alerts_enabled)
+            .await?
+            .ok_or(ServiceError::NotFound("Saved search"))
+    }
+
+    pub async fn delete(&self, user_id: &Uuid, id: &Uuid) -> Result<(), ServiceError>{
+        if !self.searches.delete(user_id, id).await?{
+            return Err(ServiceError::NotFound("Saved search"));
+        }
+        Ok(())
+    }
+
+    // the link in every alert email, works without logging in
+    pub async fn unsubscribe(&self, token: &str) -> Result<(), ServiceError>{
+        if !self.searches.disable_alerts(token).await?{
+            return Err(ServiceError::Invalid("Invalid unsubscribe token"));
+        }
+        Ok(())
+    }
+
+    // Emails every due search the jobs posted since its last alert, up to
+    // now. Searches without new jobs are only moved on, so a retry after a
+    // failure halfway never sends the same jobs twice. Returns how many
+    // emails were queued.
+    pub async fn send_alerts(&self, now: &DateTime<Utc>) -> Result<usize, ServiceError>{
+        let mut sent = 0;
+        for search in self.searches.list_due(now).await?{
+            let jobs = self
+                .jobs
+                .list_new(&search.filters, &search.last_notified_at, now, ALERT_JOB_LIMIT)
+                .await?;
+            if jobs.is_empty(){
+                self.searches.mark_notified(&search.id, now, None).await?;
+                continue;
+            }
+
+            let listed: Vec<serde_json::Value> = jobs
+                .iter()
+                .map(|listing| serde_json::json!({
+                    "title": listing.job.title,
+                    "company_name": listing.job.company_name,
+                    "city": listing.job.city,
+                    "country": listing.job.country,
+                    "link": format!("{}/jobs/{}", self.frontend_url, listing.job.id),
+                }))
+                .collect();
+            let alert = Task::SendEmail{
+                to: search.email.to_owned(),
+                template: "job_alert".to_string(),
+                context: serde_json::json!({
+                    "first_name": search.first_name,
+                    "search_name": search.name,
+                    "count": listed.len(),
+                    "jobs": listed,
+                    "unsubscribe_link": format!("{}/alerts/unsubscribe?token={}", self.frontend_url, search.unsubscribe_token),
+                }),
+            };
+            self.searches.mark_notified(&search.id, now, Some(&alert)).await?;
+            sent += 1;
+        }
+        Ok(sent)
+    }
+
+    // Skills are stored under their canonical names so renaming an alias
+    // later does not change what a search matches.
+    async fn validate(&self, search: &SavedSearchInput) -> Result<(String, JobFilters), ServiceError>{
+        let name = search.name.trim();
+        if name.is_empty() || name.chars().count() > MAX_NAME_LENGTH{
+            return Err(ServiceError::Invalid("name must be between 1 and 100 characters"));
+        }
+        if ![FREQUENCY_INSTANT, FREQUENCY_DAILY, FREQUENCY_WEEKLY].contains(&search.frequency.as_str()){
+            return Err(ServiceError::Invalid("frequency must be instant, daily or weekly"));
+        }
+
+        let search_query = search
+            .filters
+            .search_query
+            .as_deref()
+            .map(str::trim)
+            .filter(|text| !text.is_empty());
+        if search_query.is_some_and(|text| text.chars().count() > MAX_QUERY_LENGTH){
+            return Err(ServiceError::Invalid("search_query must be at most 200 characters"));
+        }
+        if search.filters.skills.len() > MAX_SKILLS{
+            return Err(ServiceError::Invalid("skills must have at most 20 entries"));
+        }
+
+        let mut skills: Vec<String> = Vec::new();
+        for skill in self.skills.resolve(&search.filters.skills).await?{
+            let skill = skill.ok_or(ServiceError::Invalid("skills must only contain known skills"))?;
+            if !skills.contains(&skill.name){
+                skills.push(skill.name);
+            }
+        }
+
+        let category = match search.filters.category.as_deref(){
+            Some(slug) => Some(
+                self.categories
+                    .find(slug)
+                    .await?
+                    .ok_or(ServiceError::Invalid("category must be a slug from /api/categories"))?
+                    .slug,
+            ),
+            None => None,
+        };
+
+        let filters = JobFilters{
+            search_query: search_query.map(str::to_string),
+            skills,
+            category,
+            ..search.filters.clone()
+        };
+        check_filters(&filters)?;
+        Ok((name.to_string(), filters))
+    }
+}
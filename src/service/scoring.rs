@@ -0,0 +1,265 @@
+use crate::{
+    model::job_model::Job,
+    schema::{
+        job_schema::{JobListing, Seniority},
+        location_schema::WORK_MODE_REMOTE,
+        recommendation_schema::{MatchProfile, ScoreBreakdown, ScoreComponent},
+    },
+};
+
+pub const SKILLS_WEIGHT: f64 = 0.5;
+pub const LOCATION_WEIGHT: f64 = 0.2;
+pub const SALARY_WEIGHT: f64 = 0.15;
+pub const SENIORITY_WEIGHT: f64 = 0.15;
+// when there is nothing to compare a factor neither helps nor hurts
+const NEUTRAL: f64 = 0.5;
+const HOURS_PER_YEAR: f64 = 2080.0;
+
+// How well a candidate fits a job, from 0 to 100, and why. The same score
+// ranks jobs for a candidate and candidates for a job.
+pub fn score(candidate: &MatchProfile, job: &JobListing) -> (u8, ScoreBreakdown){
+    let breakdown = ScoreBreakdown{
+        skills: score_skills(candidate, job),
+        location: score_location(candidate, job),
+        salary: score_salary(candidate, job),
+        seniority: score_seniority(candidate, job),
+    };
+    let total = [&breakdown.skills, &breakdown.location, &breakdown.salary, &breakdown.seniority]
+        .iter()
+        .map(|component| component.score * component.weight)
+        .sum::<f64>();
+
+    ((total * 100.0).round().clamp(0.0, 100.0) as u8, breakdown)
+}
+
+fn component(score: f64, weight: f64, reason: String) -> ScoreComponent{
+    ScoreComponent{ score: (score * 100.0).round() / 100.0, weight, reason }
+}
+
+// required skills count twice as much as nice to have ones
+fn score_skills(candidate: &MatchProfile, job: &JobListing) -> ScoreComponent{
+    if job.skills.is_empty(){
+        return component(NEUTRAL, SKILLS_WEIGHT, "The job lists no skills".to_string());
+    }
+
+    let (mut total, mut matched) = (0.0, 0.0);
+    let (mut required, mut required_matched, mut nice, mut nice_matched) = (0, 0, 0, 0);
+    let mut missing = Vec::new();
+    for skill in &job.skills{
+        let has = candidate.skill_ids.contains(&skill.id);
+        let weight = if skill.required{ 2.0 } else{ 1.0 };
+        total += weight;
+        if has{
+            matched += weight;
+        }
+        if skill.required{
+            required += 1;
+            if has{
+                required_matched += 1;
+            } else{
+                missing.push(skill.name.as_str());
+            }
+        } else{
+            nice += 1;
+            if has{
+                nice_matched += 1;
+            }
+        }
+    }
+
+    let mut reason = format!(
+        "Has {} of {} required and {} of {} nice to have skills",
+        required_matched, required, nice_matched, nice
+    );
+    if !missing.is_empty(){
+        reason.push_str(&format!(", missing {}", missing.join(", ")));
+    }
+    component(matched / total, SKILLS_WEIGHT, reason)
+}
+
+// Remote jobs are matched on the countries they hire from, other jobs on
+// where they are. Countries are compared by code once both sides could be
+// placed, by name before that.
+fn score_location(candidate: &MatchProfile, job: &JobListing) -> ScoreComponent{
+    let job = &job.job;
+    if job.work_mode == WORK_MODE_REMOTE{
+        return score_remote(candidate, job);
+    }
+    if candidate.city.is_empty() && candidate.country.is_empty(){
+        return component(NEUTRAL, LOCATION_WEIGHT, "No location on the profile".to_string());
+    }
+
+    let same_country = candidate.country.is_empty()
+        || match (&candidate.country_code, &job.country_code){
+            (Some(lives_in), Some(job_in)) => lives_in == job_in,
+            _ => candidate.country.eq_ignore_ascii_case(&job.country),
+        };
+    if same_country && candidate.city.eq_ignore_ascii_case(&job.city){
+        component(1.0, LOCATION_WEIGHT, format!("Lives in {}", job.city))
+    } else if same_country && !candidate.country.is_empty(){
+        component(0.6, LOCATION_WEIGHT, format!("Lives in {}, the job is in {}", candidate.city, job.city))
+    } else{
+        component(0.0, LOCATION_WEIGHT, format!("Lives in {}, the job is in {}", candidate.country, job.country))
+    }
+}
+
+fn score_remote(candidate: &MatchProfile, job: &Job) -> ScoreComponent{
+    if !job.remote_countries.is_empty(){
+        let hires_from = job.remote_countries.join(", ");
+        match &candidate.country_code{
+            Some(code) if !job.remote_countries.contains(code) => {
+                return component(0.0, LOCATION_WEIGHT, format!("Remote job, hires from {} only", hires_from));
+            }
+            None => {
+                return component(NEUTRAL, LOCATION_WEIGHT, format!("Remote job, hires from {}", hires_from));
+            }
+            Some(_) => {}
+        }
+    }
+
+    if candidate.open_to_remote{
+        component(1.0, LOCATION_WEIGHT, "Remote job, open to remote work".to_string())
+    } else{
+        component(0.75, LOCATION_WEIGHT, "Remote job".to_string())
+    }
+}
+
+// Full marks when the job pays the candidate's minimum, nothing once it is
+// half of it
+fn score_salary(candidate: &MatchProfile, job: &JobListing) -> ScoreComponent{
+    let expected = match candidate.salary_min.or(candidate.salary_max){
+        Some(expected) if expected > 0 => expected as f64,
+        _ => return component(NEUTRAL, SALARY_WEIGHT, "No salary expectation on the profile".to_string()),
+    };
+    // read from the salary text when the job was posted, like the facets
+    let offered = match job.job.salary_max{
+        Some(offered) => offered,
+        None => return component(NEUTRAL, SALARY_WEIGHT, "The job has no readable salary".to_string()),
+    };
+    if let (Some(wanted), Some(paid)) = (&candidate.salary_currency, &job.job.salary_currency){
+        if wanted != paid{
+            return component(
+                NEUTRAL,
+                SALARY_WEIGHT,
+                format!("The job pays in {}, the expectation is in {}", paid, wanted),
+            );
+        }
+    }
+
+    let reason = format!("Pays up to {}, expects at least {}", offered, expected);
+    if offered >= expected{
+        component(1.0, SALARY_WEIGHT, reason)
+    } else{
+        let shortfall = (expected - offered) / expected;
+        component((1.0 - shortfall * 2.0).max(0.0), SALARY_WEIGHT, reason)
+    }
+}
+
+fn score_seniority(candidate: &MatchProfile, job: &JobListing) -> ScoreComponent{
+    let wanted = job
+        .job
+        .seniority
+        .as_deref()
+        .and_then(Seniority::from_name)
+        .unwrap_or_else(|| Seniority::from_title(&job.job.title));
+    let years = match candidate.years_of_experience{
+        Some(years) => years,
+        None => return component(NEUTRAL, SENIORITY_WEIGHT, "No work experience on the profile".to_string()),
+    };
+    let level = Seniority::from_years(years);
+
+    let reason = format!("{} position, {:.0} years of experience", wanted.label(), years.floor());
+    match (level as i32 - wanted as i32).abs(){
+        0 => component(1.0, SENIORITY_WEIGHT, reason),
+        1 => component(0.5, SENIORITY_WEIGHT, reason),
+        _ => component(0.0, SENIORITY_WEIGHT, reason),
+    }
+}
+
+// yearly amounts
+#[derive(Debug, PartialEq)]
+pub struct SalaryRange{
+    pub min: f64,
+    pub max: f64,
+    pub currency: Option<String>,
+}
+
+// jobs.salary is free text: "45000-55000 EUR", "$60k - $80k", "€3.500 per
+// month", "100000". The first two amounts are the range, monthly and hourly
+// pay is turned into yearly. None when there is no amount at all.
+pub fn parse_salary(text: &str) -> Option<SalaryRange>{
+    let chars: Vec<char> = text.chars().collect();
+    let mut amounts = Vec::new();
+    let mut i = 0;
+    while i < chars.len(){
+        if !chars[i].is_ascii_digit(){
+            i += 1;
+            continue;
+        }
+        let start = i;
+        while i < chars.len()
+            && (chars[i].is_ascii_digit()
+                || ((chars[i] == ',' || chars[i] == '.') && chars.get(i + 1).is_some_and(|c| c.is_ascii_digit())))
+        {
+            i += 1;
+        }
+        let mut amount = parse_amount(&chars[start..i].iter().collect::<String>())?;
+        if chars.get(i).is_some_and(|c| *c == 'k' || *c == 'K'){
+            amount *= 1000.0;
+            i += 1;
+        }
+        amounts.push(amount);
+    }
+
+    let (mut min, mut max) = match amounts[..]{
+        [] => return None,
+        [amount] => (amount, amount),
+        [first, second, ..] => (first.min(second), first.max(second)),
+    };
+    let lower = text.to_lowercase();
+    let has = |words: &[&str]| lower.split(|c: char| !c.is_alphanumeric()).any(|word| words.contains(&word));
+    let period = if has(&["month", "monthly", "mo", "pm"]){
+        12.0
+    } else if has(&["hour", "hourly", "hr", "h"]){
+        HOURS_PER_YEAR
+    } else{
+        1.0
+    };
+    min *= period;
+    max *= period;
+
+    Some(SalaryRange{ min, max, currency: currency(text) })
+}
+
+// "45,000" and "45.000" are thousands, "60.5" is a fraction
+fn parse_amount(token: &str) -> Option<f64>{
+    let groups: Vec<&str> = token.split([',', '.']).collect();
+    if groups.len() > 1 && groups[1..].iter().all(|group| group.len() == 3){
+        groups.concat().parse().ok()
+    } else if groups.len() == 2{
+        format!("{}.{}", groups[0], groups[1]).parse().ok()
+    } else if groups.len() == 1{
+        token.parse().ok()
+    } else{
+        None
+    }
+}
+
+fn currency(text: &str) -> Option<String>{
+    if text.contains('€'){
+        return Some("EUR".to_string());
+    }
+    if text.contains('£'){
+        return Some("GBP".to_string());
+    }
+    if let Some(code) = text
+        .split(|c: char| !c.is_ascii_alphabetic())
+        .find(|word| word.len() == 3 && word.chars().all(|c| c.is_ascii_uppercase()))
+    {
+        return Some(code.to_string());
+    }
+    if text.contains('$'){
+        return Some("USD".to_string());
+    }
+    None
+}
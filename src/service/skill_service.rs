@@ -0,0 +1,86 @@
+use crate::{
+    model::skill_model::Skill,
+    repository::skill_repository::SkillRepository,
+    schema::skill_schema::{CreateSkill, JobSkill, ResolvedSkill},
+};
+
+use std::sync::Arc;
+use uuid::Uuid;
+
+use super::{user_service::UserService, ServiceError};
+
+const SUGGESTION_LIMIT: i64 = 10;
+// matches the VARCHAR(100) name and alias columns
+const MAX_SKILL_LENGTH: usize = 100;
+
+// The skills taxonomy. Jobs and candidates are tagged with its canonical
+// names, whatever spelling was typed in.
+#[derive(Clone)]
+pub struct SkillService{
+    skills: Arc<dyn SkillRepository>,
+    users: UserService,
+}
+
+impl SkillService{
+    pub fn new(skills: Arc<dyn SkillRepository>, users: UserService) -> SkillService{
+        SkillService{ skills, users }
+    }
+
+    // autocomplete, the best match first
+    pub async fn suggest(&self, query: Option<&str>) -> Result<Vec<Skill>, ServiceError>{
+        let prefix = query.map(str::trim).unwrap_or_default();
+        Ok(self.skills.search(prefix, SUGGESTION_LIMIT).await?)
+    }
+
+    // only admins grow the taxonomy
+    pub async fn create(&self, actor_id: &Uuid, skill: &CreateSkill) -> Result<Skill, ServiceError>{
+        self.users.ensure_admin(actor_id).await?;
+
+        let name = skill.name.trim();
+        if name.is_empty() || name.chars().count() > MAX_SKILL_LENGTH{
+            return Err(ServiceError::Invalid("name must be between 1 and 100 characters"));
+        }
+        let mut aliases: Vec<String> = Vec::new();
+        for alias in &skill.aliases{
+            let alias = alias.trim().to_lowercase();
+            if alias.is_empty() || alias.chars().count() > MAX_SKILL_LENGTH{
+                return Err(ServiceError::Invalid("aliases must be between 1 and 100 characters"));
+            }
+            if alias != name.to_lowercase() && !aliases.contains(&alias){
+                aliases.push(alias);
+            }
+        }
+
+        // a new name must not be somebody else's alias either, or typing it
+        // would depend on which of the two the database finds first
+        let mut keys = aliases.clone();
+        keys.push(name.to_lowercase());
+        if !self.skills.resolve(&keys).await?.is_empty(){
+            return Err(ServiceError::Conflict("Skill or alias already exists"));
+        }
+
+        match self.skills.create(name, &aliases).await{
+            Err(sqlx::Error::Database(e)) if e.is_unique_violation() => Err(ServiceError::Conflict("Skill or alias already exists")),
+            result => Ok(result?),
+        }
+    }
+
+    // One entry per name, None for names the taxonomy does not know.
+    // Matching ignores case and surrounding spaces.
+    pub async fn resolve(&self, names: &[String]) -> Result<Vec<Option<ResolvedSkill>>, ServiceError>{
+        let keys: Vec<String> = names.iter().map(|name| name.trim().to_lowercase()).collect();
+        if keys.is_empty(){
+            return Ok(Vec::new());
+        }
+        let found = self.skills.resolve(&keys).await?;
+
+        Ok(keys
+            .iter()
+            .map(|key| found.iter().find(|skill| &skill.key == key).cloned())
+            .collect())
+    }
+
+    pub async fn for_jobs(&self, job_ids: &[Uuid]) -> Result<Vec<JobSkill>, ServiceError>{
+        Ok(self.skills.for_jobs(job_ids).await?)
+    }
+}
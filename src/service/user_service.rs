@@ -1,28 +1,119 @@
-// use actix_web::web;
-// use crate::{
-//     model::user_model::User,
-//     schema::user_schema::{LoginUserSchema, RegisterUserSchema},
-//     AppState,
-// };
-
-// pub async fn create_user(body: web::Json<RegisterUserSchema>, data: web::Data<AppState>, hashed_password: String)->Result<User, sqlx::Error>{
-    
-//     sqlx::query_as!(
-//         User,
-//         "INSERT INTO users (first_name, last_name, email, password) VALUES ($1, $2, $3, $4) RETURNING *",
-//         body.first_name.to_string(),
-//         body.last_name.to_string(),
-//         body.email.to_string().to_lowercase(),
-//         hashed_password
-//     )
-//     .fetch_one(&data.db)
-//     .await
-// }
-
-// // pub async fn find_user_by_email(data: web::Data<AppState>,  &email: &str)-> Option<User>{
-
-// //     sqlx::query_as!(User, "SELECT * FROM users WHERE email = $1", )
-// //         .fetch_optional(&data.db)
-// //         .await
-// //         .unwrap()
-// // }
\ No newline at end of file
+use crate::{
+    model::user_model::User,
+    queue::task::Task,
+    repository::user_repository::{NewUser, UserRepository},
+    schema::user_schema::RegisterUserSchema,
+};
+
+use argon2::{
+    password_hash::{rand_core::OsRng, PasswordHash, PasswordHasher, PasswordVerifier, SaltString},
+    Argon2,
+};
+use std::sync::Arc;
+use uuid::Uuid;
+
+use super::ServiceError;
+
+pub const ROLE_USER: &str = "user";
+pub const ROLE_ADMIN: &str = "Admin";
+
+pub fn hash_password(password: &str) -> String{
+    let salt = SaltString::generate(&mut OsRng);
+    Argon2::default()
+        .hash_password(password.as_bytes(), &salt)
+        .expect("Error while hashing password")
+        .to_string()
+}
+
+#[derive(Clone)]
+pub struct UserService{
+    users: Arc<dyn UserRepository>,
+}
+
+impl UserService{
+    pub fn new(users: Arc<dyn UserRepository>) -> UserService{
+        UserService{ users }
+    }
+
+    // Emails are stored lowercased, so they are compared lowercased too.
+    // Regular users get a welcome email, admins are created by staff.
+    pub async fn register(&self, body: &RegisterUserSchema, role: &str) -> Result<User, ServiceError>{
+        let email = body.email.to_lowercase();
+        if self.users.email_exists(&email).await?{
+            return Err(ServiceError::Conflict("Email already in use"));
+        }
+
+        let password_hash = hash_password(&body.password);
+        let welcome = (role == ROLE_USER).then(|| Task::SendEmail{
+            to: email.to_owned(),
+            template: "welcome".to_string(),
+            context: serde_json::json!({ "first_name": body.first_name }),
+        });
+
+        let user = NewUser{
+            first_name: body.first_name.to_string(),
+            last_name: body.last_name.to_string(),
+            email,
+            password_hash,
+            role: role.to_string(),
+        };
+        Ok(self.users.create(&user, welcome.as_ref()).await?)
+    }
+
+    pub async fn authenticate(&self, email: &str, password: &str) -> Result<User, ServiceError>{
+        let user = self
+            .users
+            .find_by_email(&email.to_lowercase())
+            .await?
+            .ok_or(ServiceError::InvalidCredentials)?;
+
+        let is_valid = PasswordHash::new(&user.password).is_ok_and(|parsed_hash|{
+            Argon2::default()
+                .verify_password(password.as_bytes(), &parsed_hash)
+                .is_ok()
+        });
+        if !is_valid{
+            return Err(ServiceError::InvalidCredentials);
+        }
+
+        if !user.is_active{
+            return Err(ServiceError::Disabled);
+        }
+
+        // logging in during the grace period takes the deletion request back
+        if user.deletion_requested_at.is_some(){
+            self.users.cancel_deletion(&user.id).await?;
+            tracing::info!(user_id = %user.id, "account deletion cancelled by logging in");
+        }
+
+        Ok(user)
+    }
+
+    pub async fn find(&self, id: &Uuid) -> Result<User, ServiceError>{
+        self.users.find_by_id(id).await?.ok_or(ServiceError::NotFound("User"))
+    }
+
+    // Accounts waiting to be deleted are locked like disabled ones. A session
+    // token also has to be from after the last password change; API keys
+    // pass no session version.
+    pub async fn is_active(&self, id: &Uuid, session_version: Option<i32>) -> Result<bool, ServiceError>{
+        Ok(self.users.find_by_id(id).await?.is_some_and(|user|{
+            user.is_active
+                && user.deletion_requested_at.is_none()
+                && (session_version.is_none() || session_version == Some(user.session_version))
+        }))
+    }
+
+    pub async fn ensure_admin(&self, id: &Uuid) -> Result<(), ServiceError>{
+        match self.users.find_by_id(id).await?{
+            Some(user) if user.role == ROLE_ADMIN => Ok(()),
+            _ => Err(ServiceError::Unauthorized),
+        }
+    }
+
+    // the uploaded file is checked and previewed by the queue afterwards
+    pub async fn attach_resume(&self, id: &Uuid, path: &str) -> Result<(), ServiceError>{
+        let parse = Task::ParseResume{ user_id: *id, path: path.to_string() };
+        Ok(self.users.set_resume(id, path, Some(&parse)).await?)
+    }
+}
@@ -0,0 +1,290 @@
+mod common;
+
+use actix_web::{http::StatusCode, test::TestRequest};
+use chrono::{DateTime, Duration, Utc};
+use common::{bearer, fixtures, TestApp};
+use serde_json::json;
+use trabajo_server::{core::helpers::api_key, queue::task::Task};
+use uuid::Uuid;
+
+fn delete_request(token: &str, password: &str) -> TestRequest{
+    TestRequest::delete()
+        .uri("/api/me")
+        .insert_header(bearer(token))
+        .set_json(json!({ "password": password }))
+}
+
+async fn deletion_requested_at(app: &TestApp, user_id: Uuid) -> Option<DateTime<Utc>>{
+    sqlx::query_scalar("SELECT deletion_requested_at FROM users WHERE id = $1")
+        .bind(user_id)
+        .fetch_one(&app.db)
+        .await
+        .unwrap()
+}
+
+#[actix_web::test]
+async fn export_contains_profile_applications_saved_items_and_resume(){
+    let app = TestApp::spawn().await;
+    let user = fixtures::user().first_name("Ada").create(&app).await;
+    let token = app.token_for(&user.id);
+    let job = fixtures::job().title("Compiler Engineer").create(&app).await;
+    fixtures::application(&app, &user, &job).await;
+    let saved = TestRequest::post().uri("/api/me/saved-searches").insert_header(bearer(&token)).set_json(json!({
+        "name": "Compilers",
+        "filters": { "search_query": "compiler", "skills": ["rust"] },
+        "frequency": "weekly"
+    }));
+    assert_eq!(app.call(saved).await.status, StatusCode::CREATED);
+    let closed = fixtures::job().title("Closed").expires_at(Utc::now() - Duration::days(1)).create(&app).await;
+    for job in [&job, &closed]{
+        let save = TestRequest::post().uri(&format!("/api/me/saved-jobs/{}", job.id)).insert_header(bearer(&token));
+        assert_eq!(app.call(save).await.status, StatusCode::CREATED);
+    }
+    let resume = app.storage_dir.join("cv.pdf");
+    std::fs::write(&resume, b"%PDF-1.4 ada").unwrap();
+    sqlx::query("UPDATE users SET resume = $1 WHERE id = $2")
+        .bind(resume.to_str().unwrap())
+        .bind(user.id)
+        .execute(&app.db)
+        .await
+        .unwrap();
+
+    let res = app
+        .call(TestRequest::get().uri("/api/me/export").insert_header(bearer(&token)))
+        .await;
+
+    assert_eq!(res.status, StatusCode::OK);
+    assert!(res.headers.get("content-disposition").unwrap().to_str().unwrap().starts_with("attachment"));
+    assert_eq!(res.body["profile"]["first_name"], "Ada");
+    assert!(res.body["profile"].get("password").is_none());
+    assert_eq!(res.body["applications"][0]["title"], "Compiler Engineer");
+    let search = &res.body["saved_searches"][0];
+    assert_eq!(search["name"], "Compilers");
+    assert_eq!(search["filters"], json!({ "search_query": "compiler", "skills": ["Rust"] }));
+    assert_eq!(search["frequency"], "weekly");
+    assert!(search.get("unsubscribe_token").is_none());
+    let mut saved_jobs: Vec<(String, bool)> = res.body["saved_jobs"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|saved| (saved["title"].as_str().unwrap().to_string(), saved["is_open"].as_bool().unwrap()))
+        .collect();
+    saved_jobs.sort();
+    assert_eq!(saved_jobs, [("Closed".to_string(), false), ("Compiler Engineer".to_string(), true)]);
+    assert_eq!(res.body["documents"][0]["name"], "cv.pdf");
+    assert_eq!(res.body["documents"][0]["content_base64"], "JVBERi0xLjQgYWRh");
+}
+
+#[actix_web::test]
+async fn export_and_delete_refuse_api_keys(){
+    let app = TestApp::spawn().await;
+    let user = fixtures::user().create(&app).await;
+    let key = fixtures::api_key(&app, &user, &api_key::ALL_SCOPES, None).await;
+
+    let export = app
+        .call(TestRequest::get().uri("/api/me/export").insert_header(("X-Api-Key", key.as_str())))
+        .await;
+    let delete = app
+        .call(
+            TestRequest::delete()
+                .uri("/api/me")
+                .insert_header(("X-Api-Key", key.as_str()))
+                .set_json(json!({ "password": fixtures::DEFAULT_PASSWORD })),
+        )
+        .await;
+
+    assert_eq!(export.status, StatusCode::UNAUTHORIZED);
+    assert_eq!(delete.status, StatusCode::UNAUTHORIZED);
+}
+
+#[actix_web::test]
+async fn deleting_needs_the_password(){
+    let app = TestApp::spawn().await;
+    let user = fixtures::user().create(&app).await;
+
+    let res = app.call(delete_request(&app.token_for(&user.id), "wrong")).await;
+
+    assert_eq!(res.status, StatusCode::BAD_REQUEST);
+    assert!(deletion_requested_at(&app, user.id).await.is_none());
+}
+
+#[actix_web::test]
+async fn deleting_locks_the_account_and_schedules_the_purge(){
+    let app = TestApp::spawn().await;
+    let user = fixtures::user().create(&app).await;
+    let key = fixtures::api_key(&app, &user, &api_key::ALL_SCOPES, None).await;
+    let token = app.token_for(&user.id);
+
+    let res = app.call(delete_request(&token, fixtures::DEFAULT_PASSWORD)).await;
+    assert_eq!(res.status, StatusCode::OK);
+    assert_eq!(res.cookie("token").as_deref(), Some(""));
+
+    let me = app.call(TestRequest::get().uri("/api/auth/me").insert_header(bearer(&token))).await;
+    let with_key = app
+        .call(TestRequest::get().uri("/api/auth/me").insert_header(("X-Api-Key", key.as_str())))
+        .await;
+    assert_eq!(me.status, StatusCode::UNAUTHORIZED);
+    assert_eq!(with_key.status, StatusCode::UNAUTHORIZED);
+
+    let (kind, run_at): (String, DateTime<Utc>) = sqlx::query_as("SELECT kind, run_at FROM jobs_queue")
+        .fetch_one(&app.db)
+        .await
+        .unwrap();
+    assert_eq!(kind, "purge_account");
+    assert!(run_at > Utc::now() + Duration::days(29));
+}
+
+#[actix_web::test]
+async fn logging_in_during_the_grace_period_cancels_the_deletion(){
+    let app = TestApp::spawn().await;
+    let user = fixtures::user().create(&app).await;
+    app.call(delete_request(&app.token_for(&user.id), fixtures::DEFAULT_PASSWORD)).await;
+    let requested_at = deletion_requested_at(&app, user.id).await.unwrap();
+
+    let login = app
+        .call(TestRequest::post().uri("/api/auth/login").set_json(json!({
+            "email": user.email,
+            "password": fixtures::DEFAULT_PASSWORD
+        })))
+        .await;
+    assert_eq!(login.status, StatusCode::OK);
+    assert!(deletion_requested_at(&app, user.id).await.is_none());
+
+    // the purge that was already queued finds nothing to do
+    Task::PurgeAccount{ user_id: user.id, requested_at }
+        .run(&app.queue_context())
+        .await
+        .unwrap();
+    let email: String = sqlx::query_scalar("SELECT email FROM users WHERE id = $1")
+        .bind(user.id)
+        .fetch_one(&app.db)
+        .await
+        .unwrap();
+    assert_eq!(email, user.email);
+}
+
+#[actix_web::test]
+async fn purge_scrubs_the_account_and_keeps_anonymous_applications(){
+    let app = TestApp::spawn().await;
+    let user = fixtures::user().create(&app).await;
+    let job = fixtures::job().create(&app).await;
+    fixtures::application(&app, &user, &job).await;
+    fixtures::api_key(&app, &user, &[api_key::PROFILE_READ], None).await;
+    app.call(
+        TestRequest::post()
+            .uri("/api/me/profile/skills")
+            .insert_header(bearer(&app.token_for(&user.id)))
+            .set_json(json!({ "name": "Rust", "proficiency": "expert" })),
+    )
+    .await;
+    let job_page = format!("/api/job/{}", job.id);
+    app.call(TestRequest::get().uri(&job_page).insert_header(bearer(&app.token_for(&user.id)))).await;
+    app.call(TestRequest::get().uri(&job_page)).await;
+    let events: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM job_events").fetch_one(&app.db).await.unwrap();
+    assert_eq!(events, 2);
+    let resume = app.storage_dir.join("cv.png");
+    std::fs::write(&resume, b"png").unwrap();
+    std::fs::write(format!("{}.thumb.png", resume.display()), b"png").unwrap();
+    sqlx::query("UPDATE users SET resume = $1 WHERE id = $2")
+        .bind(resume.to_str().unwrap())
+        .bind(user.id)
+        .execute(&app.db)
+        .await
+        .unwrap();
+    app.call(delete_request(&app.token_for(&user.id), fixtures::DEFAULT_PASSWORD)).await;
+    let requested_at = deletion_requested_at(&app, user.id).await.unwrap();
+
+    Task::PurgeAccount{ user_id: user.id, requested_at }
+        .run(&app.queue_context())
+        .await
+        .unwrap();
+
+    let (first_name, email, resume_column, deleted_at): (String, String, String, Option<DateTime<Utc>>) =
+        sqlx::query_as("SELECT first_name, email, resume, deleted_at FROM users WHERE id = $1")
+            .bind(user.id)
+            .fetch_one(&app.db)
+            .await
+            .unwrap();
+    assert_eq!(first_name, "Deleted");
+    assert_eq!(email, format!("deleted-{}@deleted.invalid", user.id));
+    assert_eq!(resume_column, "");
+    assert!(deleted_at.is_some());
+    assert!(!resume.exists());
+    assert!(!std::path::Path::new(&format!("{}.thumb.png", resume.display())).exists());
+
+    let applications: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM applications").fetch_one(&app.db).await.unwrap();
+    let keys: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM api_keys").fetch_one(&app.db).await.unwrap();
+    assert_eq!(applications, 1);
+    assert_eq!(keys, 0);
+    let skills: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM candidate_skills").fetch_one(&app.db).await.unwrap();
+    assert_eq!(skills, 0);
+    // only the anonymous visitor's view is left
+    let events: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM job_events").fetch_one(&app.db).await.unwrap();
+    assert_eq!(events, 1);
+
+    // the email is free to sign up with again
+    let signup = app
+        .call(TestRequest::post().uri("/api/auth/user/register").set_json(json!({
+            "first_name": "Again",
+            "last_name": "User",
+            "email": user.email,
+            "password": fixtures::DEFAULT_PASSWORD
+        })))
+        .await;
+    assert_eq!(signup.status, StatusCode::OK);
+}
+
+#[actix_web::test]
+async fn retention_removes_old_applications_job_events_and_overdue_accounts(){
+    let app = TestApp::spawn().await;
+    let job = fixtures::job().create(&app).await;
+    let old = fixtures::user().create(&app).await;
+    let recent = fixtures::user().create(&app).await;
+    let overdue = fixtures::user().create(&app).await;
+    fixtures::application(&app, &old, &job).await;
+    fixtures::application(&app, &recent, &job).await;
+    sqlx::query("UPDATE applications SET created_at = NOW() - INTERVAL '3 years' WHERE user_id = $1")
+        .bind(old.id)
+        .execute(&app.db)
+        .await
+        .unwrap();
+    sqlx::query("UPDATE users SET deletion_requested_at = NOW() - INTERVAL '40 days' WHERE id = $1")
+        .bind(overdue.id)
+        .execute(&app.db)
+        .await
+        .unwrap();
+    for visitor in ["old", "recent"]{
+        sqlx::query("INSERT INTO job_events (job_id, kind, visitor) VALUES ($1, 'view', $2)")
+            .bind(job.id)
+            .bind(visitor)
+            .execute(&app.db)
+            .await
+            .unwrap();
+    }
+    sqlx::query("UPDATE job_events SET created_at = NOW() - INTERVAL '3 years' WHERE visitor = 'old'")
+        .execute(&app.db)
+        .await
+        .unwrap();
+
+    let now = Utc::now();
+    Task::PurgeStaleData{
+        applications_before: now - Duration::days(730),
+        deletions_requested_before: now - Duration::days(30),
+    }
+    .run(&app.queue_context())
+    .await
+    .unwrap();
+
+    let applicants: Vec<Uuid> = sqlx::query_scalar("SELECT user_id FROM applications")
+        .fetch_all(&app.db)
+        .await
+        .unwrap();
+    assert_eq!(applicants, vec![recent.id]);
+    let visitors: Vec<String> = sqlx::query_scalar("SELECT visitor FROM job_events").fetch_all(&app.db).await.unwrap();
+    assert_eq!(visitors, vec!["recent"]);
+    let deleted: Vec<Uuid> = sqlx::query_scalar("SELECT id FROM users WHERE deleted_at IS NOT NULL")
+        .fetch_all(&app.db)
+        .await
+        .unwrap();
+    assert_eq!(deleted, vec![overdue.id]);
+}
@@ -0,0 +1,136 @@
+mod common;
+
+use actix_web::{cookie::Cookie, http::StatusCode, test::TestRequest};
+use chrono::{Duration, Utc};
+use common::{bearer, fixtures, TestApp};
+use trabajo_server::core::helpers::api_key;
+
+async fn view_count(app: &TestApp) -> i64{
+    sqlx::query_scalar("SELECT COUNT(*) FROM job_events WHERE kind = 'view'")
+        .fetch_one(&app.db)
+        .await
+        .unwrap()
+}
+
+#[actix_web::test]
+async fn job_views_count_each_visitor_once_a_day(){
+    let app = TestApp::spawn().await;
+    let job = fixtures::job().create(&app).await;
+    let user = fixtures::user().create(&app).await;
+    let token = app.token_for(&user.id);
+    let uri = format!("/api/job/{}", job.id);
+
+    let first = app.call(TestRequest::get().uri(&uri)).await;
+    assert_eq!(first.status, StatusCode::OK);
+    let visitor = first.cookie("visitor").unwrap();
+    let again = app.call(TestRequest::get().uri(&uri).cookie(Cookie::new("visitor", visitor))).await;
+    assert!(again.cookie("visitor").is_none());
+    assert_eq!(view_count(&app).await, 1);
+
+    app.call(TestRequest::get().uri(&uri).insert_header(bearer(&token))).await;
+    app.call(TestRequest::get().uri(&uri).insert_header(bearer(&token))).await;
+    assert_eq!(view_count(&app).await, 2);
+
+    // the visitor is never stored as sent
+    let stored: Vec<String> = sqlx::query_scalar("SELECT visitor FROM job_events").fetch_all(&app.db).await.unwrap();
+    assert!(stored.iter().all(|visitor| visitor.len() == 64 && !visitor.contains(&user.id.to_string())));
+
+    let missing = format!("/api/job/{}/apply-click", uuid::Uuid::new_v4());
+    assert_eq!(app.call(TestRequest::post().uri(&missing)).await.status, StatusCode::NOT_FOUND);
+}
+
+#[actix_web::test]
+async fn job_analytics_show_the_funnel(){
+    let app = TestApp::spawn().await;
+    let admin = fixtures::admin().create(&app).await;
+    let job = fixtures::job().create(&app).await;
+    sqlx::query("UPDATE jobs SET created_at = NOW() - INTERVAL '5 hours' WHERE id = $1")
+        .bind(job.id)
+        .execute(&app.db)
+        .await
+        .unwrap();
+
+    for _ in 0..4{
+        app.call(TestRequest::get().uri(&format!("/api/job/{}", job.id))).await;
+    }
+    let applicant = fixtures::user().create(&app).await;
+    let click = TestRequest::post()
+        .uri(&format!("/api/job/{}/apply-click", job.id))
+        .insert_header(bearer(&app.token_for(&applicant.id)));
+    assert_eq!(app.call(click).await.status, StatusCode::OK);
+    fixtures::application(&app, &applicant, &job).await;
+
+    let analytics = |query: &str| {
+        TestRequest::get()
+            .uri(&format!("/api/job/{}/analytics{}", job.id, query))
+            .insert_header(bearer(&app.token_for(&admin.id)))
+    };
+    let res = app.call(analytics("")).await;
+    assert_eq!(res.status, StatusCode::OK);
+    let data = &res.body["data"];
+    assert_eq!(data["funnel"]["views"], 4);
+    assert_eq!(data["funnel"]["apply_clicks"], 1);
+    assert_eq!(data["funnel"]["applications"], 1);
+    assert_eq!(data["funnel"]["view_to_apply_click"], 0.25);
+    assert_eq!(data["funnel"]["apply_click_to_application"], 1.0);
+    let hours = data["time_to_first_application_hours"].as_f64().unwrap();
+    assert!((4.9..5.1).contains(&hours), "{}", hours);
+
+    let daily = data["daily"].as_array().unwrap();
+    assert_eq!(daily.len(), 30);
+    let today = daily.last().unwrap();
+    assert_eq!(today["day"], Utc::now().date_naive().to_string());
+    assert_eq!((today["views"].as_i64(), today["applications"].as_i64()), (Some(4), Some(1)));
+
+    // a range before the job existed
+    let last_year = Utc::now().date_naive() - Duration::days(365);
+    let res = app.call(analytics(&format!("?from={}&to={}", last_year, last_year + Duration::days(6)))).await;
+    assert_eq!(res.body["data"]["daily"].as_array().unwrap().len(), 7);
+    assert_eq!(res.body["data"]["funnel"]["views"], 0);
+    assert!(res.body["data"]["funnel"]["view_to_apply_click"].is_null());
+
+    assert_eq!(app.call(analytics("?from=2023-02-01&to=2023-01-01")).await.status, StatusCode::BAD_REQUEST);
+    assert_eq!(app.call(analytics("?from=2021-01-01&to=2023-01-01")).await.status, StatusCode::BAD_REQUEST);
+    let as_applicant = TestRequest::get()
+        .uri(&format!("/api/job/{}/analytics", job.id))
+        .insert_header(bearer(&app.token_for(&applicant.id)));
+    assert_eq!(app.call(as_applicant).await.status, StatusCode::UNAUTHORIZED);
+}
+
+#[actix_web::test]
+async fn company_dashboards_add_up_their_jobs(){
+    let app = TestApp::spawn().await;
+    let admin = fixtures::admin().create(&app).await;
+    let backend = fixtures::job().title("Backend").company_name("Acme").create(&app).await;
+    let frontend = fixtures::job().title("Frontend").company_name("Acme").create(&app).await;
+    let other = fixtures::job().title("Other").company_name("Globex").create(&app).await;
+    for job in [&backend, &frontend, &frontend, &other]{
+        app.call(TestRequest::get().uri(&format!("/api/job/{}", job.id))).await;
+    }
+    let applicant = fixtures::user().create(&app).await;
+    fixtures::application(&app, &applicant, &frontend).await;
+
+    let dashboard = |company: &str, key: &str| {
+        TestRequest::get().uri(&format!("/api/company/{}/analytics", company)).insert_header(("X-Api-Key", key.to_string()))
+    };
+    let key = fixtures::api_key(&app, &admin, &[api_key::JOBS_READ], None).await;
+    let res = app.call(dashboard("Acme", &key)).await;
+    assert_eq!(res.status, StatusCode::OK);
+    let data = &res.body["data"];
+    assert_eq!(data["funnel"]["views"], 3);
+    assert_eq!(data["funnel"]["applications"], 1);
+    assert_eq!(data["jobs"].as_array().unwrap().len(), 2);
+    assert_eq!(data["daily"].as_array().unwrap().last().unwrap()["views"], 3);
+    assert!(data["average_time_to_first_application_hours"].as_f64().is_some());
+
+    assert_eq!(app.call(dashboard("Initech", &key)).await.status, StatusCode::NOT_FOUND);
+    let globex_key = fixtures::api_key(&app, &admin, &[api_key::JOBS_READ], Some("Globex")).await;
+    assert_eq!(app.call(dashboard("Acme", &globex_key)).await.status, StatusCode::FORBIDDEN);
+    assert_eq!(app.call(dashboard("Globex", &globex_key)).await.status, StatusCode::OK);
+    let job_analytics = TestRequest::get()
+        .uri(&format!("/api/job/{}/analytics", backend.id))
+        .insert_header(("X-Api-Key", globex_key.clone()));
+    assert_eq!(app.call(job_analytics).await.status, StatusCode::FORBIDDEN);
+    let no_scope = fixtures::api_key(&app, &admin, &[api_key::PROFILE_READ], None).await;
+    assert_eq!(app.call(dashboard("Acme", &no_scope)).await.status, StatusCode::FORBIDDEN);
+}
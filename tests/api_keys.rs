@@ -0,0 +1,200 @@
+mod common;
+
+use actix_web::{http::StatusCode, test::TestRequest};
+use common::{bearer, fixtures, TestApp};
+use serde_json::json;
+use trabajo_server::core::helpers::api_key;
+
+#[actix_web::test]
+async fn created_key_authenticates_with_its_scopes(){
+    let app = TestApp::spawn().await;
+    let user = fixtures::user().create(&app).await;
+
+    let res = app
+        .call(
+            TestRequest::post()
+                .uri("/api/key")
+                .insert_header(bearer(&app.token_for(&user.id)))
+                .set_json(json!({ "name": "ci", "scopes": [api_key::PROFILE_READ] })),
+        )
+        .await;
+    assert_eq!(res.status, StatusCode::OK);
+    let key = res.body["data"]["key"].as_str().unwrap().to_string();
+    assert!(key.starts_with(api_key::KEY_PREFIX));
+
+    let me = app
+        .call(TestRequest::get().uri("/api/auth/me").insert_header(("X-Api-Key", key.as_str())))
+        .await;
+    assert_eq!(me.status, StatusCode::OK);
+    assert_eq!(me.body["data"]["user"]["id"], user.id.to_string());
+}
+
+#[actix_web::test]
+async fn unknown_scopes_are_rejected(){
+    let app = TestApp::spawn().await;
+    let user = fixtures::user().create(&app).await;
+
+    let res = app
+        .call(
+            TestRequest::post()
+                .uri("/api/key")
+                .insert_header(bearer(&app.token_for(&user.id)))
+                .set_json(json!({ "name": "ci", "scopes": ["everything"] })),
+        )
+        .await;
+
+    assert_eq!(res.status, StatusCode::BAD_REQUEST);
+}
+
+#[actix_web::test]
+async fn only_admins_create_company_keys(){
+    let app = TestApp::spawn().await;
+    let user = fixtures::user().create(&app).await;
+
+    let res = app
+        .call(
+            TestRequest::post()
+                .uri("/api/key")
+                .insert_header(bearer(&app.token_for(&user.id)))
+                .set_json(json!({ "name": "ats", "scopes": [api_key::JOBS_WRITE], "company_name": "Acme" })),
+        )
+        .await;
+
+    assert_eq!(res.status, StatusCode::UNAUTHORIZED);
+}
+
+#[actix_web::test]
+async fn keys_cannot_manage_keys(){
+    let app = TestApp::spawn().await;
+    let user = fixtures::user().create(&app).await;
+    let key = fixtures::api_key(&app, &user, &api_key::ALL_SCOPES, None).await;
+
+    let res = app
+        .call(TestRequest::get().uri("/api/keys").insert_header(("X-Api-Key", key.as_str())))
+        .await;
+
+    assert_eq!(res.status, StatusCode::FORBIDDEN);
+}
+
+#[actix_web::test]
+async fn keys_are_listed_without_secrets(){
+    let app = TestApp::spawn().await;
+    let user = fixtures::user().create(&app).await;
+    let other = fixtures::user().create(&app).await;
+    fixtures::api_key(&app, &user, &[api_key::JOBS_READ], None).await;
+    fixtures::api_key(&app, &other, &[api_key::JOBS_READ], None).await;
+
+    let res = app
+        .call(TestRequest::get().uri("/api/keys").insert_header(bearer(&app.token_for(&user.id))))
+        .await;
+
+    assert_eq!(res.status, StatusCode::OK);
+    let keys = res.body["data"].as_array().unwrap();
+    assert_eq!(keys.len(), 1);
+    assert!(keys[0].get("key_hash").is_none());
+}
+
+#[actix_web::test]
+async fn rotation_without_grace_period_revokes_the_old_key(){
+    let app = TestApp::spawn().await;
+    let user = fixtures::user().create(&app).await;
+    let old_key = fixtures::api_key(&app, &user, &[api_key::PROFILE_READ], None).await;
+    let key_id: uuid::Uuid = sqlx::query_scalar("SELECT id FROM api_keys").fetch_one(&app.db).await.unwrap();
+
+    let res = app
+        .call(
+            TestRequest::post()
+                .uri(&format!("/api/key/{}/rotate", key_id))
+                .insert_header(bearer(&app.token_for(&user.id))),
+        )
+        .await;
+    assert_eq!(res.status, StatusCode::OK);
+    let new_key = res.body["data"]["key"].as_str().unwrap().to_string();
+
+    let with_old = app
+        .call(TestRequest::get().uri("/api/auth/me").insert_header(("X-Api-Key", old_key.as_str())))
+        .await;
+    let with_new = app
+        .call(TestRequest::get().uri("/api/auth/me").insert_header(("X-Api-Key", new_key.as_str())))
+        .await;
+    assert_eq!(with_old.status, StatusCode::UNAUTHORIZED);
+    assert_eq!(with_new.status, StatusCode::OK);
+}
+
+#[actix_web::test]
+async fn rotation_with_grace_period_keeps_the_old_key_working(){
+    let app = TestApp::spawn().await;
+    let user = fixtures::user().create(&app).await;
+    let old_key = fixtures::api_key(&app, &user, &[api_key::PROFILE_READ], None).await;
+    let key_id: uuid::Uuid = sqlx::query_scalar("SELECT id FROM api_keys").fetch_one(&app.db).await.unwrap();
+
+    let res = app
+        .call(
+            TestRequest::post()
+                .uri(&format!("/api/key/{}/rotate", key_id))
+                .insert_header(bearer(&app.token_for(&user.id)))
+                .set_json(json!({ "grace_period_minutes": 30 })),
+        )
+        .await;
+    assert_eq!(res.status, StatusCode::OK);
+
+    let with_old = app
+        .call(TestRequest::get().uri("/api/auth/me").insert_header(("X-Api-Key", old_key.as_str())))
+        .await;
+    assert_eq!(with_old.status, StatusCode::OK);
+}
+
+#[actix_web::test]
+async fn revoked_key_stops_working(){
+    let app = TestApp::spawn().await;
+    let user = fixtures::user().create(&app).await;
+    let key = fixtures::api_key(&app, &user, &[api_key::PROFILE_READ], None).await;
+    let key_id: uuid::Uuid = sqlx::query_scalar("SELECT id FROM api_keys").fetch_one(&app.db).await.unwrap();
+    let token = app.token_for(&user.id);
+
+    let res = app
+        .call(TestRequest::delete().uri(&format!("/api/key/{}", key_id)).insert_header(bearer(&token)))
+        .await;
+    assert_eq!(res.status, StatusCode::OK);
+
+    let again = app
+        .call(TestRequest::delete().uri(&format!("/api/key/{}", key_id)).insert_header(bearer(&token)))
+        .await;
+    assert_eq!(again.status, StatusCode::NOT_FOUND);
+
+    let me = app
+        .call(TestRequest::get().uri("/api/auth/me").insert_header(("X-Api-Key", key.as_str())))
+        .await;
+    assert_eq!(me.status, StatusCode::UNAUTHORIZED);
+}
+
+#[actix_web::test]
+async fn lifetimes_out_of_range_are_rejected(){
+    let app = TestApp::spawn().await;
+    let user = fixtures::user().create(&app).await;
+    fixtures::api_key(&app, &user, &[api_key::PROFILE_READ], None).await;
+    let key_id: uuid::Uuid = sqlx::query_scalar("SELECT id FROM api_keys").fetch_one(&app.db).await.unwrap();
+    let token = app.token_for(&user.id);
+
+    for days in [json!(0), json!(3651), json!(i64::MAX)]{
+        let res = app
+            .call(
+                TestRequest::post()
+                    .uri("/api/key")
+                    .insert_header(bearer(&token))
+                    .set_json(json!({ "name": "ci", "scopes": [api_key::PROFILE_READ], "expires_in_days": days })),
+            )
+            .await;
+        assert_eq!(res.status, StatusCode::BAD_REQUEST);
+    }
+
+    let res = app
+        .call(
+            TestRequest::post()
+                .uri(&format!("/api/key/{}/rotate", key_id))
+                .insert_header(bearer(&token))
+                .set_json(json!({ "grace_period_minutes": i64::MAX })),
+        )
+        .await;
+    assert_eq!(res.status, StatusCode::BAD_REQUEST);
+}
@@ -0,0 +1,208 @@
+mod common;
+
+use actix_web::{http::StatusCode, test::TestRequest};
+use chrono::{Duration, Utc};
+use common::{bearer, fixtures, TestApp};
+use serde_json::json;
+use trabajo_server::core::helpers::api_key;
+
+#[actix_web::test]
+async fn user_can_apply_to_a_job_once(){
+    let app = TestApp::spawn().await;
+    let user = fixtures::user().create(&app).await;
+    let job = fixtures::job().create(&app).await;
+    let token = app.token_for(&user.id);
+
+    let first = app
+        .call(
+            TestRequest::post()
+                .uri("/api/application")
+                .insert_header(bearer(&token))
+                .set_json(json!({ "job_id": job.id })),
+        )
+        .await;
+    let second = app
+        .call(
+            TestRequest::post()
+                .uri("/api/application")
+                .insert_header(bearer(&token))
+                .set_json(json!({ "job_id": job.id })),
+        )
+        .await;
+
+    assert_eq!(first.status, StatusCode::OK);
+    assert_eq!(second.status, StatusCode::OK);
+    assert_eq!(first.body["data"]["id"], second.body["data"]["id"]);
+    let count: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM applications").fetch_one(&app.db).await.unwrap();
+    assert_eq!(count, 1);
+}
+
+#[actix_web::test]
+async fn applying_with_a_malformed_job_id_is_rejected(){
+    let app = TestApp::spawn().await;
+    let user = fixtures::user().create(&app).await;
+
+    let res = app
+        .call(
+            TestRequest::post()
+                .uri("/api/application")
+                .insert_header(bearer(&app.token_for(&user.id)))
+                .set_json(json!({ "job_id": "not-a-uuid" })),
+        )
+        .await;
+
+    assert_eq!(res.status, StatusCode::BAD_REQUEST);
+}
+
+#[actix_web::test]
+async fn api_key_needs_applications_write_to_apply(){
+    let app = TestApp::spawn().await;
+    let user = fixtures::user().create(&app).await;
+    let job = fixtures::job().create(&app).await;
+    let key = fixtures::api_key(&app, &user, &[api_key::APPLICATIONS_READ], None).await;
+
+    let res = app
+        .call(
+            TestRequest::post()
+                .uri("/api/application")
+                .insert_header(("X-Api-Key", key.as_str()))
+                .set_json(json!({ "job_id": job.id })),
+        )
+        .await;
+
+    assert_eq!(res.status, StatusCode::FORBIDDEN);
+}
+
+#[actix_web::test]
+async fn admin_lists_all_applications(){
+    let app = TestApp::spawn().await;
+    let admin = fixtures::admin().create(&app).await;
+    let job = fixtures::job().create(&app).await;
+    for _ in 0..3{
+        let applicant = fixtures::user().create(&app).await;
+        fixtures::application(&app, &applicant, &job).await;
+    }
+
+    let res = app
+        .call(TestRequest::get().uri("/api/applications").insert_header(bearer(&app.token_for(&admin.id))))
+        .await;
+
+    assert_eq!(res.status, StatusCode::OK);
+    assert_eq!(res.body["data"].as_array().unwrap().len(), 3);
+}
+
+#[actix_web::test]
+async fn admin_lists_applications_for_a_job(){
+    let app = TestApp::spawn().await;
+    let admin = fixtures::admin().create(&app).await;
+    let applicant = fixtures::user().first_name("Linus").create(&app).await;
+    let job = fixtures::job().create(&app).await;
+    let other_job = fixtures::job().create(&app).await;
+    fixtures::application(&app, &applicant, &job).await;
+    fixtures::application(&app, &applicant, &other_job).await;
+
+    let res = app
+        .call(
+            TestRequest::get()
+                .uri(&format!("/api/application/{}", job.id))
+                .insert_header(bearer(&app.token_for(&admin.id))),
+        )
+        .await;
+
+    assert_eq!(res.status, StatusCode::OK);
+    let applications = res.body["data"].as_array().unwrap();
+    assert_eq!(applications.len(), 1);
+    assert_eq!(applications[0]["first_name"], "Linus");
+    assert_eq!(applications[0]["applicant_id"], applicant.id.to_string());
+}
+
+#[actix_web::test]
+async fn regular_user_cannot_list_applications(){
+    let app = TestApp::spawn().await;
+    let user = fixtures::user().create(&app).await;
+    let job = fixtures::job().create(&app).await;
+    let token = app.token_for(&user.id);
+
+    let all = app
+        .call(TestRequest::get().uri("/api/applications").insert_header(bearer(&token)))
+        .await;
+    let for_job = app
+        .call(TestRequest::get().uri(&format!("/api/application/{}", job.id)).insert_header(bearer(&token)))
+        .await;
+
+    assert_eq!(all.status, StatusCode::UNAUTHORIZED);
+    assert_eq!(for_job.status, StatusCode::UNAUTHORIZED);
+}
+
+#[actix_web::test]
+async fn applying_to_a_missing_job_is_not_found(){
+    let app = TestApp::spawn().await;
+    let user = fixtures::user().create(&app).await;
+
+    let res = app
+        .call(
+            TestRequest::post()
+                .uri("/api/application")
+                .insert_header(bearer(&app.token_for(&user.id)))
+                .set_json(json!({ "job_id": uuid::Uuid::new_v4() })),
+        )
+        .await;
+
+    assert_eq!(res.status, StatusCode::NOT_FOUND);
+    assert_eq!(res.body["message"], "Job not found");
+}
+
+#[actix_web::test]
+async fn applying_to_an_expired_job_is_refused(){
+    let app = TestApp::spawn().await;
+    let user = fixtures::user().create(&app).await;
+    let job = fixtures::job().expires_at(Utc::now() - Duration::minutes(1)).create(&app).await;
+
+    let res = app
+        .call(
+            TestRequest::post()
+                .uri("/api/application")
+                .insert_header(bearer(&app.token_for(&user.id)))
+                .set_json(json!({ "job_id": job.id })),
+        )
+        .await;
+
+    assert_eq!(res.status, StatusCode::CONFLICT);
+    assert_eq!(res.body["message"], "This job is no longer accepting applications");
+    let count: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM applications").fetch_one(&app.db).await.unwrap();
+    assert_eq!(count, 0);
+}
+
+#[actix_web::test]
+async fn deleting_a_job_removes_its_applications(){
+    let app = TestApp::spawn().await;
+    let user = fixtures::user().create(&app).await;
+    let job = fixtures::job().create(&app).await;
+    fixtures::application(&app, &user, &job).await;
+
+    sqlx::query("DELETE FROM jobs WHERE id = $1").bind(job.id).execute(&app.db).await.unwrap();
+
+    let count: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM applications").fetch_one(&app.db).await.unwrap();
+    assert_eq!(count, 0);
+}
+
+#[actix_web::test]
+async fn updates_bump_updated_at(){
+    let app = TestApp::spawn().await;
+    let job = fixtures::job().create(&app).await;
+
+    // NOW() is fixed for a transaction, so each statement runs in its own
+    let before: chrono::DateTime<chrono::Utc> = sqlx::query_scalar("SELECT updated_at FROM jobs WHERE id = $1")
+        .bind(job.id)
+        .fetch_one(&app.db)
+        .await
+        .unwrap();
+    let after: chrono::DateTime<chrono::Utc> =
+        sqlx::query_scalar("UPDATE jobs SET title = 'Renamed' WHERE id = $1 RETURNING updated_at")
+            .bind(job.id)
+            .fetch_one(&app.db)
+            .await
+            .unwrap();
+
+    assert!(after > before);
+}
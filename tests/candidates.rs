@@ -0,0 +1,279 @@
+mod common;
+
+use actix_web::{http::StatusCode, test::TestRequest};
+use common::{bearer, fixtures, TestApp};
+use serde_json::json;
+use trabajo_server::core::helpers::api_key;
+
+fn post(token: &str, uri: &str, body: serde_json::Value) -> TestRequest{
+    TestRequest::post().uri(uri).insert_header(bearer(token)).set_json(body)
+}
+
+#[actix_web::test]
+async fn candidate_builds_their_profile(){
+    let app = TestApp::spawn().await;
+    let user = fixtures::user().create(&app).await;
+    let token = app.token_for(&user.id);
+
+    let profile = app
+        .call(
+            TestRequest::put()
+                .uri("/api/me/profile")
+                .insert_header(bearer(&token))
+                .set_json(json!({
+                    "headline": " Backend engineer ",
+                    "city": "Madrid",
+                    "country": "Spain",
+                    "open_to_remote": true,
+                    "desired_roles": ["Backend Engineer", " "],
+                    "salary_min": 45000,
+                    "salary_max": 60000,
+                    "salary_currency": "eur"
+                })),
+        )
+        .await;
+    assert_eq!(profile.status, StatusCode::OK);
+    assert_eq!(profile.body["data"]["headline"], "Backend engineer");
+    assert_eq!(profile.body["data"]["desired_roles"], json!(["Backend Engineer"]));
+    assert_eq!(profile.body["data"]["salary_currency"], "EUR");
+
+    let requests = [
+        ("/api/me/profile/experience", json!({ "company_name": "Acme", "title": "Engineer", "start_date": "2019-03-01" })),
+        ("/api/me/profile/education", json!({ "institution": "UPM", "degree": "BSc", "start_date": "2014-09-01", "end_date": "2018-06-30" })),
+        ("/api/me/profile/certifications", json!({ "name": "CKA", "issued_on": "2021-05-01", "credential_url": "https://example.com/cka" })),
+        ("/api/me/profile/skills", json!({ "name": "Rust", "proficiency": "Advanced", "years_of_experience": 4 })),
+        ("/api/me/profile/links", json!({ "label": "GitHub", "url": "https://github.com/example" })),
+    ];
+    for (uri, body) in requests{
+        let res = app.call(post(&token, uri, body)).await;
+        assert_eq!(res.status, StatusCode::CREATED, "{}", uri);
+    }
+
+    let res = app
+        .call(TestRequest::get().uri("/api/me/profile").insert_header(bearer(&token)))
+        .await;
+    assert_eq!(res.status, StatusCode::OK);
+    let data = &res.body["data"];
+    assert_eq!(data["profile"]["city"], "Madrid");
+    assert_eq!(data["experience"][0]["company_name"], "Acme");
+    assert!(data["experience"][0]["end_date"].is_null());
+    assert_eq!(data["education"][0]["degree"], "BSc");
+    assert_eq!(data["certifications"][0]["name"], "CKA");
+    assert_eq!(data["skills"][0]["proficiency"], "advanced");
+    assert_eq!(data["links"][0]["label"], "GitHub");
+}
+
+#[actix_web::test]
+async fn entries_can_be_replaced_and_removed(){
+    let app = TestApp::spawn().await;
+    let user = fixtures::user().create(&app).await;
+    let token = app.token_for(&user.id);
+    let created = app
+        .call(post(&token, "/api/me/profile/links", json!({ "label": "Blog", "url": "https://blog.example.com" })))
+        .await;
+    let id = created.body["data"]["id"].as_str().unwrap().to_string();
+
+    let updated = app
+        .call(
+            TestRequest::put()
+                .uri(&format!("/api/me/profile/links/{}", id))
+                .insert_header(bearer(&token))
+                .set_json(json!({ "label": "Portfolio", "url": "https://example.com" })),
+        )
+        .await;
+    assert_eq!(updated.status, StatusCode::OK);
+    assert_eq!(updated.body["data"]["label"], "Portfolio");
+
+    let removed = app
+        .call(
+            TestRequest::delete()
+                .uri(&format!("/api/me/profile/links/{}", id))
+                .insert_header(bearer(&token)),
+        )
+        .await;
+    let again = app
+        .call(
+            TestRequest::delete()
+                .uri(&format!("/api/me/profile/links/{}", id))
+                .insert_header(bearer(&token)),
+        )
+        .await;
+    let unknown_section = app
+        .call(
+            TestRequest::delete()
+                .uri(&format!("/api/me/profile/hobbies/{}", id))
+                .insert_header(bearer(&token)),
+        )
+        .await;
+
+    assert_eq!(removed.status, StatusCode::OK);
+    assert_eq!(again.status, StatusCode::NOT_FOUND);
+    assert_eq!(unknown_section.status, StatusCode::NOT_FOUND);
+}
+
+#[actix_web::test]
+async fn invalid_entries_are_rejected(){
+    let app = TestApp::spawn().await;
+    let user = fixtures::user().create(&app).await;
+    let token = app.token_for(&user.id);
+
+    let cases = [
+        ("/api/me/profile/experience", json!({ "company_name": "Acme", "title": "Engineer", "start_date": "2020-01-01", "end_date": "2019-01-01" })),
+        ("/api/me/profile/education", json!({ "institution": " ", "degree": "BSc", "start_date": "2014-09-01" })),
+        ("/api/me/profile/skills", json!({ "name": "Rust", "proficiency": "guru" })),
+        ("/api/me/profile/links", json!({ "label": "Site", "url": "javascript:alert(1)" })),
+        ("/api/me/profile/certifications", json!({ "name": "CKA", "issued_on": "2021-05-01", "credential_url": "ftp://example.com" })),
+    ];
+    for (uri, body) in cases{
+        let res = app.call(post(&token, uri, body)).await;
+        assert_eq!(res.status, StatusCode::BAD_REQUEST, "{}", uri);
+    }
+
+    let salary = app
+        .call(
+            TestRequest::put()
+                .uri("/api/me/profile")
+                .insert_header(bearer(&token))
+                .set_json(json!({ "salary_min": 60000, "salary_max": 40000 })),
+        )
+        .await;
+    assert_eq!(salary.status, StatusCode::BAD_REQUEST);
+}
+
+#[actix_web::test]
+async fn the_same_skill_twice_is_a_conflict(){
+    let app = TestApp::spawn().await;
+    let user = fixtures::user().create(&app).await;
+    let token = app.token_for(&user.id);
+
+    let first = app
+        .call(post(&token, "/api/me/profile/skills", json!({ "name": "PostgreSQL", "proficiency": "expert" })))
+        .await;
+    let second = app
+        .call(post(&token, "/api/me/profile/skills", json!({ "name": "postgresql", "proficiency": "beginner" })))
+        .await;
+
+    assert_eq!(first.status, StatusCode::CREATED);
+    assert_eq!(second.status, StatusCode::CONFLICT);
+}
+
+#[actix_web::test]
+async fn entries_of_other_candidates_are_out_of_reach(){
+    let app = TestApp::spawn().await;
+    let owner = fixtures::user().create(&app).await;
+    let other = fixtures::user().create(&app).await;
+    let created = app
+        .call(post(
+            &app.token_for(&owner.id),
+            "/api/me/profile/experience",
+            json!({ "company_name": "Acme", "title": "Engineer", "start_date": "2019-03-01" }),
+        ))
+        .await;
+    let id = created.body["data"]["id"].as_str().unwrap().to_string();
+
+    let update = app
+        .call(
+            TestRequest::put()
+                .uri(&format!("/api/me/profile/experience/{}", id))
+                .insert_header(bearer(&app.token_for(&other.id)))
+                .set_json(json!({ "company_name": "Evil", "title": "Engineer", "start_date": "2019-03-01" })),
+        )
+        .await;
+    let delete = app
+        .call(
+            TestRequest::delete()
+                .uri(&format!("/api/me/profile/experience/{}", id))
+                .insert_header(bearer(&app.token_for(&other.id))),
+        )
+        .await;
+
+    assert_eq!(update.status, StatusCode::NOT_FOUND);
+    assert_eq!(delete.status, StatusCode::NOT_FOUND);
+    let company: String = sqlx::query_scalar("SELECT company_name FROM work_experiences")
+        .fetch_one(&app.db)
+        .await
+        .unwrap();
+    assert_eq!(company, "Acme");
+}
+
+#[actix_web::test]
+async fn recruiters_see_the_profile_of_applicants(){
+    let app = TestApp::spawn().await;
+    let admin = fixtures::admin().create(&app).await;
+    let candidate = fixtures::user().create(&app).await;
+    let job = fixtures::job().create(&app).await;
+    fixtures::application(&app, &candidate, &job).await;
+    let token = app.token_for(&candidate.id);
+    app.call(
+        TestRequest::put()
+            .uri("/api/me/profile")
+            .insert_header(bearer(&token))
+            .set_json(json!({ "headline": "Rustacean", "city": "Lisbon" })),
+    )
+    .await;
+    app.call(post(&token, "/api/me/profile/skills", json!({ "name": "Rust", "proficiency": "expert" })))
+        .await;
+
+    let applications = app
+        .call(
+            TestRequest::get()
+                .uri(&format!("/api/application/{}", job.id))
+                .insert_header(bearer(&app.token_for(&admin.id))),
+        )
+        .await;
+    assert_eq!(applications.status, StatusCode::OK);
+    assert_eq!(applications.body["data"][0]["headline"], "Rustacean");
+    assert_eq!(applications.body["data"][0]["city"], "Lisbon");
+    assert_eq!(applications.body["data"][0]["skills"], json!(["Rust"]));
+
+    let uri = format!("/api/candidates/{}/profile", candidate.id);
+    let as_admin = app
+        .call(TestRequest::get().uri(&uri).insert_header(bearer(&app.token_for(&admin.id))))
+        .await;
+    let as_candidate = app
+        .call(TestRequest::get().uri(&uri).insert_header(bearer(&token)))
+        .await;
+    assert_eq!(as_admin.status, StatusCode::OK);
+    assert_eq!(as_admin.body["data"]["skills"][0]["name"], "Rust");
+    assert_eq!(as_candidate.status, StatusCode::UNAUTHORIZED);
+}
+
+#[actix_web::test]
+async fn applicants_without_a_profile_are_listed_with_empty_fields(){
+    let app = TestApp::spawn().await;
+    let admin = fixtures::admin().create(&app).await;
+    let candidate = fixtures::user().create(&app).await;
+    let job = fixtures::job().create(&app).await;
+    fixtures::application(&app, &candidate, &job).await;
+
+    let res = app
+        .call(
+            TestRequest::get()
+                .uri("/api/applications")
+                .insert_header(bearer(&app.token_for(&admin.id))),
+        )
+        .await;
+
+    assert_eq!(res.status, StatusCode::OK);
+    assert_eq!(res.body["data"][0]["headline"], "");
+    assert_eq!(res.body["data"][0]["skills"], json!([]));
+}
+
+#[actix_web::test]
+async fn reading_through_an_api_key_needs_profile_read(){
+    let app = TestApp::spawn().await;
+    let user = fixtures::user().create(&app).await;
+    let writer = fixtures::api_key(&app, &user, &[api_key::PROFILE_WRITE], None).await;
+    let reader = fixtures::api_key(&app, &user, &[api_key::PROFILE_READ], None).await;
+
+    let denied = app
+        .call(TestRequest::get().uri("/api/me/profile").insert_header(("X-Api-Key", writer.as_str())))
+        .await;
+    let allowed = app
+        .call(TestRequest::get().uri("/api/me/profile").insert_header(("X-Api-Key", reader.as_str())))
+        .await;
+
+    assert_eq!(denied.status, StatusCode::FORBIDDEN);
+    assert_eq!(allowed.status, StatusCode::OK);
+    assert!(allowed.body["data"]["profile"].is_null());
+}
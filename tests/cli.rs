@@ -0,0 +1,105 @@
+mod common;
+
+use actix_web::{http::StatusCode, test::TestRequest};
+use common::{bearer, TestApp};
+use serde_json::json;
+use trabajo_server::cli::{admin, migrate, seed};
+
+#[actix_web::test]
+async fn status_lists_every_migration_as_applied(){
+    let app = TestApp::spawn().await;
+
+    let statuses = migrate::status(&app.db).await.unwrap();
+
+    assert!(!statuses.is_empty());
+    assert!(statuses.iter().all(|status| status.state == "applied"));
+    assert_eq!(migrate::up(&app.db).await.unwrap(), Vec::<i64>::new());
+}
+
+#[actix_web::test]
+async fn migrations_revert_and_reapply(){
+    let app = TestApp::spawn().await;
+    let versions: Vec<i64> = migrate::status(&app.db).await.unwrap().iter().map(|s| s.version).collect();
+
+    let latest = migrate::down(&app.db, None).await.unwrap();
+    assert_eq!(latest, vec![*versions.last().unwrap()]);
+    assert_eq!(migrate::status(&app.db).await.unwrap().last().unwrap().state, "pending");
+
+    let rest = migrate::down(&app.db, Some(0)).await.unwrap();
+    assert_eq!(rest.len(), versions.len() - 1);
+    let tables: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM pg_tables WHERE tablename IN ('users', 'jobs')")
+        .fetch_one(&app.db)
+        .await
+        .unwrap();
+    assert_eq!(tables, 0);
+
+    assert_eq!(migrate::up(&app.db).await.unwrap(), versions);
+}
+
+#[actix_web::test]
+async fn created_admin_can_log_in_and_post_jobs(){
+    let app = TestApp::spawn().await;
+
+    let created = admin::create_admin(&app.db, "Ops@Example.com", "Ops", "Team", "a long admin password")
+        .await
+        .unwrap();
+    assert_eq!(created.email, "ops@example.com");
+    assert_eq!(created.role, "Admin");
+
+    let login = app
+        .call(
+            TestRequest::post()
+                .uri("/api/auth/login")
+                .set_json(json!({ "email": "ops@example.com", "password": "a long admin password" })),
+        )
+        .await;
+    assert_eq!(login.status, StatusCode::OK);
+
+    let res = app
+        .call(
+            TestRequest::post()
+                .uri("/api/job")
+                .insert_header(bearer(&app.token_for(&created.id)))
+                .set_json(json!({
+                    "title": "Ops",
+                    "company_name": "Acme",
+                    "city": "Madrid",
+                    "country": "Spain",
+                    "salary": "50000",
+                    "description": "Keeps the lights on"
+                })),
+        )
+        .await;
+    assert_eq!(res.status, StatusCode::OK);
+}
+
+#[actix_web::test]
+async fn create_admin_rejects_short_passwords_and_taken_emails(){
+    let app = TestApp::spawn().await;
+    admin::create_admin(&app.db, "ops@example.com", "Ops", "", "a long admin password").await.unwrap();
+
+    assert!(admin::create_admin(&app.db, "new@example.com", "Ops", "", "short").await.is_err());
+    assert!(admin::create_admin(&app.db, "ops@example.com", "Ops", "", "another long password").await.is_err());
+}
+
+#[actix_web::test]
+async fn seed_only_fills_an_empty_database(){
+    let app = TestApp::spawn().await;
+
+    assert!(seed::seed(&app.db, "password123").await.unwrap());
+    assert!(!seed::seed(&app.db, "password123").await.unwrap());
+
+    let jobs: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM jobs").fetch_one(&app.db).await.unwrap();
+    let applications: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM applications").fetch_one(&app.db).await.unwrap();
+    assert_eq!(jobs, 8);
+    assert_eq!(applications, 2);
+
+    let login = app
+        .call(
+            TestRequest::post()
+                .uri("/api/auth/login")
+                .set_json(json!({ "email": seed::DEMO_EMAIL, "password": "password123" })),
+        )
+        .await;
+    assert_eq!(login.status, StatusCode::OK);
+}
@@ -0,0 +1,299 @@
+// In-memory repositories so services can be tested without a database.
+// Follow up tasks are recorded as their JSON form instead of being queued.
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use std::sync::{Arc, Mutex};
+use trabajo_server::{
+    model::{application_model::Application, user_model::User},
+    queue::task::Task,
+    repository::{
+        application_repository::ApplicationRepository,
+        user_repository::{NewUser, PendingEmailChange, UserRepository},
+    },
+    schema::application_schema::{FetchApplication, UserApplication},
+};
+use uuid::Uuid;
+
+fn record(tasks: &Mutex<Vec<serde_json::Value>>, task: Option<&Task>){
+    if let Some(task) = task{
+        tasks.lock().unwrap().push(serde_json::to_value(task).unwrap());
+    }
+}
+
+struct EmailChange{
+    token_hash: String,
+    user_id: Uuid,
+    new_email: String,
+    expires_at: DateTime<Utc>,
+}
+
+#[derive(Default)]
+pub struct FakeUserRepository{
+    pub users: Mutex<Vec<User>>,
+    pub tasks: Mutex<Vec<serde_json::Value>>,
+    email_changes: Mutex<Vec<EmailChange>>,
+}
+
+impl FakeUserRepository{
+    fn update<T>(&self, id: &Uuid, change: impl FnOnce(&mut User) -> T) -> Option<T>{
+        self.users.lock().unwrap().iter_mut().find(|user| user.id == *id).map(change)
+    }
+}
+
+#[async_trait]
+impl UserRepository for FakeUserRepository{
+    async fn find_by_id(&self, id: &Uuid) -> Result<Option<User>, sqlx::Error>{
+        Ok(self.users.lock().unwrap().iter().find(|user| user.id == *id).cloned())
+    }
+
+    async fn find_by_email(&self, email: &str) -> Result<Option<User>, sqlx::Error>{
+        Ok(self.users.lock().unwrap().iter().find(|user| user.email == email).cloned())
+    }
+
+    async fn email_exists(&self, email: &str) -> Result<bool, sqlx::Error>{
+        Ok(self.find_by_email(email).await?.is_some())
+    }
+
+    async fn create(&self, user: &NewUser, follow_up: Option<&Task>) -> Result<User, sqlx::Error>{
+        let user = User{
+            id: Uuid::new_v4(),
+            first_name: user.first_name.to_owned(),
+            last_name: user.last_name.to_owned(),
+            email: user.email.to_owned(),
+            password: user.password_hash.to_owned(),
+            role: user.role.to_owned(),
+            resume: String::new(),
+            is_verified: false,
+            created_at: Some(Utc::now()),
+            updated_at: Some(Utc::now()),
+            is_active: true,
+            deletion_requested_at: None,
+            deleted_at: None,
+            session_version: 0,
+        };
+        self.users.lock().unwrap().push(user.clone());
+        record(&self.tasks, follow_up);
+        Ok(user)
+    }
+
+    async fn set_resume(&self, id: &Uuid, path: &str, follow_up: Option<&Task>) -> Result<(), sqlx::Error>{
+        self.update(id, |user| user.resume = path.to_string());
+        record(&self.tasks, follow_up);
+        Ok(())
+    }
+
+    async fn request_deletion(
+        &self,
+        id: &Uuid,
+        requested_at: &DateTime<Utc>,
+        purge: &Task,
+        _purge_at: DateTime<Utc>,
+    ) -> Result<(), sqlx::Error>{
+        self.update(id, |user| user.deletion_requested_at = Some(*requested_at));
+        record(&self.tasks, Some(purge));
+        Ok(())
+    }
+
+    async fn cancel_deletion(&self, id: &Uuid) -> Result<(), sqlx::Error>{
+        self.update(id, |user| user.deletion_requested_at = None);
+        Ok(())
+    }
+
+    async fn purge(&self, id: &Uuid, requested_before: &DateTime<Utc>) -> Result<Option<String>, sqlx::Error>{
+        self.email_changes.lock().unwrap().retain(|change| change.user_id != *id);
+        Ok(self
+            .update(id, |user|{
+                let due = user.deleted_at.is_none() && user.deletion_requested_at.is_some_and(|at| at <= *requested_before);
+                due.then(|| {
+                    user.first_name = "Deleted".to_string();
+                    user.last_name = "User".to_string();
+                    user.email = format!("deleted-{}@deleted.invalid", user.id);
+                    user.password = String::new();
+                    user.is_active = false;
+                    user.deleted_at = Some(Utc::now());
+                    std::mem::take(&mut user.resume)
+                })
+            })
+            .flatten())
+    }
+
+    async fn list_overdue_deletions(&self, requested_before: &DateTime<Utc>) -> Result<Vec<Uuid>, sqlx::Error>{
+        Ok(self
+            .users
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|user| user.deleted_at.is_none() && user.deletion_requested_at.is_some_and(|at| at <= *requested_before))
+            .map(|user| user.id)
+            .collect())
+    }
+
+    async fn update_profile(&self, id: &Uuid, first_name: Option<&str>, last_name: Option<&str>) -> Result<Option<User>, sqlx::Error>{
+        Ok(self.update(id, |user|{
+            if let Some(first_name) = first_name{
+                user.first_name = first_name.to_string();
+            }
+            if let Some(last_name) = last_name{
+                user.last_name = last_name.to_string();
+            }
+            user.clone()
+        }))
+    }
+
+    async fn set_password(&self, id: &Uuid, password_hash: &str) -> Result<i32, sqlx::Error>{
+        self.update(id, |user|{
+            user.password = password_hash.to_string();
+            user.session_version += 1;
+            user.session_version
+        })
+        .ok_or(sqlx::Error::RowNotFound)
+    }
+
+    async fn create_email_change(
+        &self,
+        id: &Uuid,
+        new_email: &str,
+        token_hash: &str,
+        expires_at: DateTime<Utc>,
+        follow_up: Option<&Task>,
+    ) -> Result<(), sqlx::Error>{
+        let mut changes = self.email_changes.lock().unwrap();
+        changes.retain(|change| change.user_id != *id);
+        changes.push(EmailChange{
+            token_hash: token_hash.to_string(),
+            user_id: *id,
+            new_email: new_email.to_string(),
+            expires_at,
+        });
+        record(&self.tasks, follow_up);
+        Ok(())
+    }
+
+    async fn find_email_change(&self, token_hash: &str) -> Result<Option<PendingEmailChange>, sqlx::Error>{
+        Ok(self
+            .email_changes
+            .lock()
+            .unwrap()
+            .iter()
+            .find(|change| change.token_hash == token_hash && change.expires_at > Utc::now())
+            .map(|change| PendingEmailChange{ user_id: change.user_id, new_email: change.new_email.to_owned() }))
+    }
+
+    async fn apply_email_change(&self, token_hash: &str, change: &PendingEmailChange, follow_up: Option<&Task>) -> Result<bool, sqlx::Error>{
+        let mut changes = self.email_changes.lock().unwrap();
+        let before = changes.len();
+        changes.retain(|pending| pending.token_hash != token_hash);
+        if changes.len() == before{
+            return Ok(false);
+        }
+        self.update(&change.user_id, |user| user.email = change.new_email.to_owned());
+        record(&self.tasks, follow_up);
+        Ok(true)
+    }
+}
+
+// Jobs and candidate profiles are not modelled, so listed applications only
+// carry the applicant's name. Every job exists and is open unless it is in
+// closed_jobs.
+pub struct FakeApplicationRepository{
+    pub applications: Mutex<Vec<Application>>,
+    pub closed_jobs: Mutex<Vec<Uuid>>,
+    users: Arc<FakeUserRepository>,
+}
+
+impl FakeApplicationRepository{
+    pub fn new(users: Arc<FakeUserRepository>) -> FakeApplicationRepository{
+        FakeApplicationRepository{ applications: Mutex::default(), closed_jobs: Mutex::default(), users }
+    }
+
+    fn fetched(&self, application: &Application) -> FetchApplication{
+        let users = self.users.users.lock().unwrap();
+        let applicant = users.iter().find(|user| user.id == application.user_id);
+        FetchApplication{
+            id: application.id,
+            job_id: application.job_id,
+            first_name: applicant.map(|user| user.first_name.to_owned()).unwrap_or_default(),
+            last_name: applicant.map(|user| user.last_name.to_owned()).unwrap_or_default(),
+            created_at: application.created_at,
+            applicant_id: application.user_id,
+            headline: String::new(),
+            city: String::new(),
+            country: String::new(),
+            skills: Vec::new(),
+        }
+    }
+}
+
+#[async_trait]
+impl ApplicationRepository for FakeApplicationRepository{
+    async fn create(&self, user_id: &Uuid, job_id: &Uuid) -> Result<Option<Application>, sqlx::Error>{
+        let mut applications = self.applications.lock().unwrap();
+        if applications.iter().any(|a| a.user_id == *user_id && a.job_id == *job_id){
+            return Ok(None);
+        }
+        let application = Application{
+            id: Uuid::new_v4(),
+            job_id: *job_id,
+            user_id: *user_id,
+            created_at: Some(Utc::now()),
+            updated_at: Some(Utc::now()),
+        };
+        applications.push(application.clone());
+        Ok(Some(application))
+    }
+
+    async fn find(&self, user_id: &Uuid, job_id: &Uuid) -> Result<Option<Application>, sqlx::Error>{
+        Ok(self
+            .applications
+            .lock()
+            .unwrap()
+            .iter()
+            .find(|a| a.user_id == *user_id && a.job_id == *job_id)
+            .cloned())
+    }
+
+    async fn job_is_open(&self, job_id: &Uuid) -> Result<Option<bool>, sqlx::Error>{
+        Ok(Some(!self.closed_jobs.lock().unwrap().contains(job_id)))
+    }
+
+    async fn list(&self, limit: i64, offset: i64) -> Result<Vec<FetchApplication>, sqlx::Error>{
+        let applications = self.applications.lock().unwrap().clone();
+        Ok(applications
+            .iter()
+            .rev()
+            .skip(offset as usize)
+            .take(limit as usize)
+            .map(|a| self.fetched(a))
+            .collect())
+    }
+
+    async fn list_for_job(&self, job_id: &Uuid) -> Result<Vec<FetchApplication>, sqlx::Error>{
+        let applications = self.applications.lock().unwrap().clone();
+        Ok(applications.iter().filter(|a| a.job_id == *job_id).map(|a| self.fetched(a)).collect())
+    }
+
+    async fn list_for_user(&self, user_id: &Uuid) -> Result<Vec<UserApplication>, sqlx::Error>{
+        Ok(self
+            .applications
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|a| a.user_id == *user_id)
+            .map(|a| UserApplication{
+                id: a.id,
+                job_id: a.job_id,
+                title: String::new(),
+                company_name: String::new(),
+                created_at: a.created_at,
+            })
+            .collect())
+    }
+
+    async fn delete_before(&self, before: &DateTime<Utc>) -> Result<u64, sqlx::Error>{
+        let mut applications = self.applications.lock().unwrap();
+        let count = applications.len();
+        applications.retain(|a| a.created_at.is_some_and(|at| at >= *before));
+        Ok((count - applications.len()) as u64)
+    }
+}
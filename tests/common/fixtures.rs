@@ -0,0 +1,205 @@
+use chrono::{DateTime, Utc};
+use std::sync::OnceLock;
+use trabajo_server::{
+    core::helpers::api_key,
+    model::{application_model::Application, job_model::Job, user_model::User},
+    service::{scoring::parse_salary, user_service::hash_password},
+};
+use uuid::Uuid;
+
+use super::TestApp;
+
+pub const DEFAULT_PASSWORD: &str = "correct horse battery staple";
+
+// hashing is deliberately slow, so the default password is hashed once
+fn default_password_hash() -> &'static str{
+    static HASH: OnceLock<String> = OnceLock::new();
+    HASH.get_or_init(|| hash_password(DEFAULT_PASSWORD))
+}
+
+pub struct UserBuilder{
+    first_name: String,
+    last_name: String,
+    email: String,
+    password: Option<String>,
+    role: String,
+    is_active: bool,
+}
+
+// A user with a unique email and DEFAULT_PASSWORD
+pub fn user() -> UserBuilder{
+    UserBuilder{
+        first_name: "Test".to_string(),
+        last_name: "User".to_string(),
+        email: format!("user-{}@example.com", Uuid::new_v4().simple()),
+        password: None,
+        role: "user".to_string(),
+        is_active: true,
+    }
+}
+
+pub fn admin() -> UserBuilder{
+    UserBuilder{ role: "Admin".to_string(), ..user() }
+}
+
+impl UserBuilder{
+    pub fn first_name(mut self, first_name: &str) -> Self{
+        self.first_name = first_name.to_string();
+        self
+    }
+
+    pub fn email(mut self, email: &str) -> Self{
+        self.email = email.to_string();
+        self
+    }
+
+    pub fn password(mut self, password: &str) -> Self{
+        self.password = Some(password.to_string());
+        self
+    }
+
+    pub fn inactive(mut self) -> Self{
+        self.is_active = false;
+        self
+    }
+
+    pub async fn create(self, app: &TestApp) -> User{
+        let password_hash = match &self.password{
+            Some(password) => hash_password(password),
+            None => default_password_hash().to_string(),
+        };
+        sqlx::query_as::<_, User>(
+            "INSERT INTO users (first_name, last_name, email, password, role, is_active)
+             VALUES ($1, $2, $3, $4, $5, $6) RETURNING *",
+        )
+        .bind(self.first_name)
+        .bind(self.last_name)
+        .bind(self.email)
+        .bind(password_hash)
+        .bind(self.role)
+        .bind(self.is_active)
+        .fetch_one(&app.db)
+        .await
+        .unwrap()
+    }
+}
+
+pub struct JobBuilder{
+    title: String,
+    company_name: String,
+    city: String,
+    country: String,
+    salary: String,
+    description: String,
+    // canonical skill names and whether they are required
+    skills: Vec<(String, bool)>,
+    expires_at: Option<DateTime<Utc>>,
+}
+
+pub fn job() -> JobBuilder{
+    JobBuilder{
+        title: "Backend Engineer".to_string(),
+        company_name: "Acme".to_string(),
+        city: "Lagos".to_string(),
+        country: "Nigeria".to_string(),
+        salary: "100000".to_string(),
+        description: "Build APIs".to_string(),
+        skills: Vec::new(),
+        expires_at: None,
+    }
+}
+
+impl JobBuilder{
+    pub fn title(mut self, title: &str) -> Self{
+        self.title = title.to_string();
+        self
+    }
+
+    pub fn company_name(mut self, company_name: &str) -> Self{
+        self.company_name = company_name.to_string();
+        self
+    }
+
+    pub fn salary(mut self, salary: &str) -> Self{
+        self.salary = salary.to_string();
+        self
+    }
+
+    pub fn description(mut self, description: &str) -> Self{
+        self.description = description.to_string();
+        self
+    }
+
+    pub fn skill(mut self, name: &str, required: bool) -> Self{
+        self.skills.push((name.to_string(), required));
+        self
+    }
+
+    // may be in the past, unlike through the API
+    pub fn expires_at(mut self, expires_at: DateTime<Utc>) -> Self{
+        self.expires_at = Some(expires_at);
+        self
+    }
+
+    // the salary range is read from the text like the API does
+    pub async fn create(self, app: &TestApp) -> Job{
+        let range = parse_salary(&self.salary);
+        let job = sqlx::query_as::<_, Job>(
+            "INSERT INTO jobs (title, company_name, city, country, salary, description, expires_at,
+                               salary_min, salary_max, salary_currency)
+             VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10) RETURNING *",
+        )
+        .bind(self.title)
+        .bind(self.company_name)
+        .bind(self.city)
+        .bind(self.country)
+        .bind(self.salary)
+        .bind(self.description)
+        .bind(self.expires_at)
+        .bind(range.as_ref().map(|range| range.min))
+        .bind(range.as_ref().map(|range| range.max))
+        .bind(range.and_then(|range| range.currency))
+        .fetch_one(&app.db)
+        .await
+        .unwrap();
+
+        for (name, required) in self.skills{
+            sqlx::query("INSERT INTO job_skills (job_id, skill_id, required) SELECT $1, id, $3 FROM skills WHERE name = $2")
+                .bind(job.id)
+                .bind(name)
+                .bind(required)
+                .execute(&app.db)
+                .await
+                .unwrap();
+        }
+        job
+    }
+}
+
+pub async fn application(app: &TestApp, user: &User, job: &Job) -> Application{
+    sqlx::query_as::<_, Application>("INSERT INTO applications (job_id, user_id) VALUES ($1, $2) RETURNING *")
+        .bind(job.id)
+        .bind(user.id)
+        .fetch_one(&app.db)
+        .await
+        .unwrap()
+}
+
+// Returns the plain key to send in X-Api-Key
+pub async fn api_key(app: &TestApp, user: &User, scopes: &[&str], company_name: Option<&str>) -> String{
+    let generated = api_key::generate();
+    let scopes: Vec<String> = scopes.iter().map(|s| s.to_string()).collect();
+    sqlx::query(
+        "INSERT INTO api_keys (user_id, company_name, name, prefix, key_hash, scopes)
+         VALUES ($1, $2, 'test', $3, $4, $5)",
+    )
+    .bind(user.id)
+    .bind(company_name)
+    .bind(&generated.prefix)
+    .bind(&generated.hash)
+    .bind(&scopes)
+    .execute(&app.db)
+    .await
+    .unwrap();
+    generated.key
+}
@@ -0,0 +1,212 @@
+// Shared by every integration test binary, not all of them use everything.
+#![allow(dead_code)]
+
+pub mod fakes;
+pub mod fixtures;
+
+use actix_web::{
+    http::{header::HeaderMap, StatusCode},
+    test, web, App,
+};
+use sqlx::{
+    postgres::{PgConnectOptions, PgPoolOptions},
+    Connection, Executor, PgConnection, Pool, Postgres,
+};
+use std::{path::PathBuf, str::FromStr};
+use tokio::sync::{Mutex, OnceCell};
+use trabajo_server::{
+    core::{
+        config::config::Config,
+        helpers::{metrics::Metrics, shutdown::BackgroundTasks, token::JwtKeys},
+        middleware::{csrf, request_id::RequestTracing, request_metrics::RequestMetrics},
+    },
+    queue::{self, mailer::Mailer},
+    route, AppState,
+};
+use uuid::Uuid;
+
+const TEMPLATE_DATABASE: &str = "trabajo_test_template";
+
+// Migrations run once per test binary into a template database; every test
+// then gets its own copy, which Postgres makes by copying files instead of
+// replaying the migrations.
+static TEMPLATE: OnceCell<()> = OnceCell::const_new();
+// CREATE DATABASE ... TEMPLATE fails while another copy of the same template
+// is being made
+static CREATE_DATABASE: Mutex<()> = Mutex::const_new(());
+
+// TEST_DATABASE_URL, falling back to DATABASE_URL, must point at a server the
+// user may create databases on. The database named in it is not touched.
+fn server_options() -> PgConnectOptions{
+    let url = std::env::var("TEST_DATABASE_URL")
+        .or_else(|_| std::env::var("DATABASE_URL"))
+        .expect("set TEST_DATABASE_URL or DATABASE_URL to run the integration tests");
+    PgConnectOptions::from_str(&url).expect("invalid TEST_DATABASE_URL")
+}
+
+async fn admin_connection() -> PgConnection{
+    PgConnection::connect_with(&server_options().database("postgres"))
+        .await
+        .expect("cannot connect to the test database server")
+}
+
+async fn ensure_template(){
+    TEMPLATE
+        .get_or_init(|| async{
+            let mut admin = admin_connection().await;
+            admin
+                .execute(format!("DROP DATABASE IF EXISTS {} WITH (FORCE)", TEMPLATE_DATABASE).as_str())
+                .await
+                .unwrap();
+            admin
+                .execute(format!("CREATE DATABASE {}", TEMPLATE_DATABASE).as_str())
+                .await
+                .unwrap();
+
+            let mut template = PgConnection::connect_with(&server_options().database(TEMPLATE_DATABASE))
+                .await
+                .unwrap();
+            trabajo_server::MIGRATOR.run(&mut template).await.unwrap();
+            template.close().await.unwrap();
+        })
+        .await;
+}
+
+pub struct TestApp{
+    pub state: web::Data<AppState>,
+    pub db: Pool<Postgres>,
+    pub storage_dir: PathBuf,
+    database: String,
+    _background: BackgroundTasks,
+}
+
+pub struct TestResponse{
+    pub status: StatusCode,
+    pub headers: HeaderMap,
+    pub body: serde_json::Value,
+}
+
+impl TestResponse{
+    pub fn cookie(&self, name: &str) -> Option<String>{
+        self.headers
+            .get_all("set-cookie")
+            .filter_map(|value| value.to_str().ok())
+            .filter_map(|value| value.split(';').next())
+            .filter_map(|pair| pair.split_once('='))
+            .find(|(key, _)| *key == name)
+            .map(|(_, value)| value.to_string())
+    }
+}
+
+impl TestApp{
+    pub async fn spawn() -> TestApp{
+        ensure_template().await;
+
+        let database = format!("trabajo_test_{}", Uuid::new_v4().simple());
+        {
+            let _guard = CREATE_DATABASE.lock().await;
+            let mut admin = admin_connection().await;
+            admin
+                .execute(format!("CREATE DATABASE {} TEMPLATE {}", database, TEMPLATE_DATABASE).as_str())
+                .await
+                .unwrap();
+        }
+
+        let db = PgPoolOptions::new()
+            .max_connections(5)
+            .connect_with(server_options().database(&database))
+            .await
+            .unwrap();
+
+        let storage_dir = std::env::temp_dir().join(&database);
+        std::fs::create_dir_all(&storage_dir).unwrap();
+
+        // the pool above is what the app uses, database.url only has to be set
+        let config = Config::from_toml(&format!(
+            r#"
+            [database]
+            url = "postgresql://unused"
+            [storage]
+            dir = "{}"
+            [jwt]
+            algorithm = "HS256"
+            secret = "integration-test-secret"
+            [queue]
+            workers = 0
+            "#,
+            storage_dir.display()
+        ))
+        .unwrap();
+
+        let jwt_keys = JwtKeys::from_config(&config).unwrap();
+        let background = BackgroundTasks::new();
+        let state = web::Data::new(AppState::new(
+            db.clone(),
+            config,
+            jwt_keys,
+            Metrics::new(),
+            background.shutdown_signal(),
+        ));
+
+        TestApp{ state, db, storage_dir, database, _background: background }
+    }
+
+    // Runs the request through the same routes and middleware as the server,
+    // minus CORS and static files.
+    pub async fn call(&self, req: test::TestRequest) -> TestResponse{
+        let app = test::init_service(
+            App::new()
+                .app_data(self.state.clone())
+                .configure(route::config)
+                .wrap(csrf::Csrf)
+                .wrap(RequestMetrics::new(self.state.metrics.clone()))
+                .wrap(RequestTracing),
+        )
+        .await;
+
+        let res = test::call_service(&app, req.to_request()).await;
+        let status = res.status();
+        let headers = res.headers().clone();
+        let bytes = test::read_body(res).await;
+        let body = serde_json::from_slice(&bytes)
+            .unwrap_or_else(|_| serde_json::Value::String(String::from_utf8_lossy(&bytes).to_string()));
+
+        TestResponse{ status, headers, body }
+    }
+
+    pub fn token_for(&self, user_id: &Uuid) -> String{
+        self.state.jwt_keys.issue(user_id, 0, chrono::Duration::minutes(60)).unwrap()
+    }
+
+    // for running tasks directly instead of through a worker
+    pub fn queue_context(&self) -> queue::Context{
+        queue::Context{
+            db: self.db.clone(),
+            mailer: Mailer::from_config(&self.state.env).unwrap(),
+            state: self.state.clone(),
+        }
+    }
+}
+
+// Drop has no async, so the database is removed from a throwaway runtime on
+// its own thread. FORCE disconnects the pool that is still open.
+impl Drop for TestApp{
+    fn drop(&mut self){
+        let database = self.database.to_owned();
+        let _ = std::fs::remove_dir_all(&self.storage_dir);
+        let _ = std::thread::spawn(move ||{
+            let runtime = tokio::runtime::Builder::new_current_thread().enable_all().build().unwrap();
+            runtime.block_on(async{
+                let mut admin = admin_connection().await;
+                let _ = admin
+                    .execute(format!("DROP DATABASE IF EXISTS {} WITH (FORCE)", database).as_str())
+                    .await;
+            });
+        })
+        .join();
+    }
+}
+
+pub fn bearer(token: &str) -> (&'static str, String){
+    ("Authorization", format!("Bearer {}", token))
+}
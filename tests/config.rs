@@ -0,0 +1,63 @@
+use trabajo_server::core::config::config::{parse_duration, Config};
+
+fn config(extra: &str) -> Result<Config, String>{
+    Config::from_toml(&format!("[database]\nurl = \"postgresql://unused\"\n[jwt]\nsecret = \"secret\"\n{}", extra))
+        .map_err(|e| e.to_string())
+}
+
+#[test]
+fn durations_out_of_range_are_config_errors(){
+    assert_eq!(parse_duration("90"), Some(chrono::Duration::minutes(90)));
+    assert_eq!(parse_duration("1h 30m"), Some(chrono::Duration::minutes(90)));
+    assert_eq!(parse_duration("999999999999999"), None);
+    assert_eq!(parse_duration("9223372036854775807"), None);
+    assert_eq!(parse_duration("-5"), None);
+
+    let error = config("maxage = \"999999999999999\"").unwrap_err();
+    assert!(error.contains("jwt.maxage must be a duration"), "{}", error);
+}
+
+#[test]
+fn wildcard_origins_match_subdomains_only(){
+    let config = config("[cors]\nallowed_origins = [\"https://*.example.com\", \"http://localhost:3000\"]").unwrap();
+
+    assert!(config.origin_allowed("https://a.example.com"));
+    assert!(config.origin_allowed("https://a.b.example.com"));
+    assert!(config.origin_allowed("http://localhost:3000"));
+    for origin in [
+        "https://example.com",
+        "https://evilexample.com",
+        "http://a.example.com",
+        "https://.example.com",
+        "https://a.example.com.evil.com",
+        "http://localhost:3001",
+    ]{
+        assert!(!config.origin_allowed(origin), "{}", origin);
+    }
+}
+
+#[test]
+fn default_cors_methods_cover_the_put_routes(){
+    let config = config("").unwrap();
+    assert_eq!(config.cors_allowed_methods, ["GET", "POST", "PUT", "PATCH", "DELETE"]);
+}
+
+#[test]
+fn invalid_origins_are_config_errors(){
+    for origin in ["*", "example.com", "ftp://example.com", "https://*.*.example.com", "https://example.com/path", "https://"]{
+        let error = config(&format!("[cors]\nallowed_origins = [{:?}]", origin)).unwrap_err();
+        assert!(error.contains("cors.allowed_origins"), "{}: {}", origin, error);
+    }
+}
+
+// the only test that touches the environment, on a key no other test reads
+#[test]
+fn environment_beats_file_beats_default(){
+    assert_eq!(config("").unwrap().server_port, 8000);
+    assert_eq!(config("[server]\nport = 9000").unwrap().server_port, 9000);
+
+    std::env::set_var("SERVER_PORT", "9100");
+    let from_env = config("[server]\nport = 9000").map(|config| config.server_port);
+    std::env::remove_var("SERVER_PORT");
+    assert_eq!(from_env.unwrap(), 9100);
+}
@@ -0,0 +1,79 @@
+mod common;
+
+use actix_web::{cookie::Cookie, http::StatusCode, test::TestRequest};
+use common::{bearer, fixtures, TestApp};
+use serde_json::json;
+
+fn apply_request(job_id: uuid::Uuid) -> TestRequest{
+    TestRequest::post()
+        .uri("/api/application")
+        .set_json(json!({ "job_id": job_id }))
+}
+
+#[actix_web::test]
+async fn cookie_session_needs_the_csrf_header_for_writes(){
+    let app = TestApp::spawn().await;
+    let user = fixtures::user().create(&app).await;
+    let job = fixtures::job().create(&app).await;
+    let session = app.token_for(&user.id);
+
+    let res = app
+        .call(
+            apply_request(job.id)
+                .cookie(Cookie::new("token", session.to_owned()))
+                .cookie(Cookie::new("csrf_token", "abc")),
+        )
+        .await;
+    assert_eq!(res.status, StatusCode::FORBIDDEN);
+
+    let res = app
+        .call(
+            apply_request(job.id)
+                .cookie(Cookie::new("token", session.to_owned()))
+                .cookie(Cookie::new("csrf_token", "abc"))
+                .insert_header(("X-CSRF-Token", "abd")),
+        )
+        .await;
+    assert_eq!(res.status, StatusCode::FORBIDDEN);
+
+    let res = app
+        .call(
+            apply_request(job.id)
+                .cookie(Cookie::new("token", session))
+                .cookie(Cookie::new("csrf_token", "abc"))
+                .insert_header(("X-CSRF-Token", "abc")),
+        )
+        .await;
+    assert_eq!(res.status, StatusCode::OK);
+}
+
+#[actix_web::test]
+async fn header_authenticated_writes_skip_the_csrf_check(){
+    let app = TestApp::spawn().await;
+    let user = fixtures::user().create(&app).await;
+    let job = fixtures::job().create(&app).await;
+    let token = app.token_for(&user.id);
+
+    let res = app
+        .call(
+            apply_request(job.id)
+                .cookie(Cookie::new("token", token.to_owned()))
+                .insert_header(bearer(&token)),
+        )
+        .await;
+
+    assert_eq!(res.status, StatusCode::OK);
+}
+
+#[actix_web::test]
+async fn csrf_endpoint_issues_a_matching_cookie(){
+    let app = TestApp::spawn().await;
+    let user = fixtures::user().create(&app).await;
+
+    let res = app
+        .call(TestRequest::get().uri("/api/auth/csrf").insert_header(bearer(&app.token_for(&user.id))))
+        .await;
+
+    assert_eq!(res.status, StatusCode::OK);
+    assert_eq!(res.cookie("csrf_token").as_deref(), res.body["csrf_token"].as_str());
+}
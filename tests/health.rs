@@ -0,0 +1,46 @@
+mod common;
+
+use actix_web::{http::StatusCode, test::TestRequest};
+use common::TestApp;
+
+#[actix_web::test]
+async fn liveness_is_always_ok(){
+    let app = TestApp::spawn().await;
+
+    let res = app.call(TestRequest::get().uri("/healthz")).await;
+
+    assert_eq!(res.status, StatusCode::OK);
+}
+
+#[actix_web::test]
+async fn readiness_checks_database_and_storage(){
+    let app = TestApp::spawn().await;
+
+    let res = app.call(TestRequest::get().uri("/readyz")).await;
+
+    assert_eq!(res.status, StatusCode::OK);
+    assert_eq!(res.body["data"]["database"], "ok");
+    assert_eq!(res.body["data"]["storage"], "ok");
+}
+
+#[actix_web::test]
+async fn readiness_fails_without_storage(){
+    let app = TestApp::spawn().await;
+    std::fs::remove_dir_all(&app.storage_dir).unwrap();
+
+    let res = app.call(TestRequest::get().uri("/readyz")).await;
+
+    assert_eq!(res.status, StatusCode::SERVICE_UNAVAILABLE);
+}
+
+#[actix_web::test]
+async fn metrics_count_requests_by_route(){
+    let app = TestApp::spawn().await;
+    app.call(TestRequest::get().uri("/healthz")).await;
+
+    let res = app.call(TestRequest::get().uri("/metrics")).await;
+
+    assert_eq!(res.status, StatusCode::OK);
+    let text = res.body.as_str().unwrap();
+    assert!(text.contains(r#"trabajo_http_requests_total{method="GET",route="/healthz",status="200"} 1"#));
+}
@@ -0,0 +1,145 @@
+mod common;
+
+use actix_web::{http::StatusCode, test::TestRequest};
+use common::{bearer, fixtures, TestApp};
+use serde_json::json;
+
+fn job_body(title: &str, extra: serde_json::Value) -> serde_json::Value{
+    let mut body = json!({
+        "title": title,
+        "company_name": "Acme",
+        "city": "Madrid",
+        "country": "Spain",
+        "salary": "50000",
+        "description": "Build things"
+    });
+    for (key, value) in extra.as_object().unwrap(){
+        body[key] = value.clone();
+    }
+    body
+}
+
+async fn post_job(app: &TestApp, token: &str, body: serde_json::Value) -> StatusCode{
+    app.call(TestRequest::post().uri("/api/job").insert_header(bearer(token)).set_json(body))
+        .await
+        .status
+}
+
+#[actix_web::test]
+async fn categories_form_a_tree_admins_extend(){
+    let app = TestApp::spawn().await;
+    let admin = fixtures::admin().create(&app).await;
+    let token = app.token_for(&admin.id);
+
+    let res = app.call(TestRequest::get().uri("/api/categories")).await;
+    assert_eq!(res.status, StatusCode::OK);
+    let engineering = res.body["data"].as_array().unwrap().iter().find(|category| category["slug"] == "engineering").unwrap();
+    assert!(engineering["children"].as_array().unwrap().iter().any(|child| child["slug"] == "backend"));
+
+    let create = |body: serde_json::Value| TestRequest::post().uri("/api/categories").insert_header(bearer(&token)).set_json(body);
+    let res = app.call(create(json!({ "name": "Site Reliability", "parent": "devops" }))).await;
+    assert_eq!(res.status, StatusCode::CREATED);
+    assert_eq!(res.body["data"]["slug"], "site-reliability");
+    assert_eq!(app.call(create(json!({ "name": "site reliability" }))).await.status, StatusCode::CONFLICT);
+    assert_eq!(app.call(create(json!({ "name": "Legal", "parent": "nowhere" }))).await.status, StatusCode::BAD_REQUEST);
+    assert_eq!(app.call(create(json!({ "name": " -- " }))).await.status, StatusCode::BAD_REQUEST);
+
+    let res = app.call(TestRequest::get().uri("/api/categories")).await;
+    let engineering = res.body["data"].as_array().unwrap().iter().find(|category| category["slug"] == "engineering").unwrap();
+    let devops = engineering["children"].as_array().unwrap().iter().find(|child| child["slug"] == "devops").unwrap();
+    assert_eq!(devops["children"][0]["slug"], "site-reliability");
+}
+
+#[actix_web::test]
+async fn jobs_are_classified_when_posted(){
+    let app = TestApp::spawn().await;
+    let admin = fixtures::admin().create(&app).await;
+    let token = app.token_for(&admin.id);
+
+    let body = job_body("Rust Developer", json!({ "employment_type": "contract", "seniority": "senior", "category": "Backend" }));
+    assert_eq!(post_job(&app, &token, body).await, StatusCode::OK);
+    assert_eq!(post_job(&app, &token, job_body("Junior Tester", json!({}))).await, StatusCode::OK);
+
+    let (employment_type, seniority): (String, Option<String>) =
+        sqlx::query_as("SELECT employment_type, seniority FROM jobs WHERE title = 'Rust Developer'")
+            .fetch_one(&app.db)
+            .await
+            .unwrap();
+    assert_eq!((employment_type.as_str(), seniority.as_deref()), ("contract", Some("senior")));
+    // defaults, with the seniority guessed from the title
+    let (employment_type, seniority): (String, Option<String>) =
+        sqlx::query_as("SELECT employment_type, seniority FROM jobs WHERE title = 'Junior Tester'")
+            .fetch_one(&app.db)
+            .await
+            .unwrap();
+    assert_eq!((employment_type.as_str(), seniority.as_deref()), ("full-time", Some("junior")));
+
+    for extra in [json!({ "employment_type": "gig" }), json!({ "seniority": "expert" }), json!({ "category": "astrology" })]{
+        assert_eq!(post_job(&app, &token, job_body("Invalid", extra)).await, StatusCode::BAD_REQUEST);
+    }
+}
+
+#[actix_web::test]
+async fn jobs_are_filtered_and_counted_by_classification(){
+    let app = TestApp::spawn().await;
+    let admin = fixtures::admin().create(&app).await;
+    let token = app.token_for(&admin.id);
+    for (title, extra) in [
+        ("Backend job", json!({ "category": "backend", "employment_type": "contract" })),
+        ("Frontend job", json!({ "category": "frontend" })),
+        ("Senior design job", json!({ "category": "product-design", "employment_type": "part-time" })),
+        ("Unsorted job", json!({})),
+    ]{
+        assert_eq!(post_job(&app, &token, job_body(title, extra)).await, StatusCode::OK);
+    }
+    let titles = |body: &serde_json::Value| {
+        let mut titles: Vec<String> =
+            body["data"].as_array().unwrap().iter().map(|job| job["title"].as_str().unwrap().to_string()).collect();
+        titles.sort();
+        titles
+    };
+
+    let all = app.call(TestRequest::get().uri("/api/jobs")).await;
+    assert_eq!(all.body["facets"]["employment_type"], json!({ "contract": 1, "full-time": 2, "part-time": 1 }));
+    assert_eq!(all.body["facets"]["seniority"], json!({ "mid": 3, "senior": 1 }));
+    assert_eq!(all.body["facets"]["category"]["engineering"], 2);
+    assert_eq!(all.body["facets"]["category"]["backend"], 1);
+    assert_eq!(all.body["facets"]["category"]["design"], 1);
+
+    // a category takes in the ones below it, facets follow the search
+    let engineering = app.call(TestRequest::get().uri("/api/jobs?category=engineering")).await;
+    assert_eq!(titles(&engineering.body), vec!["Backend job", "Frontend job"]);
+    assert_eq!(engineering.body["facets"]["employment_type"], json!({ "contract": 1, "full-time": 1 }));
+    assert!(engineering.body["facets"]["category"].get("design").is_none());
+
+    let contract = app.call(TestRequest::get().uri("/api/jobs?employment_type=contract")).await;
+    assert_eq!(titles(&contract.body), vec!["Backend job"]);
+    let senior = app.call(TestRequest::get().uri("/api/jobs?seniority=senior")).await;
+    assert_eq!(titles(&senior.body), vec!["Senior design job"]);
+
+    let unknown = app.call(TestRequest::get().uri("/api/jobs?category=astrology")).await;
+    assert_eq!(unknown.status, StatusCode::OK);
+    assert!(unknown.body["data"].as_array().unwrap().is_empty());
+    for uri in ["/api/jobs?employment_type=gig", "/api/jobs?seniority=expert"]{
+        assert_eq!(app.call(TestRequest::get().uri(uri)).await.status, StatusCode::BAD_REQUEST);
+    }
+}
+
+#[actix_web::test]
+async fn saved_searches_only_keep_known_categories(){
+    let app = TestApp::spawn().await;
+    let user = fixtures::user().create(&app).await;
+    let token = app.token_for(&user.id);
+    let save = |category: &str| {
+        TestRequest::post().uri("/api/me/saved-searches").insert_header(bearer(&token)).set_json(json!({
+            "name": "Contracts",
+            "frequency": "daily",
+            "filters": { "category": category, "employment_type": "contract" }
+        }))
+    };
+
+    let res = app.call(save("Backend")).await;
+    assert_eq!(res.status, StatusCode::CREATED);
+    assert_eq!(res.body["data"]["filters"]["category"], "backend");
+    assert_eq!(app.call(save("astrology")).await.status, StatusCode::BAD_REQUEST);
+}
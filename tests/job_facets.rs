@@ -0,0 +1,87 @@
+mod common;
+
+use actix_web::{http::StatusCode, test::TestRequest};
+use common::{bearer, fixtures, TestApp};
+use serde_json::json;
+
+async fn post_job(app: &TestApp, token: &str, title: &str, company_name: &str, city: &str, salary: &str){
+    let res = app
+        .call(TestRequest::post().uri("/api/job").insert_header(bearer(token)).set_json(json!({
+            "title": title,
+            "company_name": company_name,
+            "city": city,
+            "country": "Spain",
+            "salary": salary,
+            "description": "Build things"
+        })))
+        .await;
+    assert_eq!(res.status, StatusCode::OK);
+}
+
+#[actix_web::test]
+async fn facets_count_the_jobs_of_the_search(){
+    let app = TestApp::spawn().await;
+    let admin = fixtures::admin().create(&app).await;
+    let token = app.token_for(&admin.id);
+    post_job(&app, &token, "Rust Developer", "Acme", "Madrid", "45000-55000 EUR").await;
+    post_job(&app, &token, "Go Developer", "Acme", "Madrid", "$60k - $80k").await;
+    post_job(&app, &token, "Rust Lead", "Globex", "Valencia", "120000").await;
+    post_job(&app, &token, "Designer", "Globex", "Madrid", "Competitive").await;
+    let old = fixtures::job().title("Old Rust job").company_name("Initech").create(&app).await;
+    sqlx::query("UPDATE jobs SET created_at = NOW() - INTERVAL '10 days' WHERE id = $1")
+        .bind(old.id)
+        .execute(&app.db)
+        .await
+        .unwrap();
+
+    let res = app.call(TestRequest::get().uri("/api/jobs/facets")).await;
+    assert_eq!(res.status, StatusCode::OK);
+    let facets = &res.body["data"];
+    assert_eq!(facets["company"], json!({ "Acme": 2, "Globex": 2, "Initech": 1 }));
+    assert_eq!(facets["city"], json!({ "Lagos": 1, "Madrid": 3, "Valencia": 1 }));
+    assert_eq!(facets["country"], json!({ "Nigeria": 1, "Spain": 4 }));
+    // buckets are per currency; the lead's salary names none, the designer's
+    // has no amount and the old fixture job was never parsed
+    assert_eq!(facets["salary"], json!({ "EUR": { "50k-75k": 1 }, "USD": { "75k-100k": 1 } }));
+    assert_eq!(facets["posted"], json!({ "24h": 4, "7d": 4, "30d": 5 }));
+    assert_eq!(facets["employment_type"], json!({ "full-time": 5 }));
+
+    let rust = app.call(TestRequest::get().uri("/api/jobs/facets?search_query=rust")).await;
+    assert_eq!(rust.body["data"]["company"], json!({ "Acme": 1, "Globex": 1, "Initech": 1 }));
+    assert_eq!(rust.body["data"]["posted"], json!({ "24h": 2, "7d": 2, "30d": 3 }));
+
+    let res = app.call(TestRequest::get().uri("/api/jobs/facets?radius_km=5")).await;
+    assert_eq!(res.status, StatusCode::BAD_REQUEST);
+}
+
+#[actix_web::test]
+async fn posted_salaries_are_read_into_a_yearly_range(){
+    let app = TestApp::spawn().await;
+    let admin = fixtures::admin().create(&app).await;
+    post_job(&app, &app.token_for(&admin.id), "Rust Developer", "Acme", "Madrid", "€3.500 per month").await;
+
+    let (salary_min, salary_max, salary_currency): (Option<f64>, Option<f64>, Option<String>) =
+        sqlx::query_as("SELECT salary_min, salary_max, salary_currency FROM jobs WHERE title = 'Rust Developer'")
+            .fetch_one(&app.db)
+            .await
+            .unwrap();
+    assert_eq!(salary_min, Some(42000.0));
+    assert_eq!(salary_max, Some(42000.0));
+    assert_eq!(salary_currency.as_deref(), Some("EUR"));
+}
+
+#[actix_web::test]
+async fn facets_of_a_search_are_cached_briefly(){
+    let app = TestApp::spawn().await;
+    fixtures::job().company_name("Acme").create(&app).await;
+
+    let first = app.call(TestRequest::get().uri("/api/jobs/facets")).await;
+    assert_eq!(first.body["data"]["company"], json!({ "Acme": 1 }));
+
+    fixtures::job().company_name("Globex").create(&app).await;
+    let cached = app.call(TestRequest::get().uri("/api/jobs/facets")).await;
+    assert_eq!(cached.body["data"]["company"], json!({ "Acme": 1 }));
+    // another search is counted afresh
+    let other = app.call(TestRequest::get().uri("/api/jobs/facets?search_query=build")).await;
+    assert_eq!(other.body["data"]["company"], json!({ "Acme": 1, "Globex": 1 }));
+}
@@ -0,0 +1,177 @@
+mod common;
+
+use actix_web::{http::StatusCode, test::TestRequest};
+use chrono::{Duration, Utc};
+use common::{bearer, fixtures, TestApp};
+use serde_json::json;
+use trabajo_server::core::helpers::api_key;
+
+fn job_body(company_name: &str) -> serde_json::Value{
+    json!({
+        "title": "Rust Developer",
+        "company_name": company_name,
+        "city": "Madrid",
+        "country": "Spain",
+        "salary": "60000",
+        "description": "Write services"
+    })
+}
+
+#[actix_web::test]
+async fn admin_can_post_a_job(){
+    let app = TestApp::spawn().await;
+    let admin = fixtures::admin().create(&app).await;
+
+    let res = app
+        .call(
+            TestRequest::post()
+                .uri("/api/job")
+                .insert_header(bearer(&app.token_for(&admin.id)))
+                .set_json(job_body("Acme")),
+        )
+        .await;
+
+    assert_eq!(res.status, StatusCode::OK);
+    let id: uuid::Uuid = res.body["data"]["id"].as_str().unwrap().parse().unwrap();
+    let title: String = sqlx::query_scalar("SELECT title FROM jobs WHERE id = $1")
+        .bind(id)
+        .fetch_one(&app.db)
+        .await
+        .unwrap();
+    assert_eq!(title, "Rust Developer");
+}
+
+#[actix_web::test]
+async fn job_expiry_must_be_in_the_future(){
+    let app = TestApp::spawn().await;
+    let admin = fixtures::admin().create(&app).await;
+    let post = |expires_at: &str| {
+        let mut body = job_body("Acme");
+        body["expires_at"] = json!(expires_at);
+        TestRequest::post().uri("/api/job").insert_header(bearer(&app.token_for(&admin.id))).set_json(body)
+    };
+
+    assert_eq!(app.call(post("2020-01-01T00:00:00Z")).await.status, StatusCode::BAD_REQUEST);
+    let res = app.call(post("2999-01-01T00:00:00Z")).await;
+    assert_eq!(res.status, StatusCode::OK);
+    let job = app.call(TestRequest::get().uri(&format!("/api/job/{}", res.body["data"]["id"].as_str().unwrap()))).await;
+    assert_eq!(job.body["data"]["expiresAt"], "2999-01-01T00:00:00Z");
+}
+
+#[actix_web::test]
+async fn regular_user_cannot_post_a_job(){
+    let app = TestApp::spawn().await;
+    let user = fixtures::user().create(&app).await;
+
+    let res = app
+        .call(
+            TestRequest::post()
+                .uri("/api/job")
+                .insert_header(bearer(&app.token_for(&user.id)))
+                .set_json(job_body("Acme")),
+        )
+        .await;
+
+    assert_eq!(res.status, StatusCode::UNAUTHORIZED);
+}
+
+#[actix_web::test]
+async fn company_api_key_only_posts_for_its_company(){
+    let app = TestApp::spawn().await;
+    let admin = fixtures::admin().create(&app).await;
+    let key = fixtures::api_key(&app, &admin, &[api_key::JOBS_WRITE], Some("Acme")).await;
+
+    let res = app
+        .call(
+            TestRequest::post()
+                .uri("/api/job")
+                .insert_header(("X-Api-Key", key.as_str()))
+                .set_json(job_body("Globex")),
+        )
+        .await;
+    assert_eq!(res.status, StatusCode::FORBIDDEN);
+
+    let res = app
+        .call(
+            TestRequest::post()
+                .uri("/api/job")
+                .insert_header(("X-Api-Key", key.as_str()))
+                .set_json(job_body("Acme")),
+        )
+        .await;
+    assert_eq!(res.status, StatusCode::OK);
+}
+
+#[actix_web::test]
+async fn api_key_without_jobs_write_cannot_post(){
+    let app = TestApp::spawn().await;
+    let admin = fixtures::admin().create(&app).await;
+    let key = fixtures::api_key(&app, &admin, &[api_key::JOBS_READ], None).await;
+
+    let res = app
+        .call(
+            TestRequest::post()
+                .uri("/api/job")
+                .insert_header(("X-Api-Key", key.as_str()))
+                .set_json(job_body("Acme")),
+        )
+        .await;
+
+    assert_eq!(res.status, StatusCode::FORBIDDEN);
+    assert_eq!(res.body["message"], "API key is missing the jobs:write scope");
+}
+
+#[actix_web::test]
+async fn jobs_are_listed_ten_per_page(){
+    let app = TestApp::spawn().await;
+    for i in 0..12{
+        fixtures::job().title(&format!("Job {}", i)).create(&app).await;
+    }
+
+    let first = app.call(TestRequest::get().uri("/api/jobs")).await;
+    let second = app.call(TestRequest::get().uri("/api/jobs?page=1")).await;
+
+    assert_eq!(first.status, StatusCode::OK);
+    assert_eq!(first.body["data"].as_array().unwrap().len(), 10);
+    assert_eq!(second.body["data"].as_array().unwrap().len(), 2);
+}
+
+#[actix_web::test]
+async fn expired_jobs_are_left_out_of_listings(){
+    let app = TestApp::spawn().await;
+    fixtures::job().title("Open").company_name("Acme").create(&app).await;
+    fixtures::job().title("Closing").company_name("Acme").expires_at(Utc::now() + Duration::days(1)).create(&app).await;
+    let expired = fixtures::job().title("Expired").company_name("Globex").expires_at(Utc::now() - Duration::days(1)).create(&app).await;
+
+    let listed = app.call(TestRequest::get().uri("/api/jobs")).await;
+    let facets = app.call(TestRequest::get().uri("/api/jobs/facets")).await;
+    let by_id = app.call(TestRequest::get().uri(&format!("/api/job/{}", expired.id))).await;
+
+    let mut titles: Vec<&str> = listed.body["data"].as_array().unwrap().iter().map(|job| job["title"].as_str().unwrap()).collect();
+    titles.sort();
+    assert_eq!(titles, ["Closing", "Open"]);
+    assert_eq!(facets.body["data"]["company"], json!({ "Acme": 2 }));
+    assert_eq!(by_id.status, StatusCode::OK);
+}
+
+#[actix_web::test]
+async fn job_can_be_fetched_by_id(){
+    let app = TestApp::spawn().await;
+    let job = fixtures::job().title("Data Engineer").create(&app).await;
+
+    let res = app.call(TestRequest::get().uri(&format!("/api/job/{}", job.id))).await;
+
+    assert_eq!(res.status, StatusCode::OK);
+    assert_eq!(res.body["data"]["title"], "Data Engineer");
+}
+
+#[actix_web::test]
+async fn unknown_job_is_not_found(){
+    let app = TestApp::spawn().await;
+
+    let res = app
+        .call(TestRequest::get().uri(&format!("/api/job/{}", uuid::Uuid::new_v4())))
+        .await;
+
+    assert_eq!(res.status, StatusCode::NOT_FOUND);
+}
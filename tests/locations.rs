@@ -0,0 +1,189 @@
+mod common;
+
+use actix_web::{http::StatusCode, test::TestRequest};
+use common::{bearer, fixtures, TestApp};
+use serde_json::json;
+use std::collections::HashMap;
+use trabajo_server::cli::locations;
+
+const COUNTRIES: &str = "#ISO\tISO3\tISO-Numeric\tfips\tCountry\tCapital\n\
+ES\tESP\t724\tSP\tSpain\tMadrid\n\
+PT\tPRT\t620\tPO\tPortugal\tLisbon\n";
+
+const REGIONS: &str = "ES.29\tMadrid\tMadrid\t3117732\nES.56\tCatalonia\tCatalonia\t3336901\n";
+
+fn city(id: i64, name: &str, latitude: f64, longitude: f64, country: &str, admin1: &str, population: i64) -> String{
+    format!(
+        "{id}\t{name}\t{name}\t\t{latitude}\t{longitude}\tP\tPPLA\t{country}\t\t{admin1}\t\t\t\t{population}\t\t600\tEurope/Madrid\t2023-01-01"
+    )
+}
+
+// Madrid, Getafe (about 13 km from Madrid) and Barcelona (about 500 km away)
+async fn import_sample(app: &TestApp) -> locations::ImportSummary{
+    let dir = &app.storage_dir;
+    let cities = [
+        city(3117735, "Madrid", 40.4165, -3.70256, "ES", "29", 3255944),
+        city(3121960, "Getafe", 40.30571, -3.73295, "ES", "29", 170115),
+        city(3128760, "Barcelona", 41.38879, 2.15899, "ES", "56", 1620343),
+        // a smaller Madrid elsewhere must not win over the capital
+        city(9999999, "Madrid", 42.0, -5.0, "ES", "", 100),
+        // a region, not a populated place
+        "2510769\tSpain\tSpain\t\t40.0\t-4.0\tA\tPCLI\tES\t\t00\t\t\t\t46723749\t\t600\tEurope/Madrid\t2023-01-01".to_string(),
+    ]
+    .join("\n");
+    std::fs::write(dir.join("countryInfo.txt"), COUNTRIES).unwrap();
+    std::fs::write(dir.join("admin1CodesASCII.txt"), REGIONS).unwrap();
+    std::fs::write(dir.join("cities.txt"), cities).unwrap();
+
+    locations::import(
+        &app.db,
+        &dir.join("countryInfo.txt"),
+        &dir.join("cities.txt"),
+        Some(&dir.join("admin1CodesASCII.txt")),
+    )
+    .await
+    .unwrap()
+}
+
+async fn post_job(app: &TestApp, token: &str, body: serde_json::Value) -> actix_web::http::StatusCode{
+    app.call(TestRequest::post().uri("/api/job").insert_header(bearer(token)).set_json(body))
+        .await
+        .status
+}
+
+fn job_body(title: &str, city: &str, country: &str) -> serde_json::Value{
+    json!({
+        "title": title,
+        "company_name": "Acme",
+        "city": city,
+        "country": country,
+        "salary": "50000",
+        "description": "Build things"
+    })
+}
+
+#[test]
+fn geonames_lines_are_parsed(){
+    let regions = HashMap::from([("ES.29".to_string(), "Madrid".to_string())]);
+
+    let madrid = locations::parse_location(&city(3117735, "Madrid", 40.4165, -3.70256, "ES", "29", 3255944), &regions).unwrap();
+    assert_eq!(madrid.id, 3117735);
+    assert_eq!(madrid.country_code, "ES");
+    assert_eq!(madrid.region.as_deref(), Some("Madrid"));
+    assert_eq!(madrid.population, 3255944);
+    assert_eq!(madrid.timezone, "Europe/Madrid");
+
+    assert!(locations::parse_country("#ISO\tISO3").is_none());
+    assert_eq!(locations::parse_country("ES\tESP\t724\tSP\tSpain\tMadrid").unwrap().name, "Spain");
+    assert!(locations::parse_location("not\ta\tgeoname", &regions).is_none());
+}
+
+#[actix_web::test]
+async fn import_geocodes_jobs_posted_before_it(){
+    let app = TestApp::spawn().await;
+    let job = fixtures::job().create(&app).await;
+    sqlx::query("UPDATE jobs SET city = 'madrid', country = 'Spain' WHERE id = $1")
+        .bind(job.id)
+        .execute(&app.db)
+        .await
+        .unwrap();
+
+    let summary = import_sample(&app).await;
+    assert_eq!(summary.countries, 2);
+    assert_eq!(summary.locations, 4);
+    assert_eq!(summary.jobs_placed, 1);
+
+    let res = app.call(TestRequest::get().uri(&format!("/api/job/{}", job.id))).await;
+    assert_eq!(res.body["data"]["location_id"], 3117735);
+    assert_eq!(res.body["data"]["country_code"], "ES");
+    assert_eq!(res.body["data"]["region"], "Madrid");
+}
+
+#[actix_web::test]
+async fn posted_jobs_are_geocoded(){
+    let app = TestApp::spawn().await;
+    import_sample(&app).await;
+    let admin = fixtures::admin().create(&app).await;
+    let token = app.token_for(&admin.id);
+
+    assert_eq!(post_job(&app, &token, job_body("Known", "Getafe", "ES")).await, StatusCode::OK);
+    assert_eq!(post_job(&app, &token, job_body("Unknown city", "Atlantis", "Spain")).await, StatusCode::OK);
+
+    let (latitude, country_code): (Option<f64>, Option<String>) =
+        sqlx::query_as("SELECT latitude, country_code FROM jobs WHERE title = 'Known'")
+            .fetch_one(&app.db)
+            .await
+            .unwrap();
+    assert_eq!(latitude, Some(40.30571));
+    assert_eq!(country_code.as_deref(), Some("ES"));
+
+    let (latitude, country_code): (Option<f64>, Option<String>) =
+        sqlx::query_as("SELECT latitude, country_code FROM jobs WHERE title = 'Unknown city'")
+            .fetch_one(&app.db)
+            .await
+            .unwrap();
+    assert_eq!(latitude, None);
+    assert_eq!(country_code.as_deref(), Some("ES"));
+}
+
+#[actix_web::test]
+async fn jobs_are_found_within_a_radius(){
+    let app = TestApp::spawn().await;
+    import_sample(&app).await;
+    let admin = fixtures::admin().create(&app).await;
+    let token = app.token_for(&admin.id);
+    for (title, city) in [("Madrid job", "Madrid"), ("Getafe job", "Getafe"), ("Barcelona job", "Barcelona")]{
+        post_job(&app, &token, job_body(title, city, "Spain")).await;
+    }
+
+    let titles = |body: &serde_json::Value| {
+        let mut titles: Vec<String> =
+            body["data"].as_array().unwrap().iter().map(|job| job["title"].as_str().unwrap().to_string()).collect();
+        titles.sort();
+        titles
+    };
+
+    let near = app.call(TestRequest::get().uri("/api/jobs?near=40.4165,-3.70256&radius_km=5")).await;
+    assert_eq!(titles(&near.body), vec!["Madrid job"]);
+    let near = app.call(TestRequest::get().uri("/api/jobs?near=40.4165,-3.70256&radius_km=20")).await;
+    assert_eq!(titles(&near.body), vec!["Getafe job", "Madrid job"]);
+    // 50 km when no radius is given
+    let near = app.call(TestRequest::get().uri("/api/jobs?near=41.39,2.16")).await;
+    assert_eq!(titles(&near.body), vec!["Barcelona job"]);
+
+    for uri in ["/api/jobs?near=north", "/api/jobs?near=91,0", "/api/jobs?radius_km=10", "/api/jobs?near=40,-3&radius_km=0"]{
+        assert_eq!(app.call(TestRequest::get().uri(uri)).await.status, StatusCode::BAD_REQUEST);
+    }
+}
+
+#[actix_web::test]
+async fn work_mode_is_validated_and_filterable(){
+    let app = TestApp::spawn().await;
+    let admin = fixtures::admin().create(&app).await;
+    let token = app.token_for(&admin.id);
+
+    let mut remote = job_body("Remote job", "Anywhere", "Spain");
+    remote["work_mode"] = json!("remote");
+    remote["remote_countries"] = json!(["es", "PT"]);
+    remote["remote_timezones"] = json!(["Europe/Madrid"]);
+    assert_eq!(post_job(&app, &token, remote).await, StatusCode::OK);
+    assert_eq!(post_job(&app, &token, job_body("Office job", "Madrid", "Spain")).await, StatusCode::OK);
+
+    let mut invalid = job_body("Invalid", "Madrid", "Spain");
+    invalid["work_mode"] = json!("sometimes");
+    assert_eq!(post_job(&app, &token, invalid).await, StatusCode::BAD_REQUEST);
+    let mut invalid = job_body("Invalid", "Madrid", "Spain");
+    invalid["remote_countries"] = json!(["ES"]);
+    assert_eq!(post_job(&app, &token, invalid).await, StatusCode::BAD_REQUEST);
+    let mut invalid = job_body("Invalid", "Madrid", "Spain");
+    invalid["work_mode"] = json!("remote");
+    invalid["remote_countries"] = json!(["Spain"]);
+    assert_eq!(post_job(&app, &token, invalid).await, StatusCode::BAD_REQUEST);
+
+    let res = app.call(TestRequest::get().uri("/api/jobs?work_mode=remote")).await;
+    let jobs = res.body["data"].as_array().unwrap();
+    assert_eq!(jobs.len(), 1);
+    assert_eq!(jobs[0]["title"], "Remote job");
+    assert_eq!(jobs[0]["remote_countries"], json!(["ES", "PT"]));
+    assert_eq!(app.call(TestRequest::get().uri("/api/jobs?work_mode=beach")).await.status, StatusCode::BAD_REQUEST);
+}
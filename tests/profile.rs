@@ -0,0 +1,215 @@
+mod common;
+
+use actix_web::{http::StatusCode, test::TestRequest};
+use common::{bearer, fixtures, TestApp};
+use serde_json::json;
+use trabajo_server::core::helpers::api_key;
+
+fn login(email: &str, password: &str) -> TestRequest{
+    TestRequest::post()
+        .uri("/api/auth/login")
+        .set_json(json!({ "email": email, "password": password }))
+}
+
+// the confirmation token only ever leaves the server inside the email
+async fn token_from_verification_email(app: &TestApp) -> String{
+    let payload: serde_json::Value =
+        sqlx::query_scalar("SELECT payload FROM jobs_queue WHERE payload->>'template' = 'verify_email'")
+            .fetch_one(&app.db)
+            .await
+            .unwrap();
+    let link = payload["context"]["link"].as_str().unwrap();
+    link.split_once("token=").unwrap().1.to_string()
+}
+
+#[actix_web::test]
+async fn user_updates_their_name(){
+    let app = TestApp::spawn().await;
+    let user = fixtures::user().create(&app).await;
+
+    let res = app
+        .call(
+            TestRequest::patch()
+                .uri("/api/me")
+                .insert_header(bearer(&app.token_for(&user.id)))
+                .set_json(json!({ "first_name": "  Grace " })),
+        )
+        .await;
+
+    assert_eq!(res.status, StatusCode::OK);
+    assert_eq!(res.body["data"]["user"]["first_name"], "Grace");
+    assert_eq!(res.body["data"]["user"]["last_name"], user.last_name);
+}
+
+#[actix_web::test]
+async fn blank_first_name_is_rejected(){
+    let app = TestApp::spawn().await;
+    let user = fixtures::user().create(&app).await;
+
+    let res = app
+        .call(
+            TestRequest::patch()
+                .uri("/api/me")
+                .insert_header(bearer(&app.token_for(&user.id)))
+                .set_json(json!({ "first_name": " " })),
+        )
+        .await;
+
+    assert_eq!(res.status, StatusCode::BAD_REQUEST);
+}
+
+#[actix_web::test]
+async fn profile_update_through_api_key_needs_profile_write(){
+    let app = TestApp::spawn().await;
+    let user = fixtures::user().create(&app).await;
+    let read_only = fixtures::api_key(&app, &user, &[api_key::PROFILE_READ], None).await;
+    let writer = fixtures::api_key(&app, &user, &[api_key::PROFILE_WRITE], None).await;
+
+    let denied = app
+        .call(
+            TestRequest::patch()
+                .uri("/api/me")
+                .insert_header(("X-Api-Key", read_only.as_str()))
+                .set_json(json!({ "last_name": "Hopper" })),
+        )
+        .await;
+    let allowed = app
+        .call(
+            TestRequest::patch()
+                .uri("/api/me")
+                .insert_header(("X-Api-Key", writer.as_str()))
+                .set_json(json!({ "last_name": "Hopper" })),
+        )
+        .await;
+
+    assert_eq!(denied.status, StatusCode::FORBIDDEN);
+    assert_eq!(allowed.status, StatusCode::OK);
+}
+
+#[actix_web::test]
+async fn password_change_needs_the_current_password_and_a_long_new_one(){
+    let app = TestApp::spawn().await;
+    let user = fixtures::user().create(&app).await;
+    let token = app.token_for(&user.id);
+
+    for (current, new) in [("wrong", "a brand new password"), (fixtures::DEFAULT_PASSWORD, "short")]{
+        let res = app
+            .call(
+                TestRequest::post()
+                    .uri("/api/me/password")
+                    .insert_header(bearer(&token))
+                    .set_json(json!({ "current_password": current, "new_password": new })),
+            )
+            .await;
+        assert_eq!(res.status, StatusCode::BAD_REQUEST);
+    }
+}
+
+#[actix_web::test]
+async fn password_change_signs_out_every_other_session(){
+    let app = TestApp::spawn().await;
+    let user = fixtures::user().create(&app).await;
+    let other_session = app.call(login(&user.email, fixtures::DEFAULT_PASSWORD)).await.body["token"]
+        .as_str()
+        .unwrap()
+        .to_string();
+
+    let res = app
+        .call(
+            TestRequest::post()
+                .uri("/api/me/password")
+                .insert_header(bearer(&app.token_for(&user.id)))
+                .set_json(json!({
+                    "current_password": fixtures::DEFAULT_PASSWORD,
+                    "new_password": "a brand new password"
+                })),
+        )
+        .await;
+    assert_eq!(res.status, StatusCode::OK);
+    let new_token = res.body["token"].as_str().unwrap();
+    assert_eq!(res.cookie("token").as_deref(), Some(new_token));
+
+    let old = app.call(TestRequest::get().uri("/api/auth/me").insert_header(bearer(&other_session))).await;
+    let new = app.call(TestRequest::get().uri("/api/auth/me").insert_header(bearer(new_token))).await;
+    assert_eq!(old.status, StatusCode::UNAUTHORIZED);
+    assert_eq!(new.status, StatusCode::OK);
+
+    assert_eq!(app.call(login(&user.email, fixtures::DEFAULT_PASSWORD)).await.status, StatusCode::BAD_REQUEST);
+    assert_eq!(app.call(login(&user.email, "a brand new password")).await.status, StatusCode::OK);
+}
+
+#[actix_web::test]
+async fn email_changes_once_the_new_address_is_confirmed(){
+    let app = TestApp::spawn().await;
+    let user = fixtures::user().create(&app).await;
+
+    let requested = app
+        .call(
+            TestRequest::post()
+                .uri("/api/me/email")
+                .insert_header(bearer(&app.token_for(&user.id)))
+                .set_json(json!({ "new_email": "New@Example.com", "password": fixtures::DEFAULT_PASSWORD })),
+        )
+        .await;
+    assert_eq!(requested.status, StatusCode::ACCEPTED);
+    assert_eq!(app.call(login("new@example.com", fixtures::DEFAULT_PASSWORD)).await.status, StatusCode::BAD_REQUEST);
+
+    let token = token_from_verification_email(&app).await;
+    let confirm = || TestRequest::post().uri("/api/me/email/confirm").set_json(json!({ "token": token }));
+    let confirmed = app.call(confirm()).await;
+    assert_eq!(confirmed.status, StatusCode::OK);
+    assert_eq!(confirmed.body["data"]["email"], "new@example.com");
+    assert_eq!(app.call(login("new@example.com", fixtures::DEFAULT_PASSWORD)).await.status, StatusCode::OK);
+
+    let notice_to: String =
+        sqlx::query_scalar("SELECT payload->>'to' FROM jobs_queue WHERE payload->>'template' = 'email_changed'")
+            .fetch_one(&app.db)
+            .await
+            .unwrap();
+    assert_eq!(notice_to, user.email);
+
+    assert_eq!(app.call(confirm()).await.status, StatusCode::BAD_REQUEST);
+}
+
+#[actix_web::test]
+async fn email_change_to_a_taken_address_is_a_conflict(){
+    let app = TestApp::spawn().await;
+    let user = fixtures::user().create(&app).await;
+    let other = fixtures::user().create(&app).await;
+
+    let res = app
+        .call(
+            TestRequest::post()
+                .uri("/api/me/email")
+                .insert_header(bearer(&app.token_for(&user.id)))
+                .set_json(json!({ "new_email": other.email, "password": fixtures::DEFAULT_PASSWORD })),
+        )
+        .await;
+
+    assert_eq!(res.status, StatusCode::CONFLICT);
+}
+
+#[actix_web::test]
+async fn expired_email_confirmation_is_rejected(){
+    let app = TestApp::spawn().await;
+    let user = fixtures::user().create(&app).await;
+    app.call(
+        TestRequest::post()
+            .uri("/api/me/email")
+            .insert_header(bearer(&app.token_for(&user.id)))
+            .set_json(json!({ "new_email": "later@example.com", "password": fixtures::DEFAULT_PASSWORD })),
+    )
+    .await;
+    let token = token_from_verification_email(&app).await;
+    sqlx::query("UPDATE email_changes SET expires_at = NOW() - INTERVAL '1 minute'")
+        .execute(&app.db)
+        .await
+        .unwrap();
+
+    let res = app
+        .call(TestRequest::post().uri("/api/me/email/confirm").set_json(json!({ "token": token })))
+        .await;
+
+    assert_eq!(res.status, StatusCode::BAD_REQUEST);
+    assert_eq!(res.body["message"], "Invalid or expired token");
+}
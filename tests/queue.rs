@@ -0,0 +1,213 @@
+mod common;
+
+use actix_web::{http::StatusCode, test::TestRequest};
+use common::{bearer, fixtures, TestApp};
+use std::{sync::Arc, time::Duration};
+use trabajo_server::{
+    core::helpers::{api_key, shutdown::BackgroundTasks},
+    queue::{self, task::Task, worker::{self, Worker}},
+};
+use uuid::Uuid;
+
+async fn insert_job(app: &TestApp, kind: &str, status: &str) -> Uuid{
+    sqlx::query_scalar("INSERT INTO jobs_queue (kind, payload, status) VALUES ($1, '{}', $2) RETURNING id")
+        .bind(kind)
+        .bind(status)
+        .fetch_one(&app.db)
+        .await
+        .unwrap()
+}
+
+async fn status_of(app: &TestApp, id: Uuid) -> String{
+    sqlx::query_scalar("SELECT status FROM jobs_queue WHERE id = $1")
+        .bind(id)
+        .fetch_one(&app.db)
+        .await
+        .unwrap()
+}
+
+// Runs one worker until every job has left the pending and running states
+async fn drain(app: &TestApp){
+    let mut background = BackgroundTasks::new();
+    let worker = Worker{
+        name: "test-worker".to_string(),
+        ctx: Arc::new(app.queue_context()),
+        metrics: app.state.metrics.clone(),
+        poll_interval: Duration::from_millis(20),
+        lock_timeout: chrono::Duration::minutes(5),
+    };
+    background.spawn("queue_worker", worker.run(background.shutdown_signal()));
+
+    for _ in 0..250{
+        let busy: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM jobs_queue WHERE status IN ('pending', 'running')")
+            .fetch_one(&app.db)
+            .await
+            .unwrap();
+        if busy == 0{
+            break;
+        }
+        tokio::time::sleep(Duration::from_millis(20)).await;
+    }
+    background.join(Duration::from_secs(5)).await;
+}
+
+#[actix_web::test]
+async fn worker_completes_jobs_and_dead_letters_undecodable_ones(){
+    let app = TestApp::spawn().await;
+    let email = Task::SendEmail{
+        to: "someone@example.com".to_string(),
+        template: "welcome".to_string(),
+        context: serde_json::json!({ "first_name": "Someone" }),
+    };
+    let good = queue::enqueue(&app.db, &email, None).await.unwrap();
+    let bad = insert_job(&app, "no_such_task", "pending").await;
+
+    drain(&app).await;
+
+    assert_eq!(status_of(&app, good).await, queue::STATUS_COMPLETED);
+    assert_eq!(status_of(&app, bad).await, queue::STATUS_DEAD);
+}
+
+#[actix_web::test]
+async fn delayed_jobs_wait_for_their_run_at(){
+    let app = TestApp::spawn().await;
+    let email = Task::SendEmail{
+        to: "someone@example.com".to_string(),
+        template: "welcome".to_string(),
+        context: serde_json::json!({ "first_name": "Someone" }),
+    };
+    let later = chrono::Utc::now() + chrono::Duration::hours(1);
+    let id = queue::enqueue(&app.db, &email, Some(later)).await.unwrap();
+
+    let claimable: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM jobs_queue WHERE id = $1 AND run_at <= NOW()")
+        .bind(id)
+        .fetch_one(&app.db)
+        .await
+        .unwrap();
+    assert_eq!(claimable, 0);
+}
+
+#[actix_web::test]
+async fn admin_sees_queue_stats_and_jobs(){
+    let app = TestApp::spawn().await;
+    let admin = fixtures::admin().create(&app).await;
+    insert_job(&app, "send_email", "pending").await;
+    insert_job(&app, "send_email", "dead").await;
+    insert_job(&app, "parse_resume", "dead").await;
+    let token = app.token_for(&admin.id);
+
+    let stats = app
+        .call(TestRequest::get().uri("/api/queue/stats").insert_header(bearer(&token)))
+        .await;
+    assert_eq!(stats.status, StatusCode::OK);
+    assert_eq!(stats.body["data"]["pending"], 1);
+    assert_eq!(stats.body["data"]["dead"], 2);
+    assert_eq!(stats.body["data"]["completed"], 0);
+
+    let dead_emails = app
+        .call(
+            TestRequest::get()
+                .uri("/api/queue/jobs?status=dead&kind=send_email")
+                .insert_header(bearer(&token)),
+        )
+        .await;
+    assert_eq!(dead_emails.body["data"].as_array().unwrap().len(), 1);
+}
+
+#[actix_web::test]
+async fn admin_can_fetch_and_retry_a_dead_job(){
+    let app = TestApp::spawn().await;
+    let admin = fixtures::admin().create(&app).await;
+    let dead = insert_job(&app, "send_email", "dead").await;
+    let pending = insert_job(&app, "send_email", "pending").await;
+    let token = app.token_for(&admin.id);
+
+    let fetched = app
+        .call(TestRequest::get().uri(&format!("/api/queue/job/{}", dead)).insert_header(bearer(&token)))
+        .await;
+    assert_eq!(fetched.status, StatusCode::OK);
+    assert_eq!(fetched.body["data"]["status"], "dead");
+
+    let retried = app
+        .call(TestRequest::post().uri(&format!("/api/queue/job/{}/retry", dead)).insert_header(bearer(&token)))
+        .await;
+    assert_eq!(retried.status, StatusCode::OK);
+    assert_eq!(status_of(&app, dead).await, queue::STATUS_PENDING);
+
+    let not_dead = app
+        .call(TestRequest::post().uri(&format!("/api/queue/job/{}/retry", pending)).insert_header(bearer(&token)))
+        .await;
+    assert_eq!(not_dead.status, StatusCode::NOT_FOUND);
+
+    // a periodic job waits already, the dead one stays dead
+    let dead_alerts = insert_job(&app, "send_job_alerts", "dead").await;
+    insert_job(&app, "send_job_alerts", "pending").await;
+    let conflict = app
+        .call(TestRequest::post().uri(&format!("/api/queue/job/{}/retry", dead_alerts)).insert_header(bearer(&token)))
+        .await;
+    assert_eq!(conflict.status, StatusCode::CONFLICT);
+    assert_eq!(status_of(&app, dead_alerts).await, queue::STATUS_DEAD);
+}
+
+#[actix_web::test]
+async fn queue_is_closed_to_users_and_api_keys(){
+    let app = TestApp::spawn().await;
+    let user = fixtures::user().create(&app).await;
+    let admin = fixtures::admin().create(&app).await;
+    let key = fixtures::api_key(&app, &admin, &api_key::ALL_SCOPES, None).await;
+
+    let as_user = app
+        .call(TestRequest::get().uri("/api/queue/stats").insert_header(bearer(&app.token_for(&user.id))))
+        .await;
+    let as_key = app
+        .call(TestRequest::get().uri("/api/queue/stats").insert_header(("X-Api-Key", key.as_str())))
+        .await;
+
+    assert_eq!(as_user.status, StatusCode::UNAUTHORIZED);
+    assert_eq!(as_key.status, StatusCode::UNAUTHORIZED);
+}
+
+#[test]
+fn every_process_names_its_workers_differently(){
+    let (first, second) = (worker::process_name(), worker::process_name());
+    assert_ne!(first, second);
+    // locked_by is a VARCHAR(100) and the worker index is appended
+    assert!(first.len() <= 90, "{}", first);
+}
+
+#[actix_web::test]
+async fn enqueue_once_skips_a_kind_that_is_still_waiting(){
+    let app = TestApp::spawn().await;
+    let now = chrono::Utc::now();
+    let retention = Task::PurgeStaleData{ applications_before: now, deletions_requested_before: now };
+
+    let first = queue::enqueue_once(&app.db, &retention, None).await.unwrap();
+    let second = queue::enqueue_once(&app.db, &retention, None).await.unwrap();
+
+    assert!(first.is_some());
+    assert!(second.is_none());
+}
+
+#[actix_web::test]
+async fn instances_racing_to_schedule_queue_one_task(){
+    let app = TestApp::spawn().await;
+    let alerts = Task::SendJobAlerts{ due_at: chrono::Utc::now() };
+
+    // the second instance checks before the first one has committed
+    let mut tx = app.db.begin().await.unwrap();
+    assert!(queue::enqueue_once(&mut *tx, &alerts, None).await.unwrap().is_some());
+    let db = app.db.clone();
+    let racing = tokio::spawn(async move{
+        let alerts = Task::SendJobAlerts{ due_at: chrono::Utc::now() };
+        queue::enqueue_once(&db, &alerts, None).await
+    });
+    tokio::time::sleep(Duration::from_millis(200)).await;
+    tx.commit().await.unwrap();
+
+    assert!(racing.await.unwrap().unwrap().is_none());
+    let queued: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM jobs_queue WHERE kind = 'send_job_alerts'")
+        .fetch_one(&app.db)
+        .await
+        .unwrap();
+    assert_eq!(queued, 1);
+}
@@ -0,0 +1,248 @@
+mod common;
+
+use actix_web::{http::StatusCode, test::TestRequest};
+use common::{bearer, fixtures, TestApp};
+use serde_json::json;
+use trabajo_server::{
+    schema::job_schema::Seniority,
+    service::scoring::{parse_salary, SalaryRange},
+};
+
+async fn build_profile(app: &TestApp, token: &str, profile: serde_json::Value, skills: &[&str], started: &str){
+    let res = app
+        .call(TestRequest::put().uri("/api/me/profile").insert_header(bearer(token)).set_json(profile))
+        .await;
+    assert_eq!(res.status, StatusCode::OK);
+    for skill in skills{
+        app.call(
+            TestRequest::post()
+                .uri("/api/me/profile/skills")
+                .insert_header(bearer(token))
+                .set_json(json!({ "name": skill, "proficiency": "advanced" })),
+        )
+        .await;
+    }
+    app.call(
+        TestRequest::post()
+            .uri("/api/me/profile/experience")
+            .insert_header(bearer(token))
+            .set_json(json!({ "company_name": "Acme", "title": "Engineer", "start_date": started })),
+    )
+    .await;
+}
+
+#[test]
+fn salaries_are_read_from_free_text(){
+    let range = |min: f64, max: f64, currency: Option<&str>| {
+        Some(SalaryRange{ min, max, currency: currency.map(str::to_string) })
+    };
+
+    assert_eq!(parse_salary("45000-55000 EUR"), range(45000.0, 55000.0, Some("EUR")));
+    assert_eq!(parse_salary("$60k - $80k"), range(60000.0, 80000.0, Some("USD")));
+    assert_eq!(parse_salary("€3.500 per month"), range(42000.0, 42000.0, Some("EUR")));
+    assert_eq!(parse_salary("£45,000"), range(45000.0, 45000.0, Some("GBP")));
+    assert_eq!(parse_salary("60.5k"), range(60500.0, 60500.0, None));
+    assert_eq!(parse_salary("100000"), range(100000.0, 100000.0, None));
+    assert_eq!(parse_salary("Competitive"), None);
+}
+
+#[test]
+fn seniority_comes_from_titles_and_years(){
+    assert_eq!(Seniority::from_title("Junior QA Engineer"), Seniority::Junior);
+    assert_eq!(Seniority::from_title("Sr. Backend Developer"), Seniority::Senior);
+    assert_eq!(Seniority::from_title("Engineering Manager"), Seniority::Lead);
+    assert_eq!(Seniority::from_title("Backend Engineer"), Seniority::Mid);
+    assert_eq!(Seniority::from_years(0.5), Seniority::Junior);
+    assert_eq!(Seniority::from_years(6.0), Seniority::Senior);
+}
+
+#[actix_web::test]
+async fn jobs_are_ranked_by_fit_with_an_explanation(){
+    let app = TestApp::spawn().await;
+    let user = fixtures::user().create(&app).await;
+    let token = app.token_for(&user.id);
+    build_profile(
+        &app,
+        &token,
+        json!({ "city": "Madrid", "country": "Spain", "salary_min": 50000, "salary_currency": "EUR" }),
+        &["Rust", "PostgreSQL"],
+        "2019-01-01",
+    )
+    .await;
+
+    fixtures::job().title("Designer").skill("Figma", true).create(&app).await;
+    let best = fixtures::job()
+        .title("Senior Rust Engineer")
+        .skill("Rust", true)
+        .skill("PostgreSQL", false)
+        .salary("55000-65000 EUR")
+        .create(&app)
+        .await;
+    sqlx::query("UPDATE jobs SET city = 'Madrid', country = 'Spain' WHERE id = $1")
+        .bind(best.id)
+        .execute(&app.db)
+        .await
+        .unwrap();
+    let applied = fixtures::job().title("Rust Developer").skill("Rust", true).create(&app).await;
+    fixtures::application(&app, &user, &applied).await;
+
+    let res = app
+        .call(TestRequest::get().uri("/api/me/recommended-jobs").insert_header(bearer(&token)))
+        .await;
+
+    assert_eq!(res.status, StatusCode::OK);
+    let jobs = res.body["data"].as_array().unwrap();
+    assert_eq!(jobs.len(), 2);
+    assert_eq!(jobs[0]["title"], "Senior Rust Engineer");
+    assert_eq!(jobs[0]["score"], 100);
+    assert_eq!(jobs[0]["breakdown"]["skills"]["reason"], "Has 1 of 1 required and 1 of 1 nice to have skills");
+    assert_eq!(jobs[0]["breakdown"]["location"]["reason"], "Lives in Madrid");
+    assert_eq!(jobs[0]["breakdown"]["salary"]["reason"], "Pays up to 65000, expects at least 50000");
+    assert_eq!(jobs[1]["title"], "Designer");
+    assert_eq!(jobs[1]["breakdown"]["skills"]["score"], 0.0);
+    assert!(jobs[1]["breakdown"]["skills"]["reason"].as_str().unwrap().ends_with("missing Figma"));
+    assert!(jobs[0]["score"].as_u64() > jobs[1]["score"].as_u64());
+}
+
+#[actix_web::test]
+async fn an_empty_profile_scores_neutral(){
+    let app = TestApp::spawn().await;
+    let user = fixtures::user().create(&app).await;
+    fixtures::job().create(&app).await;
+
+    let res = app
+        .call(TestRequest::get().uri("/api/me/recommended-jobs").insert_header(bearer(&app.token_for(&user.id))))
+        .await;
+
+    assert_eq!(res.status, StatusCode::OK);
+    assert_eq!(res.body["data"][0]["score"], 50);
+    assert_eq!(res.body["data"][0]["breakdown"]["salary"]["reason"], "No salary expectation on the profile");
+}
+
+#[actix_web::test]
+async fn recruiters_get_ranked_candidates_for_a_job(){
+    let app = TestApp::spawn().await;
+    let admin = fixtures::admin().create(&app).await;
+    let strong = fixtures::user().first_name("Strong").create(&app).await;
+    let weak = fixtures::user().first_name("Weak").create(&app).await;
+    fixtures::user().first_name("Empty").create(&app).await;
+    build_profile(&app, &app.token_for(&strong.id), json!({ "headline": "Rustacean", "city": "Lagos", "country": "Nigeria" }), &["Rust", "Go"], "2020-01-01").await;
+    build_profile(&app, &app.token_for(&weak.id), json!({ "city": "Lima", "country": "Peru" }), &["PHP"], "2023-01-01").await;
+    let job = fixtures::job().skill("Rust", true).skill("Go", false).create(&app).await;
+
+    let res = app
+        .call(
+            TestRequest::get()
+                .uri(&format!("/api/job/{}/candidates", job.id))
+                .insert_header(bearer(&app.token_for(&admin.id))),
+        )
+        .await;
+    let as_user = app
+        .call(
+            TestRequest::get()
+                .uri(&format!("/api/job/{}/candidates", job.id))
+                .insert_header(bearer(&app.token_for(&weak.id))),
+        )
+        .await;
+
+    assert_eq!(res.status, StatusCode::OK);
+    let candidates = res.body["data"].as_array().unwrap();
+    // users without a profile are not candidates
+    assert_eq!(candidates.len(), 2);
+    assert_eq!(candidates[0]["first_name"], "Strong");
+    assert_eq!(candidates[0]["headline"], "Rustacean");
+    assert_eq!(candidates[0]["breakdown"]["skills"]["score"], 1.0);
+    assert_eq!(candidates[1]["first_name"], "Weak");
+    assert_eq!(as_user.status, StatusCode::UNAUTHORIZED);
+}
+
+#[actix_web::test]
+async fn remote_jobs_match_the_countries_they_hire_from(){
+    let app = TestApp::spawn().await;
+    sqlx::query("INSERT INTO countries (code, name) VALUES ('ES', 'Spain'), ('PT', 'Portugal'), ('NG', 'Nigeria')")
+        .execute(&app.db)
+        .await
+        .unwrap();
+    let admin = fixtures::admin().create(&app).await;
+    let madrid = fixtures::user().first_name("Madrid").create(&app).await;
+    let lagos = fixtures::user().first_name("Lagos").create(&app).await;
+    let nowhere = fixtures::user().first_name("Nowhere").create(&app).await;
+    build_profile(&app, &app.token_for(&madrid.id), json!({ "city": "Madrid", "country": "spain", "open_to_remote": true }), &["Rust"], "2020-01-01").await;
+    build_profile(&app, &app.token_for(&lagos.id), json!({ "city": "Lagos", "country": "NG", "open_to_remote": true }), &["Rust"], "2020-01-01").await;
+    build_profile(&app, &app.token_for(&nowhere.id), json!({ "city": "Atlantis", "country": "Atlantis" }), &["Rust"], "2020-01-01").await;
+
+    let iberia = fixtures::job().title("Remote in Iberia").skill("Rust", true).create(&app).await;
+    let anywhere = fixtures::job().title("Remote anywhere").skill("Rust", true).create(&app).await;
+    // a city called Remote is just a city unless the work mode says so
+    let office = fixtures::job().title("Office").skill("Rust", true).create(&app).await;
+    for (job, work_mode, remote_countries, city, country, code) in [
+        (&iberia, "remote", vec!["ES", "PT"], "Anywhere", "Europe", None),
+        (&anywhere, "remote", vec![], "Anywhere", "", None),
+        (&office, "onsite", vec![], "Remote", "España", Some("ES")),
+    ]{
+        sqlx::query(
+            "UPDATE jobs SET work_mode = $2, remote_countries = $3, city = $4, country = $5, country_code = $6 WHERE id = $1",
+        )
+        .bind(job.id)
+        .bind(work_mode)
+        .bind(remote_countries)
+        .bind(city)
+        .bind(country)
+        .bind(code)
+        .execute(&app.db)
+        .await
+        .unwrap();
+    }
+
+    let locations = |job_id: uuid::Uuid| {
+        let app = &app;
+        let token = app.token_for(&admin.id);
+        async move{
+            let res = app
+                .call(TestRequest::get().uri(&format!("/api/job/{}/candidates", job_id)).insert_header(bearer(&token)))
+                .await;
+            assert_eq!(res.status, StatusCode::OK);
+            let mut locations: Vec<(String, f64, String)> = res.body["data"]
+                .as_array()
+                .unwrap()
+                .iter()
+                .map(|candidate|{
+                    let location = &candidate["breakdown"]["location"];
+                    (
+                        candidate["first_name"].as_str().unwrap().to_string(),
+                        location["score"].as_f64().unwrap(),
+                        location["reason"].as_str().unwrap().to_string(),
+                    )
+                })
+                .collect();
+            locations.sort_by(|a, b| a.0.cmp(&b.0));
+            locations
+        }
+    };
+
+    let hires = |name: &str, score: f64, reason: &str| (name.to_string(), score, reason.to_string());
+    assert_eq!(
+        locations(iberia.id).await,
+        [
+            hires("Lagos", 0.0, "Remote job, hires from ES, PT only"),
+            hires("Madrid", 1.0, "Remote job, open to remote work"),
+            hires("Nowhere", 0.5, "Remote job, hires from ES, PT"),
+        ]
+    );
+    assert_eq!(
+        locations(anywhere.id).await,
+        [
+            hires("Lagos", 1.0, "Remote job, open to remote work"),
+            hires("Madrid", 1.0, "Remote job, open to remote work"),
+            hires("Nowhere", 0.75, "Remote job"),
+        ]
+    );
+    assert_eq!(
+        locations(office.id).await,
+        [
+            hires("Lagos", 0.0, "Lives in NG, the job is in España"),
+            hires("Madrid", 0.6, "Lives in Madrid, the job is in Remote"),
+            hires("Nowhere", 0.0, "Lives in Atlantis, the job is in España"),
+        ]
+    );
+}
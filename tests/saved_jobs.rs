@@ -0,0 +1,104 @@
+mod common;
+
+use actix_web::{http::StatusCode, test::TestRequest};
+use chrono::{Duration, Utc};
+use common::{bearer, fixtures, TestApp};
+use trabajo_server::queue::task::Task;
+
+async fn reminder_emails(app: &TestApp) -> Vec<serde_json::Value>{
+    sqlx::query_scalar("SELECT payload FROM jobs_queue WHERE payload->>'template' = 'saved_job_reminder'")
+        .fetch_all(&app.db)
+        .await
+        .unwrap()
+}
+
+async fn send_reminders(app: &TestApp){
+    Task::SendSavedJobReminders{ expiring_before: Utc::now() + Duration::days(3) }
+        .run(&app.queue_context())
+        .await
+        .unwrap();
+}
+
+#[actix_web::test]
+async fn user_saves_and_removes_jobs(){
+    let app = TestApp::spawn().await;
+    let user = fixtures::user().create(&app).await;
+    let token = app.token_for(&user.id);
+    let open = fixtures::job().title("Open").skill("Rust", true).create(&app).await;
+    let closed = fixtures::job().title("Closed").expires_at(Utc::now() - Duration::days(1)).create(&app).await;
+
+    let save = |id: uuid::Uuid| TestRequest::post().uri(&format!("/api/me/saved-jobs/{}", id)).insert_header(bearer(&token));
+    assert_eq!(app.call(save(open.id)).await.status, StatusCode::CREATED);
+    assert_eq!(app.call(save(open.id)).await.status, StatusCode::OK);
+    assert_eq!(app.call(save(closed.id)).await.status, StatusCode::CREATED);
+    assert_eq!(app.call(save(uuid::Uuid::new_v4())).await.status, StatusCode::NOT_FOUND);
+
+    let listed = app.call(TestRequest::get().uri("/api/me/saved-jobs").insert_header(bearer(&token))).await;
+    assert_eq!(listed.status, StatusCode::OK);
+    let jobs = listed.body["data"].as_array().unwrap();
+    assert_eq!(jobs.len(), 2);
+    assert_eq!(jobs[0]["title"], "Closed");
+    assert_eq!(jobs[0]["is_open"], false);
+    assert_eq!(jobs[1]["title"], "Open");
+    assert_eq!(jobs[1]["is_open"], true);
+    assert_eq!(jobs[1]["skills"][0]["name"], "Rust");
+
+    let remove = || TestRequest::delete().uri(&format!("/api/me/saved-jobs/{}", closed.id)).insert_header(bearer(&token));
+    assert_eq!(app.call(remove()).await.status, StatusCode::OK);
+    assert_eq!(app.call(remove()).await.status, StatusCode::NOT_FOUND);
+
+    let listed = app.call(TestRequest::get().uri("/api/me/saved-jobs").insert_header(bearer(&token))).await;
+    assert_eq!(listed.body["data"].as_array().unwrap().len(), 1);
+}
+
+#[actix_web::test]
+async fn saved_jobs_are_private(){
+    let app = TestApp::spawn().await;
+    let owner = fixtures::user().create(&app).await;
+    let other = fixtures::user().create(&app).await;
+    let job = fixtures::job().create(&app).await;
+    app.call(
+        TestRequest::post()
+            .uri(&format!("/api/me/saved-jobs/{}", job.id))
+            .insert_header(bearer(&app.token_for(&owner.id))),
+    )
+    .await;
+
+    let token = app.token_for(&other.id);
+    let listed = app.call(TestRequest::get().uri("/api/me/saved-jobs").insert_header(bearer(&token))).await;
+    assert!(listed.body["data"].as_array().unwrap().is_empty());
+    let removed = app
+        .call(TestRequest::delete().uri(&format!("/api/me/saved-jobs/{}", job.id)).insert_header(bearer(&token)))
+        .await;
+    assert_eq!(removed.status, StatusCode::NOT_FOUND);
+}
+
+#[actix_web::test]
+async fn reminder_goes_out_once_before_a_saved_job_expires(){
+    let app = TestApp::spawn().await;
+    let user = fixtures::user().first_name("Ada").create(&app).await;
+    let soon = fixtures::job().title("Closing Soon").expires_at(Utc::now() + Duration::days(1)).create(&app).await;
+    let later = fixtures::job().expires_at(Utc::now() + Duration::days(30)).create(&app).await;
+    let applied = fixtures::job().expires_at(Utc::now() + Duration::days(1)).create(&app).await;
+    let expired = fixtures::job().expires_at(Utc::now() - Duration::days(1)).create(&app).await;
+    for job in [&soon, &later, &applied, &expired]{
+        app.call(
+            TestRequest::post()
+                .uri(&format!("/api/me/saved-jobs/{}", job.id))
+                .insert_header(bearer(&app.token_for(&user.id))),
+        )
+        .await;
+    }
+    fixtures::application(&app, &user, &applied).await;
+
+    send_reminders(&app).await;
+    let emails = reminder_emails(&app).await;
+    assert_eq!(emails.len(), 1);
+    assert_eq!(emails[0]["to"], user.email);
+    assert_eq!(emails[0]["context"]["first_name"], "Ada");
+    assert_eq!(emails[0]["context"]["title"], "Closing Soon");
+    assert!(emails[0]["context"]["link"].as_str().unwrap().ends_with(&format!("/jobs/{}", soon.id)));
+
+    send_reminders(&app).await;
+    assert_eq!(reminder_emails(&app).await.len(), 1);
+}
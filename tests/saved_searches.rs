@@ -0,0 +1,191 @@
+mod common;
+
+use actix_web::{http::StatusCode, test::TestRequest};
+use chrono::Utc;
+use common::{bearer, fixtures, TestApp};
+use serde_json::json;
+use trabajo_server::queue::task::Task;
+
+async fn save_search(app: &TestApp, token: &str, search: serde_json::Value) -> serde_json::Value{
+    let res = app
+        .call(TestRequest::post().uri("/api/me/saved-searches").insert_header(bearer(token)).set_json(search))
+        .await;
+    assert_eq!(res.status, StatusCode::CREATED);
+    res.body["data"].clone()
+}
+
+async fn send_alerts(app: &TestApp){
+    Task::SendJobAlerts{ due_at: Utc::now() }
+        .run(&app.queue_context())
+        .await
+        .unwrap();
+}
+
+async fn alert_emails(app: &TestApp) -> Vec<serde_json::Value>{
+    sqlx::query_scalar("SELECT payload FROM jobs_queue WHERE payload->>'template' = 'job_alert' ORDER BY created_at")
+        .fetch_all(&app.db)
+        .await
+        .unwrap()
+}
+
+#[actix_web::test]
+async fn user_manages_their_saved_searches(){
+    let app = TestApp::spawn().await;
+    let user = fixtures::user().create(&app).await;
+    let token = app.token_for(&user.id);
+
+    let search = save_search(
+        &app,
+        &token,
+        json!({
+            "name": " Rust jobs ",
+            "filters": { "search_query": " backend ", "skills": ["rust", "Postgres"] },
+            "frequency": "daily"
+        }),
+    )
+    .await;
+    assert_eq!(search["name"], "Rust jobs");
+    assert_eq!(search["filters"], json!({ "search_query": "backend", "skills": ["Rust", "PostgreSQL"] }));
+    assert_eq!(search["alerts_enabled"], true);
+    assert!(search.get("unsubscribe_token").is_none());
+    let id = search["id"].as_str().unwrap();
+
+    let updated = app
+        .call(
+            TestRequest::put()
+                .uri(&format!("/api/me/saved-searches/{}", id))
+                .insert_header(bearer(&token))
+                .set_json(json!({ "name": "Go jobs", "filters": { "skills": ["golang"] }, "frequency": "weekly", "alerts_enabled": false })),
+        )
+        .await;
+    assert_eq!(updated.status, StatusCode::OK);
+    assert_eq!(updated.body["data"]["filters"], json!({ "search_query": null, "skills": ["Go"] }));
+    assert_eq!(updated.body["data"]["frequency"], "weekly");
+    assert_eq!(updated.body["data"]["alerts_enabled"], false);
+
+    let listed = app
+        .call(TestRequest::get().uri("/api/me/saved-searches").insert_header(bearer(&token)))
+        .await;
+    assert_eq!(listed.body["data"].as_array().unwrap().len(), 1);
+    assert_eq!(listed.body["data"][0]["name"], "Go jobs");
+
+    let remove = || TestRequest::delete().uri(&format!("/api/me/saved-searches/{}", id)).insert_header(bearer(&token));
+    assert_eq!(app.call(remove()).await.status, StatusCode::OK);
+    assert_eq!(app.call(remove()).await.status, StatusCode::NOT_FOUND);
+}
+
+#[actix_web::test]
+async fn invalid_saved_searches_are_rejected(){
+    let app = TestApp::spawn().await;
+    let user = fixtures::user().create(&app).await;
+    let token = app.token_for(&user.id);
+
+    for search in [
+        json!({ "name": "", "frequency": "daily" }),
+        json!({ "name": "Jobs", "frequency": "hourly" }),
+        json!({ "name": "Jobs", "filters": { "skills": ["Underwater Basket Weaving"] }, "frequency": "daily" }),
+        json!({ "name": "Jobs", "filters": { "search_query": "x".repeat(201) }, "frequency": "daily" }),
+    ]{
+        let res = app
+            .call(TestRequest::post().uri("/api/me/saved-searches").insert_header(bearer(&token)).set_json(search))
+            .await;
+        assert_eq!(res.status, StatusCode::BAD_REQUEST);
+    }
+}
+
+#[actix_web::test]
+async fn searches_of_other_users_cannot_be_changed(){
+    let app = TestApp::spawn().await;
+    let owner = fixtures::user().create(&app).await;
+    let other = fixtures::user().create(&app).await;
+    let search = save_search(&app, &app.token_for(&owner.id), json!({ "name": "Jobs", "frequency": "daily" })).await;
+    let uri = format!("/api/me/saved-searches/{}", search["id"].as_str().unwrap());
+    let token = app.token_for(&other.id);
+
+    let updated = app
+        .call(
+            TestRequest::put()
+                .uri(&uri)
+                .insert_header(bearer(&token))
+                .set_json(json!({ "name": "Mine now", "frequency": "daily" })),
+        )
+        .await;
+    assert_eq!(updated.status, StatusCode::NOT_FOUND);
+    assert_eq!(app.call(TestRequest::delete().uri(&uri).insert_header(bearer(&token))).await.status, StatusCode::NOT_FOUND);
+}
+
+#[actix_web::test]
+async fn alerts_list_only_new_matching_jobs(){
+    let app = TestApp::spawn().await;
+    let user = fixtures::user().first_name("Ada").create(&app).await;
+    fixtures::job().title("Rust Engineer").skill("Rust", true).create(&app).await;
+    save_search(
+        &app,
+        &app.token_for(&user.id),
+        json!({ "name": "Rust", "filters": { "skills": ["Rust"] }, "frequency": "instant" }),
+    )
+    .await;
+
+    let new = fixtures::job().title("Senior Rust Engineer").skill("Rust", true).create(&app).await;
+    fixtures::job().title("Go Engineer").skill("Go", true).create(&app).await;
+    send_alerts(&app).await;
+
+    let emails = alert_emails(&app).await;
+    assert_eq!(emails.len(), 1);
+    assert_eq!(emails[0]["to"], user.email);
+    let context = &emails[0]["context"];
+    assert_eq!(context["first_name"], "Ada");
+    assert_eq!(context["count"], 1);
+    assert_eq!(context["jobs"][0]["title"], "Senior Rust Engineer");
+    assert!(context["jobs"][0]["link"].as_str().unwrap().ends_with(&format!("/jobs/{}", new.id)));
+    assert!(context["unsubscribe_link"].as_str().unwrap().contains("/alerts/unsubscribe?token="));
+
+    // the same jobs are never sent twice
+    send_alerts(&app).await;
+    assert_eq!(alert_emails(&app).await.len(), 1);
+}
+
+#[actix_web::test]
+async fn daily_alerts_wait_for_a_day_to_pass(){
+    let app = TestApp::spawn().await;
+    let user = fixtures::user().create(&app).await;
+    save_search(&app, &app.token_for(&user.id), json!({ "name": "Anything", "frequency": "daily" })).await;
+    fixtures::job().create(&app).await;
+
+    send_alerts(&app).await;
+    assert!(alert_emails(&app).await.is_empty());
+
+    sqlx::query("UPDATE saved_searches SET last_notified_at = NOW() - INTERVAL '25 hours'")
+        .execute(&app.db)
+        .await
+        .unwrap();
+    send_alerts(&app).await;
+    assert_eq!(alert_emails(&app).await.len(), 1);
+}
+
+#[actix_web::test]
+async fn unsubscribe_link_turns_the_alerts_off(){
+    let app = TestApp::spawn().await;
+    let user = fixtures::user().create(&app).await;
+    let token = app.token_for(&user.id);
+    save_search(&app, &token, json!({ "name": "Anything", "frequency": "instant" })).await;
+    fixtures::job().create(&app).await;
+    send_alerts(&app).await;
+
+    let emails = alert_emails(&app).await;
+    let link = emails[0]["context"]["unsubscribe_link"].as_str().unwrap();
+    let unsubscribe_token = link.split_once("token=").unwrap().1;
+
+    let unsubscribe = |token: &str| TestRequest::post().uri("/api/alerts/unsubscribe").set_json(json!({ "token": token }));
+    assert_eq!(app.call(unsubscribe(unsubscribe_token)).await.status, StatusCode::OK);
+    assert_eq!(app.call(unsubscribe("not-a-token")).await.status, StatusCode::BAD_REQUEST);
+
+    let listed = app
+        .call(TestRequest::get().uri("/api/me/saved-searches").insert_header(bearer(&token)))
+        .await;
+    assert_eq!(listed.body["data"][0]["alerts_enabled"], false);
+
+    fixtures::job().create(&app).await;
+    send_alerts(&app).await;
+    assert_eq!(alert_emails(&app).await.len(), 1);
+}
@@ -0,0 +1,141 @@
+mod common;
+
+use common::fakes::{FakeApplicationRepository, FakeUserRepository};
+use std::sync::Arc;
+use trabajo_server::{
+    core::helpers::response::error_response,
+    schema::user_schema::RegisterUserSchema,
+    service::{
+        application_service::ApplicationService,
+        user_service::{UserService, ROLE_ADMIN, ROLE_USER},
+        ServiceError,
+    },
+};
+use uuid::Uuid;
+
+fn registration(email: &str) -> RegisterUserSchema{
+    RegisterUserSchema{
+        first_name: "Ana".to_string(),
+        last_name: "García".to_string(),
+        email: email.to_string(),
+        password: "password123".to_string(),
+    }
+}
+
+fn users() -> (Arc<FakeUserRepository>, UserService){
+    let repository = Arc::new(FakeUserRepository::default());
+    (repository.clone(), UserService::new(repository))
+}
+
+#[actix_web::test]
+async fn registering_lowercases_emails_and_welcomes_users_only(){
+    let (repository, users) = users();
+
+    let user = users.register(&registration("Ana@Example.com"), ROLE_USER).await.unwrap();
+    assert_eq!(user.email, "ana@example.com");
+    assert_ne!(user.password, "password123");
+    let duplicate = users.register(&registration("ANA@example.com"), ROLE_USER).await;
+    assert!(matches!(duplicate, Err(ServiceError::Conflict(_))));
+
+    users.register(&registration("staff@example.com"), ROLE_ADMIN).await.unwrap();
+    let tasks = repository.tasks.lock().unwrap();
+    assert_eq!(tasks.len(), 1);
+    assert_eq!(tasks[0]["payload"]["template"], "welcome");
+    assert_eq!(tasks[0]["payload"]["to"], "ana@example.com");
+}
+
+#[actix_web::test]
+async fn authentication_checks_the_password_and_the_account(){
+    let (repository, users) = users();
+    let user = users.register(&registration("ana@example.com"), ROLE_USER).await.unwrap();
+
+    assert_eq!(users.authenticate("ANA@example.com", "password123").await.unwrap().id, user.id);
+    assert!(matches!(users.authenticate("ana@example.com", "wrong").await, Err(ServiceError::InvalidCredentials)));
+    assert!(matches!(users.authenticate("bob@example.com", "password123").await, Err(ServiceError::InvalidCredentials)));
+
+    // logging in takes a deletion request back
+    repository.users.lock().unwrap()[0].deletion_requested_at = Some(chrono::Utc::now());
+    assert!(!users.is_active(&user.id, None).await.unwrap());
+    users.authenticate("ana@example.com", "password123").await.unwrap();
+    assert!(users.is_active(&user.id, None).await.unwrap());
+
+    repository.users.lock().unwrap()[0].is_active = false;
+    assert!(matches!(users.authenticate("ana@example.com", "password123").await, Err(ServiceError::Disabled)));
+}
+
+#[actix_web::test]
+async fn sessions_and_admin_rights_follow_the_stored_user(){
+    let (repository, users) = users();
+    let user = users.register(&registration("ana@example.com"), ROLE_USER).await.unwrap();
+    let admin = users.register(&registration("staff@example.com"), ROLE_ADMIN).await.unwrap();
+
+    assert!(users.is_active(&user.id, Some(0)).await.unwrap());
+    repository.users.lock().unwrap()[0].session_version = 1;
+    assert!(!users.is_active(&user.id, Some(0)).await.unwrap());
+    assert!(users.is_active(&user.id, None).await.unwrap());
+    assert!(!users.is_active(&Uuid::new_v4(), None).await.unwrap());
+
+    users.ensure_admin(&admin.id).await.unwrap();
+    assert!(matches!(users.ensure_admin(&user.id).await, Err(ServiceError::Unauthorized)));
+    assert!(matches!(users.ensure_admin(&Uuid::new_v4()).await, Err(ServiceError::Unauthorized)));
+    assert!(matches!(users.find(&Uuid::new_v4()).await, Err(ServiceError::NotFound("User"))));
+}
+
+#[actix_web::test]
+async fn applying_twice_returns_the_first_application(){
+    let (repository, users) = users();
+    let applications = ApplicationService::new(Arc::new(FakeApplicationRepository::new(repository)), users.clone());
+    let user = users.register(&registration("ana@example.com"), ROLE_USER).await.unwrap();
+    let job_id = Uuid::new_v4();
+
+    let (first, created) = applications.apply(&user.id, &job_id).await.unwrap();
+    assert!(created);
+    let (again, created) = applications.apply(&user.id, &job_id).await.unwrap();
+    assert!(!created);
+    assert_eq!(again.id, first.id);
+}
+
+#[actix_web::test]
+async fn closed_jobs_take_no_applications(){
+    let (repository, users) = users();
+    let fake = Arc::new(FakeApplicationRepository::new(repository));
+    let applications = ApplicationService::new(fake.clone(), users.clone());
+    let user = users.register(&registration("ana@example.com"), ROLE_USER).await.unwrap();
+    let job_id = Uuid::new_v4();
+    fake.closed_jobs.lock().unwrap().push(job_id);
+
+    assert!(matches!(applications.apply(&user.id, &job_id).await, Err(ServiceError::Conflict(_))));
+    assert!(fake.applications.lock().unwrap().is_empty());
+}
+
+#[actix_web::test]
+async fn applicants_are_listed_to_admins_only(){
+    let (repository, users) = users();
+    let applications = ApplicationService::new(Arc::new(FakeApplicationRepository::new(repository)), users.clone());
+    let user = users.register(&registration("ana@example.com"), ROLE_USER).await.unwrap();
+    let admin = users.register(&registration("staff@example.com"), ROLE_ADMIN).await.unwrap();
+    let (backend, frontend) = (Uuid::new_v4(), Uuid::new_v4());
+    applications.apply(&user.id, &backend).await.unwrap();
+    applications.apply(&user.id, &frontend).await.unwrap();
+
+    assert!(matches!(applications.list(&user.id, 0).await, Err(ServiceError::Unauthorized)));
+    assert!(matches!(applications.list_for_job(&user.id, &backend).await, Err(ServiceError::Unauthorized)));
+
+    let all = applications.list(&admin.id, 0).await.unwrap();
+    assert_eq!(all.len(), 2);
+    assert!(all.iter().all(|a| a.applicant_id == user.id && a.first_name == "Ana"));
+    assert!(applications.list(&admin.id, 1).await.unwrap().is_empty());
+    let for_backend = applications.list_for_job(&admin.id, &backend).await.unwrap();
+    assert_eq!(for_backend.iter().map(|a| a.job_id).collect::<Vec<_>>(), vec![backend]);
+}
+
+#[actix_web::test]
+async fn database_errors_reach_clients_without_their_details(){
+    let error = sqlx::Error::Configuration("postgresql://admin:secret@db/trabajo".into());
+    let response = error_response(ServiceError::Database(error));
+    assert_eq!(response.status(), actix_web::http::StatusCode::INTERNAL_SERVER_ERROR);
+
+    let body = actix_web::body::to_bytes(response.into_body()).await.unwrap();
+    let body: serde_json::Value = serde_json::from_slice(&body).unwrap();
+    assert_eq!(body["message"], "Internal server error");
+}
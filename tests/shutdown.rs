@@ -0,0 +1,97 @@
+use actix_web::{get, web, App, HttpResponse, HttpServer, Responder};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpStream;
+use trabajo_server::core::helpers::shutdown::{self, BackgroundTasks};
+
+type Events = Arc<Mutex<Vec<&'static str>>>;
+
+#[get("/slow")]
+async fn slow(events: web::Data<Events>)-> impl Responder{
+    events.lock().unwrap().push("request started");
+    tokio::time::sleep(Duration::from_millis(300)).await;
+    events.lock().unwrap().push("request finished");
+    HttpResponse::Ok().body("done")
+}
+
+async fn get(port: u16, path: &str) -> std::io::Result<String>{
+    let mut stream = TcpStream::connect(("127.0.0.1", port)).await?;
+    stream
+        .write_all(format!("GET {} HTTP/1.1\r\nHost: localhost\r\nConnection: close\r\n\r\n", path).as_bytes())
+        .await?;
+    let mut response = String::new();
+    stream.read_to_string(&mut response).await?;
+    Ok(response)
+}
+
+async fn wait_for(events: &Events, event: &str){
+    while !events.lock().unwrap().contains(&event){
+        tokio::time::sleep(Duration::from_millis(10)).await;
+    }
+}
+
+#[actix_web::test]
+async fn signal_drains_requests_then_joins_workers(){
+    let events: Events = Arc::default();
+
+    let mut background = BackgroundTasks::new();
+    let mut worker_shutdown = background.shutdown_signal();
+    let worker_events = events.clone();
+    background.spawn("worker", async move{
+        worker_shutdown.triggered().await;
+        // finishing the job at hand
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        worker_events.lock().unwrap().push("worker stopped");
+    });
+
+    let app_events = events.clone();
+    let server = HttpServer::new(move || App::new().app_data(web::Data::new(app_events.clone())).service(slow))
+        .workers(1)
+        .disable_signals()
+        .shutdown_timeout(5)
+        .bind(("127.0.0.1", 0))
+        .unwrap();
+    let port = server.addrs()[0].port();
+    let server = server.run();
+
+    let (signal_tx, signal_rx) = tokio::sync::oneshot::channel::<()>();
+    let serving = tokio::spawn(shutdown::serve_until(
+        server,
+        background,
+        async{
+            let _ = signal_rx.await;
+        },
+        Duration::from_secs(5),
+    ));
+
+    let request = tokio::spawn(get(port, "/slow"));
+    wait_for(&events, "request started").await;
+    signal_tx.send(()).unwrap();
+
+    tokio::time::timeout(Duration::from_secs(10), serving).await.unwrap().unwrap().unwrap();
+
+    // the request in flight was answered, the worker was waited for
+    let response = request.await.unwrap().unwrap();
+    assert!(response.starts_with("HTTP/1.1 200"), "{}", response);
+    let seen = events.lock().unwrap().clone();
+    assert!(seen.contains(&"request finished"));
+    assert!(seen.contains(&"worker stopped"));
+    // and nothing is listening any more
+    assert!(get(port, "/slow").await.is_err());
+}
+
+#[tokio::test]
+async fn workers_that_do_not_stop_are_aborted(){
+    let mut background = BackgroundTasks::new();
+    let mut shutdown = background.shutdown_signal();
+    background.spawn("polite", async move{
+        shutdown.triggered().await;
+    });
+    background.spawn("stuck", std::future::pending());
+
+    let started = std::time::Instant::now();
+    background.join(Duration::from_millis(200)).await;
+    let elapsed = started.elapsed();
+    assert!(elapsed >= Duration::from_millis(200) && elapsed < Duration::from_secs(2), "{:?}", elapsed);
+}
@@ -0,0 +1,202 @@
+mod common;
+
+use actix_web::{http::StatusCode, test::TestRequest};
+use common::{bearer, fixtures, TestApp};
+use serde_json::json;
+
+fn titles(body: &serde_json::Value) -> Vec<String>{
+    let mut titles: Vec<String> = body["data"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|job| job["title"].as_str().unwrap().to_string())
+        .collect();
+    titles.sort();
+    titles
+}
+
+#[actix_web::test]
+async fn autocomplete_matches_names_and_aliases(){
+    let app = TestApp::spawn().await;
+
+    let by_name = app.call(TestRequest::get().uri("/api/skills?q=kube")).await;
+    let by_alias = app.call(TestRequest::get().uri("/api/skills?q=K8")).await;
+    let wildcard = app.call(TestRequest::get().uri("/api/skills?q=%25")).await;
+
+    assert_eq!(by_name.status, StatusCode::OK);
+    assert_eq!(by_name.body["data"][0]["name"], "Kubernetes");
+    assert_eq!(by_alias.body["data"][0]["name"], "Kubernetes");
+    assert_eq!(by_alias.body["data"][0]["aliases"], json!(["k8s"]));
+    assert_eq!(wildcard.body["data"], json!([]));
+}
+
+#[actix_web::test]
+async fn exact_matches_come_first(){
+    let app = TestApp::spawn().await;
+
+    let res = app.call(TestRequest::get().uri("/api/skills?q=go")).await;
+
+    assert_eq!(res.body["data"][0]["name"], "Go");
+    assert!(res.body["data"].as_array().unwrap().iter().any(|skill| skill["name"] == "Google Cloud"));
+}
+
+#[actix_web::test]
+async fn admins_add_skills_with_aliases(){
+    let app = TestApp::spawn().await;
+    let admin = fixtures::admin().create(&app).await;
+    let user = fixtures::user().create(&app).await;
+    let body = json!({ "name": "Elixir", "aliases": [" EX ", "ex"] });
+
+    let denied = app
+        .call(TestRequest::post().uri("/api/skills").insert_header(bearer(&app.token_for(&user.id))).set_json(&body))
+        .await;
+    let created = app
+        .call(TestRequest::post().uri("/api/skills").insert_header(bearer(&app.token_for(&admin.id))).set_json(&body))
+        .await;
+    let duplicate = app
+        .call(
+            TestRequest::post()
+                .uri("/api/skills")
+                .insert_header(bearer(&app.token_for(&admin.id)))
+                .set_json(json!({ "name": "Erlang", "aliases": ["ex"] })),
+        )
+        .await;
+
+    assert_eq!(denied.status, StatusCode::UNAUTHORIZED);
+    assert_eq!(created.status, StatusCode::CREATED);
+    assert_eq!(created.body["data"]["aliases"], json!(["ex"]));
+    assert_eq!(duplicate.status, StatusCode::CONFLICT);
+}
+
+#[actix_web::test]
+async fn jobs_are_posted_with_required_and_nice_to_have_skills(){
+    let app = TestApp::spawn().await;
+    let admin = fixtures::admin().create(&app).await;
+    let token = app.token_for(&admin.id);
+
+    let res = app
+        .call(
+            TestRequest::post()
+                .uri("/api/job")
+                .insert_header(bearer(&token))
+                .set_json(json!({
+                    "title": "Platform Engineer",
+                    "company_name": "Acme",
+                    "city": "Madrid",
+                    "country": "Spain",
+                    "salary": "60000",
+                    "description": "Run the platform",
+                    "required_skills": ["rust", "k8s"],
+                    "nice_to_have_skills": ["Terraform", "Kubernetes"]
+                })),
+        )
+        .await;
+    assert_eq!(res.status, StatusCode::OK);
+
+    let job = app
+        .call(TestRequest::get().uri(&format!("/api/job/{}", res.body["data"]["id"].as_str().unwrap())))
+        .await;
+    assert_eq!(
+        job.body["data"]["skills"],
+        json!([
+            { "id": job.body["data"]["skills"][0]["id"], "name": "Kubernetes", "required": true },
+            { "id": job.body["data"]["skills"][1]["id"], "name": "Rust", "required": true },
+            { "id": job.body["data"]["skills"][2]["id"], "name": "Terraform", "required": false },
+        ])
+    );
+
+    let unknown = app
+        .call(
+            TestRequest::post()
+                .uri("/api/job")
+                .insert_header(bearer(&token))
+                .set_json(json!({
+                    "title": "Wizard",
+                    "company_name": "Acme",
+                    "city": "Madrid",
+                    "country": "Spain",
+                    "salary": "60000",
+                    "description": "Magic",
+                    "required_skills": ["Sorcery"]
+                })),
+        )
+        .await;
+    assert_eq!(unknown.status, StatusCode::BAD_REQUEST);
+}
+
+#[actix_web::test]
+async fn job_listing_filters_by_skills_and_text(){
+    let app = TestApp::spawn().await;
+    fixtures::job().title("Rust Backend").skill("Rust", true).skill("PostgreSQL", false).create(&app).await;
+    fixtures::job().title("Rust Embedded").skill("Rust", true).create(&app).await;
+    fixtures::job().title("Frontend").skill("React", true).description("Some Rust on the side").create(&app).await;
+
+    let rust = app.call(TestRequest::get().uri("/api/jobs?skills=rust")).await;
+    let rust_and_postgres = app.call(TestRequest::get().uri("/api/jobs?skills=Rust,%20postgres")).await;
+    let unknown = app.call(TestRequest::get().uri("/api/jobs?skills=rust,sorcery")).await;
+    let text = app.call(TestRequest::get().uri("/api/jobs?search_query=rust")).await;
+    let text_and_skill = app.call(TestRequest::get().uri("/api/jobs?search_query=rust&skills=react")).await;
+
+    assert_eq!(titles(&rust.body), ["Rust Backend", "Rust Embedded"]);
+    assert_eq!(titles(&rust_and_postgres.body), ["Rust Backend"]);
+    assert!(titles(&unknown.body).is_empty());
+    assert_eq!(titles(&text.body), ["Frontend", "Rust Backend", "Rust Embedded"]);
+    assert_eq!(titles(&text_and_skill.body), ["Frontend"]);
+}
+
+#[actix_web::test]
+async fn a_skill_named_twice_is_filtered_once(){
+    let app = TestApp::spawn().await;
+    fixtures::job().title("Platform").company_name("Acme").skill("Kubernetes", true).skill("Rust", false).create(&app).await;
+    fixtures::job().title("Rust Backend").company_name("Globex").skill("Rust", true).create(&app).await;
+
+    let same_case = app.call(TestRequest::get().uri("/api/jobs?skills=rust,Rust")).await;
+    let alias = app.call(TestRequest::get().uri("/api/jobs?skills=k8s,kubernetes")).await;
+    let alias_and_other = app.call(TestRequest::get().uri("/api/jobs?skills=k8s,rust,Kubernetes")).await;
+    let facets = app.call(TestRequest::get().uri("/api/jobs/facets?skills=k8s,kubernetes")).await;
+
+    assert_eq!(titles(&same_case.body), ["Platform", "Rust Backend"]);
+    assert_eq!(titles(&alias.body), ["Platform"]);
+    assert_eq!(titles(&alias_and_other.body), ["Platform"]);
+    assert_eq!(facets.status, StatusCode::OK);
+    assert_eq!(facets.body["data"]["company"], json!({ "Acme": 1 }));
+}
+
+#[actix_web::test]
+async fn candidate_skills_are_stored_under_their_canonical_name(){
+    let app = TestApp::spawn().await;
+    let user = fixtures::user().create(&app).await;
+    let token = app.token_for(&user.id);
+
+    let known = app
+        .call(
+            TestRequest::post()
+                .uri("/api/me/profile/skills")
+                .insert_header(bearer(&token))
+                .set_json(json!({ "name": "k8s", "proficiency": "advanced" })),
+        )
+        .await;
+    let duplicate = app
+        .call(
+            TestRequest::post()
+                .uri("/api/me/profile/skills")
+                .insert_header(bearer(&token))
+                .set_json(json!({ "name": "Kubernetes", "proficiency": "expert" })),
+        )
+        .await;
+    let unknown = app
+        .call(
+            TestRequest::post()
+                .uri("/api/me/profile/skills")
+                .insert_header(bearer(&token))
+                .set_json(json!({ "name": "Underwater basket weaving", "proficiency": "expert" })),
+        )
+        .await;
+
+    assert_eq!(known.status, StatusCode::CREATED);
+    assert_eq!(known.body["data"]["name"], "Kubernetes");
+    assert!(known.body["data"]["skill_id"].is_string());
+    assert_eq!(duplicate.status, StatusCode::CONFLICT);
+    assert_eq!(unknown.status, StatusCode::CREATED);
+    assert!(unknown.body["data"]["skill_id"].is_null());
+}
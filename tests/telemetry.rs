@@ -0,0 +1,68 @@
+mod common;
+
+use actix_web::{http::StatusCode, test::TestRequest};
+use common::{bearer, fixtures, TestApp};
+use futures_util::future::{ready, BoxFuture};
+use opentelemetry::{
+    global,
+    sdk::{
+        export::trace::{ExportResult, SpanData, SpanExporter},
+        propagation::TraceContextPropagator,
+        trace::TracerProvider,
+    },
+    trace::{SpanKind, TracerProvider as _},
+};
+use std::sync::{Arc, Mutex};
+use tracing_subscriber::layer::SubscriberExt;
+
+// Stands in for the OTLP collector, keeping whatever it is sent.
+#[derive(Debug, Clone, Default)]
+struct Collector(Arc<Mutex<Vec<SpanData>>>);
+
+impl SpanExporter for Collector{
+    fn export(&mut self, batch: Vec<SpanData>) -> BoxFuture<'static, ExportResult>{
+        self.0.lock().unwrap().extend(batch);
+        Box::pin(ready(Ok(())))
+    }
+}
+
+#[actix_web::test]
+async fn request_and_query_spans_continue_the_callers_trace(){
+    let app = TestApp::spawn().await;
+    let user = fixtures::user().create(&app).await;
+
+    let collector = Collector::default();
+    let provider = TracerProvider::builder().with_simple_exporter(collector.clone()).build();
+    global::set_text_map_propagator(TraceContextPropagator::new());
+    let subscriber = tracing_subscriber::registry()
+        .with(tracing_opentelemetry::layer().with_tracer(provider.tracer("test")));
+
+    let trace_id = "4bf92f3577b34da6a3ce929d0e0e4736";
+    let caller_span_id = "00f067aa0ba902b7";
+    let res = {
+        let _guard = tracing::subscriber::set_default(subscriber);
+        app.call(
+            TestRequest::get()
+                .uri("/api/auth/me")
+                .insert_header(bearer(&app.token_for(&user.id)))
+                .insert_header(("traceparent", format!("00-{}-{}-01", trace_id, caller_span_id))),
+        )
+        .await
+    };
+    assert_eq!(res.status, StatusCode::OK);
+    provider.force_flush();
+
+    let spans = collector.0.lock().unwrap();
+    let request = spans.iter().find(|span| span.span_kind == SpanKind::Server).expect("no request span exported");
+    assert_eq!(request.span_context.trace_id().to_string(), trace_id);
+    assert_eq!(request.parent_span_id.to_string(), caller_span_id);
+    assert!(request.name.starts_with("GET "), "{}", request.name);
+
+    let query = spans
+        .iter()
+        .find(|span| span.span_kind == SpanKind::Client && span.name == "SELECT users")
+        .expect("no query span exported");
+    assert_eq!(query.span_context.trace_id().to_string(), trace_id);
+    assert_eq!(query.parent_span_id, request.span_context.span_id());
+    assert!(query.attributes.iter().any(|(key, value)| key.as_str() == "db.system" && value.as_str() == "postgresql"));
+}
@@ -0,0 +1,92 @@
+mod common;
+
+use actix_web::{http::StatusCode, test::TestRequest};
+use common::{bearer, fixtures, TestApp};
+use trabajo_server::core::helpers::api_key;
+
+const BOUNDARY: &str = "trabajo-test-boundary";
+
+fn multipart(filename: &str, content_type: &str, contents: &[u8]) -> Vec<u8>{
+    let mut body = format!(
+        "--{}\r\nContent-Disposition: form-data; name=\"file\"; filename=\"{}\"\r\nContent-Type: {}\r\n\r\n",
+        BOUNDARY, filename, content_type
+    )
+    .into_bytes();
+    body.extend_from_slice(contents);
+    body.extend_from_slice(format!("\r\n--{}--\r\n", BOUNDARY).as_bytes());
+    body
+}
+
+fn upload_request(body: Vec<u8>) -> TestRequest{
+    TestRequest::patch()
+        .uri("/api/resume")
+        .insert_header(("Content-Type", format!("multipart/form-data; boundary={}", BOUNDARY)))
+        .insert_header(("Content-Length", body.len().to_string()))
+        .set_payload(body)
+}
+
+#[actix_web::test]
+async fn resume_is_stored_and_queued_for_parsing(){
+    let app = TestApp::spawn().await;
+    let user = fixtures::user().create(&app).await;
+
+    let res = app
+        .call(
+            upload_request(multipart("cv.pdf", "application/pdf", b"%PDF-1.4 test"))
+                .insert_header(bearer(&app.token_for(&user.id))),
+        )
+        .await;
+    assert_eq!(res.status, StatusCode::OK);
+
+    let resume: String = sqlx::query_scalar("SELECT resume FROM users WHERE id = $1")
+        .bind(user.id)
+        .fetch_one(&app.db)
+        .await
+        .unwrap();
+    assert!(resume.ends_with("-cv.pdf"));
+    assert_eq!(std::fs::read(&resume).unwrap(), b"%PDF-1.4 test");
+
+    let (kind, payload): (String, serde_json::Value) = sqlx::query_as("SELECT kind, payload FROM jobs_queue")
+        .fetch_one(&app.db)
+        .await
+        .unwrap();
+    assert_eq!(kind, "parse_resume");
+    assert_eq!(payload["path"], resume);
+}
+
+#[actix_web::test]
+async fn unsupported_file_types_are_ignored(){
+    let app = TestApp::spawn().await;
+    let user = fixtures::user().create(&app).await;
+
+    let res = app
+        .call(
+            upload_request(multipart("cv.exe", "application/octet-stream", b"MZ"))
+                .insert_header(bearer(&app.token_for(&user.id))),
+        )
+        .await;
+    assert_eq!(res.status, StatusCode::OK);
+
+    let resume: String = sqlx::query_scalar("SELECT resume FROM users WHERE id = $1")
+        .bind(user.id)
+        .fetch_one(&app.db)
+        .await
+        .unwrap();
+    assert_eq!(resume, "");
+}
+
+#[actix_web::test]
+async fn upload_needs_profile_write_scope(){
+    let app = TestApp::spawn().await;
+    let user = fixtures::user().create(&app).await;
+    let key = fixtures::api_key(&app, &user, &[api_key::PROFILE_READ], None).await;
+
+    let res = app
+        .call(
+            upload_request(multipart("cv.pdf", "application/pdf", b"%PDF-1.4"))
+                .insert_header(("X-Api-Key", key.as_str())),
+        )
+        .await;
+
+    assert_eq!(res.status, StatusCode::FORBIDDEN);
+}
@@ -0,0 +1,324 @@
+mod common;
+
+use actix_web::{http::StatusCode, test::TestRequest};
+use common::{bearer, fixtures, TestApp};
+use serde_json::json;
+use std::path::{Path, PathBuf};
+use trabajo_server::{
+    core::{config::config::Config, helpers::token::JwtKeys},
+    schema::user_schema::TokenClaims,
+};
+use uuid::Uuid;
+
+fn register_body(email: &str) -> serde_json::Value{
+    json!({
+        "first_name": "Ada",
+        "last_name": "Lovelace",
+        "email": email,
+        "password": "a long enough password"
+    })
+}
+
+#[actix_web::test]
+async fn register_creates_user_and_queues_welcome_email(){
+    let app = TestApp::spawn().await;
+
+    let res = app
+        .call(TestRequest::post().uri("/api/auth/user/register").set_json(register_body("Ada@Example.com")))
+        .await;
+
+    assert_eq!(res.status, StatusCode::OK);
+    assert_eq!(res.body["data"]["user"]["email"], "ada@example.com");
+    assert_eq!(res.body["data"]["user"]["role"], "user");
+    assert!(res.body["data"]["user"].get("password").is_none());
+
+    let queued: (String, serde_json::Value) = sqlx::query_as("SELECT kind, payload FROM jobs_queue")
+        .fetch_one(&app.db)
+        .await
+        .unwrap();
+    assert_eq!(queued.0, "send_email");
+    assert_eq!(queued.1["to"], "ada@example.com");
+    assert_eq!(queued.1["template"], "welcome");
+}
+
+#[actix_web::test]
+async fn register_rejects_taken_email_in_any_case(){
+    let app = TestApp::spawn().await;
+    fixtures::user().email("ada@example.com").create(&app).await;
+
+    let res = app
+        .call(TestRequest::post().uri("/api/auth/user/register").set_json(register_body("ADA@example.com")))
+        .await;
+
+    assert_eq!(res.status, StatusCode::CONFLICT);
+    assert_eq!(res.body["message"], "Email already in use");
+}
+
+#[actix_web::test]
+async fn register_admin_creates_admin_without_welcome_email(){
+    let app = TestApp::spawn().await;
+
+    let res = app
+        .call(TestRequest::post().uri("/api/auth/admin/register").set_json(register_body("boss@example.com")))
+        .await;
+
+    assert_eq!(res.status, StatusCode::OK);
+    assert_eq!(res.body["data"]["user"]["role"], "Admin");
+    let queued: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM jobs_queue").fetch_one(&app.db).await.unwrap();
+    assert_eq!(queued, 0);
+}
+
+#[actix_web::test]
+async fn login_returns_token_and_sets_cookies(){
+    let app = TestApp::spawn().await;
+    let user = fixtures::user().create(&app).await;
+
+    let res = app
+        .call(TestRequest::post().uri("/api/auth/login").set_json(json!({
+            "email": user.email,
+            "password": fixtures::DEFAULT_PASSWORD
+        })))
+        .await;
+
+    assert_eq!(res.status, StatusCode::OK);
+    let token = res.body["token"].as_str().unwrap();
+    assert_eq!(res.cookie("token").as_deref(), Some(token));
+    assert_eq!(res.cookie("csrf_token").as_deref(), res.body["csrf_token"].as_str());
+}
+
+#[actix_web::test]
+async fn login_rejects_wrong_password_and_unknown_email(){
+    let app = TestApp::spawn().await;
+    let user = fixtures::user().create(&app).await;
+
+    for (email, password) in [(user.email.as_str(), "wrong"), ("nobody@example.com", fixtures::DEFAULT_PASSWORD)]{
+        let res = app
+            .call(TestRequest::post().uri("/api/auth/login").set_json(json!({
+                "email": email,
+                "password": password
+            })))
+            .await;
+
+        assert_eq!(res.status, StatusCode::BAD_REQUEST);
+        assert_eq!(res.body["message"], "Invalid login details");
+    }
+}
+
+#[actix_web::test]
+async fn login_rejects_inactive_user(){
+    let app = TestApp::spawn().await;
+    let user = fixtures::user().inactive().create(&app).await;
+
+    let res = app
+        .call(TestRequest::post().uri("/api/auth/login").set_json(json!({
+            "email": user.email,
+            "password": fixtures::DEFAULT_PASSWORD
+        })))
+        .await;
+
+    assert_eq!(res.status, StatusCode::FORBIDDEN);
+}
+
+#[actix_web::test]
+async fn me_returns_the_authenticated_user(){
+    let app = TestApp::spawn().await;
+    let user = fixtures::user().first_name("Grace").create(&app).await;
+
+    let res = app
+        .call(TestRequest::get().uri("/api/auth/me").insert_header(bearer(&app.token_for(&user.id))))
+        .await;
+
+    assert_eq!(res.status, StatusCode::OK);
+    assert_eq!(res.body["data"]["user"]["id"], user.id.to_string());
+    assert_eq!(res.body["data"]["user"]["first_name"], "Grace");
+}
+
+#[actix_web::test]
+async fn me_requires_authentication(){
+    let app = TestApp::spawn().await;
+
+    let res = app.call(TestRequest::get().uri("/api/auth/me")).await;
+    assert_eq!(res.status, StatusCode::UNAUTHORIZED);
+
+    let res = app
+        .call(TestRequest::get().uri("/api/auth/me").insert_header(("Authorization", "Token abc")))
+        .await;
+    assert_eq!(res.status, StatusCode::UNAUTHORIZED);
+}
+
+#[actix_web::test]
+async fn me_rejects_tokens_of_deactivated_users(){
+    let app = TestApp::spawn().await;
+    let user = fixtures::user().inactive().create(&app).await;
+
+    let res = app
+        .call(TestRequest::get().uri("/api/auth/me").insert_header(bearer(&app.token_for(&user.id))))
+        .await;
+
+    assert_eq!(res.status, StatusCode::UNAUTHORIZED);
+}
+
+#[actix_web::test]
+async fn logout_expires_session_cookies(){
+    let app = TestApp::spawn().await;
+    let user = fixtures::user().create(&app).await;
+
+    let res = app
+        .call(TestRequest::get().uri("/api/auth/logout").insert_header(bearer(&app.token_for(&user.id))))
+        .await;
+
+    assert_eq!(res.status, StatusCode::OK);
+    assert_eq!(res.cookie("token").as_deref(), Some(""));
+    assert_eq!(res.cookie("csrf_token").as_deref(), Some(""));
+}
+
+#[actix_web::test]
+async fn jwks_is_empty_for_shared_secret_tokens(){
+    let app = TestApp::spawn().await;
+
+    let res = app.call(TestRequest::get().uri("/.well-known/jwks.json")).await;
+
+    assert_eq!(res.status, StatusCode::OK);
+    assert_eq!(res.body["keys"], json!([]));
+}
+
+fn jwt_keys(jwt: &str) -> JwtKeys{
+    let config = Config::from_toml(&format!("[database]\nurl = \"postgresql://unused\"\n[jwt]\n{}", jwt)).unwrap();
+    JwtKeys::from_config(&config).unwrap()
+}
+
+// a keys directory holding the given pairs from tests/keys
+fn keys_dir(kids: &[&str]) -> PathBuf{
+    let dir = std::env::temp_dir().join(format!("trabajo_keys_{}", Uuid::new_v4().simple()));
+    std::fs::create_dir_all(&dir).unwrap();
+    for kid in kids{
+        add_key(&dir, kid);
+    }
+    dir
+}
+
+fn add_key(dir: &Path, kid: &str){
+    let source = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/keys");
+    for file in [format!("{}.pem", kid), format!("{}.pub.pem", kid)]{
+        std::fs::copy(source.join(&file), dir.join(&file)).unwrap();
+    }
+}
+
+fn asymmetric_keys(algorithm: &str, dir: &Path, active_kid: &str) -> JwtKeys{
+    jwt_keys(&format!(
+        "algorithm = \"{}\"\nkeys_dir = \"{}\"\nactive_kid = \"{}\"",
+        algorithm,
+        dir.display(),
+        active_kid
+    ))
+}
+
+#[actix_web::test]
+async fn malformed_authorization_headers_are_rejected(){
+    let app = TestApp::spawn().await;
+    let user = fixtures::user().create(&app).await;
+    let token = app.token_for(&user.id);
+
+    for header in [
+        "Bearer".to_string(),
+        "Bearer ".to_string(),
+        "Bearer x".to_string(),
+        token.to_owned(),
+        format!("Basic {}", token),
+        format!("Bearer {} extra", token),
+        format!("Bearer {}", &token[..token.len() - 10]),
+    ]{
+        let res = app
+            .call(TestRequest::get().uri("/api/auth/me").insert_header(("Authorization", header.as_str())))
+            .await;
+        assert_eq!(res.status, StatusCode::UNAUTHORIZED, "{}", header);
+    }
+
+    // the scheme is case insensitive
+    let res = app
+        .call(TestRequest::get().uri("/api/auth/me").insert_header(("Authorization", format!("bearer {}", token))))
+        .await;
+    assert_eq!(res.status, StatusCode::OK);
+}
+
+#[actix_web::test]
+async fn tokens_for_another_issuer_or_audience_are_rejected(){
+    let app = TestApp::spawn().await;
+    let user = fixtures::user().create(&app).await;
+    let secret = "secret = \"integration-test-secret\"";
+
+    let other_issuer = jwt_keys(&format!("{}\nissuer = \"someone-else\"", secret));
+    let other_audience = jwt_keys(&format!("{}\naudience = \"another-app\"", secret));
+    for keys in [other_issuer, other_audience]{
+        let token = keys.issue(&user.id, 0, chrono::Duration::minutes(60)).unwrap();
+        let res = app.call(TestRequest::get().uri("/api/auth/me").insert_header(bearer(&token))).await;
+        assert_eq!(res.status, StatusCode::UNAUTHORIZED);
+    }
+}
+
+#[actix_web::test]
+async fn tokens_with_an_unknown_kid_are_rejected(){
+    let app = TestApp::spawn().await;
+    let user = fixtures::user().create(&app).await;
+    let now = chrono::Utc::now().timestamp() as usize;
+    let claims = TokenClaims{
+        sub: user.id.to_string(),
+        iat: now,
+        exp: now + 3600,
+        iss: "trabajo-server".to_string(),
+        aud: "trabajo".to_string(),
+        ver: 0,
+    };
+
+    let sign = |kid: Option<&str>|{
+        let mut header = jsonwebtoken::Header::new(jsonwebtoken::Algorithm::HS256);
+        header.kid = kid.map(str::to_string);
+        jsonwebtoken::encode(&header, &claims, &jsonwebtoken::EncodingKey::from_secret(b"integration-test-secret")).unwrap()
+    };
+    let res = app.call(TestRequest::get().uri("/api/auth/me").insert_header(bearer(&sign(None)))).await;
+    assert_eq!(res.status, StatusCode::OK);
+    let res = app.call(TestRequest::get().uri("/api/auth/me").insert_header(bearer(&sign(Some("other"))))).await;
+    assert_eq!(res.status, StatusCode::UNAUTHORIZED);
+}
+
+#[test]
+fn asymmetric_keys_verify_across_a_rotation(){
+    let user_id = Uuid::new_v4();
+    let lifetime = chrono::Duration::minutes(60);
+
+    for (algorithm, old, new) in [("RS256", "rsa-1", "rsa-2"), ("EdDSA", "ed-1", "ed-2")]{
+        let dir = keys_dir(&[old]);
+        let before = asymmetric_keys(algorithm, &dir, old);
+        let old_token = before.issue(&user_id, 0, lifetime).unwrap();
+        assert_eq!(before.verify(&old_token).unwrap().sub, user_id.to_string());
+
+        // a new pair becomes active, tokens of the old one keep working
+        add_key(&dir, new);
+        let during = asymmetric_keys(algorithm, &dir, new);
+        let new_token = during.issue(&user_id, 0, lifetime).unwrap();
+        assert_eq!(jsonwebtoken::decode_header(&new_token).unwrap().kid.as_deref(), Some(new));
+        assert!(during.verify(&old_token).is_ok());
+        assert!(during.verify(&new_token).is_ok());
+        assert_eq!(during.jwks()["keys"].as_array().unwrap().len(), 2);
+        // the old instance has never heard of the new key
+        assert!(before.verify(&new_token).is_err());
+
+        // once the old public key is gone so are its tokens
+        std::fs::remove_file(dir.join(format!("{}.pub.pem", old))).unwrap();
+        std::fs::remove_file(dir.join(format!("{}.pem", old))).unwrap();
+        let after = asymmetric_keys(algorithm, &dir, new);
+        assert!(after.verify(&old_token).is_err());
+        assert!(after.verify(&new_token).is_ok());
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    // a token is only accepted with the algorithm the server is set to
+    let (rsa_dir, ed_dir) = (keys_dir(&["rsa-1"]), keys_dir(&["ed-1"]));
+    let rsa = asymmetric_keys("RS256", &rsa_dir, "rsa-1");
+    let ed = asymmetric_keys("EdDSA", &ed_dir, "ed-1");
+    assert!(ed.verify(&rsa.issue(&user_id, 0, lifetime).unwrap()).is_err());
+    for dir in [rsa_dir, ed_dir]{
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}